@@ -0,0 +1,33 @@
+// Shimmer of hot air over the dunes. Redraws the finished picture with everything in a band
+// above the ground nudged about, the way air boiling off the sand bends what's behind it.
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+struct HeatHaze {
+    view_origin: vec2<f32>,
+    view_size: vec2<f32>,
+    bottom: f32,
+    top: f32,
+    time: f32,
+    strength: f32,
+}
+
+@group(0) @binding(0) var screen_texture: texture_2d<f32>;
+@group(0) @binding(1) var screen_sampler: sampler;
+@group(0) @binding(2) var<uniform> haze: HeatHaze;
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let t = haze.time;
+    // uv.y runs from 0 at the top of the screen to 1 at the bottom, world y the other way.
+    let position = haze.view_origin + vec2(in.uv.x, -in.uv.y) * haze.view_size;
+    // Strongest down by the sand and gone by the top of the band.
+    let height = (position.y - haze.bottom) / (haze.top - haze.bottom);
+    let fade = step(0.0, height) * pow(1.0 - saturate(height), 2.0);
+    // Streaks that wobble sideways as they rise.
+    let wobble = sin(position.y * 0.25 - t * 4.0) * 1.5;
+    let ripple = sin(position.x * 0.07 + wobble + t * 1.2) * sin(position.x * 0.023 - t * 0.7);
+    let rise = sin(position.x * 0.11 + t * 2.3) * 0.3;
+    let shift = vec2(ripple, rise) * haze.strength * fade;
+    let uv = in.uv + vec2(shift.x, -shift.y) / haze.view_size;
+    return textureSample(screen_texture, screen_sampler, uv);
+}
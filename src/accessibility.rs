@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::profile::ProfileNameEntry;
+
+/// Effects motion-sensitive players can turn off, and help for those who can't hear the
+/// game. Saved with the profile alongside the rest of the settings.
+#[derive(Resource, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccessibilitySettings {
+    /// Jolts to the picture: the jeep's recoil, the bounce of its suspension and the
+    /// hit-stop on big kills.
+    pub screen_shake: bool,
+    /// Brief bright flashes: muzzle flashes, hit flashes and the blinking siren banner.
+    pub flashes: bool,
+    /// Scenery that drifts at its own pace against the scrolling ground, like clouds.
+    pub parallax: bool,
+    /// Captions on screen for sounds that tell the player something.
+    pub visual_audio_cues: bool,
+}
+
+impl Default for AccessibilitySettings {
+    fn default() -> Self {
+        Self {
+            screen_shake: true,
+            flashes: true,
+            parallax: true,
+            visual_audio_cues: false,
+        }
+    }
+}
+
+pub struct AccessibilityPlugin;
+
+impl Plugin for AccessibilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AccessibilitySettings>().add_systems(
+            Update,
+            (
+                toggle_effects,
+                toggle_visual_audio_cues.run_if(not(resource_exists::<ProfileNameEntry>)),
+            ),
+        );
+    }
+}
+
+pub fn run_if_screen_shake(accessibility: Res<AccessibilitySettings>) -> bool {
+    accessibility.screen_shake
+}
+
+pub fn run_if_parallax(accessibility: Res<AccessibilitySettings>) -> bool {
+    accessibility.parallax
+}
+
+pub fn run_if_visual_audio_cues(accessibility: Res<AccessibilitySettings>) -> bool {
+    accessibility.visual_audio_cues
+}
+
+/// `F8`, `F10` and `F11` turn screen shake, flashes and parallax on and off.
+fn toggle_effects(
+    key_input: Res<ButtonInput<KeyCode>>,
+    mut accessibility: ResMut<AccessibilitySettings>,
+) {
+    if key_input.just_pressed(KeyCode::F8) {
+        accessibility.screen_shake = !accessibility.screen_shake;
+    }
+    if key_input.just_pressed(KeyCode::F10) {
+        accessibility.flashes = !accessibility.flashes;
+    }
+    if key_input.just_pressed(KeyCode::F11) {
+        accessibility.parallax = !accessibility.parallax;
+    }
+}
+
+/// `V` turns the captions for sounds on and off.
+fn toggle_visual_audio_cues(
+    key_input: Res<ButtonInput<KeyCode>>,
+    mut accessibility: ResMut<AccessibilitySettings>,
+) {
+    if key_input.just_pressed(KeyCode::KeyV) {
+        accessibility.visual_audio_cues = !accessibility.visual_audio_cues;
+    }
+}
@@ -0,0 +1,218 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    health::Health, rng::GameRng, smoke_screen::InSmoke, GameSet, GameState, Player, Rocket,
+};
+
+/// Horizontal distance to the jeep at which an enemy switches to attacking.
+const ATTACK_RANGE: f32 = 300.0;
+/// Enemies at or below this fraction of their health give up and flee.
+const FLEE_HEALTH_FRACTION: f32 = 0.5;
+const FLEE_SPEED_MULTIPLIER: f32 = 1.5;
+const FLEE_CLIMB_SPEED: f32 = 80.0;
+/// How strongly an enemy pulls back to its cruise altitude, per second.
+const ALTITUDE_RETURN_RATE: f32 = 3.0;
+/// Rockets further away than this are ignored.
+const THREAT_RANGE: f32 = 260.0;
+/// Half-angle of the cone above a rocket that counts as its line of fire.
+const THREAT_CONE_HALF_ANGLE: f32 = 0.35;
+/// How often a threatened enemy decides whether to evade.
+const REACTION_INTERVAL: f32 = 0.5;
+const EVADE_CHANCE: f64 = 0.35;
+const EVADE_DURATION: f32 = 0.4;
+const EVADE_SPEED: f32 = 140.0;
+/// Climb and sink speed of the wobble of an enemy one hit from going down. Less damaged
+/// enemies wobble less.
+const DAMAGE_WOBBLE_SPEED: f32 = 60.0;
+/// Wobbles per second.
+const DAMAGE_WOBBLE_RATE: f32 = 1.5;
+
+#[derive(Reflect, Clone, Serialize, Deserialize)]
+pub enum AiState {
+    /// Cruise across the screen at the enemy's normal speed and altitude.
+    Patrol,
+    /// The jeep is in reach; weapons are free to fire.
+    Attack,
+    /// Climb (`direction` 1.0) or dive (-1.0) out of a rocket's line of fire.
+    Evade { direction: f32, timer: Timer },
+    /// Badly damaged: climb away and leave the screen. Never left once entered.
+    Flee,
+}
+
+/// Shared behavior state machine for enemies. Each frame the AI senses its surroundings,
+/// picks a state, and steers according to it; the resulting velocity moves the enemy.
+/// Enemies that [`SteersItself`] only have the state picked for them.
+#[derive(Component, Reflect, Clone, Serialize, Deserialize)]
+pub struct EnemyAi {
+    pub state: AiState,
+    pub velocity: Vec2,
+    cruise_speed: f32,
+    cruise_altitude: f32,
+    reaction: Timer,
+    /// Whether it gives up and flees when badly damaged.
+    flees: bool,
+}
+
+impl EnemyAi {
+    pub fn new(cruise_speed: f32, cruise_altitude: f32) -> Self {
+        Self {
+            state: AiState::Patrol,
+            velocity: Vec2::new(-cruise_speed, 0.0),
+            cruise_speed,
+            cruise_altitude,
+            reaction: Timer::from_seconds(REACTION_INTERVAL, TimerMode::Once),
+            flees: true,
+        }
+    }
+
+    /// Fights to the end instead of fleeing, as bosses do.
+    pub fn never_flees(mut self) -> Self {
+        self.flees = false;
+        self
+    }
+
+    pub fn is_attacking(&self) -> bool {
+        matches!(self.state, AiState::Attack)
+    }
+
+    pub fn is_fleeing(&self) -> bool {
+        matches!(self.state, AiState::Flee)
+    }
+
+    /// How fast the enemy means to go: its cruise speed, or faster when it's fleeing.
+    pub fn speed(&self) -> f32 {
+        match self.state {
+            AiState::Flee => self.cruise_speed * FLEE_SPEED_MULTIPLIER,
+            _ => self.cruise_speed,
+        }
+    }
+}
+
+/// An enemy that doesn't fly across the screen like the planes do, so it moves itself
+/// according to the state its [`EnemyAi`] is in, after [`update_ai_state`] has picked it.
+#[derive(Component)]
+pub struct SteersItself;
+
+pub struct AiPlugin;
+
+impl Plugin for AiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (update_ai_state, steer_enemies, move_enemies)
+                .chain()
+                .in_set(GameSet::Movement)
+                .run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+/// Whether something at `target` sits inside the line of fire of a rocket at `rocket`.
+fn in_threat_cone(rocket: Vec2, target: Vec2) -> bool {
+    let to_target = target - rocket;
+    to_target.length() <= THREAT_RANGE
+        && Vec2::Y.angle_between(to_target).abs() <= THREAT_CONE_HALF_ANGLE
+}
+
+/// A jeep hidden in smoke can't be found, so nothing switches to attacking it.
+pub fn update_ai_state(
+    time: Res<Time>,
+    mut enemy_query: Query<(&Transform, &mut EnemyAi, Option<&Health>)>,
+    rocket_query: Query<&Transform, With<Rocket>>,
+    player_query: Query<&Transform, (With<Player>, Without<InSmoke>)>,
+    mut rng: ResMut<GameRng>,
+) {
+    let rng = &mut rng.0;
+    let player_x = player_query
+        .get_single()
+        .ok()
+        .map(|player_transform| player_transform.translation.x);
+
+    for (enemy_transform, mut ai, health) in &mut enemy_query {
+        ai.reaction.tick(time.delta());
+
+        if matches!(ai.state, AiState::Flee) {
+            continue;
+        }
+        if ai.flees
+            && health
+                .is_some_and(|health| health.max > 1.0 && health.fraction() <= FLEE_HEALTH_FRACTION)
+        {
+            ai.state = AiState::Flee;
+            continue;
+        }
+
+        if let AiState::Evade { timer, .. } = &mut ai.state {
+            timer.tick(time.delta());
+            if !timer.finished() {
+                continue;
+            }
+        }
+
+        let position = enemy_transform.translation.truncate();
+        if ai.reaction.finished()
+            && rocket_query.iter().any(|rocket_transform| {
+                in_threat_cone(rocket_transform.translation.truncate(), position)
+            })
+        {
+            // Whether or not it reacts, the enemy gets one decision per interval.
+            ai.reaction.reset();
+            if rng.gen_bool(EVADE_CHANCE) {
+                ai.state = AiState::Evade {
+                    direction: if rng.gen_bool(0.5) { 1.0 } else { -1.0 },
+                    timer: Timer::from_seconds(EVADE_DURATION, TimerMode::Once),
+                };
+                continue;
+            }
+        }
+
+        let in_range =
+            player_x.is_some_and(|player_x| (position.x - player_x).abs() <= ATTACK_RANGE);
+        ai.state = if in_range {
+            AiState::Attack
+        } else {
+            AiState::Patrol
+        };
+    }
+}
+
+fn steer_enemies(
+    time: Res<Time>,
+    mut enemy_query: Query<
+        (Entity, &Transform, &mut EnemyAi, Option<&Health>),
+        Without<SteersItself>,
+    >,
+) {
+    for (enemy_entity, enemy_transform, mut ai, health) in &mut enemy_query {
+        let altitude_correction =
+            (ai.cruise_altitude - enemy_transform.translation.y) * ALTITUDE_RETURN_RATE;
+        ai.velocity = match ai.state {
+            AiState::Patrol | AiState::Attack => Vec2::new(-ai.cruise_speed, altitude_correction),
+            AiState::Evade { direction, .. } => {
+                Vec2::new(-ai.cruise_speed, direction * EVADE_SPEED)
+            }
+            AiState::Flee => Vec2::new(-ai.speed(), FLEE_CLIMB_SPEED),
+        };
+
+        // Damaged enemies struggle to fly level. Each wobbles out of step with the rest.
+        let damage = health.map_or(0.0, |health| 1.0 - health.fraction());
+        if damage > 0.0 {
+            let phase = enemy_entity.index() as f32;
+            let wobble = (time.elapsed_seconds() * DAMAGE_WOBBLE_RATE * TAU + phase).sin();
+            ai.velocity.y += wobble * DAMAGE_WOBBLE_SPEED * damage;
+        }
+    }
+}
+
+fn move_enemies(
+    time: Res<Time>,
+    mut enemy_query: Query<(&mut Transform, &EnemyAi), Without<SteersItself>>,
+) {
+    for (mut enemy_transform, ai) in &mut enemy_query {
+        enemy_transform.translation += (ai.velocity * time.delta_seconds()).extend(0.0);
+    }
+}
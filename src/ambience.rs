@@ -0,0 +1,142 @@
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use crate::{camera::OFFSCREEN_MARGIN, PLAY_AREA};
+
+const CLOUD_COUNT: usize = 6;
+const BIRD_COUNT: usize = 4;
+/// Props are drawn behind everything else in the scene.
+const PROP_DEPTH: f32 = -0.9;
+/// How far past the screen edge a prop travels before it is recycled.
+const RECYCLE_MARGIN: f32 = 150.0;
+const CLOUD_COLOR: Color = Color::rgba(1.0, 1.0, 1.0, 0.8);
+const BIRD_COLOR: Color = Color::rgb(0.15, 0.15, 0.2);
+const BIRD_FLAP_SPEED: f32 = 8.0;
+
+#[derive(Clone, Copy)]
+enum PropKind {
+    Cloud,
+    Bird,
+}
+
+/// Purely decorative entity drifting across the sky. Props are spawned once and wrapped back
+/// around to the right-hand side when they leave the screen instead of being despawned.
+#[derive(Component)]
+struct AmbientProp {
+    kind: PropKind,
+    speed: f32,
+}
+
+#[derive(Component)]
+struct BirdWing {
+    side: f32,
+}
+
+pub struct AmbiencePlugin;
+
+impl Plugin for AmbiencePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_ambient_props)
+            .add_systems(Update, (move_ambient_props, flap_bird_wings));
+    }
+}
+
+fn spawn_ambient_props(mut commands: Commands) {
+    let mut rng = thread_rng();
+
+    for _ in 0..CLOUD_COUNT {
+        let x = rng.gen_range(0.0..PLAY_AREA.x);
+        let (y, speed) = random_lane(PropKind::Cloud, &mut rng);
+        commands
+            .spawn((
+                SpatialBundle::from_transform(Transform::from_xyz(x, y, PROP_DEPTH)),
+                AmbientProp {
+                    kind: PropKind::Cloud,
+                    speed,
+                },
+            ))
+            .with_children(|cloud| {
+                for _ in 0..rng.gen_range(3..6) {
+                    let puff_size = rng.gen_range(30.0..60.0);
+                    cloud.spawn(SpriteBundle {
+                        sprite: Sprite {
+                            color: CLOUD_COLOR,
+                            custom_size: Some(Vec2::new(puff_size * 1.6, puff_size)),
+                            ..default()
+                        },
+                        transform: Transform::from_xyz(
+                            rng.gen_range(-50.0..50.0),
+                            rng.gen_range(-12.0..12.0),
+                            0.0,
+                        ),
+                        ..default()
+                    });
+                }
+            });
+    }
+
+    for _ in 0..BIRD_COUNT {
+        let x = rng.gen_range(0.0..PLAY_AREA.x);
+        let (y, speed) = random_lane(PropKind::Bird, &mut rng);
+        commands
+            .spawn((
+                SpatialBundle::from_transform(Transform::from_xyz(x, y, PROP_DEPTH + 0.05)),
+                AmbientProp {
+                    kind: PropKind::Bird,
+                    speed,
+                },
+            ))
+            .with_children(|bird| {
+                for side in [-1.0, 1.0] {
+                    bird.spawn((
+                        SpriteBundle {
+                            sprite: Sprite {
+                                color: BIRD_COLOR,
+                                custom_size: Some(Vec2::new(8.0, 2.0)),
+                                ..default()
+                            },
+                            transform: Transform::from_xyz(side * 3.5, 0.0, 0.0),
+                            ..default()
+                        },
+                        BirdWing { side },
+                    ));
+                }
+            });
+    }
+}
+
+/// Picks an altitude and drift speed for a prop entering from the right.
+fn random_lane(kind: PropKind, rng: &mut impl Rng) -> (f32, f32) {
+    match kind {
+        PropKind::Cloud => (
+            rng.gen_range(PLAY_AREA.y * 0.45..PLAY_AREA.y * 0.95),
+            rng.gen_range(10.0..30.0),
+        ),
+        PropKind::Bird => (
+            rng.gen_range(PLAY_AREA.y * 0.35..PLAY_AREA.y * 0.8),
+            rng.gen_range(40.0..80.0),
+        ),
+    }
+}
+
+fn move_ambient_props(time: Res<Time>, mut prop_query: Query<(&mut Transform, &mut AmbientProp)>) {
+    let mut rng = thread_rng();
+    let margin = OFFSCREEN_MARGIN.x + RECYCLE_MARGIN;
+
+    for (mut prop_transform, mut prop) in &mut prop_query {
+        prop_transform.translation.x -= prop.speed * time.delta_seconds();
+        if prop_transform.translation.x < -margin {
+            let (y, speed) = random_lane(prop.kind, &mut rng);
+            prop_transform.translation.x = PLAY_AREA.x + margin;
+            prop_transform.translation.y = y;
+            prop.speed = speed;
+        }
+    }
+}
+
+fn flap_bird_wings(time: Res<Time>, mut wing_query: Query<(&mut Transform, &BirdWing)>) {
+    let flap = (time.elapsed_seconds() * BIRD_FLAP_SPEED).sin() * 0.6;
+    for (mut wing_transform, wing) in &mut wing_query {
+        wing_transform.rotation = Quat::from_rotation_z(wing.side * flap);
+    }
+}
@@ -0,0 +1,223 @@
+use bevy::prelude::*;
+
+use crate::{
+    cosmetics::Garage, health::Health, localization::Locale, profile::ActiveProfile,
+    toast::ToastEvent, GameState, Player, PLAYER_HEALTH,
+};
+
+/// Sits just in front of the jeep sprite.
+const DECOR_DEPTH: f32 = 0.05;
+const SANDBAG_COLOR: Color = Color::rgb(0.76, 0.66, 0.45);
+const PLATE_COLOR: Color = Color::rgb(0.5, 0.52, 0.55);
+const HEAVY_PLATE_COLOR: Color = Color::rgb(0.32, 0.34, 0.36);
+
+/// A piece of armour drawn on the jeep: where it sits on the unscaled sprite, and its size.
+struct Decor {
+    offset: Vec2,
+    size: Vec2,
+    color: Color,
+}
+
+const SANDBAGS: Decor = Decor {
+    offset: Vec2::new(0.0, -7.0),
+    size: Vec2::new(28.0, 5.0),
+    color: SANDBAG_COLOR,
+};
+
+pub struct ArmorTier {
+    /// Locale key of its name.
+    pub name: &'static str,
+    pub extra_health: f32,
+    /// Credits it costs to fit, once the tier below is fitted.
+    pub cost: u32,
+    decor: &'static [Decor],
+}
+
+/// Each tier is bought in turn and replaces the one before.
+pub const ARMOR_TIERS: &[ArmorTier] = &[
+    ArmorTier {
+        name: "armor-none",
+        extra_health: 0.0,
+        cost: 0,
+        decor: &[],
+    },
+    ArmorTier {
+        name: "armor-sandbags",
+        extra_health: 1.0,
+        cost: 400,
+        decor: &[SANDBAGS],
+    },
+    ArmorTier {
+        name: "armor-plating",
+        extra_health: 2.0,
+        cost: 1200,
+        decor: &[
+            SANDBAGS,
+            Decor {
+                offset: Vec2::new(-12.0, 1.0),
+                size: Vec2::new(4.0, 12.0),
+                color: PLATE_COLOR,
+            },
+            Decor {
+                offset: Vec2::new(12.0, 1.0),
+                size: Vec2::new(4.0, 12.0),
+                color: PLATE_COLOR,
+            },
+        ],
+    },
+    ArmorTier {
+        name: "armor-heavy",
+        extra_health: 3.0,
+        cost: 3000,
+        decor: &[
+            Decor {
+                offset: Vec2::new(0.0, -7.0),
+                size: Vec2::new(30.0, 6.0),
+                color: HEAVY_PLATE_COLOR,
+            },
+            Decor {
+                offset: Vec2::new(-13.0, 1.0),
+                size: Vec2::new(5.0, 14.0),
+                color: HEAVY_PLATE_COLOR,
+            },
+            Decor {
+                offset: Vec2::new(13.0, 1.0),
+                size: Vec2::new(5.0, 14.0),
+                color: HEAVY_PLATE_COLOR,
+            },
+            Decor {
+                offset: Vec2::new(0.0, 6.0),
+                size: Vec2::new(14.0, 3.0),
+                color: PLATE_COLOR,
+            },
+        ],
+    },
+];
+
+/// The armour fitted on a profile, falling back to none if the save names a tier that
+/// isn't there.
+pub fn armor_tier(index: usize) -> &'static ArmorTier {
+    ARMOR_TIERS.get(index).unwrap_or(&ARMOR_TIERS[0])
+}
+
+/// A piece of the fitted armour, a child of the jeep.
+#[derive(Component)]
+struct ArmorDecor;
+
+/// The garage's line about armour.
+#[derive(Component)]
+pub struct ArmorLabel;
+
+pub struct ArmorPlugin;
+
+impl Plugin for ArmorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, fit_armor).add_systems(
+            Update,
+            (buy_armor, update_armor_label)
+                .chain()
+                .run_if(in_state(GameState::GameOver).and_then(resource_exists::<Garage>)),
+        );
+    }
+}
+
+/// Fits the profile's armour to the jeep whenever it changes: more health, and the
+/// sandbags or plates to show for it. Armour is only bought between runs, so the jeep
+/// is patched up to its new best as well.
+fn fit_armor(
+    mut commands: Commands,
+    profile: Res<ActiveProfile>,
+    mut fitted: Local<Option<usize>>,
+    mut player_query: Query<(Entity, &mut Health), With<Player>>,
+    decor_query: Query<Entity, With<ArmorDecor>>,
+) {
+    if *fitted == Some(profile.0.armor) {
+        return;
+    }
+    let Ok((player_entity, mut health)) = player_query.get_single_mut() else {
+        return;
+    };
+    let tier = armor_tier(profile.0.armor);
+    health.max = PLAYER_HEALTH + tier.extra_health;
+    health.current = health.max;
+
+    for decor_entity in &decor_query {
+        commands.entity(decor_entity).despawn_recursive();
+    }
+    commands.entity(player_entity).with_children(|parent| {
+        for decor in tier.decor {
+            parent.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: decor.color,
+                        custom_size: Some(decor.size),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(decor.offset.extend(DECOR_DEPTH)),
+                    ..default()
+                },
+                ArmorDecor,
+            ));
+        }
+    });
+    *fitted = Some(profile.0.armor);
+}
+
+/// `B` in the garage buys the next tier of armour, if the profile has the credits.
+fn buy_armor(
+    key_input: Res<ButtonInput<KeyCode>>,
+    locale: Res<Locale>,
+    mut profile: ResMut<ActiveProfile>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    if !key_input.just_pressed(KeyCode::KeyB) {
+        return;
+    }
+    let Some(next) = ARMOR_TIERS.get(profile.0.armor + 1) else {
+        return;
+    };
+    let name = locale.get(next.name).to_string();
+    if profile.0.credits < next.cost {
+        toast_events.send(ToastEvent(locale.format(
+            "armor-too-expensive",
+            &[("name", &name), ("cost", &next.cost)],
+        )));
+        return;
+    }
+    profile.0.credits -= next.cost;
+    profile.0.armor += 1;
+    profile.0.save();
+    toast_events.send(ToastEvent(
+        locale.format("armor-fitted", &[("name", &name)]),
+    ));
+}
+
+fn update_armor_label(
+    profile: Res<ActiveProfile>,
+    locale: Res<Locale>,
+    mut label_query: Query<&mut Text, With<ArmorLabel>>,
+) {
+    let Ok(mut text) = label_query.get_single_mut() else {
+        return;
+    };
+    let tier = armor_tier(profile.0.armor);
+    let fitted = locale.format(
+        "garage-armor",
+        &[
+            ("name", &locale.get(tier.name)),
+            ("health", &tier.extra_health),
+            ("credits", &profile.0.credits),
+        ],
+    );
+    let next = match ARMOR_TIERS.get(profile.0.armor + 1) {
+        Some(next) => locale.format(
+            "garage-armor-next",
+            &[("name", &locale.get(next.name)), ("cost", &next.cost)],
+        ),
+        None => locale.get("garage-armor-maxed").to_string(),
+    };
+    let label = format!("{fitted}\n{next}");
+    if text.sections[0].value != label {
+        text.sections[0].value = label;
+    }
+}
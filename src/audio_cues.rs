@@ -0,0 +1,122 @@
+use bevy::prelude::*;
+
+use crate::{
+    accessibility::run_if_visual_audio_cues, camera::view_rect, localization::Localized,
+    settings::Settings, GameState, PLAY_AREA,
+};
+
+const CAPTION_DURATION: f32 = 0.8;
+/// Captions float up by this much over their lifetime.
+const CAPTION_RISE: f32 = 30.0;
+const CAPTION_FONT_SIZE: f32 = 18.0;
+const CAPTION_COLOR: Color = Color::WHITE;
+/// Captions for sounds off screen are pinned this far in from the edge of the view.
+const CAPTION_INSET: f32 = 40.0;
+/// In front of everything in the world, but under the letterbox.
+const CAPTION_DEPTH: f32 = 40.0;
+
+/// A sound the player might need to know about.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AudioCue {
+    /// The thud of a rocket hitting a plane square on.
+    DirectHit,
+    /// The ping of a rocket clipping a plane's nose or tail.
+    GlancingHit,
+    /// The whistle of a bomb coming down near the jeep.
+    BombWhistle,
+    /// The engine of a friendly supply plane coming in.
+    SupplyPlane,
+    /// The chime announcing a kill streak.
+    Fanfare,
+}
+
+impl AudioCue {
+    /// Locale key of the caption shown for the cue.
+    fn caption(self) -> &'static str {
+        match self {
+            Self::DirectHit => "caption-direct-hit",
+            Self::GlancingHit => "caption-glancing-hit",
+            Self::BombWhistle => "caption-bomb-whistle",
+            Self::SupplyPlane => "caption-supply-plane",
+            Self::Fanfare => "caption-fanfare",
+        }
+    }
+}
+
+/// Sent alongside a sound, so it can be shown to players who can't hear it.
+#[derive(Event)]
+pub struct AudioCueEvent {
+    pub cue: AudioCue,
+    /// Where in the world the sound comes from.
+    pub position: Vec2,
+}
+
+#[derive(Component)]
+struct Caption {
+    timer: Timer,
+    start_y: f32,
+}
+
+pub struct AudioCuesPlugin;
+
+impl Plugin for AudioCuesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<AudioCueEvent>()
+            .add_systems(
+                Update,
+                show_captions
+                    .run_if(in_state(GameState::Playing).and_then(run_if_visual_audio_cues)),
+            )
+            .add_systems(Update, fade_captions);
+    }
+}
+
+/// Each cue gets a caption where its sound came from, kept inside the view so sounds from
+/// off screen still show which way they are.
+fn show_captions(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut cue_events: EventReader<AudioCueEvent>,
+) {
+    let view = view_rect(&settings);
+    let inset = CAPTION_INSET * view.width() / PLAY_AREA.x;
+    for event in cue_events.read() {
+        let position = event.position.clamp(view.min + inset, view.max - inset);
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size: CAPTION_FONT_SIZE,
+                        color: CAPTION_COLOR,
+                        ..default()
+                    },
+                ),
+                transform: Transform::from_translation(position.extend(CAPTION_DEPTH)),
+                ..default()
+            },
+            Localized(event.cue.caption()),
+            Caption {
+                timer: Timer::from_seconds(CAPTION_DURATION, TimerMode::Once),
+                start_y: position.y,
+            },
+        ));
+    }
+}
+
+fn fade_captions(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut caption_query: Query<(Entity, &mut Caption, &mut Transform, &mut Text)>,
+) {
+    for (caption_entity, mut caption, mut transform, mut text) in &mut caption_query {
+        caption.timer.tick(time.delta());
+        if caption.timer.finished() {
+            commands.entity(caption_entity).despawn();
+            continue;
+        }
+        let progress = caption.timer.fraction();
+        transform.translation.y = caption.start_y + CAPTION_RISE * progress;
+        text.sections[0].style.color = CAPTION_COLOR.with_a(1.0 - progress);
+    }
+}
@@ -0,0 +1,230 @@
+use bevy::{
+    ecs::system::RunSystemOnce,
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+    sprite::Anchor,
+};
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    camera::OFFSCREEN_MARGIN, explosion::ExplosionEvent, health::Health, pause::Paused,
+    rng::GameRng, rocket_collision, terrain::Terrain, DamageEvent, DamageSource, GameSet,
+    GameState, Rocket, PLAY_AREA, ROCKET_DAMAGE, ROCKET_HALF_SIZE,
+};
+
+const BARRAGE_INTERVAL: std::ops::Range<f32> = 12.0..20.0;
+const BARRAGE_SIZE: std::ops::Range<usize> = 1..4;
+/// Gap between neighbouring balloons in a barrage, wide enough to fire up between them.
+const BALLOON_SPACING: f32 = 140.0;
+const BALLOON_ALTITUDE: std::ops::Range<f32> = (PLAY_AREA.y * 0.4)..(PLAY_AREA.y * 0.6);
+const BALLOON_SIZE: Vec2 = Vec2::new(44.0, 28.0);
+const BALLOON_COLOR: Color = Color::rgb(0.7, 0.72, 0.68);
+const BALLOON_HEALTH: f32 = 3.0;
+const CABLE_WIDTH: f32 = 2.0;
+const CABLE_COLOR: Color = Color::rgb(0.2, 0.2, 0.2);
+/// Behind the planes, so they're seen flying in front of the barrage.
+const BALLOON_DEPTH: f32 = -0.3;
+/// Balloons this far past the left edge of the view are despawned.
+const DESPAWN_MARGIN: f32 = 100.0;
+const CABLE_SPARK_RADIUS: f32 = 8.0;
+
+/// A barrage balloon moored to the ground. Rockets burst on the balloon itself, which can
+/// be shot down, and on its cable, which can't.
+#[derive(Component, Clone, Serialize, Deserialize)]
+struct Balloon {
+    cable_length: f32,
+}
+
+#[derive(Resource)]
+struct BarrageSpawner(Timer);
+
+impl FromWorld for BarrageSpawner {
+    fn from_world(world: &mut World) -> Self {
+        Self(random_interval(&mut world.resource_mut::<GameRng>().0))
+    }
+}
+
+fn random_interval(rng: &mut impl Rng) -> Timer {
+    Timer::from_seconds(rng.gen_range(BARRAGE_INTERVAL), TimerMode::Once)
+}
+
+pub struct BalloonsPlugin;
+
+impl Plugin for BalloonsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BarrageSpawner>()
+            .add_systems(
+                Update,
+                (
+                    spawn_barrages.in_set(GameSet::Spawning),
+                    scroll_balloons.in_set(GameSet::Movement),
+                    block_rockets
+                        .before(rocket_collision)
+                        .in_set(GameSet::Collision),
+                )
+                    .run_if(in_state(GameState::Playing).and_then(not(resource_exists::<Paused>))),
+            )
+            .add_systems(OnExit(GameState::GameOver), despawn_balloons);
+    }
+}
+
+/// A row of balloons comes in from the right every so often, moored across the dunes.
+fn spawn_barrages(
+    mut commands: Commands,
+    time: Res<Time>,
+    terrain: Res<Terrain>,
+    mut spawner: ResMut<BarrageSpawner>,
+    mut rng: ResMut<GameRng>,
+) {
+    let rng = &mut rng.0;
+    spawner.0.tick(time.delta());
+    if !spawner.0.finished() {
+        return;
+    }
+    spawner.0 = random_interval(rng);
+
+    let start_x = PLAY_AREA.x + OFFSCREEN_MARGIN.x + BALLOON_SIZE.x;
+    for index in 0..rng.gen_range(BARRAGE_SIZE) {
+        let x = start_x + index as f32 * BALLOON_SPACING;
+        let y = rng.gen_range(BALLOON_ALTITUDE);
+        let cable_length = y - BALLOON_SIZE.y / 2.0 - terrain.height_at(x);
+        spawn_balloon(
+            &mut commands,
+            Transform::from_xyz(x, y, BALLOON_DEPTH),
+            Balloon { cable_length },
+            Health::new(BALLOON_HEALTH),
+        );
+    }
+}
+
+fn spawn_balloon(commands: &mut Commands, transform: Transform, balloon: Balloon, health: Health) {
+    let cable_length = balloon.cable_length;
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: BALLOON_COLOR,
+                    custom_size: Some(BALLOON_SIZE),
+                    ..default()
+                },
+                transform,
+                ..default()
+            },
+            balloon,
+            health,
+        ))
+        .with_children(|parent| {
+            parent.spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: CABLE_COLOR,
+                    custom_size: Some(Vec2::new(CABLE_WIDTH, cable_length)),
+                    anchor: Anchor::TopCenter,
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, -BALLOON_SIZE.y / 2.0, 0.0),
+                ..default()
+            });
+        });
+}
+
+/// Balloons are moored to the ground, so they drift left with the terrain.
+fn scroll_balloons(
+    mut commands: Commands,
+    time: Res<Time>,
+    terrain: Res<Terrain>,
+    mut balloon_query: Query<(Entity, &mut Transform), With<Balloon>>,
+) {
+    let despawn_x = -OFFSCREEN_MARGIN.x - DESPAWN_MARGIN;
+    for (balloon_entity, mut balloon_transform) in &mut balloon_query {
+        balloon_transform.translation.x -= terrain.scroll_speed() * time.delta_seconds();
+        if balloon_transform.translation.x < despawn_x {
+            commands.entity(balloon_entity).despawn_recursive();
+        }
+    }
+}
+
+/// Rockets that fly into a balloon or its cable go off there, before they can reach any
+/// plane behind it.
+fn block_rockets(
+    mut commands: Commands,
+    rocket_query: Query<(Entity, &Transform), With<Rocket>>,
+    balloon_query: Query<(Entity, &Transform, &Balloon)>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+) {
+    for (rocket_entity, rocket_transform) in &rocket_query {
+        let rocket_position = rocket_transform.translation.truncate();
+        let rocket_box = Aabb2d::new(rocket_position, ROCKET_HALF_SIZE);
+        for (balloon_entity, balloon_transform, balloon) in &balloon_query {
+            let center = balloon_transform.translation.truncate();
+            let balloon_box = Aabb2d::new(center, BALLOON_SIZE / 2.0);
+            let cable_top = center.y - BALLOON_SIZE.y / 2.0;
+            let cable_box = Aabb2d::new(
+                Vec2::new(center.x, cable_top - balloon.cable_length / 2.0),
+                Vec2::new(CABLE_WIDTH / 2.0, balloon.cable_length / 2.0),
+            );
+
+            if rocket_box.intersects(&balloon_box) {
+                damage_events.send(DamageEvent {
+                    target: balloon_entity,
+                    amount: ROCKET_DAMAGE,
+                    source: DamageSource::Player,
+                });
+            } else if rocket_box.intersects(&cable_box) {
+                explosion_events.send(ExplosionEvent {
+                    position: rocket_position,
+                    radius: CABLE_SPARK_RADIUS,
+                    source: DamageSource::Player,
+                });
+            } else {
+                continue;
+            }
+            commands.entity(rocket_entity).despawn();
+            break;
+        }
+    }
+}
+
+fn despawn_balloons(mut commands: Commands, balloon_query: Query<Entity, With<Balloon>>) {
+    for balloon_entity in &balloon_query {
+        commands.entity(balloon_entity).despawn_recursive();
+    }
+}
+
+/// What a saved run keeps of the barrage balloons in the sky.
+#[derive(Serialize, Deserialize)]
+pub struct SavedBalloons(Vec<(Transform, Balloon, Health)>);
+
+impl SavedBalloons {
+    pub fn capture(world: &mut World) -> Self {
+        world.run_system_once(save_balloons)
+    }
+
+    /// Swaps the balloons in play for these.
+    pub fn restore(self, world: &mut World) {
+        world.run_system_once_with(self, load_balloons);
+    }
+}
+
+fn save_balloons(balloon_query: Query<(&Transform, &Balloon, &Health)>) -> SavedBalloons {
+    SavedBalloons(
+        balloon_query
+            .iter()
+            .map(|(transform, balloon, health)| (*transform, balloon.clone(), health.clone()))
+            .collect(),
+    )
+}
+
+fn load_balloons(
+    In(saved): In<SavedBalloons>,
+    mut commands: Commands,
+    balloon_query: Query<Entity, With<Balloon>>,
+) {
+    for balloon_entity in &balloon_query {
+        commands.entity(balloon_entity).despawn_recursive();
+    }
+    for (transform, balloon, health) in saved.0 {
+        spawn_balloon(&mut commands, transform, balloon, health);
+    }
+}
@@ -0,0 +1,338 @@
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ai::EnemyAi, camera::OFFSCREEN_MARGIN, config::GameConfig, director::EnemyKind,
+    explosion::ExplosionEvent, health::Health, pause::Paused, pick_plane_lanes, plane_entry_x,
+    rng::GameRng, spread, spread_count, timers::BombTimer, Collider, DamageSource, GameSet,
+    GameState, Plane, PlaneLaunches, PlaneSprites, Rocket, PLAY_AREA, ROCKET_HALF_SIZE,
+};
+
+const BOMBER_SCALE: f32 = 2.5;
+const BOMBER_HALF_SIZE: Vec2 = Vec2::splat(40.0);
+const BOMBER_HEALTH: f32 = 6.0;
+/// Painted darker than the other planes, whatever the livery, so it stands out as the
+/// one that shoots back.
+const BOMBER_TINT: Color = Color::rgb(0.5, 0.55, 0.45);
+/// Compared to an ordinary plane.
+const BOMBER_SPEED: f32 = 0.7;
+const BOMBER_BOMB_LOAD: u32 = 2;
+const BOMBER_BOMB_INTERVAL: f32 = 0.75;
+
+/// Where the gun sits on the bomber, at the tail. Bombers fly leftwards.
+const TAIL_GUN_OFFSET: Vec2 = Vec2::new(34.0, -6.0);
+/// The gunner only sees rockets within this range...
+const TAIL_GUN_RANGE: f32 = 340.0;
+/// ...and can't swing round to ones further forward than this.
+const TAIL_GUN_FORWARD_REACH: f32 = 16.0;
+const TAIL_GUN_INTERVAL: f32 = 0.4;
+/// Most bursts miss by up to this much, so only some rockets are brought down.
+const TAIL_GUN_SPREAD: f32 = 0.14;
+
+const BULLET_SPEED: f32 = 420.0;
+const BULLET_LIFETIME: f32 = 0.8;
+const BULLET_SIZE: Vec2 = Vec2::new(6.0, 2.0);
+const BULLET_COLOR: Color = Color::rgb(1.0, 0.85, 0.35);
+const BULLET_HIT_RADIUS: f32 = 4.0;
+const INTERCEPT_BLAST_RADIUS: f32 = 14.0;
+
+/// A heavy plane, slower and tougher than the rest, that carries more bombs and has a
+/// gunner in the tail.
+#[derive(Component)]
+pub struct Bomber;
+
+#[derive(Component, Clone, Serialize, Deserialize)]
+struct TailGun {
+    cooldown: Timer,
+}
+
+/// Fired from a tail gun at rockets coming up behind the bomber. It's no danger to the
+/// jeep.
+#[derive(Component, Clone, Serialize, Deserialize)]
+struct TailGunBullet {
+    velocity: Vec2,
+    lifetime: Timer,
+}
+
+pub struct BomberPlugin;
+
+impl Plugin for BomberPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                spawn_bombers
+                    .after(pick_plane_lanes)
+                    .in_set(GameSet::Spawning),
+                (fire_tail_gun, move_bullets)
+                    .chain()
+                    .in_set(GameSet::Movement),
+                shoot_down_rockets.in_set(GameSet::Collision),
+            )
+                .run_if(in_state(GameState::Playing).and_then(not(resource_exists::<Paused>))),
+        )
+        .add_systems(OnExit(GameState::GameOver), clear_bullets);
+    }
+}
+
+/// Takes off the bombers given a lane this frame, the same way other planes do.
+fn spawn_bombers(
+    mut commands: Commands,
+    plane_sprites: PlaneSprites,
+    launches: Res<PlaneLaunches>,
+    config: Res<GameConfig>,
+    mut rng: ResMut<GameRng>,
+) {
+    let rng = &mut rng.0;
+    for &(kind, altitude) in &launches.ready {
+        if kind != EnemyKind::Bomber {
+            continue;
+        }
+        let transform = Transform::from_xyz(plane_entry_x(), altitude, 0.0).with_scale(Vec3::new(
+            BOMBER_SCALE,
+            BOMBER_SCALE,
+            0.0,
+        ));
+        commands.spawn(bomber(
+            &plane_sprites,
+            transform,
+            Plane {
+                number_of_bombs: spread_count(rng, config.plane_bombs) * BOMBER_BOMB_LOAD,
+            },
+            BombTimer::new(
+                spread(
+                    rng,
+                    config.plane_bomb_interval,
+                    config.plane_bomb_interval_spread,
+                ) * BOMBER_BOMB_INTERVAL,
+            ),
+            EnemyAi::new(
+                spread(rng, config.plane_speed, config.plane_speed_spread) * BOMBER_SPEED,
+                altitude,
+            ),
+            Health::new(BOMBER_HEALTH),
+            TailGun {
+                cooldown: Timer::from_seconds(TAIL_GUN_INTERVAL, TimerMode::Once),
+            },
+        ));
+    }
+}
+
+fn bomber(
+    plane_sprites: &PlaneSprites,
+    transform: Transform,
+    plane: Plane,
+    bomb_timer: BombTimer,
+    ai: EnemyAi,
+    health: Health,
+    tail_gun: TailGun,
+) -> impl Bundle {
+    let mut sprite = plane_sprites.sprite(transform);
+    sprite.sprite.color = BOMBER_TINT;
+    (
+        sprite,
+        plane,
+        bomb_timer,
+        ai,
+        health,
+        Collider {
+            half_size: BOMBER_HALF_SIZE,
+        },
+        Bomber,
+        tail_gun,
+    )
+}
+
+/// The gunner picks out the nearest rocket climbing up behind the bomber and fires where
+/// it's about to be.
+fn fire_tail_gun(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut gun_query: Query<(&Transform, &mut TailGun), With<Bomber>>,
+    rocket_query: Query<(&Transform, &Rocket)>,
+    mut rng: ResMut<GameRng>,
+) {
+    let rng = &mut rng.0;
+    for (bomber_transform, mut gun) in &mut gun_query {
+        gun.cooldown.tick(time.delta());
+        if !gun.cooldown.finished() {
+            continue;
+        }
+        let muzzle = bomber_transform.translation.truncate() + TAIL_GUN_OFFSET;
+        let target = rocket_query
+            .iter()
+            .map(|(transform, rocket)| (transform.translation.truncate(), rocket.movement_speed))
+            .filter(|&(position, _)| {
+                position.y < muzzle.y
+                    && position.x > muzzle.x - TAIL_GUN_FORWARD_REACH
+                    && position.distance(muzzle) < TAIL_GUN_RANGE
+            })
+            .min_by(|(a, _), (b, _)| a.distance(muzzle).total_cmp(&b.distance(muzzle)));
+        let Some((position, rocket_speed)) = target else {
+            continue;
+        };
+
+        // Rockets fly straight up, so lead it by however far it climbs while the burst
+        // is on its way.
+        let flight_time = position.distance(muzzle) / BULLET_SPEED;
+        let aim = position + Vec2::new(0.0, rocket_speed * flight_time) - muzzle;
+        let angle = aim.y.atan2(aim.x) + rng.gen_range(-TAIL_GUN_SPREAD..TAIL_GUN_SPREAD);
+        let direction = Vec2::from_angle(angle);
+
+        commands.spawn(bullet(
+            Transform::from_translation(muzzle.extend(0.1))
+                .with_rotation(Quat::from_rotation_z(angle)),
+            TailGunBullet {
+                velocity: direction * BULLET_SPEED,
+                lifetime: Timer::from_seconds(BULLET_LIFETIME, TimerMode::Once),
+            },
+        ));
+        gun.cooldown.reset();
+    }
+}
+
+fn bullet(transform: Transform, bullet: TailGunBullet) -> impl Bundle {
+    (
+        SpriteBundle {
+            sprite: Sprite {
+                color: BULLET_COLOR,
+                custom_size: Some(BULLET_SIZE),
+                ..default()
+            },
+            transform,
+            ..default()
+        },
+        bullet,
+    )
+}
+
+fn move_bullets(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut bullet_query: Query<(Entity, &mut TailGunBullet, &mut Transform)>,
+) {
+    for (bullet_entity, mut bullet, mut transform) in &mut bullet_query {
+        bullet.lifetime.tick(time.delta());
+        let position = transform.translation.truncate();
+        let offscreen = position.x < -OFFSCREEN_MARGIN.x
+            || position.x > PLAY_AREA.x + OFFSCREEN_MARGIN.x
+            || position.y < -OFFSCREEN_MARGIN.y;
+        if bullet.lifetime.finished() || offscreen {
+            commands.entity(bullet_entity).despawn();
+            continue;
+        }
+        transform.translation += (bullet.velocity * time.delta_seconds()).extend(0.0);
+    }
+}
+
+fn shoot_down_rockets(
+    mut commands: Commands,
+    bullet_query: Query<(Entity, &Transform), With<TailGunBullet>>,
+    rocket_query: Query<(Entity, &Transform), With<Rocket>>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+) {
+    let reach = BULLET_HIT_RADIUS + ROCKET_HALF_SIZE.max_element();
+    let mut downed = Vec::new();
+    for (bullet_entity, bullet_transform) in &bullet_query {
+        let bullet_position = bullet_transform.translation.truncate();
+        let hit = rocket_query
+            .iter()
+            .find(|&(rocket_entity, rocket_transform)| {
+                !downed.contains(&rocket_entity)
+                    && rocket_transform
+                        .translation
+                        .truncate()
+                        .distance(bullet_position)
+                        < reach
+            });
+        if let Some((rocket_entity, rocket_transform)) = hit {
+            downed.push(rocket_entity);
+            commands.entity(bullet_entity).despawn();
+            commands.entity(rocket_entity).despawn();
+            explosion_events.send(ExplosionEvent {
+                position: rocket_transform.translation.truncate(),
+                radius: INTERCEPT_BLAST_RADIUS,
+                source: DamageSource::Enemy,
+            });
+        }
+    }
+}
+
+fn clear_bullets(mut commands: Commands, bullet_query: Query<Entity, With<TailGunBullet>>) {
+    for bullet_entity in &bullet_query {
+        commands.entity(bullet_entity).despawn();
+    }
+}
+
+/// What a saved run keeps of the bombers in the air and the bursts their gunners have
+/// fired.
+#[derive(Serialize, Deserialize)]
+pub struct SavedBombers {
+    bombers: Vec<(Transform, Plane, BombTimer, EnemyAi, Health, TailGun)>,
+    bullets: Vec<(Transform, TailGunBullet)>,
+}
+
+impl SavedBombers {
+    pub fn capture(world: &mut World) -> Self {
+        world.run_system_once(save_bombers)
+    }
+
+    /// Swaps the bombers in play for these.
+    pub fn restore(self, world: &mut World) {
+        world.run_system_once_with(self, load_bombers);
+    }
+}
+
+fn save_bombers(
+    bomber_query: Query<(&Transform, &Plane, &BombTimer, &EnemyAi, &Health, &TailGun)>,
+    bullet_query: Query<(&Transform, &TailGunBullet)>,
+) -> SavedBombers {
+    SavedBombers {
+        bombers: bomber_query
+            .iter()
+            .map(|(transform, plane, bomb_timer, ai, health, tail_gun)| {
+                (
+                    *transform,
+                    plane.clone(),
+                    bomb_timer.clone(),
+                    ai.clone(),
+                    health.clone(),
+                    tail_gun.clone(),
+                )
+            })
+            .collect(),
+        bullets: bullet_query
+            .iter()
+            .map(|(transform, tail_gun_bullet)| (*transform, tail_gun_bullet.clone()))
+            .collect(),
+    }
+}
+
+fn load_bombers(
+    In(saved): In<SavedBombers>,
+    mut commands: Commands,
+    plane_sprites: PlaneSprites,
+    entity_query: Query<Entity, Or<(With<Bomber>, With<TailGunBullet>)>>,
+) {
+    for entity in &entity_query {
+        commands.entity(entity).despawn_recursive();
+    }
+    for (transform, plane, bomb_timer, ai, health, tail_gun) in saved.bombers {
+        commands.spawn(bomber(
+            &plane_sprites,
+            transform,
+            plane,
+            bomb_timer,
+            ai,
+            health,
+            tail_gun,
+        ));
+    }
+    commands.spawn_batch(
+        saved
+            .bullets
+            .into_iter()
+            .map(|(transform, tail_gun_bullet)| bullet(transform, tail_gun_bullet)),
+    );
+}
@@ -0,0 +1,378 @@
+use bevy::{
+    ecs::system::RunSystemOnce,
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+};
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    director::{Director, WaveEndEvent},
+    explosion::ExplosionEvent,
+    ground_units::GroundUnit,
+    health::Boss,
+    localization::Locale,
+    pause::Paused,
+    rng::GameRng,
+    stats::RunStats,
+    Bomb, DamageSource, GameSet, GameState, Plane, Rocket, PLAY_AREA, ROCKET_HALF_SIZE,
+};
+
+/// A bonus round follows every this many waves.
+const BONUS_EVERY: u32 = 3;
+const BONUS_DURATION: f32 = 20.0;
+const TARGET_INTERVAL: f32 = 0.6;
+const TARGET_SIZE: Vec2 = Vec2::new(24.0, 30.0);
+const TARGET_COLORS: [Color; 3] = [
+    Color::rgb(0.95, 0.3, 0.3),
+    Color::rgb(0.95, 0.8, 0.2),
+    Color::rgb(0.4, 0.6, 0.95),
+];
+const TARGET_RISE_SPEED: std::ops::Range<f32> = 80.0..140.0;
+const TARGET_SWAY: f32 = 20.0;
+const TARGET_SWAY_SPEED: f32 = 2.0;
+const TARGET_SCORE: u32 = 150;
+/// Targets float up from the horizon and are let go at the top of the sky.
+const TARGET_START_Y: f32 = PLAY_AREA.y * 0.25;
+const TARGET_DEPTH: f32 = -0.2;
+const BONUS_FONT_SIZE: f32 = 28.0;
+const BONUS_TEXT_COLOR: Color = Color::rgb(1.0, 0.9, 0.4);
+const BONUS_PADDING: Val = Val::Px(48.0);
+
+/// What part of a run is being played. Only means anything while the game is being
+/// played; a run always starts on its waves.
+#[derive(States, Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Round {
+    #[default]
+    Waves,
+    /// A short breather with nothing shooting back, just targets to pop for points.
+    Bonus,
+}
+
+/// Anything that can still hurt the jeep once it's been fired or dropped, swept off the
+/// field with the planes, bombs and infantry when a bonus round starts.
+#[derive(Component)]
+pub struct Hostile;
+
+/// Progress through the bonus round being played.
+#[derive(Resource, Clone, Serialize, Deserialize)]
+struct BonusRound {
+    timer: Timer,
+    spawn_timer: Timer,
+    popped: u32,
+}
+
+impl Default for BonusRound {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(BONUS_DURATION, TimerMode::Once),
+            spawn_timer: Timer::from_seconds(TARGET_INTERVAL, TimerMode::Repeating),
+            popped: 0,
+        }
+    }
+}
+
+#[derive(Component, Clone, Serialize, Deserialize)]
+struct BonusTarget {
+    rise_speed: f32,
+    /// Where the target's sway is centred.
+    center_x: f32,
+    /// How far through its sway the target is, in radians.
+    phase: f32,
+}
+
+/// The targets and the round's readout, cleared away when the round ends.
+#[derive(Component)]
+struct BonusScene;
+
+#[derive(Component)]
+struct BonusText;
+
+pub struct BonusPlugin;
+
+impl Plugin for BonusPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<Round>()
+            .add_systems(OnEnter(Round::Bonus), start_bonus_round)
+            .add_systems(OnExit(Round::Bonus), end_bonus_round)
+            .add_systems(OnExit(GameState::Playing), leave_bonus_round)
+            .add_systems(
+                Update,
+                start_after_waves.run_if(
+                    in_state(GameState::Playing)
+                        .and_then(in_state(Round::Waves))
+                        .and_then(on_event::<WaveEndEvent>()),
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    spawn_targets.in_set(GameSet::Spawning),
+                    float_targets.in_set(GameSet::Movement),
+                    pop_targets.in_set(GameSet::Collision),
+                    (count_down, update_bonus_text)
+                        .chain()
+                        .in_set(GameSet::Cleanup),
+                )
+                    .run_if(
+                        in_state(GameState::Playing)
+                            .and_then(in_state(Round::Bonus))
+                            .and_then(not(resource_exists::<Paused>)),
+                    ),
+            );
+    }
+}
+
+/// Bosses aren't swept away like the rest, so a round that's due while one is still up
+/// is skipped rather than cutting the fight short.
+fn start_after_waves(
+    director: Res<Director>,
+    boss_query: Query<(), With<Boss>>,
+    mut next_round: ResMut<NextState<Round>>,
+) {
+    if director.waves_cleared().is_multiple_of(BONUS_EVERY) && boss_query.is_empty() {
+        next_round.set(Round::Bonus);
+    }
+}
+
+/// Clears the field of anything still shooting and puts up the round's readout.
+fn start_bonus_round(
+    mut commands: Commands,
+    threat_query: Query<Entity, Or<(With<Plane>, With<Bomb>, With<GroundUnit>, With<Hostile>)>>,
+) {
+    for entity in &threat_query {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.init_resource::<BonusRound>();
+    spawn_bonus_text(&mut commands);
+}
+
+fn spawn_bonus_text(commands: &mut Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    padding: UiRect::top(BONUS_PADDING),
+                    ..default()
+                },
+                ..default()
+            },
+            BonusScene,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: BONUS_FONT_SIZE,
+                        color: BONUS_TEXT_COLOR,
+                        ..default()
+                    },
+                ),
+                BonusText,
+            ));
+        });
+}
+
+fn end_bonus_round(mut commands: Commands, scene_query: Query<Entity, With<BonusScene>>) {
+    for entity in &scene_query {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<BonusRound>();
+}
+
+/// A run that ends mid-round starts the next one back on its waves.
+fn leave_bonus_round(mut next_round: ResMut<NextState<Round>>) {
+    next_round.set(Round::Waves);
+}
+
+fn spawn_targets(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut round: ResMut<BonusRound>,
+    mut rng: ResMut<GameRng>,
+) {
+    round.spawn_timer.tick(time.delta());
+    if !round.spawn_timer.just_finished() {
+        return;
+    }
+
+    let rng = &mut rng.0;
+    let center_x = rng.gen_range(TARGET_SIZE.x..PLAY_AREA.x - TARGET_SIZE.x);
+    commands.spawn(bonus_target(
+        Transform::from_xyz(center_x, TARGET_START_Y, TARGET_DEPTH),
+        BonusTarget {
+            rise_speed: rng.gen_range(TARGET_RISE_SPEED),
+            center_x,
+            phase: rng.gen_range(0.0..std::f32::consts::TAU),
+        },
+        *TARGET_COLORS.choose(rng).unwrap_or(&TARGET_COLORS[0]),
+    ));
+}
+
+fn bonus_target(transform: Transform, target: BonusTarget, color: Color) -> impl Bundle {
+    (
+        SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(TARGET_SIZE),
+                ..default()
+            },
+            transform,
+            ..default()
+        },
+        target,
+        BonusScene,
+    )
+}
+
+/// Targets drift upwards, swaying from side to side.
+fn float_targets(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut target_query: Query<(Entity, &mut Transform, &mut BonusTarget)>,
+) {
+    let dt = time.delta_seconds();
+    for (target_entity, mut transform, mut target) in &mut target_query {
+        target.phase += TARGET_SWAY_SPEED * dt;
+        transform.translation.y += target.rise_speed * dt;
+        transform.translation.x = target.center_x + target.phase.sin() * TARGET_SWAY;
+        if transform.translation.y > PLAY_AREA.y + TARGET_SIZE.y {
+            commands.entity(target_entity).despawn();
+        }
+    }
+}
+
+fn pop_targets(
+    mut commands: Commands,
+    rocket_query: Query<(Entity, &Transform), With<Rocket>>,
+    target_query: Query<(Entity, &Transform), With<BonusTarget>>,
+    mut round: ResMut<BonusRound>,
+    mut stats: ResMut<RunStats>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+) {
+    let mut popped = Vec::new();
+    for (rocket_entity, rocket_transform) in &rocket_query {
+        let rocket_box = Aabb2d::new(rocket_transform.translation.truncate(), ROCKET_HALF_SIZE);
+        let hit = target_query
+            .iter()
+            .find(|(target_entity, target_transform)| {
+                !popped.contains(target_entity)
+                    && rocket_box.intersects(&Aabb2d::new(
+                        target_transform.translation.truncate(),
+                        TARGET_SIZE / 2.0,
+                    ))
+            });
+        let Some((target_entity, target_transform)) = hit else {
+            continue;
+        };
+
+        commands.entity(rocket_entity).despawn();
+        commands.entity(target_entity).despawn();
+        popped.push(target_entity);
+        round.popped += 1;
+        stats.score += TARGET_SCORE;
+        explosion_events.send(ExplosionEvent {
+            position: target_transform.translation.truncate(),
+            radius: TARGET_SIZE.y / 2.0,
+            source: DamageSource::Player,
+        });
+    }
+}
+
+fn count_down(
+    time: Res<Time>,
+    mut round: ResMut<BonusRound>,
+    mut next_round: ResMut<NextState<Round>>,
+) {
+    round.timer.tick(time.delta());
+    if round.timer.finished() {
+        next_round.set(Round::Waves);
+    }
+}
+
+fn update_bonus_text(
+    round: Res<BonusRound>,
+    locale: Res<Locale>,
+    mut text_query: Query<&mut Text, With<BonusText>>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+    let info = locale.format(
+        "bonus-stage",
+        &[
+            ("seconds", &round.timer.remaining_secs().ceil()),
+            ("popped", &round.popped),
+        ],
+    );
+    if text.sections[0].value != info {
+        text.sections[0].value = info;
+    }
+}
+
+/// What a saved run keeps of the round being played, and of the bonus round's progress
+/// and targets if it's one of those.
+#[derive(Serialize, Deserialize)]
+pub struct SavedBonus {
+    round: Round,
+    /// `None` unless a bonus round is being played.
+    progress: Option<BonusRound>,
+    targets: Vec<(Transform, BonusTarget, Color)>,
+}
+
+impl SavedBonus {
+    pub fn capture(world: &mut World) -> Self {
+        world.run_system_once(save_bonus)
+    }
+
+    /// Swaps the round in play for this one. The round is set straight away rather than
+    /// through a transition, so starting a bonus round doesn't sweep away the field that was
+    /// just put back.
+    pub fn restore(self, world: &mut World) {
+        world.run_system_once_with(self, load_bonus);
+    }
+}
+
+fn save_bonus(
+    round: Res<State<Round>>,
+    progress: Option<Res<BonusRound>>,
+    target_query: Query<(&Transform, &BonusTarget, &Sprite)>,
+) -> SavedBonus {
+    SavedBonus {
+        round: *round.get(),
+        progress: progress.map(|progress| progress.clone()),
+        targets: target_query
+            .iter()
+            .map(|(transform, target, sprite)| (*transform, target.clone(), sprite.color))
+            .collect(),
+    }
+}
+
+fn load_bonus(
+    In(saved): In<SavedBonus>,
+    mut commands: Commands,
+    mut next_round: ResMut<NextState<Round>>,
+    scene_query: Query<Entity, With<BonusScene>>,
+) {
+    for entity in &scene_query {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<BonusRound>();
+    commands.insert_resource(State::new(saved.round));
+    next_round.0 = None;
+
+    let Some(progress) = saved.progress else {
+        return;
+    };
+    commands.insert_resource(progress);
+    spawn_bonus_text(&mut commands);
+    commands.spawn_batch(
+        saved
+            .targets
+            .into_iter()
+            .map(|(transform, target, color)| bonus_target(transform, target, color)),
+    );
+}
@@ -0,0 +1,145 @@
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+    render::camera::ScalingMode,
+};
+
+use crate::{photo_mode::PhotoMode, settings::Settings, PLAY_AREA};
+
+/// Zoom is a magnification of the play area, which fills the window at 1.0. Anything
+/// spawned or despawned off-screen has to allow for the widest view.
+const MIN_ZOOM: f32 = 0.8;
+const MAX_ZOOM: f32 = 1.5;
+/// Zoom change per notch of the mouse wheel.
+const ZOOM_STEP: f32 = 0.05;
+/// Touchpads scroll in pixels; this many count as one notch.
+const PIXELS_PER_NOTCH: f32 = 40.0;
+
+/// How much further than the play area the camera can see when fully zoomed out.
+/// Off-screen spawn and despawn positions add this so nothing visibly pops in or out.
+pub const OFFSCREEN_MARGIN: Vec2 = Vec2::new(
+    PLAY_AREA.x * (1.0 / MIN_ZOOM - 1.0) / 2.0,
+    PLAY_AREA.y * (1.0 / MIN_ZOOM - 1.0) / 2.0,
+);
+
+const LETTERBOX_COLOR: Color = Color::BLACK;
+/// In front of the whole scene, night overlay included.
+const LETTERBOX_DEPTH: f32 = 50.0;
+/// Long enough to cover the window whatever its shape.
+const LETTERBOX_LENGTH: f32 = 100_000.0;
+
+/// The part of the world currently on screen.
+pub fn view_rect(settings: &Settings) -> Rect {
+    Rect::from_center_size(
+        PLAY_AREA / 2.0,
+        PLAY_AREA / settings.camera_zoom.clamp(MIN_ZOOM, MAX_ZOOM),
+    )
+}
+
+/// The bars masking whatever the camera sees beyond the view when the window isn't 16:9.
+#[derive(Component, Clone, Copy)]
+pub enum Letterbox {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_camera).add_systems(
+            Update,
+            (zoom_camera, (apply_camera_zoom, frame_letterbox))
+                .chain()
+                .run_if(not(resource_exists::<PhotoMode>)),
+        );
+    }
+}
+
+/// The projection always fits the play area inside the window; the letterbox covers the
+/// spare room on the longer side.
+fn setup_camera(mut commands: Commands) {
+    let mut camera = Camera2dBundle {
+        transform: Transform::from_xyz(PLAY_AREA.x / 2.0, PLAY_AREA.y / 2.0, 0.0),
+        ..Default::default()
+    };
+    camera.projection.scaling_mode = ScalingMode::AutoMin {
+        min_width: PLAY_AREA.x,
+        min_height: PLAY_AREA.y,
+    };
+    commands.spawn(camera);
+
+    for side in [
+        Letterbox::Left,
+        Letterbox::Right,
+        Letterbox::Top,
+        Letterbox::Bottom,
+    ] {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: LETTERBOX_COLOR,
+                    custom_size: Some(Vec2::splat(LETTERBOX_LENGTH)),
+                    ..default()
+                },
+                ..default()
+            },
+            side,
+        ));
+    }
+}
+
+/// The mouse wheel zooms in and out, centred on the middle of the battlefield.
+fn zoom_camera(mut wheel_events: EventReader<MouseWheel>, mut settings: ResMut<Settings>) {
+    let notches: f32 = wheel_events
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / PIXELS_PER_NOTCH,
+        })
+        .sum();
+    if notches == 0.0 {
+        return;
+    }
+
+    let zoom = (settings.camera_zoom + notches * ZOOM_STEP).clamp(MIN_ZOOM, MAX_ZOOM);
+    if zoom != settings.camera_zoom {
+        settings.camera_zoom = zoom;
+    }
+}
+
+fn apply_camera_zoom(
+    settings: Res<Settings>,
+    mut projection_query: Query<&mut OrthographicProjection, With<Camera2d>>,
+) {
+    let Ok(mut projection) = projection_query.get_single_mut() else {
+        return;
+    };
+
+    let scale = 1.0 / settings.camera_zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+    if projection.scale != scale {
+        projection.scale = scale;
+    }
+}
+
+/// Butts each bar up against its edge of the view.
+fn frame_letterbox(
+    settings: Res<Settings>,
+    mut letterbox_query: Query<(&mut Transform, &Letterbox)>,
+) {
+    let view = view_rect(&settings);
+    let half = LETTERBOX_LENGTH / 2.0;
+    let center = view.center();
+
+    for (mut bar_transform, side) in &mut letterbox_query {
+        let position = match side {
+            Letterbox::Left => Vec2::new(view.min.x - half, center.y),
+            Letterbox::Right => Vec2::new(view.max.x + half, center.y),
+            Letterbox::Top => Vec2::new(center.x, view.max.y + half),
+            Letterbox::Bottom => Vec2::new(center.x, view.min.y - half),
+        };
+        bar_transform.translation = position.extend(LETTERBOX_DEPTH);
+    }
+}
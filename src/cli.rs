@@ -0,0 +1,100 @@
+use bevy::{
+    log::{BoxedSubscriber, Level, LogPlugin},
+    prelude::*,
+    window::WindowResolution,
+};
+use clap::{Parser, ValueEnum};
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{crash, log_level, rng::GameRng, terrain::Terrain, Mission};
+
+/// Launch options, mostly useful for testing and speedrunning:
+/// `battle_jeep --seed 42 --windowed 1280x720 --mode night`.
+#[derive(Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// Generate the battlefield and the fight on it from this seed, so every run with it
+    /// drives over the same ground and meets the same enemies.
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Open a window of this size instead of the default one.
+    #[arg(long, value_name = "WIDTHxHEIGHT", value_parser = parse_window_size)]
+    windowed: Option<Vec2>,
+    /// Mission for the first run. Later runs are picked on the game over screen.
+    #[arg(long, value_enum, default_value_t = Mode::Day)]
+    mode: Mode,
+    /// Most verbose log messages to show at first: error, warn, info, debug or trace.
+    /// `` ` `` changes it in game. `RUST_LOG` takes precedence and can set levels per
+    /// module.
+    #[arg(long, value_name = "LEVEL", default_value_t = Level::INFO)]
+    log_level: Level,
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+enum Mode {
+    Day,
+    Night,
+    Coast,
+    Escort,
+}
+
+impl Cli {
+    pub fn window(&self) -> Window {
+        match self.windowed {
+            Some(size) => Window {
+                resolution: WindowResolution::new(size.x, size.y),
+                ..default()
+            },
+            None => Window::default(),
+        }
+    }
+
+    /// The log plugin's own level is left wide open, and `--log-level` goes to the filter
+    /// that can be changed while the game runs instead.
+    pub fn log(&self) -> LogPlugin {
+        log_level::start_at(self.log_level);
+        LogPlugin {
+            level: Level::TRACE,
+            update_subscriber: Some(update_subscriber),
+            ..default()
+        }
+    }
+
+    pub fn mission(&self) -> Mission {
+        Mission {
+            night: matches!(self.mode, Mode::Night),
+            coastal: matches!(self.mode, Mode::Coast),
+            escort: matches!(self.mode, Mode::Escort),
+        }
+    }
+
+    /// Seeded from `--seed` if there is one, or else from entropy.
+    pub fn rng(&self) -> GameRng {
+        GameRng::new(self.seed)
+    }
+
+    /// Terrain to start with when a seed was given.
+    pub fn terrain(&self) -> Option<Terrain> {
+        let seed = self.seed?;
+        Some(Terrain::generate(&mut StdRng::seed_from_u64(seed)))
+    }
+}
+
+fn update_subscriber(subscriber: BoxedSubscriber) -> BoxedSubscriber {
+    crash::keep_recent_log(log_level::reloadable_level(subscriber))
+}
+
+fn parse_window_size(size: &str) -> Result<Vec2, String> {
+    let (width, height) = size
+        .split_once('x')
+        .ok_or_else(|| format!("expected WIDTHxHEIGHT, got `{size}`"))?;
+    let parse = |value: &str| {
+        value
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|&pixels| pixels > 0)
+            .ok_or_else(|| format!("`{value}` is not a window size in pixels"))
+    };
+    Ok(Vec2::new(parse(width)? as f32, parse(height)? as f32))
+}
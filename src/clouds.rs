@@ -0,0 +1,140 @@
+use bevy::{
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+};
+use rand::prelude::*;
+
+use crate::{
+    accessibility::run_if_parallax, camera::OFFSCREEN_MARGIN, rng::GameRng, GameState, Plane,
+    PLANE_FLIGHT_OFFSET, PLAY_AREA,
+};
+
+const COVER_CLOUD_COUNT: usize = 2;
+const COVER_CLOUD_HALF_SIZE: Vec2 = Vec2::new(90.0, 40.0);
+const COVER_CLOUD_COLOR: Color = Color::rgb(0.95, 0.95, 0.97);
+/// In front of planes (and their health bars) so they disappear behind the cloud.
+const COVER_CLOUD_DEPTH: f32 = 2.0;
+const RECYCLE_MARGIN: f32 = 200.0;
+
+/// An opaque cloud drifting through the plane altitude band.
+#[derive(Component)]
+struct CoverCloud {
+    speed: f32,
+}
+
+/// Marks a plane that is currently hidden inside a cover cloud. It keeps flying and
+/// bombing, it just can't be seen.
+#[derive(Component)]
+pub struct Concealed;
+
+pub struct CloudsPlugin;
+
+impl Plugin for CloudsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_cover_clouds).add_systems(
+            Update,
+            (move_cover_clouds.run_if(run_if_parallax), conceal_planes)
+                .chain()
+                .run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+fn spawn_cover_clouds(mut commands: Commands, mut rng: ResMut<GameRng>) {
+    let rng = &mut rng.0;
+
+    for cloud_index in 0..COVER_CLOUD_COUNT {
+        // Spread the clouds out so they don't start stacked on top of each other.
+        let x = PLAY_AREA.x * (cloud_index as f32 + 0.5) / COVER_CLOUD_COUNT as f32;
+        let y = cloud_altitude(rng);
+        commands
+            .spawn((
+                SpatialBundle::from_transform(Transform::from_xyz(x, y, COVER_CLOUD_DEPTH)),
+                CoverCloud {
+                    speed: rng.gen_range(15.0..35.0),
+                },
+            ))
+            .with_children(|cloud| {
+                cloud.spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color: COVER_CLOUD_COLOR,
+                        custom_size: Some(COVER_CLOUD_HALF_SIZE * 2.0),
+                        ..default()
+                    },
+                    ..default()
+                });
+                // Puffs around the edge soften the silhouette.
+                for _ in 0..5 {
+                    let puff_size = rng.gen_range(40.0..70.0);
+                    cloud.spawn(SpriteBundle {
+                        sprite: Sprite {
+                            color: COVER_CLOUD_COLOR,
+                            custom_size: Some(Vec2::splat(puff_size)),
+                            ..default()
+                        },
+                        transform: Transform::from_xyz(
+                            rng.gen_range(-COVER_CLOUD_HALF_SIZE.x..COVER_CLOUD_HALF_SIZE.x),
+                            rng.gen_range(-COVER_CLOUD_HALF_SIZE.y..COVER_CLOUD_HALF_SIZE.y),
+                            0.0,
+                        ),
+                        ..default()
+                    });
+                }
+            });
+    }
+}
+
+fn cloud_altitude(rng: &mut impl Rng) -> f32 {
+    PLAY_AREA.y - PLANE_FLIGHT_OFFSET + rng.gen_range(-20.0..20.0)
+}
+
+fn move_cover_clouds(
+    time: Res<Time>,
+    mut cloud_query: Query<(&mut Transform, &mut CoverCloud)>,
+    mut rng: ResMut<GameRng>,
+) {
+    let rng = &mut rng.0;
+    let margin = OFFSCREEN_MARGIN.x + RECYCLE_MARGIN;
+
+    for (mut cloud_transform, mut cloud) in &mut cloud_query {
+        cloud_transform.translation.x -= cloud.speed * time.delta_seconds();
+        if cloud_transform.translation.x < -margin {
+            cloud_transform.translation.x = PLAY_AREA.x + margin;
+            cloud_transform.translation.y = cloud_altitude(rng);
+            cloud.speed = rng.gen_range(15.0..35.0);
+        }
+    }
+}
+
+/// Hides planes whose center is inside a cover cloud and reveals them once they fly out.
+fn conceal_planes(
+    mut commands: Commands,
+    cloud_query: Query<&Transform, With<CoverCloud>>,
+    mut plane_query: Query<
+        (Entity, &Transform, &mut Visibility, Has<Concealed>),
+        (With<Plane>, Without<CoverCloud>),
+    >,
+) {
+    let clouds: Vec<Aabb2d> = cloud_query
+        .iter()
+        .map(|cloud_transform| {
+            Aabb2d::new(
+                cloud_transform.translation.truncate(),
+                COVER_CLOUD_HALF_SIZE,
+            )
+        })
+        .collect();
+
+    for (plane_entity, plane_transform, mut plane_visibility, concealed) in &mut plane_query {
+        let plane_point = Aabb2d::new(plane_transform.translation.truncate(), Vec2::ZERO);
+        let inside_cloud = clouds.iter().any(|cloud| cloud.intersects(&plane_point));
+
+        if inside_cloud && !concealed {
+            *plane_visibility = Visibility::Hidden;
+            commands.entity(plane_entity).try_insert(Concealed);
+        } else if !inside_cloud && concealed {
+            *plane_visibility = Visibility::Inherited;
+            commands.entity(plane_entity).remove::<Concealed>();
+        }
+    }
+}
@@ -0,0 +1,772 @@
+use bevy::{
+    ecs::system::RunSystemOnce,
+    math::bounding::{Aabb2d, BoundingCircle, IntersectsVolume},
+    prelude::*,
+};
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ai::{update_ai_state, EnemyAi, SteersItself},
+    bonus::{Hostile, Round},
+    camera::OFFSCREEN_MARGIN,
+    explosion::ExplosionEvent,
+    health::Health,
+    pause::Paused,
+    rng::GameRng,
+    smoke_screen::InSmoke,
+    terrain::Terrain,
+    timers::ShellTimer,
+    trails::ENEMY_MISSILE_TRAIL,
+    tween::{Ease, Tween},
+    Collider, DamageCause, DamageSource, GameSet, GameState, Mission, Player, PlayerDamageEvent,
+    PlayerState, Rocket, GROUND_HEIGHT, PLAY_AREA, ROCKET_HALF_SIZE,
+};
+
+/// Surface of the sea along the bottom of the screen. The lowest dips between the dunes
+/// are under water.
+const WATER_LEVEL: f32 = GROUND_HEIGHT + 14.0;
+const WATER_COLOR: Color = Color::rgba(0.12, 0.38, 0.62, 0.85);
+const WAVE_COLOR: Color = Color::rgba(0.85, 0.95, 1.0, 0.7);
+const WAVE_HEIGHT: f32 = 2.5;
+const WAVE_LENGTH: f32 = 90.0;
+const WAVE_SPEED: f32 = 1.5;
+const WAVE_SEGMENTS: usize = 64;
+/// In front of the jeep and the boats, so hulls sit in the water rather than on it.
+const WATER_DEPTH: f32 = 1.0;
+
+const GUNBOAT_INTERVAL: std::ops::Range<f32> = 8.0..14.0;
+const MAX_GUNBOATS: usize = 2;
+const GUNBOAT_SPEED: f32 = 60.0;
+/// How much of its cruise speed a gunboat keeps while it's shelling the jeep.
+const GUNBOAT_ATTACK_SLOWDOWN: f32 = 0.4;
+const GUNBOAT_HULL_SIZE: Vec2 = Vec2::new(90.0, 20.0);
+const GUNBOAT_CABIN_SIZE: Vec2 = Vec2::new(30.0, 14.0);
+const GUNBOAT_HULL_COLOR: Color = Color::rgb(0.3, 0.32, 0.35);
+const GUNBOAT_CABIN_COLOR: Color = Color::rgb(0.42, 0.44, 0.46);
+const GUNBOAT_DEPTH: f32 = 0.8;
+const GUNBOAT_HEALTH: f32 = 4.0;
+/// Gunboats start and finish this far past the edge of the view.
+const GUNBOAT_MARGIN: f32 = 100.0;
+const SHELL_INTERVAL: f32 = 3.0;
+
+const SHELL_SIZE: Vec2 = Vec2::new(8.0, 5.0);
+const SHELL_COLOR: Color = Color::rgb(0.15, 0.15, 0.15);
+const SHELL_GRAVITY: f32 = 350.0;
+/// Shells take longer to arrive the further they have to go.
+const SHELL_HORIZONTAL_SPEED: f32 = 350.0;
+const SHELL_MIN_FLIGHT: f32 = 1.4;
+const SHELL_MAX_FLIGHT: f32 = 2.6;
+/// Gunners aim for the jeep but land anywhere this close to it.
+const SHELL_SPREAD: f32 = 70.0;
+const SHELL_BLAST_RADIUS: f32 = 60.0;
+const SHELL_BLAST_DAMAGE: f32 = 1.0;
+
+const SUBMARINE_HULL_SIZE: Vec2 = Vec2::new(110.0, 16.0);
+const SUBMARINE_TOWER_SIZE: Vec2 = Vec2::new(18.0, 16.0);
+const SUBMARINE_COLOR: Color = Color::rgb(0.2, 0.24, 0.22);
+/// How far under the water line the submarine cruises between launches.
+const SUBMARINE_DIVE_DEPTH: f32 = 40.0;
+const SUBMARINE_SUBMERGED_TIME: std::ops::Range<f32> = 6.0..10.0;
+/// Time spent coming up or going back down.
+const SUBMARINE_EMERGE_TIME: f32 = 1.5;
+/// Time spent on the surface, with the missile launched as soon as it's up.
+const SUBMARINE_SURFACED_TIME: f32 = 2.0;
+const SUBMARINE_SPEED: f32 = 50.0;
+/// The submarine keeps at least this far in from the edges of the play area.
+const SUBMARINE_MARGIN: f32 = 150.0;
+
+const SUB_MISSILE_SIZE: Vec2 = Vec2::new(6.0, 20.0);
+const SUB_MISSILE_COLOR: Color = Color::rgb(0.85, 0.85, 0.8);
+/// Slow enough on the way up for the jeep to shoot it down.
+const SUB_MISSILE_CLIMB_SPEED: f32 = 70.0;
+/// Height at which the missile turns over and comes down on the jeep.
+const SUB_MISSILE_APEX: f32 = PLAY_AREA.y * 0.8;
+const SUB_MISSILE_DIVE_SPEED: f32 = 220.0;
+const SUB_MISSILE_BLAST_RADIUS: f32 = 80.0;
+const SUB_MISSILE_BLAST_DAMAGE: f32 = 2.0;
+const SUB_MISSILE_INTERCEPT_RADIUS: f32 = 30.0;
+
+/// Water and anything sailing on it, cleared away when play stops.
+#[derive(Component)]
+struct CoastScene;
+
+/// Sinks like any other enemy once its health runs out, to rockets and to the missiles
+/// that lock onto it.
+#[derive(Component, Clone, Serialize, Deserialize)]
+pub struct Gunboat {
+    /// +1 sailing right, -1 sailing left.
+    heading: f32,
+}
+
+#[derive(Component, Clone, Serialize, Deserialize)]
+struct Shell {
+    velocity: Vec2,
+}
+
+/// Anything fired at the jeep from the water, and how hard it goes off when it lands.
+#[derive(Component)]
+struct Warhead {
+    half_size: Vec2,
+    blast_radius: f32,
+    damage: f32,
+}
+
+const SHELL_WARHEAD: Warhead = Warhead {
+    half_size: Vec2::new(SHELL_SIZE.x / 2.0, SHELL_SIZE.y / 2.0),
+    blast_radius: SHELL_BLAST_RADIUS,
+    damage: SHELL_BLAST_DAMAGE,
+};
+const SUB_MISSILE_WARHEAD: Warhead = Warhead {
+    half_size: Vec2::new(SUB_MISSILE_SIZE.x / 2.0, SUB_MISSILE_SIZE.y / 2.0),
+    blast_radius: SUB_MISSILE_BLAST_RADIUS,
+    damage: SUB_MISSILE_BLAST_DAMAGE,
+};
+
+#[derive(Component, Clone, Serialize, Deserialize)]
+struct Submarine {
+    state: SubmarineState,
+    /// Where it will next come up, headed for while submerged.
+    target_x: f32,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+enum SubmarineState {
+    Submerged(Timer),
+    Surfacing(Tween),
+    Surfaced(Timer),
+    Diving(Tween),
+}
+
+impl SubmarineState {
+    fn submerged(rng: &mut impl Rng) -> Self {
+        SubmarineState::Submerged(Timer::from_seconds(
+            rng.gen_range(SUBMARINE_SUBMERGED_TIME),
+            TimerMode::Once,
+        ))
+    }
+}
+
+#[derive(Component, Clone, Serialize, Deserialize)]
+enum SubMissile {
+    Climbing,
+    Diving(Vec2),
+}
+
+#[derive(Resource)]
+struct GunboatSpawner(Timer);
+
+impl FromWorld for GunboatSpawner {
+    fn from_world(world: &mut World) -> Self {
+        Self(random_interval(&mut world.resource_mut::<GameRng>().0))
+    }
+}
+
+fn random_interval(rng: &mut impl Rng) -> Timer {
+    Timer::from_seconds(rng.gen_range(GUNBOAT_INTERVAL), TimerMode::Once)
+}
+
+pub struct CoastPlugin;
+
+impl Plugin for CoastPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GunboatSpawner>()
+            .add_systems(
+                OnEnter(GameState::Playing),
+                spawn_coast_scene.run_if(run_if_coastal),
+            )
+            .add_systems(OnExit(GameState::Playing), despawn_coast_scene)
+            .add_systems(
+                Update,
+                (
+                    (spawn_gunboats, fire_shells)
+                        .in_set(GameSet::Spawning)
+                        .run_if(in_state(Round::Waves)),
+                    steer_submarine
+                        .in_set(GameSet::Movement)
+                        .run_if(in_state(Round::Waves)),
+                    (
+                        sail_gunboats.after(update_ai_state),
+                        fly_shells,
+                        fly_sub_missiles,
+                    )
+                        .in_set(GameSet::Movement),
+                    (intercept_sub_missiles, detonate_warheads)
+                        .chain()
+                        .in_set(GameSet::Collision),
+                )
+                    .run_if(
+                        in_state(GameState::Playing)
+                            .and_then(run_if_coastal)
+                            .and_then(not(resource_exists::<Paused>)),
+                    ),
+            )
+            .add_systems(
+                Update,
+                draw_waves.run_if(in_state(GameState::Playing).and_then(run_if_coastal)),
+            );
+    }
+}
+
+fn run_if_coastal(mission: Res<Mission>) -> bool {
+    mission.coastal
+}
+
+/// The sea fills the bottom of the widest view up to the water line, with a submarine
+/// lurking under it.
+fn spawn_coast_scene(mut commands: Commands, mut rng: ResMut<GameRng>) {
+    let rng = &mut rng.0;
+    spawn_sea(&mut commands);
+    commands.insert_resource(GunboatSpawner(random_interval(rng)));
+
+    let x = random_surfacing_x(rng);
+    spawn_submarine(
+        &mut commands,
+        Transform::from_xyz(x, WATER_LEVEL - SUBMARINE_DIVE_DEPTH, GUNBOAT_DEPTH),
+        Submarine {
+            state: SubmarineState::submerged(rng),
+            target_x: x,
+        },
+    );
+}
+
+fn spawn_sea(commands: &mut Commands) {
+    let bottom = -OFFSCREEN_MARGIN.y;
+    let size = Vec2::new(PLAY_AREA.x + 2.0 * OFFSCREEN_MARGIN.x, WATER_LEVEL - bottom);
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: WATER_COLOR,
+                custom_size: Some(size),
+                ..default()
+            },
+            transform: Transform::from_xyz(PLAY_AREA.x / 2.0, bottom + size.y / 2.0, WATER_DEPTH),
+            ..default()
+        },
+        CoastScene,
+    ));
+}
+
+fn spawn_submarine(commands: &mut Commands, transform: Transform, submarine: Submarine) {
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: SUBMARINE_COLOR,
+                    custom_size: Some(SUBMARINE_HULL_SIZE),
+                    ..default()
+                },
+                transform,
+                ..default()
+            },
+            submarine,
+            CoastScene,
+        ))
+        .with_children(|parent| {
+            parent.spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: SUBMARINE_COLOR,
+                    custom_size: Some(SUBMARINE_TOWER_SIZE),
+                    ..default()
+                },
+                transform: Transform::from_xyz(
+                    0.0,
+                    (SUBMARINE_HULL_SIZE.y + SUBMARINE_TOWER_SIZE.y) / 2.0,
+                    0.0,
+                ),
+                ..default()
+            });
+        });
+}
+
+fn random_surfacing_x(rng: &mut impl Rng) -> f32 {
+    rng.gen_range(SUBMARINE_MARGIN..PLAY_AREA.x - SUBMARINE_MARGIN)
+}
+
+fn despawn_coast_scene(mut commands: Commands, scene_query: Query<Entity, With<CoastScene>>) {
+    for entity in &scene_query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// What a saved run keeps of the coast: the boats and the submarine, and whatever they've
+/// fired that's still in the air.
+#[derive(Serialize, Deserialize)]
+pub struct SavedCoast {
+    gunboats: Vec<(Transform, Gunboat, EnemyAi, Health, ShellTimer)>,
+    submarines: Vec<(Transform, Submarine)>,
+    shells: Vec<(Transform, Shell)>,
+    sub_missiles: Vec<(Transform, SubMissile)>,
+}
+
+impl SavedCoast {
+    pub fn capture(world: &mut World) -> Self {
+        world.run_system_once(save_coast)
+    }
+
+    /// Swaps the coast in play for this one. The sea only goes back in if the loaded run
+    /// is a coastal mission.
+    pub fn restore(self, world: &mut World) {
+        world.run_system_once_with(self, load_coast);
+    }
+}
+
+fn save_coast(
+    gunboat_query: Query<(&Transform, &Gunboat, &EnemyAi, &Health, &ShellTimer)>,
+    submarine_query: Query<(&Transform, &Submarine)>,
+    shell_query: Query<(&Transform, &Shell)>,
+    missile_query: Query<(&Transform, &SubMissile)>,
+) -> SavedCoast {
+    SavedCoast {
+        gunboats: gunboat_query
+            .iter()
+            .map(|(transform, gunboat, ai, health, shell_timer)| {
+                (
+                    *transform,
+                    gunboat.clone(),
+                    ai.clone(),
+                    health.clone(),
+                    shell_timer.clone(),
+                )
+            })
+            .collect(),
+        submarines: submarine_query
+            .iter()
+            .map(|(transform, submarine)| (*transform, submarine.clone()))
+            .collect(),
+        shells: shell_query
+            .iter()
+            .map(|(transform, shell)| (*transform, shell.clone()))
+            .collect(),
+        sub_missiles: missile_query
+            .iter()
+            .map(|(transform, missile)| (*transform, missile.clone()))
+            .collect(),
+    }
+}
+
+fn load_coast(
+    In(saved): In<SavedCoast>,
+    mut commands: Commands,
+    mission: Res<Mission>,
+    scene_query: Query<Entity, With<CoastScene>>,
+) {
+    for entity in &scene_query {
+        commands.entity(entity).despawn_recursive();
+    }
+    if !mission.coastal {
+        return;
+    }
+
+    spawn_sea(&mut commands);
+    for (transform, gunboat, ai, health, shell_timer) in saved.gunboats {
+        spawn_gunboat(&mut commands, transform, gunboat, ai, health, shell_timer);
+    }
+    for (transform, submarine) in saved.submarines {
+        spawn_submarine(&mut commands, transform, submarine);
+    }
+    commands.spawn_batch(
+        saved
+            .shells
+            .into_iter()
+            .map(|(transform, saved_shell)| shell(transform, saved_shell)),
+    );
+    for (transform, missile) in saved.sub_missiles {
+        spawn_sub_missile(&mut commands, transform, missile);
+    }
+}
+
+/// Crests rolling along the water line.
+fn draw_waves(mut gizmos: Gizmos, time: Res<Time>) {
+    let left = -OFFSCREEN_MARGIN.x;
+    let width = PLAY_AREA.x + 2.0 * OFFSCREEN_MARGIN.x;
+    let phase = time.elapsed_seconds() * WAVE_SPEED;
+    gizmos.linestrip_2d(
+        (0..=WAVE_SEGMENTS).map(|segment| {
+            let x = left + width * segment as f32 / WAVE_SEGMENTS as f32;
+            let crest = (x / WAVE_LENGTH * std::f32::consts::TAU + phase).sin() * WAVE_HEIGHT;
+            Vec2::new(x, WATER_LEVEL + crest)
+        }),
+        WAVE_COLOR,
+    );
+}
+
+/// Gunboats come in from either side now and then and sail across.
+fn spawn_gunboats(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut spawner: ResMut<GunboatSpawner>,
+    gunboat_query: Query<(), With<Gunboat>>,
+    mut rng: ResMut<GameRng>,
+) {
+    let rng = &mut rng.0;
+    spawner.0.tick(time.delta());
+    if !spawner.0.finished() {
+        return;
+    }
+    spawner.0 = random_interval(rng);
+    if gunboat_query.iter().len() >= MAX_GUNBOATS {
+        return;
+    }
+
+    let heading = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+    let start_x = if heading > 0.0 {
+        -OFFSCREEN_MARGIN.x - GUNBOAT_MARGIN
+    } else {
+        PLAY_AREA.x + OFFSCREEN_MARGIN.x + GUNBOAT_MARGIN
+    };
+    spawn_gunboat(
+        &mut commands,
+        Transform::from_xyz(start_x, WATER_LEVEL, GUNBOAT_DEPTH),
+        Gunboat { heading },
+        EnemyAi::new(GUNBOAT_SPEED, WATER_LEVEL),
+        Health::new(GUNBOAT_HEALTH),
+        ShellTimer::new(SHELL_INTERVAL),
+    );
+}
+
+fn spawn_gunboat(
+    commands: &mut Commands,
+    transform: Transform,
+    gunboat: Gunboat,
+    ai: EnemyAi,
+    health: Health,
+    shell_timer: ShellTimer,
+) {
+    let heading = gunboat.heading;
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: GUNBOAT_HULL_COLOR,
+                    custom_size: Some(GUNBOAT_HULL_SIZE),
+                    ..default()
+                },
+                transform,
+                ..default()
+            },
+            gunboat,
+            ai,
+            SteersItself,
+            health,
+            Collider {
+                half_size: GUNBOAT_HULL_SIZE / 2.0,
+            },
+            shell_timer,
+            CoastScene,
+        ))
+        .with_children(|parent| {
+            // The cabin sits towards the stern.
+            parent.spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: GUNBOAT_CABIN_COLOR,
+                    custom_size: Some(GUNBOAT_CABIN_SIZE),
+                    ..default()
+                },
+                transform: Transform::from_xyz(
+                    -heading * GUNBOAT_HULL_SIZE.x / 4.0,
+                    (GUNBOAT_HULL_SIZE.y + GUNBOAT_CABIN_SIZE.y) / 2.0,
+                    0.0,
+                ),
+                ..default()
+            });
+        });
+}
+
+/// Gunboats patrol along the shore at their cruise speed and slow down to shell the jeep
+/// once it's in reach. One that's badly damaged makes for open water at full steam. Boats
+/// can't climb or dive, so they sail on through a rocket's line of fire.
+fn sail_gunboats(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut gunboat_query: Query<(Entity, &mut Transform, &Gunboat, &mut EnemyAi)>,
+) {
+    let limit = OFFSCREEN_MARGIN.x + GUNBOAT_MARGIN;
+    for (gunboat_entity, mut transform, gunboat, mut ai) in &mut gunboat_query {
+        let speed = if ai.is_attacking() {
+            ai.speed() * GUNBOAT_ATTACK_SLOWDOWN
+        } else {
+            ai.speed()
+        };
+        ai.velocity = Vec2::X * gunboat.heading * speed;
+        transform.translation.x += ai.velocity.x * time.delta_seconds();
+        let x = transform.translation.x;
+        let gone = if gunboat.heading > 0.0 {
+            x > PLAY_AREA.x + limit
+        } else {
+            x < -limit
+        };
+        if gone {
+            commands.entity(gunboat_entity).despawn_recursive();
+        }
+    }
+}
+
+/// Gunboats in view lob shells in an arc that comes down near the jeep while they're
+/// attacking it. A jeep hidden in smoke can't be found, so they hold fire.
+fn fire_shells(
+    mut commands: Commands,
+    gunboat_query: Query<(&Transform, &ShellTimer, &EnemyAi), With<Gunboat>>,
+    player_query: Query<(&Transform, &PlayerState), (With<Player>, Without<InSmoke>)>,
+    mut rng: ResMut<GameRng>,
+) {
+    let Ok((player_transform, PlayerState::Alive)) = player_query.get_single() else {
+        return;
+    };
+    let rng = &mut rng.0;
+    for (gunboat_transform, shell_timer, ai) in &gunboat_query {
+        if !ai.is_attacking() {
+            continue;
+        }
+        let muzzle = gunboat_transform.translation.truncate()
+            + Vec2::Y * (GUNBOAT_HULL_SIZE.y / 2.0 + GUNBOAT_CABIN_SIZE.y);
+        if !shell_timer.0.just_finished() || !(0.0..=PLAY_AREA.x).contains(&muzzle.x) {
+            continue;
+        }
+
+        let target = player_transform.translation.truncate()
+            + Vec2::X * rng.gen_range(-SHELL_SPREAD..SHELL_SPREAD);
+        let offset = target - muzzle;
+        let flight =
+            (offset.x.abs() / SHELL_HORIZONTAL_SPEED).clamp(SHELL_MIN_FLIGHT, SHELL_MAX_FLIGHT);
+        let velocity = Vec2::new(
+            offset.x / flight,
+            (offset.y + 0.5 * SHELL_GRAVITY * flight * flight) / flight,
+        );
+        commands.spawn(shell(
+            Transform::from_translation(muzzle.extend(GUNBOAT_DEPTH)),
+            Shell { velocity },
+        ));
+    }
+}
+
+fn shell(transform: Transform, shell: Shell) -> impl Bundle {
+    (
+        SpriteBundle {
+            sprite: Sprite {
+                color: SHELL_COLOR,
+                custom_size: Some(SHELL_SIZE),
+                ..default()
+            },
+            transform,
+            ..default()
+        },
+        shell,
+        Hostile,
+        SHELL_WARHEAD,
+        CoastScene,
+    )
+}
+
+fn fly_shells(time: Res<Time>, mut shell_query: Query<(&mut Transform, &mut Shell)>) {
+    let dt = time.delta_seconds();
+    for (mut transform, mut shell) in &mut shell_query {
+        shell.velocity.y -= SHELL_GRAVITY * dt;
+        transform.translation += (shell.velocity * dt).extend(0.0);
+        transform.rotation = Quat::from_rotation_z(shell.velocity.to_angle());
+    }
+}
+
+/// The submarine creeps somewhere new under the water, comes up, launches a missile and
+/// goes back down again.
+fn steer_submarine(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut submarine_query: Query<(&mut Transform, &mut Submarine)>,
+    mut rng: ResMut<GameRng>,
+) {
+    let rng = &mut rng.0;
+    let surfaced_y = WATER_LEVEL;
+    let submerged_y = WATER_LEVEL - SUBMARINE_DIVE_DEPTH;
+    for (mut transform, mut submarine) in &mut submarine_query {
+        let submarine = submarine.as_mut();
+        let next_state = match &mut submarine.state {
+            SubmarineState::Submerged(timer) => {
+                let offset = submarine.target_x - transform.translation.x;
+                let step = SUBMARINE_SPEED * time.delta_seconds();
+                transform.translation.x += offset.clamp(-step, step);
+                timer.tick(time.delta());
+                (timer.finished() && offset.abs() <= step).then(|| {
+                    SubmarineState::Surfacing(Tween::new(
+                        submerged_y,
+                        surfaced_y,
+                        SUBMARINE_EMERGE_TIME,
+                        Ease::QuadOut,
+                    ))
+                })
+            }
+            SubmarineState::Surfacing(tween) => {
+                tween.tick(time.delta());
+                transform.translation.y = tween.value();
+                tween.finished().then(|| {
+                    launch_sub_missile(&mut commands, &transform);
+                    SubmarineState::Surfaced(Timer::from_seconds(
+                        SUBMARINE_SURFACED_TIME,
+                        TimerMode::Once,
+                    ))
+                })
+            }
+            SubmarineState::Surfaced(timer) => {
+                timer.tick(time.delta());
+                timer.finished().then(|| {
+                    SubmarineState::Diving(Tween::new(
+                        surfaced_y,
+                        submerged_y,
+                        SUBMARINE_EMERGE_TIME,
+                        Ease::Linear,
+                    ))
+                })
+            }
+            SubmarineState::Diving(tween) => {
+                tween.tick(time.delta());
+                transform.translation.y = tween.value();
+                tween.finished().then(|| {
+                    submarine.target_x = random_surfacing_x(rng);
+                    SubmarineState::submerged(rng)
+                })
+            }
+        };
+        if let Some(next_state) = next_state {
+            submarine.state = next_state;
+        }
+    }
+}
+
+/// Missiles leave the conning tower pointing straight up.
+fn launch_sub_missile(commands: &mut Commands, submarine_transform: &Transform) {
+    let tower_top = SUBMARINE_HULL_SIZE.y / 2.0 + SUBMARINE_TOWER_SIZE.y;
+    spawn_sub_missile(
+        commands,
+        Transform::from_translation(submarine_transform.translation + Vec3::Y * tower_top),
+        SubMissile::Climbing,
+    );
+}
+
+fn spawn_sub_missile(commands: &mut Commands, transform: Transform, missile: SubMissile) {
+    let armed = matches!(missile, SubMissile::Diving(_));
+    let mut missile_entity = commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: SUB_MISSILE_COLOR,
+                custom_size: Some(SUB_MISSILE_SIZE),
+                ..default()
+            },
+            transform,
+            ..default()
+        },
+        missile,
+        Hostile,
+        ENEMY_MISSILE_TRAIL,
+        CoastScene,
+    ));
+    if armed {
+        missile_entity.insert(SUB_MISSILE_WARHEAD);
+    }
+}
+
+/// Missiles climb slowly to the top of the sky, then turn over and dive at wherever the
+/// jeep is at that moment, or straight down if it's hidden in smoke. They only arm once
+/// they're on the way down, so one launched from behind a dune doesn't go off in the sand.
+fn fly_sub_missiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut missile_query: Query<(Entity, &mut Transform, &mut SubMissile)>,
+    player_query: Query<&Transform, (With<Player>, Without<SubMissile>, Without<InSmoke>)>,
+) {
+    let dt = time.delta_seconds();
+    for (missile_entity, mut transform, mut missile) in &mut missile_query {
+        let position = transform.translation.truncate();
+        if let SubMissile::Climbing = *missile {
+            if position.y < SUB_MISSILE_APEX {
+                transform.translation.y += SUB_MISSILE_CLIMB_SPEED * dt;
+                continue;
+            }
+            let target = player_query
+                .get_single()
+                .map_or(Vec2::new(position.x, GROUND_HEIGHT), |player_transform| {
+                    player_transform.translation.truncate()
+                });
+            let direction = (target - position).normalize_or_zero();
+            *missile = SubMissile::Diving(direction * SUB_MISSILE_DIVE_SPEED);
+            commands.entity(missile_entity).insert(SUB_MISSILE_WARHEAD);
+        }
+        if let SubMissile::Diving(velocity) = *missile {
+            transform.translation += (velocity * dt).extend(0.0);
+            transform.rotation =
+                Quat::from_rotation_z(velocity.to_angle() - std::f32::consts::FRAC_PI_2);
+        }
+    }
+}
+
+/// A rocket anywhere near a submarine's missile brings it down before it can turn over.
+fn intercept_sub_missiles(
+    mut commands: Commands,
+    rocket_query: Query<(Entity, &Transform), With<Rocket>>,
+    missile_query: Query<(Entity, &Transform), With<SubMissile>>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+) {
+    for (missile_entity, missile_transform) in &missile_query {
+        let position = missile_transform.translation.truncate();
+        let hit = rocket_query.iter().find(|(_, rocket_transform)| {
+            BoundingCircle::new(position, SUB_MISSILE_INTERCEPT_RADIUS).intersects(&Aabb2d::new(
+                rocket_transform.translation.truncate(),
+                ROCKET_HALF_SIZE,
+            ))
+        });
+        let Some((rocket_entity, _)) = hit else {
+            continue;
+        };
+
+        commands.entity(rocket_entity).despawn();
+        commands.entity(missile_entity).despawn();
+        explosion_events.send(ExplosionEvent {
+            position,
+            radius: SUB_MISSILE_INTERCEPT_RADIUS,
+            source: DamageSource::Enemy,
+        });
+    }
+}
+
+/// Shells and missiles go off when they hit the jeep, the beach or the water, catching the
+/// jeep in the blast if it's close.
+fn detonate_warheads(
+    mut commands: Commands,
+    terrain: Res<Terrain>,
+    warhead_query: Query<(Entity, &Transform, &Warhead)>,
+    player_query: Query<(&Transform, &Collider, &PlayerState), With<Player>>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+    mut player_damage_events: EventWriter<PlayerDamageEvent>,
+) {
+    let player_box = player_query
+        .get_single()
+        .ok()
+        .filter(|(_, _, state)| matches!(state, PlayerState::Alive))
+        .map(|(transform, collider, _)| {
+            Aabb2d::new(transform.translation.truncate(), collider.half_size)
+        });
+
+    for (warhead_entity, warhead_transform, warhead) in &warhead_query {
+        let position = warhead_transform.translation.truncate();
+        let surface = terrain.height_at(position.x).max(WATER_LEVEL);
+        let hit_jeep = player_box.is_some_and(|player_box| {
+            Aabb2d::new(position, warhead.half_size).intersects(&player_box)
+        });
+        if position.y > surface && !hit_jeep {
+            continue;
+        }
+
+        commands.entity(warhead_entity).despawn();
+        explosion_events.send(ExplosionEvent {
+            position,
+            radius: warhead.blast_radius,
+            source: DamageSource::Enemy,
+        });
+        let blast = BoundingCircle::new(position, warhead.blast_radius);
+        if player_box.is_some_and(|player_box| blast.intersects(&player_box)) {
+            player_damage_events.send(PlayerDamageEvent {
+                amount: warhead.damage,
+                cause: DamageCause::Shelling,
+            });
+        }
+    }
+}
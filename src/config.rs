@@ -0,0 +1,134 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+/// Relative to the asset folder.
+const CONFIG_PATH: &str = "game_config.ron";
+
+/// Gameplay tuning values, read from `assets/game_config.ron`. The file is watched, so
+/// saving it while the game runs applies the new values straight away. Anything left
+/// out of the file keeps its built-in default.
+#[derive(Asset, Resource, TypePath, Clone, Deserialize)]
+#[serde(default)]
+pub struct GameConfig {
+    /// Top speed the jeep drives at.
+    pub player_speed: f32,
+    /// How quickly the jeep picks up speed while driving, in units a second squared.
+    pub player_acceleration: f32,
+    /// How quickly the jeep rolls to a stop once nothing is held.
+    pub player_deceleration: f32,
+    /// How quickly the jeep sheds speed when driven the other way. Lower than
+    /// `player_acceleration` lets it skid a little before turning round.
+    pub player_skid_deceleration: f32,
+    pub player_jump_speed: f32,
+    pub player_gravity: f32,
+    /// Seconds between losing a life and the jeep coming back.
+    pub respawn_delay: f32,
+    pub rocket_speed: f32,
+    /// Most of the jeep's rockets that can be in the air at once, so shots have to be
+    /// timed. 0 takes the limit off.
+    pub max_live_rockets: u32,
+    pub plane_speed: f32,
+    /// Each plane flies up to this share faster or slower than `plane_speed`.
+    pub plane_speed_spread: f32,
+    /// Seconds between bombs from a plane that is attacking.
+    pub plane_bomb_interval: f32,
+    /// Each plane bombs up to this share more or less often than `plane_bomb_interval`.
+    pub plane_bomb_interval_spread: f32,
+    /// Fewest and most bombs a plane carries, picked anew for every plane.
+    pub plane_bombs: (u32, u32),
+    pub bomb_falling_speed: f32,
+    /// Seconds between buildings or craters scrolling in.
+    pub obstacle_interval: f32,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            player_speed: 500.0,
+            player_acceleration: 2500.0,
+            player_deceleration: 3000.0,
+            player_skid_deceleration: 1800.0,
+            player_jump_speed: 700.0,
+            player_gravity: 1800.0,
+            respawn_delay: 1.0,
+            rocket_speed: 600.0,
+            max_live_rockets: 3,
+            plane_speed: 100.0,
+            plane_speed_spread: 0.2,
+            plane_bomb_interval: 2.0,
+            plane_bomb_interval_spread: 0.25,
+            plane_bombs: (3, 5),
+            bomb_falling_speed: 100.0,
+            obstacle_interval: 4.0,
+        }
+    }
+}
+
+#[derive(Default)]
+struct GameConfigLoader;
+
+impl AssetLoader for GameConfigLoader {
+    type Asset = GameConfig;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<GameConfig, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            Ok(ron::de::from_bytes(&bytes)?)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
+
+/// Keeps the config asset loaded so it is reloaded when the file changes.
+#[derive(Resource)]
+struct GameConfigHandle(Handle<GameConfig>);
+
+pub struct ConfigPlugin;
+
+impl Plugin for ConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<GameConfig>()
+            .init_asset_loader::<GameConfigLoader>()
+            .init_resource::<GameConfig>()
+            .add_systems(Startup, load_config)
+            .add_systems(PreUpdate, apply_config);
+    }
+}
+
+fn load_config(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(GameConfigHandle(asset_server.load(CONFIG_PATH)));
+}
+
+/// Copies the config into its resource whenever the file is loaded or edited. Until then
+/// the defaults are used.
+fn apply_config(
+    mut asset_events: EventReader<AssetEvent<GameConfig>>,
+    handle: Res<GameConfigHandle>,
+    configs: Res<Assets<GameConfig>>,
+    mut config: ResMut<GameConfig>,
+) {
+    for event in asset_events.read() {
+        if !event.is_loaded_with_dependencies(&handle.0) && !event.is_modified(&handle.0) {
+            continue;
+        }
+        if let Some(loaded) = configs.get(&handle.0) {
+            *config = loaded.clone();
+            info!("applied game config");
+        }
+    }
+}
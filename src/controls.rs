@@ -0,0 +1,300 @@
+use bevy::{
+    input::InputSystem,
+    prelude::*,
+    utils::{HashMap, HashSet},
+    window::PrimaryWindow,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    crash::CrashNotice, localization::Locale, pause::Paused, photo_mode::PhotoMode,
+    quit::QuitPrompt, settings::Settings, toast::ToastEvent, Player, PLAYER_HALF_SIZE,
+};
+
+/// Rockets go off this often while autofire is doing the shooting.
+const AUTOFIRE_INTERVAL: f32 = 0.25;
+/// A press stays buffered this long, so pressing fire a few frames before a weapon is
+/// ready still gets the shot off the moment it is.
+const INPUT_BUFFER: f32 = 0.15;
+/// The jeep stops chasing the cursor once it is this close, so it doesn't jitter under it.
+const CURSOR_DEAD_ZONE: f32 = PLAYER_HALF_SIZE.x / 2.0;
+
+/// Something the player can do in a run, whatever it's bound to.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    Jump,
+    Fire,
+    Missile,
+    CycleTarget,
+    Repair,
+    Smoke,
+    Build,
+    Flak,
+    Laser,
+}
+
+/// Which set of bindings drives the jeep.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum InputPreset {
+    /// Arrow keys to drive, Space to fire, X for missiles, Down for flak, right Shift for
+    /// the laser, Tab to lock on, R to repair, Z for smoke and C to build a turret.
+    #[default]
+    Standard,
+    /// Everything under the left hand: WASD to drive, Space to fire, Shift for missiles, S
+    /// for flak, G for the laser, R to repair, F for smoke and C to build a turret.
+    LeftHand,
+    /// The jeep drives towards the cursor and fires on its own. Left click jumps, right
+    /// click launches a missile, middle click locks on, the back button repairs and the
+    /// forward button pops smoke. There are no buttons left over for flak, the laser or
+    /// building turrets.
+    MouseOnly,
+}
+
+impl InputPreset {
+    fn next(self) -> Self {
+        match self {
+            Self::Standard => Self::LeftHand,
+            Self::LeftHand => Self::MouseOnly,
+            Self::MouseOnly => Self::Standard,
+        }
+    }
+
+    fn keys(self, action: Action) -> &'static [KeyCode] {
+        match (self, action) {
+            (Self::Standard, Action::MoveLeft) => &[KeyCode::ArrowLeft],
+            (Self::Standard, Action::MoveRight) => &[KeyCode::ArrowRight],
+            (Self::Standard, Action::Jump) => &[KeyCode::ArrowUp],
+            (Self::Standard, Action::Fire) => &[KeyCode::Space],
+            (Self::Standard, Action::Missile) => &[KeyCode::KeyX],
+            (Self::Standard, Action::Smoke) => &[KeyCode::KeyZ],
+            (Self::Standard, Action::Flak) => &[KeyCode::ArrowDown],
+            (Self::Standard, Action::Laser) => &[KeyCode::ShiftRight],
+            (Self::Standard | Self::LeftHand, Action::CycleTarget) => &[KeyCode::Tab],
+            (Self::Standard | Self::LeftHand, Action::Repair) => &[KeyCode::KeyR],
+            (Self::Standard | Self::LeftHand, Action::Build) => &[KeyCode::KeyC],
+            (Self::LeftHand, Action::MoveLeft) => &[KeyCode::KeyA],
+            (Self::LeftHand, Action::MoveRight) => &[KeyCode::KeyD],
+            (Self::LeftHand, Action::Jump) => &[KeyCode::KeyW],
+            (Self::LeftHand, Action::Fire) => &[KeyCode::Space],
+            (Self::LeftHand, Action::Missile) => &[KeyCode::ShiftLeft],
+            (Self::LeftHand, Action::Smoke) => &[KeyCode::KeyF],
+            (Self::LeftHand, Action::Flak) => &[KeyCode::KeyS],
+            (Self::LeftHand, Action::Laser) => &[KeyCode::KeyG],
+            (Self::MouseOnly, _) => &[],
+        }
+    }
+
+    fn mouse_button(self, action: Action) -> Option<MouseButton> {
+        match (self, action) {
+            (Self::MouseOnly, Action::Jump) => Some(MouseButton::Left),
+            (Self::MouseOnly, Action::Missile) => Some(MouseButton::Right),
+            (Self::MouseOnly, Action::CycleTarget) => Some(MouseButton::Middle),
+            (Self::MouseOnly, Action::Repair) => Some(MouseButton::Back),
+            (Self::MouseOnly, Action::Smoke) => Some(MouseButton::Forward),
+            _ => None,
+        }
+    }
+}
+
+const ACTIONS: [Action; 11] = [
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::Jump,
+    Action::Fire,
+    Action::Missile,
+    Action::CycleTarget,
+    Action::Repair,
+    Action::Smoke,
+    Action::Build,
+    Action::Flak,
+    Action::Laser,
+];
+
+/// The actions held and started this frame, read by gameplay instead of the raw input.
+#[derive(Resource)]
+pub struct Controls {
+    pressed: HashSet<Action>,
+    just_pressed: HashSet<Action>,
+    /// Presses not acted on yet, with how many seconds each has left in the buffer.
+    buffered: HashMap<Action, f32>,
+    autofire: Timer,
+}
+
+impl Default for Controls {
+    fn default() -> Self {
+        Self {
+            pressed: HashSet::new(),
+            just_pressed: HashSet::new(),
+            buffered: HashMap::new(),
+            autofire: Timer::from_seconds(AUTOFIRE_INTERVAL, TimerMode::Repeating),
+        }
+    }
+}
+
+impl Controls {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    /// Whether the action was started recently and hasn't been used up with
+    /// [`Self::consume`] yet. Actions that can't always happen straight away, like firing
+    /// a weapon that's cooling down, should go by this rather than `just_pressed`.
+    pub fn buffered(&self, action: Action) -> bool {
+        self.buffered.contains_key(&action)
+    }
+
+    /// Takes a buffered press out once it has done what it asked for.
+    pub fn consume(&mut self, action: Action) {
+        self.buffered.remove(&action);
+    }
+
+    fn press(&mut self, action: Action, just_pressed: bool) {
+        self.pressed.insert(action);
+        if just_pressed {
+            self.just_pressed.insert(action);
+            self.buffered.insert(action, INPUT_BUFFER);
+        }
+    }
+}
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Controls>()
+            .add_systems(
+                PreUpdate,
+                (read_buttons, autofire, follow_cursor)
+                    .chain()
+                    .after(InputSystem),
+            )
+            .add_systems(
+                Update,
+                (
+                    cycle_input_preset,
+                    toggle_autofire.run_if(
+                        resource_exists::<Paused>
+                            .and_then(not(resource_exists::<PhotoMode>))
+                            .and_then(not(resource_exists::<QuitPrompt>))
+                            .and_then(not(resource_exists::<CrashNotice>)),
+                    ),
+                ),
+            );
+    }
+}
+
+/// `F1` steps through the input presets.
+fn cycle_input_preset(key_input: Res<ButtonInput<KeyCode>>, mut settings: ResMut<Settings>) {
+    if key_input.just_pressed(KeyCode::F1) {
+        settings.input_preset = settings.input_preset.next();
+    }
+}
+
+/// `A` on the pause menu turns autofire on and off.
+fn toggle_autofire(
+    key_input: Res<ButtonInput<KeyCode>>,
+    locale: Res<Locale>,
+    mut settings: ResMut<Settings>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    if !key_input.just_pressed(KeyCode::KeyA) {
+        return;
+    }
+    settings.autofire = !settings.autofire;
+    let key = if settings.autofire {
+        "autofire-on"
+    } else {
+        "autofire-off"
+    };
+    toast_events.send(ToastEvent(locale.get(key).to_string()));
+}
+
+fn read_buttons(
+    time: Res<Time<Real>>,
+    key_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    settings: Res<Settings>,
+    mut controls: ResMut<Controls>,
+) {
+    controls.pressed.clear();
+    controls.just_pressed.clear();
+    // Real time, so a press doesn't sit in the buffer across the pause menu.
+    let dt = time.delta_seconds();
+    controls.buffered.retain(|_, time_left| {
+        *time_left -= dt;
+        *time_left > 0.0
+    });
+
+    let preset = settings.input_preset;
+    for action in ACTIONS {
+        let keys = preset.keys(action);
+        let button = preset.mouse_button(action);
+        if key_input.any_pressed(keys.iter().copied())
+            || button.is_some_and(|button| mouse_input.pressed(button))
+        {
+            let just_pressed = key_input.any_just_pressed(keys.iter().copied())
+                || button.is_some_and(|button| mouse_input.just_pressed(button));
+            controls.press(action, just_pressed);
+        }
+    }
+}
+
+/// With autofire on, holding fire keeps the rockets coming and holding the missile button
+/// launches one each time the launcher is ready. The mouse-only preset fires rockets on
+/// its own the whole time.
+fn autofire(time: Res<Time>, settings: Res<Settings>, mut controls: ResMut<Controls>) {
+    let mouse_only = settings.input_preset == InputPreset::MouseOnly;
+    if mouse_only || (settings.autofire && controls.pressed(Action::Fire)) {
+        controls.autofire.tick(time.delta());
+        if controls.autofire.just_finished() {
+            controls.press(Action::Fire, true);
+        }
+    } else {
+        // The next hold fires on the press itself and counts from there.
+        controls.autofire.reset();
+    }
+
+    if settings.autofire && controls.pressed(Action::Missile) {
+        controls.buffered.insert(Action::Missile, INPUT_BUFFER);
+    }
+}
+
+/// In the mouse-only preset the jeep heads for the cursor.
+fn follow_cursor(
+    settings: Res<Settings>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    player_query: Query<&Transform, With<Player>>,
+    mut controls: ResMut<Controls>,
+) {
+    if settings.input_preset != InputPreset::MouseOnly {
+        return;
+    }
+
+    let (Ok(window), Ok((camera, camera_transform)), Ok(player_transform)) = (
+        window_query.get_single(),
+        camera_query.get_single(),
+        player_query.get_single(),
+    ) else {
+        return;
+    };
+    let Some(cursor) = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor))
+    else {
+        return;
+    };
+
+    let offset = cursor.x - player_transform.translation.x;
+    if offset < -CURSOR_DEAD_ZONE {
+        controls.press(Action::MoveLeft, false);
+    } else if offset > CURSOR_DEAD_ZONE {
+        controls.press(Action::MoveRight, false);
+    }
+}
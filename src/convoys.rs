@@ -0,0 +1,205 @@
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    camera::OFFSCREEN_MARGIN, health::Health, pause::Paused, rng::GameRng, terrain::Terrain,
+    Collider, GameSet, GameState, PLAY_AREA,
+};
+
+/// Seconds between civilian trucks.
+const TRUCK_INTERVAL: std::ops::Range<f32> = 20.0..35.0;
+const TRUCK_SPEED: f32 = 90.0;
+const TRUCK_HALF_SIZE: Vec2 = Vec2::new(26.0, 11.0);
+const TRUCK_CAB_SIZE: Vec2 = Vec2::new(16.0, 14.0);
+const TRUCK_COLOR: Color = Color::rgb(0.85, 0.85, 0.8);
+const TRUCK_CAB_COLOR: Color = Color::rgb(0.3, 0.55, 0.8);
+const TRUCK_HEALTH: f32 = 2.0;
+/// Behind the jeep, so it drives past rather than over the truck.
+const TRUCK_DEPTH: f32 = -0.1;
+/// Trucks start and finish this far past the edge of the view.
+const TRUCK_MARGIN: f32 = 60.0;
+
+/// Friendly to the jeep: anything of the player's that hurts one costs points instead of
+/// scoring them.
+#[derive(Component)]
+pub struct Civilian;
+
+#[derive(Component, Clone, Serialize, Deserialize)]
+struct Truck {
+    /// +1 driving right, -1 driving left.
+    heading: f32,
+}
+
+#[derive(Resource)]
+struct TruckSpawner(Timer);
+
+impl FromWorld for TruckSpawner {
+    fn from_world(world: &mut World) -> Self {
+        Self(random_interval(&mut world.resource_mut::<GameRng>().0))
+    }
+}
+
+fn random_interval(rng: &mut impl Rng) -> Timer {
+    Timer::from_seconds(rng.gen_range(TRUCK_INTERVAL), TimerMode::Once)
+}
+
+pub struct ConvoysPlugin;
+
+impl Plugin for ConvoysPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TruckSpawner>()
+            .add_systems(
+                Update,
+                (
+                    spawn_trucks.in_set(GameSet::Spawning),
+                    drive_trucks.in_set(GameSet::Movement),
+                )
+                    .run_if(in_state(GameState::Playing).and_then(not(resource_exists::<Paused>))),
+            )
+            .add_systems(OnExit(GameState::GameOver), despawn_trucks);
+    }
+}
+
+/// Now and then a civilian truck drives across from one side or the other.
+fn spawn_trucks(
+    mut commands: Commands,
+    time: Res<Time>,
+    terrain: Res<Terrain>,
+    mut spawner: ResMut<TruckSpawner>,
+    mut rng: ResMut<GameRng>,
+) {
+    let rng = &mut rng.0;
+    spawner.0.tick(time.delta());
+    if !spawner.0.finished() {
+        return;
+    }
+    spawner.0 = random_interval(rng);
+
+    let heading = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+    let x = if heading > 0.0 {
+        -OFFSCREEN_MARGIN.x - TRUCK_MARGIN
+    } else {
+        PLAY_AREA.x + OFFSCREEN_MARGIN.x + TRUCK_MARGIN
+    };
+    let truck_entity = spawn_truck(&mut commands, &terrain, x, heading);
+    commands.entity(truck_entity).insert(Truck { heading });
+}
+
+/// A civilian truck on the ground at `x`, facing along `heading`.
+pub fn spawn_truck(commands: &mut Commands, terrain: &Terrain, x: f32, heading: f32) -> Entity {
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: TRUCK_COLOR,
+                    custom_size: Some(TRUCK_HALF_SIZE * 2.0),
+                    ..default()
+                },
+                transform: Transform::from_xyz(x, truck_height(terrain, x), TRUCK_DEPTH),
+                ..default()
+            },
+            Civilian,
+            Health::new(TRUCK_HEALTH),
+            Collider {
+                half_size: TRUCK_HALF_SIZE,
+            },
+        ))
+        .with_children(|parent| {
+            // The cab sits up front, on top of the chassis.
+            parent.spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: TRUCK_CAB_COLOR,
+                    custom_size: Some(TRUCK_CAB_SIZE),
+                    ..default()
+                },
+                transform: Transform::from_xyz(
+                    heading * (TRUCK_HALF_SIZE.x - TRUCK_CAB_SIZE.x / 2.0),
+                    TRUCK_HALF_SIZE.y + TRUCK_CAB_SIZE.y / 2.0,
+                    0.1,
+                ),
+                ..default()
+            });
+        })
+        .id()
+}
+
+/// Where a truck's centre sits with its wheels on the ground at `x`.
+pub fn truck_height(terrain: &Terrain, x: f32) -> f32 {
+    terrain.height_at(x) + TRUCK_HALF_SIZE.y
+}
+
+/// Trucks keep to the road surface, following the dunes as they go.
+fn drive_trucks(
+    mut commands: Commands,
+    time: Res<Time>,
+    terrain: Res<Terrain>,
+    mut truck_query: Query<(Entity, &mut Transform, &Truck)>,
+) {
+    let limit = OFFSCREEN_MARGIN.x + TRUCK_MARGIN;
+    for (truck_entity, mut transform, truck) in &mut truck_query {
+        transform.translation.x += truck.heading * TRUCK_SPEED * time.delta_seconds();
+        let x = transform.translation.x;
+        transform.translation.y = truck_height(&terrain, x);
+        let gone = if truck.heading > 0.0 {
+            x > PLAY_AREA.x + limit
+        } else {
+            x < -limit
+        };
+        if gone {
+            commands.entity(truck_entity).despawn_recursive();
+        }
+    }
+}
+
+fn despawn_trucks(mut commands: Commands, truck_query: Query<Entity, With<Truck>>) {
+    for truck_entity in &truck_query {
+        commands.entity(truck_entity).despawn_recursive();
+    }
+}
+
+/// What a saved run keeps of the civilian trucks on the road.
+#[derive(Serialize, Deserialize)]
+pub struct SavedTrucks(Vec<(Transform, Truck, Health)>);
+
+impl SavedTrucks {
+    pub fn capture(world: &mut World) -> Self {
+        world.run_system_once(save_trucks)
+    }
+
+    /// Swaps the trucks in play for these.
+    pub fn restore(self, world: &mut World) {
+        world.run_system_once_with(self, load_trucks);
+    }
+}
+
+fn save_trucks(truck_query: Query<(&Transform, &Truck, &Health)>) -> SavedTrucks {
+    SavedTrucks(
+        truck_query
+            .iter()
+            .map(|(transform, truck, health)| (*transform, truck.clone(), health.clone()))
+            .collect(),
+    )
+}
+
+fn load_trucks(
+    In(saved): In<SavedTrucks>,
+    mut commands: Commands,
+    terrain: Res<Terrain>,
+    truck_query: Query<Entity, With<Truck>>,
+) {
+    for truck_entity in &truck_query {
+        commands.entity(truck_entity).despawn_recursive();
+    }
+    for (transform, truck, health) in saved.0 {
+        let truck_entity = spawn_truck(
+            &mut commands,
+            &terrain,
+            transform.translation.x,
+            truck.heading,
+        );
+        commands
+            .entity(truck_entity)
+            .insert((transform, truck, health));
+    }
+}
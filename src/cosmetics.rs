@@ -0,0 +1,504 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    armor::ArmorLabel,
+    grading::Grade,
+    localization::{Locale, Localized},
+    profile::{ActiveProfile, Profile, ProfileNameEntry},
+    quit::QuitPrompt,
+    rank::{rank_index, RANKS},
+    stats_screen::StatsScreen,
+    toast::ToastEvent,
+    GameState, Player,
+};
+
+/// What it takes to earn a cosmetic, or the laser.
+pub enum Unlock {
+    Always,
+    /// Best score in any mode.
+    HighScore(u32),
+    /// Planes and infantry destroyed across every run on the profile.
+    LifetimeKills(u32),
+    /// Reaching this entry in `RANKS`.
+    Rank(usize),
+    /// Waves graded this well on average, over at least this many of them.
+    AverageGrade(Grade, u32),
+}
+
+impl Unlock {
+    pub fn is_met(&self, profile: &Profile) -> bool {
+        match *self {
+            Unlock::Always => true,
+            Unlock::HighScore(score) => profile.high_scores.values().any(|&best| best >= score),
+            Unlock::LifetimeKills(kills) => profile.stats.kills >= kills,
+            Unlock::Rank(rank) => rank_index(profile.xp) >= rank,
+            Unlock::AverageGrade(grade, waves) => {
+                profile.stats.graded_waves >= waves
+                    && profile
+                        .stats
+                        .average_grade()
+                        .is_some_and(|average| average.points() >= grade.points())
+            }
+        }
+    }
+
+    pub fn describe(&self, locale: &Locale) -> String {
+        match self {
+            Unlock::Always => String::new(),
+            Unlock::HighScore(score) => locale.format("unlock-high-score", &[("score", score)]),
+            Unlock::LifetimeKills(kills) => locale.format("unlock-kills", &[("kills", kills)]),
+            Unlock::Rank(rank) => {
+                locale.format("unlock-rank", &[("rank", &locale.get(RANKS[*rank].title))])
+            }
+            Unlock::AverageGrade(grade, waves) => locale.format(
+                "unlock-grade",
+                &[("grade", &grade.letter()), ("waves", waves)],
+            ),
+        }
+    }
+}
+
+pub struct Skin {
+    pub name: &'static str,
+    pub texture: &'static str,
+    pub tint: Color,
+    pub unlock: Unlock,
+}
+
+pub const JEEP_SKINS: &[Skin] = &[
+    Skin {
+        name: "Standard",
+        texture: "../assets/jeep.png",
+        tint: Color::WHITE,
+        unlock: Unlock::Always,
+    },
+    Skin {
+        name: "Desert",
+        texture: "../assets/jeep.png",
+        tint: Color::rgb(1.0, 0.85, 0.6),
+        unlock: Unlock::HighScore(1000),
+    },
+    Skin {
+        name: "Arctic",
+        texture: "../assets/jeep.png",
+        tint: Color::rgb(0.8, 0.9, 1.0),
+        unlock: Unlock::LifetimeKills(50),
+    },
+    Skin {
+        name: "Midnight",
+        texture: "../assets/jeep.png",
+        tint: Color::rgb(0.45, 0.45, 0.6),
+        unlock: Unlock::HighScore(3000),
+    },
+    Skin {
+        name: "Veteran",
+        texture: "../assets/jeep.png",
+        tint: Color::rgb(0.65, 0.7, 0.45),
+        unlock: Unlock::Rank(3),
+    },
+    Skin {
+        name: "Ace",
+        texture: "../assets/jeep.png",
+        tint: Color::rgb(1.0, 0.95, 0.75),
+        unlock: Unlock::AverageGrade(Grade::A, 20),
+    },
+];
+
+pub const PLANE_LIVERIES: &[Skin] = &[
+    Skin {
+        name: "Standard",
+        texture: "../assets/plane.png",
+        tint: Color::WHITE,
+        unlock: Unlock::Always,
+    },
+    Skin {
+        name: "Crimson",
+        texture: "../assets/plane.png",
+        tint: Color::rgb(1.0, 0.5, 0.5),
+        unlock: Unlock::LifetimeKills(25),
+    },
+    Skin {
+        name: "Ghost Grey",
+        texture: "../assets/plane.png",
+        tint: Color::rgb(0.7, 0.7, 0.75),
+        unlock: Unlock::HighScore(2000),
+    },
+    Skin {
+        name: "Top Brass",
+        texture: "../assets/plane.png",
+        tint: Color::rgb(1.0, 0.85, 0.4),
+        unlock: Unlock::Rank(5),
+    },
+];
+
+/// Which skin and livery are selected, by name so saves survive the lists changing.
+#[derive(Resource, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Cosmetics {
+    pub jeep_skin: String,
+    pub plane_livery: String,
+}
+
+impl Default for Cosmetics {
+    fn default() -> Self {
+        Self {
+            jeep_skin: JEEP_SKINS[0].name.to_string(),
+            plane_livery: PLANE_LIVERIES[0].name.to_string(),
+        }
+    }
+}
+
+impl Cosmetics {
+    pub fn jeep(&self) -> &'static Skin {
+        find_skin(JEEP_SKINS, &self.jeep_skin)
+    }
+
+    pub fn plane(&self) -> &'static Skin {
+        find_skin(PLANE_LIVERIES, &self.plane_livery)
+    }
+}
+
+fn find_skin(skins: &'static [Skin], name: &str) -> &'static Skin {
+    skins
+        .iter()
+        .find(|skin| skin.name == name)
+        .unwrap_or(&skins[0])
+}
+
+/// Key under which an unlocked cosmetic is recorded in the profile.
+fn unlock_key(kind: &str, skin: &Skin) -> String {
+    format!("{kind}:{}", skin.name)
+}
+
+fn is_unlocked(profile: &Profile, kind: &str, skin: &Skin) -> bool {
+    matches!(skin.unlock, Unlock::Always) || profile.unlocks.contains(&unlock_key(kind, skin))
+}
+
+/// Open while the player browses skins on the game over screen; the indices are what's
+/// being previewed, which may still be locked.
+#[derive(Resource, Default)]
+pub struct Garage {
+    jeep: usize,
+    plane: usize,
+}
+
+#[derive(Component)]
+struct GarageScreen;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum GarageSlot {
+    Jeep,
+    Plane,
+}
+
+#[derive(Component)]
+struct GaragePreview(GarageSlot);
+
+#[derive(Component)]
+struct GarageLabel(GarageSlot);
+
+pub struct CosmeticsPlugin;
+
+impl Plugin for CosmeticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Cosmetics>()
+            .add_systems(Update, (grant_unlocks, apply_jeep_skin))
+            .add_systems(
+                Update,
+                (
+                    open_garage.run_if(
+                        not(resource_exists::<Garage>)
+                            .and_then(not(resource_exists::<StatsScreen>))
+                            .and_then(not(resource_exists::<ProfileNameEntry>))
+                            .and_then(not(resource_exists::<QuitPrompt>)),
+                    ),
+                    // Closing the garage removes it between the two systems.
+                    (browse_garage, update_garage)
+                        .chain()
+                        .distributive_run_if(resource_exists::<Garage>),
+                )
+                    .run_if(in_state(GameState::GameOver)),
+            )
+            .add_systems(OnExit(GameState::GameOver), close_garage);
+    }
+}
+
+/// Records newly earned cosmetics in the profile so they stay unlocked.
+fn grant_unlocks(
+    mut profile: ResMut<ActiveProfile>,
+    locale: Res<Locale>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    if !profile.is_changed() {
+        return;
+    }
+    let earned: Vec<(String, &Skin)> = [("jeep", JEEP_SKINS), ("plane", PLANE_LIVERIES)]
+        .into_iter()
+        .flat_map(|(kind, skins)| {
+            skins
+                .iter()
+                .filter(|skin| {
+                    skin.unlock.is_met(&profile.0) && !is_unlocked(&profile.0, kind, skin)
+                })
+                .map(move |skin| (unlock_key(kind, skin), skin))
+        })
+        .collect();
+    if earned.is_empty() {
+        return;
+    }
+    for (key, skin) in earned {
+        profile.0.unlocks.push(key);
+        toast_events.send(ToastEvent(
+            locale.format("toast-unlocked", &[("name", &skin.name)]),
+        ));
+    }
+    profile.0.save();
+}
+
+/// The jeep is spawned once, so a new skin is painted onto it in place.
+fn apply_jeep_skin(
+    asset_server: Res<AssetServer>,
+    cosmetics: Res<Cosmetics>,
+    mut player_query: Query<(&mut Handle<Image>, &mut Sprite), With<Player>>,
+) {
+    if !cosmetics.is_changed() {
+        return;
+    }
+    let Ok((mut texture, mut sprite)) = player_query.get_single_mut() else {
+        return;
+    };
+    let skin = cosmetics.jeep();
+    *texture = asset_server.load(skin.texture);
+    sprite.color = skin.tint;
+}
+
+/// `G` opens the garage.
+fn open_garage(
+    mut commands: Commands,
+    key_input: Res<ButtonInput<KeyCode>>,
+    asset_server: Res<AssetServer>,
+    cosmetics: Res<Cosmetics>,
+) {
+    if !key_input.just_pressed(KeyCode::KeyG) {
+        return;
+    }
+
+    let position = |skins: &[Skin], name: &str| {
+        skins
+            .iter()
+            .position(|skin| skin.name == name)
+            .unwrap_or_default()
+    };
+    commands.insert_resource(Garage {
+        jeep: position(JEEP_SKINS, &cosmetics.jeep_skin),
+        plane: position(PLANE_LIVERIES, &cosmetics.plane_livery),
+    });
+
+    let label_style = TextStyle {
+        font_size: 20.0,
+        color: Color::WHITE,
+        ..default()
+    };
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(16.0),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.85).into(),
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            GarageScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 48.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                Localized("garage"),
+            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(64.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    for (slot, skin) in [
+                        (GarageSlot::Jeep, cosmetics.jeep()),
+                        (GarageSlot::Plane, cosmetics.plane()),
+                    ] {
+                        row.spawn(NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                align_items: AlignItems::Center,
+                                row_gap: Val::Px(8.0),
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .with_children(|column| {
+                            column.spawn((
+                                ImageBundle {
+                                    style: Style {
+                                        width: Val::Px(128.0),
+                                        height: Val::Px(128.0),
+                                        ..default()
+                                    },
+                                    image: UiImage::new(asset_server.load(skin.texture)),
+                                    background_color: skin.tint.into(),
+                                    ..default()
+                                },
+                                GaragePreview(slot),
+                            ));
+                            column.spawn((
+                                TextBundle::from_section("", label_style.clone()),
+                                GarageLabel(slot),
+                            ));
+                        });
+                    }
+                });
+            parent.spawn((
+                TextBundle::from_section("", label_style.clone())
+                    .with_text_justify(JustifyText::Center),
+                ArmorLabel,
+            ));
+            parent.spawn((
+                TextBundle::from_section("", label_style.clone()),
+                Localized("garage-controls"),
+            ));
+        });
+}
+
+/// Arrow keys flip through skins. Unlocked ones are selected straight away.
+fn browse_garage(
+    mut commands: Commands,
+    key_input: Res<ButtonInput<KeyCode>>,
+    profile: Res<ActiveProfile>,
+    mut garage: ResMut<Garage>,
+    mut cosmetics: ResMut<Cosmetics>,
+    screen_query: Query<Entity, With<GarageScreen>>,
+) {
+    if key_input.just_pressed(KeyCode::KeyG) && !garage.is_added() {
+        commands.remove_resource::<Garage>();
+        for screen_entity in &screen_query {
+            commands.entity(screen_entity).despawn_recursive();
+        }
+        return;
+    }
+
+    let step = |index: usize, len: usize, back: KeyCode, forward: KeyCode| {
+        if key_input.just_pressed(back) {
+            (index + len - 1) % len
+        } else if key_input.just_pressed(forward) {
+            (index + 1) % len
+        } else {
+            index
+        }
+    };
+    let jeep = step(
+        garage.jeep,
+        JEEP_SKINS.len(),
+        KeyCode::ArrowLeft,
+        KeyCode::ArrowRight,
+    );
+    let plane = step(
+        garage.plane,
+        PLANE_LIVERIES.len(),
+        KeyCode::ArrowDown,
+        KeyCode::ArrowUp,
+    );
+
+    if jeep != garage.jeep {
+        garage.jeep = jeep;
+        if let Some(skin) = JEEP_SKINS.get(jeep) {
+            if is_unlocked(&profile.0, "jeep", skin) {
+                cosmetics.jeep_skin = skin.name.to_string();
+            }
+        }
+    }
+    if plane != garage.plane {
+        garage.plane = plane;
+        if let Some(skin) = PLANE_LIVERIES.get(plane) {
+            if is_unlocked(&profile.0, "plane", skin) {
+                cosmetics.plane_livery = skin.name.to_string();
+            }
+        }
+    }
+}
+
+fn update_garage(
+    asset_server: Res<AssetServer>,
+    profile: Res<ActiveProfile>,
+    garage: Res<Garage>,
+    cosmetics: Res<Cosmetics>,
+    locale: Res<Locale>,
+    mut preview_query: Query<(&GaragePreview, &mut UiImage, &mut BackgroundColor)>,
+    mut label_query: Query<(&GarageLabel, &mut Text)>,
+) {
+    let previewed = |slot: GarageSlot| match slot {
+        GarageSlot::Jeep => JEEP_SKINS.get(garage.jeep).map(|skin| ("jeep", skin)),
+        GarageSlot::Plane => PLANE_LIVERIES.get(garage.plane).map(|skin| ("plane", skin)),
+    };
+
+    for (GaragePreview(slot), mut image, mut background) in &mut preview_query {
+        let Some((_, skin)) = previewed(*slot) else {
+            continue;
+        };
+        let texture = asset_server.load(skin.texture);
+        if image.texture != texture {
+            image.texture = texture;
+        }
+        if background.0 != skin.tint {
+            background.0 = skin.tint;
+        }
+    }
+
+    for (GarageLabel(slot), mut text) in &mut label_query {
+        let Some((kind, skin)) = previewed(*slot) else {
+            continue;
+        };
+        let selected = match slot {
+            GarageSlot::Jeep => cosmetics.jeep_skin == skin.name,
+            GarageSlot::Plane => cosmetics.plane_livery == skin.name,
+        };
+        let label = if !is_unlocked(&profile.0, kind, skin) {
+            locale.format(
+                "garage-locked",
+                &[
+                    ("name", &skin.name),
+                    ("requirement", &skin.unlock.describe(&locale)),
+                ],
+            )
+        } else if selected {
+            locale.format("garage-selected", &[("name", &skin.name)])
+        } else {
+            skin.name.to_string()
+        };
+        if text.sections[0].value != label {
+            text.sections[0].value = label;
+        }
+    }
+}
+
+fn close_garage(mut commands: Commands, screen_query: Query<Entity, With<GarageScreen>>) {
+    commands.remove_resource::<Garage>();
+    for screen_entity in &screen_query {
+        commands.entity(screen_entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,340 @@
+use std::{
+    backtrace::Backtrace,
+    collections::VecDeque,
+    fmt::Write as _,
+    fs, io,
+    panic::{self, PanicHookInfo},
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{Mutex, MutexGuard, TryLockError},
+    thread,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::{
+    log::{
+        tracing_subscriber::{layer::Context, prelude::*, Layer},
+        BoxedSubscriber,
+    },
+    prelude::*,
+    utils::tracing::{
+        field::{Field, Visit},
+        Event as LogEvent, Subscriber,
+    },
+};
+
+use crate::{
+    director::Director, ground_units::GroundUnit, localization::Localized, pause::Paused,
+    stats::RunStats, Bomb, GameState, Plane, Rocket,
+};
+
+/// Crash reports are saved in here as `crash-<unix time>.txt`.
+const CRASH_DIR: &str = "crashes";
+/// Holds the path of a report the player hasn't been told about yet.
+const UNSEEN_REPORT_FILE: &str = "crashes/unseen.txt";
+/// How many of the latest log lines go into a report.
+const RECENT_LOG_LINES: usize = 200;
+
+/// The latest log lines, oldest first, kept around for the next crash report.
+static RECENT_LOG: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+/// What the game looked like at the end of the last frame.
+static LAST_FRAME: Mutex<FrameSummary> = Mutex::new(FrameSummary {
+    state: "starting up",
+    paused: false,
+    wave: 0,
+    score: 0,
+    entities: 0,
+    planes: 0,
+    bombs: 0,
+    rockets: 0,
+    ground_units: 0,
+});
+
+struct FrameSummary {
+    state: &'static str,
+    paused: bool,
+    wave: u32,
+    score: u32,
+    entities: usize,
+    planes: usize,
+    bombs: usize,
+    rockets: usize,
+    ground_units: usize,
+}
+
+/// Present while the player is being told about the crash last time they played. The game
+/// stays paused until they've dealt with it.
+#[derive(Resource)]
+pub struct CrashNotice {
+    report: PathBuf,
+}
+
+#[derive(Component)]
+struct CrashDialog;
+
+pub struct CrashPlugin;
+
+impl Plugin for CrashPlugin {
+    fn build(&self, app: &mut App) {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            match write_report(info) {
+                Ok(report) => eprintln!("crash report saved to {}", report.display()),
+                Err(error) => eprintln!("couldn't save a crash report: {error}"),
+            }
+            default_hook(info);
+        }));
+
+        app.add_systems(Startup, show_last_crash)
+            .add_systems(
+                Update,
+                answer_crash_notice.run_if(resource_exists::<CrashNotice>),
+            )
+            .add_systems(Last, remember_frame);
+    }
+}
+
+/// Tees the game's log into [`RECENT_LOG`]. Meant for [`LogPlugin::update_subscriber`].
+pub fn keep_recent_log(subscriber: BoxedSubscriber) -> BoxedSubscriber {
+    Box::new(subscriber.with(RecentLog))
+}
+
+struct RecentLog;
+
+impl<S: Subscriber> Layer<S> for RecentLog {
+    fn on_event(&self, event: &LogEvent, _context: Context<S>) {
+        let metadata = event.metadata();
+        let mut line = format!("{} {}:", metadata.level(), metadata.target());
+        event.record(&mut LogLine(&mut line));
+        if let Some(mut recent_log) = lock(&RECENT_LOG) {
+            if recent_log.len() == RECENT_LOG_LINES {
+                recent_log.pop_front();
+            }
+            recent_log.push_back(line);
+        }
+    }
+}
+
+/// Writes out an event's message and fields the way the console log does.
+struct LogLine<'a>(&'a mut String);
+
+impl Visit for LogLine<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        // Writing to a `String` can't fail.
+        let _ = if field.name() == "message" {
+            write!(self.0, " {value:?}")
+        } else {
+            write!(self.0, " {}={value:?}", field.name())
+        };
+    }
+}
+
+/// Never blocks, since a panic can strike while the lock is held. A lock poisoned by an
+/// earlier panic still holds good data.
+fn lock<T>(mutex: &Mutex<T>) -> Option<MutexGuard<'_, T>> {
+    match mutex.try_lock() {
+        Ok(guard) => Some(guard),
+        Err(TryLockError::Poisoned(poisoned)) => Some(poisoned.into_inner()),
+        Err(TryLockError::WouldBlock) => None,
+    }
+}
+
+/// Keeps [`LAST_FRAME`] up to date, so a report can tell where the game was when it went
+/// down.
+fn remember_frame(
+    state: Res<State<GameState>>,
+    paused: Option<Res<Paused>>,
+    director: Res<Director>,
+    stats: Res<RunStats>,
+    entity_query: Query<()>,
+    plane_query: Query<(), With<Plane>>,
+    other_query: Query<(Has<Bomb>, Has<Rocket>, Has<GroundUnit>)>,
+) {
+    let Some(mut frame) = lock(&LAST_FRAME) else {
+        return;
+    };
+    let mut bombs = 0;
+    let mut rockets = 0;
+    let mut ground_units = 0;
+    for (bomb, rocket, ground_unit) in &other_query {
+        bombs += usize::from(bomb);
+        rockets += usize::from(rocket);
+        ground_units += usize::from(ground_unit);
+    }
+    *frame = FrameSummary {
+        state: match state.get() {
+            GameState::Playing => "playing",
+            GameState::GameOver => "game over",
+        },
+        paused: paused.is_some(),
+        wave: director.wave(),
+        score: stats.score,
+        entities: entity_query.iter().len(),
+        planes: plane_query.iter().len(),
+        bombs,
+        rockets,
+        ground_units,
+    };
+}
+
+fn write_report(info: &PanicHookInfo) -> io::Result<PathBuf> {
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs());
+    let report = PathBuf::from(CRASH_DIR).join(format!("crash-{time}.txt"));
+
+    let mut text = format!(
+        "Battle Jeep {} crashed at unix time {time}.\n\n{info}\n\n",
+        env!("CARGO_PKG_VERSION"),
+    );
+    // Writing to a `String` can't fail.
+    if let Some(frame) = lock(&LAST_FRAME) {
+        let _ = write!(
+            text,
+            "State: {}{}\nWave: {}\nScore: {}\n\nEntities: {}\n  planes: {}\n  bombs: {}\n  \
+             rockets: {}\n  ground units: {}\n\n",
+            frame.state,
+            if frame.paused { " (paused)" } else { "" },
+            frame.wave,
+            frame.score,
+            frame.entities,
+            frame.planes,
+            frame.bombs,
+            frame.rockets,
+            frame.ground_units,
+        );
+    }
+    let _ = write!(text, "Backtrace:\n{}\n\n", Backtrace::force_capture());
+    text.push_str("Recent log:\n");
+    if let Some(recent_log) = lock(&RECENT_LOG) {
+        for line in recent_log.iter() {
+            text.push_str(line);
+            text.push('\n');
+        }
+    }
+
+    fs::create_dir_all(CRASH_DIR)?;
+    fs::write(&report, text)?;
+    fs::write(UNSEEN_REPORT_FILE, report.to_string_lossy().as_bytes())?;
+    Ok(report)
+}
+
+/// Owns up to the last crash, if the player hasn't heard about it yet, and offers to
+/// open the report.
+fn show_last_crash(mut commands: Commands, mut time: ResMut<Time<Virtual>>) {
+    let Ok(report) = fs::read_to_string(UNSEEN_REPORT_FILE) else {
+        return;
+    };
+    // Only ever bring it up once, even if the game goes down again before it's answered.
+    if let Err(error) = fs::remove_file(UNSEEN_REPORT_FILE) {
+        warn!("couldn't clear {UNSEEN_REPORT_FILE}: {error}");
+    }
+    let report = PathBuf::from(report.trim());
+    info!("the game crashed last time, see {}", report.display());
+
+    commands.insert_resource(Paused);
+    time.pause();
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(12.0),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.8).into(),
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            CrashDialog,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 48.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                Localized("crash-title"),
+            ));
+            parent.spawn(TextBundle::from_section(
+                report.display().to_string(),
+                TextStyle {
+                    font_size: 24.0,
+                    color: Color::rgb(0.7, 0.7, 0.7),
+                    ..default()
+                },
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 24.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                Localized("crash-controls"),
+            ));
+        });
+    commands.insert_resource(CrashNotice { report });
+}
+
+/// `O` opens the report; either that or Enter gets on with the game.
+fn answer_crash_notice(
+    mut commands: Commands,
+    key_input: Res<ButtonInput<KeyCode>>,
+    notice: Res<CrashNotice>,
+    mut time: ResMut<Time<Virtual>>,
+    dialog_query: Query<Entity, With<CrashDialog>>,
+) {
+    if key_input.just_pressed(KeyCode::KeyO) {
+        open_report(&notice.report);
+    } else if !key_input.just_pressed(KeyCode::Enter) {
+        return;
+    }
+
+    commands.remove_resource::<CrashNotice>();
+    commands.remove_resource::<Paused>();
+    time.unpause();
+    for dialog_entity in &dialog_query {
+        commands.entity(dialog_entity).despawn_recursive();
+    }
+}
+
+/// Hands the report to whatever the system opens text files with.
+fn open_report(report: &Path) {
+    let report = report.to_path_buf();
+    // Wait on the opener off the main thread, so it's cleaned up once it exits.
+    thread::spawn(move || {
+        if let Err(error) = file_opener().arg(&report).status() {
+            warn!("couldn't open {}: {error}", report.display());
+        }
+    });
+}
+
+#[cfg(target_os = "windows")]
+fn file_opener() -> Command {
+    let mut command = Command::new("cmd");
+    // `start` takes the first quoted argument as a window title, hence the empty one.
+    command.args(["/C", "start", ""]);
+    command
+}
+
+#[cfg(target_os = "macos")]
+fn file_opener() -> Command {
+    Command::new("open")
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn file_opener() -> Command {
+    Command::new("xdg-open")
+}
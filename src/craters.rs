@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use crate::{director::WaveEndEvent, rng::GameRng, terrain::Terrain, BombImpactEvent, GameState};
+
+const CRATER_HALF_WIDTH: std::ops::Range<f32> = 22.0..32.0;
+const CRATER_DEPTH: std::ops::Range<f32> = 7.0..11.0;
+/// Oldest craters are filled in first once there are more than this.
+const MAX_CRATERS: usize = 24;
+/// Ground sunk at least this far counts as being in a crater.
+const CRATER_DRAG_DEPTH: f32 = 2.0;
+/// Share of its top speed the jeep can manage while churning through a crater.
+const CRATER_SPEED_FACTOR: f32 = 0.55;
+
+pub struct CratersPlugin;
+
+impl Plugin for CratersPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (dig_craters, fill_craters_after_wave).run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(OnExit(GameState::GameOver), fill_craters);
+    }
+}
+
+/// How much of its top speed the jeep keeps on the ground at `x`.
+pub fn crater_speed_factor(terrain: &Terrain, x: f32) -> f32 {
+    if terrain.crater_depth_at(x) >= CRATER_DRAG_DEPTH {
+        CRATER_SPEED_FACTOR
+    } else {
+        1.0
+    }
+}
+
+/// Every bomb that reaches the ground blows a hollow out of it.
+fn dig_craters(
+    mut impact_events: EventReader<BombImpactEvent>,
+    mut terrain: ResMut<Terrain>,
+    mut rng: ResMut<GameRng>,
+) {
+    let rng = &mut rng.0;
+    for impact in impact_events.read() {
+        terrain.dig_crater(
+            impact.position.x,
+            rng.gen_range(CRATER_HALF_WIDTH),
+            rng.gen_range(CRATER_DEPTH),
+            MAX_CRATERS,
+        );
+    }
+}
+
+/// Craters last the wave they were made in, and the ground is smooth again for the next.
+fn fill_craters_after_wave(
+    mut wave_end_events: EventReader<WaveEndEvent>,
+    mut terrain: ResMut<Terrain>,
+) {
+    if wave_end_events.read().count() > 0 {
+        terrain.fill_craters();
+    }
+}
+
+fn fill_craters(mut terrain: ResMut<Terrain>) {
+    terrain.fill_craters();
+}
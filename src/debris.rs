@@ -0,0 +1,78 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::prelude::*;
+
+const DEBRIS_PIECES: usize = 6;
+const DEBRIS_LIFETIME: f32 = 2.5;
+const DEBRIS_SIZE: Vec2 = Vec2::new(8.0, 4.0);
+const DEBRIS_COLOR: Color = Color::rgb(0.35, 0.35, 0.35);
+
+/// Scatter wreckage from a destroyed plane at `position`.
+#[derive(Event)]
+pub struct DebrisEvent {
+    pub position: Vec2,
+}
+
+#[derive(Component)]
+struct Debris {
+    lifetime: Timer,
+}
+
+pub struct DebrisPlugin;
+
+impl Plugin for DebrisPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0))
+            .add_event::<DebrisEvent>()
+            .add_systems(Update, (spawn_debris, fade_debris));
+    }
+}
+
+fn spawn_debris(mut commands: Commands, mut debris_events: EventReader<DebrisEvent>) {
+    let mut rng = thread_rng();
+    for debris in debris_events.read() {
+        for _ in 0..DEBRIS_PIECES {
+            // Kick each piece up and outwards; gravity and the ground take it from there.
+            let linvel = Vec2::new(rng.gen_range(-150.0..150.0), rng.gen_range(50.0..250.0));
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: DEBRIS_COLOR,
+                        custom_size: Some(DEBRIS_SIZE),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(debris.position.extend(0.5))
+                        .with_rotation(Quat::from_rotation_z(rng.gen_range(0.0..TAU))),
+                    ..default()
+                },
+                RigidBody::Dynamic,
+                Collider::cuboid(DEBRIS_SIZE.x / 2.0, DEBRIS_SIZE.y / 2.0),
+                Restitution::coefficient(0.5),
+                Velocity {
+                    linvel,
+                    angvel: rng.gen_range(-10.0..10.0),
+                },
+                Debris {
+                    lifetime: Timer::from_seconds(DEBRIS_LIFETIME, TimerMode::Once),
+                },
+            ));
+        }
+    }
+}
+
+fn fade_debris(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut debris_query: Query<(Entity, &mut Debris, &mut Sprite)>,
+) {
+    for (debris_entity, mut debris, mut sprite) in &mut debris_query {
+        debris.lifetime.tick(time.delta());
+        if debris.lifetime.finished() {
+            commands.entity(debris_entity).despawn();
+        } else {
+            sprite.color.set_a(1.0 - debris.lifetime.fraction());
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use bevy::{input::common_conditions::input_toggle_active, prelude::*};
+use bevy_inspector_egui::quick::WorldInspectorPlugin;
+
+use crate::{
+    ai::{AiState, EnemyAi},
+    ground_units::{GroundUnit, Obstacle},
+    health::Health,
+    timers::BombTimer,
+    Bomb, Plane, Player, PlayerState, Rocket,
+};
+
+/// `F12` shows the world inspector. Only built with the `dev-tools` feature.
+pub struct DevToolsPlugin;
+
+impl Plugin for DevToolsPlugin {
+    fn build(&self, app: &mut App) {
+        // Gameplay components have to be registered to be editable in the inspector.
+        app.register_type::<Player>()
+            .register_type::<PlayerState>()
+            .register_type::<Rocket>()
+            .register_type::<Plane>()
+            .register_type::<BombTimer>()
+            .register_type::<Bomb>()
+            .register_type::<Health>()
+            .register_type::<EnemyAi>()
+            .register_type::<AiState>()
+            .register_type::<GroundUnit>()
+            .register_type::<Obstacle>()
+            .add_plugins(
+                WorldInspectorPlugin::default().run_if(input_toggle_active(false, KeyCode::F12)),
+            );
+    }
+}
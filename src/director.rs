@@ -0,0 +1,340 @@
+use bevy::prelude::*;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bomber::Bomber, bonus::Round, ground_units::GroundUnit, health::Health,
+    repairs::BuildingDamageEvent, rng::GameRng, settings::Settings, stats::RunStats, GameSet,
+    GameState, Plane, Player, PlayerDamageEvent,
+};
+
+/// Threat points earned per second while building up, for a jeep at full health.
+const BUDGET_RATE: f32 = 1.2;
+/// Unspent budget is capped so a long quiet spell can't bank a flood of enemies.
+const MAX_BUDGET: f32 = 8.0;
+/// Total cost of enemies allowed on the field at once.
+const MAX_LIVE_THREAT: f32 = 10.0;
+/// Minimum time between two spawns.
+const SPAWN_INTERVAL: f32 = 0.8;
+/// Intensity gained per point of damage the jeep takes.
+const DAMAGE_INTENSITY: f32 = 35.0;
+/// Intensity gained per second for each point of threat on the field.
+const PRESSURE_INTENSITY: f32 = 1.5;
+const INTENSITY_DECAY: f32 = 4.0;
+/// Crossing this intensity starts the peak of a wave.
+const PEAK_INTENSITY: f32 = 60.0;
+/// Build-up gives way to a peak after this long even if the player is coping.
+const MAX_BUILD_UP: f32 = 25.0;
+const PEAK_DURATION: f32 = 5.0;
+const RELAX_DURATION: f32 = 6.0;
+/// With adaptive difficulty on, each recent death cuts the budget rate by this much...
+const EASE_PER_DEATH: f32 = 0.12;
+const MAX_EASE: f32 = 0.35;
+/// ...and going this long without a scratch raises it by up to `MAX_TIGHTEN`.
+const FLAWLESS_STREAK: f32 = 40.0;
+const MAX_TIGHTEN: f32 = 0.25;
+
+/// Something the director can decide to send at the player.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EnemyKind {
+    Plane,
+    Infantry,
+    /// A slow, heavy plane with a gunner in the tail.
+    Bomber,
+    /// A plane that lays mines for the jeep to drive into.
+    MineLayer,
+    /// A plane that scrambles the jeep's warnings while it's up.
+    Jammer,
+}
+
+impl EnemyKind {
+    const ALL: [EnemyKind; 5] = [
+        EnemyKind::Plane,
+        EnemyKind::Infantry,
+        EnemyKind::Bomber,
+        EnemyKind::MineLayer,
+        EnemyKind::Jammer,
+    ];
+
+    /// Budget spent to spawn one, and the pressure it puts on the player while alive.
+    pub fn threat(self) -> f32 {
+        match self {
+            EnemyKind::Plane => 2.0,
+            EnemyKind::Infantry => 1.0,
+            EnemyKind::Bomber => 4.0,
+            EnemyKind::MineLayer => 3.0,
+            EnemyKind::Jammer => 3.0,
+        }
+    }
+
+    /// How likely the director is to pick it next, relative to the others.
+    fn weight(self) -> u32 {
+        match self {
+            EnemyKind::Plane | EnemyKind::Infantry => 3,
+            EnemyKind::Bomber | EnemyKind::MineLayer | EnemyKind::Jammer => 1,
+        }
+    }
+
+    /// Whether it comes in from the side through the plane lanes.
+    pub fn is_aircraft(self) -> bool {
+        matches!(
+            self,
+            EnemyKind::Plane | EnemyKind::Bomber | EnemyKind::MineLayer | EnemyKind::Jammer
+        )
+    }
+}
+
+/// Sent by the director when it wants an enemy on the field; the module that owns the
+/// enemy type does the actual spawning.
+#[derive(Event)]
+pub struct SpawnEvent(pub EnemyKind);
+
+/// Sent when a wave has played out and the breather before the next one begins.
+#[derive(Event)]
+pub struct WaveEndEvent {
+    /// Whether the wave was got through without the jeep or any building taking damage.
+    pub perfect: bool,
+}
+
+/// Sent with the wave's number when a wave begins, including the first one of a run.
+#[derive(Event)]
+pub struct WaveStartEvent(pub u32);
+
+#[derive(Clone, Serialize, Deserialize)]
+enum Pacing {
+    /// Budget accumulates and is spent while intensity climbs.
+    BuildUp(Timer),
+    /// The wave crests: spending continues but nothing else changes.
+    Peak(Timer),
+    /// Nothing new spawns so the player gets a breather.
+    Relax(Timer),
+}
+
+/// Decides what to spawn and when. It earns a threat budget over time, faster when the
+/// player is doing well, and spends it on enemies. Damage to the jeep and enemies on the
+/// field raise its intensity; once that peaks it backs off for a while, giving the run a
+/// rhythm of tension and release instead of a flat spawn rate.
+#[derive(Resource, Clone, Serialize, Deserialize)]
+pub struct Director {
+    budget: f32,
+    intensity: f32,
+    pacing: Pacing,
+    spawn_cooldown: Timer,
+    /// Threat of the enemies on the field, as of this frame.
+    live_threat: f32,
+    /// Saved up for before anything else, so cheap enemies don't crowd out the rest.
+    next: EnemyKind,
+    #[serde(default)]
+    waves_cleared: u32,
+    /// Nothing has been damaged yet this wave. Runs saved before this was tracked load
+    /// with it unset, so the wave they were on can't count as perfect.
+    #[serde(default)]
+    unscathed: bool,
+}
+
+impl Default for Director {
+    fn default() -> Self {
+        Self {
+            budget: 0.0,
+            intensity: 0.0,
+            pacing: Pacing::BuildUp(Timer::from_seconds(MAX_BUILD_UP, TimerMode::Once)),
+            spawn_cooldown: Timer::from_seconds(SPAWN_INTERVAL, TimerMode::Once),
+            live_threat: 0.0,
+            next: EnemyKind::Plane,
+            waves_cleared: 0,
+            unscathed: true,
+        }
+    }
+}
+
+impl Director {
+    /// Waves played out so far this run.
+    pub fn waves_cleared(&self) -> u32 {
+        self.waves_cleared
+    }
+
+    /// Number of the wave being played, or coming up after the breather, counting from 1.
+    pub fn wave(&self) -> u32 {
+        self.waves_cleared + 1
+    }
+
+    /// Whether the director is holding off for the breather between waves.
+    pub fn is_between_waves(&self) -> bool {
+        matches!(self.pacing, Pacing::Relax(_))
+    }
+}
+
+pub struct DirectorPlugin;
+
+impl Plugin for DirectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Director>()
+            .add_event::<SpawnEvent>()
+            .add_event::<WaveEndEvent>()
+            .add_event::<WaveStartEvent>()
+            .add_systems(
+                Update,
+                (
+                    update_intensity,
+                    track_unscathed,
+                    update_pacing,
+                    spend_budget,
+                )
+                    .chain()
+                    // So enemies it asks for are spawned the same frame.
+                    .before(GameSet::Spawning)
+                    .run_if(in_state(GameState::Playing).and_then(in_state(Round::Waves))),
+            )
+            .add_systems(OnEnter(GameState::Playing), announce_wave)
+            .add_systems(OnExit(GameState::GameOver), reset_director);
+    }
+}
+
+/// Budget rate multiplier for adaptive difficulty: below 1 while the player keeps dying,
+/// above 1 while they go untouched.
+fn rubber_band(stats: &RunStats) -> f32 {
+    let ease = (stats.recent_deaths * EASE_PER_DEATH).min(MAX_EASE);
+    let tighten = (stats.time_since_hit / FLAWLESS_STREAK).min(1.0) * MAX_TIGHTEN;
+    1.0 - ease + tighten
+}
+
+/// Threat currently on the field.
+fn live_threat(planes: usize, bombers: usize, infantry: usize) -> f32 {
+    planes as f32 * EnemyKind::Plane.threat()
+        + bombers as f32 * EnemyKind::Bomber.threat()
+        + infantry as f32 * EnemyKind::Infantry.threat()
+}
+
+fn update_intensity(
+    time: Res<Time>,
+    mut director: ResMut<Director>,
+    mut player_damage_events: EventReader<PlayerDamageEvent>,
+    plane_query: Query<Has<Bomber>, With<Plane>>,
+    infantry_query: Query<(), With<GroundUnit>>,
+) {
+    let damage: f32 = player_damage_events.read().map(|event| event.amount).sum();
+    let bombers = plane_query.iter().filter(|&is_bomber| is_bomber).count();
+    let pressure = live_threat(
+        plane_query.iter().len() - bombers,
+        bombers,
+        infantry_query.iter().len(),
+    );
+    let dt = time.delta_seconds();
+    director.live_threat = pressure;
+    director.intensity =
+        (director.intensity + damage * DAMAGE_INTENSITY + pressure * PRESSURE_INTENSITY * dt
+            - INTENSITY_DECAY * dt)
+            .max(0.0);
+}
+
+/// Any damage to the jeep or a building spoils the wave. Damage during the breather
+/// counts against the wave coming up.
+fn track_unscathed(
+    mut director: ResMut<Director>,
+    mut player_damage_events: EventReader<PlayerDamageEvent>,
+    mut building_damage_events: EventReader<BuildingDamageEvent>,
+) {
+    let player_hurt = player_damage_events.read().any(|event| event.amount > 0.0);
+    let building_hurt = building_damage_events.read().count() > 0;
+    if player_hurt || building_hurt {
+        director.unscathed = false;
+    }
+}
+
+/// A run, fresh or loaded, starts by announcing the wave it is on.
+fn announce_wave(director: Res<Director>, mut wave_start_events: EventWriter<WaveStartEvent>) {
+    wave_start_events.send(WaveStartEvent(director.wave()));
+}
+
+fn update_pacing(
+    time: Res<Time>,
+    mut director: ResMut<Director>,
+    mut wave_end_events: EventWriter<WaveEndEvent>,
+    mut wave_start_events: EventWriter<WaveStartEvent>,
+) {
+    let intensity = director.intensity;
+    let next = match &mut director.pacing {
+        Pacing::BuildUp(timer) => {
+            timer.tick(time.delta());
+            (intensity >= PEAK_INTENSITY || timer.finished())
+                .then(|| Pacing::Peak(Timer::from_seconds(PEAK_DURATION, TimerMode::Once)))
+        }
+        Pacing::Peak(timer) => {
+            timer.tick(time.delta());
+            timer
+                .finished()
+                .then(|| Pacing::Relax(Timer::from_seconds(RELAX_DURATION, TimerMode::Once)))
+        }
+        Pacing::Relax(timer) => {
+            timer.tick(time.delta());
+            timer
+                .finished()
+                .then(|| Pacing::BuildUp(Timer::from_seconds(MAX_BUILD_UP, TimerMode::Once)))
+        }
+    };
+    if let Some(next) = next {
+        if matches!(next, Pacing::Relax(_)) {
+            // Whatever was left over goes unspent; the next wave starts from scratch.
+            director.budget = 0.0;
+            director.waves_cleared += 1;
+            wave_end_events.send(WaveEndEvent {
+                perfect: director.unscathed,
+            });
+        }
+        if matches!(next, Pacing::BuildUp(_)) {
+            director.unscathed = true;
+            wave_start_events.send(WaveStartEvent(director.wave()));
+        }
+        director.pacing = next;
+    }
+}
+
+fn spend_budget(
+    time: Res<Time>,
+    mut director: ResMut<Director>,
+    settings: Res<Settings>,
+    stats: Res<RunStats>,
+    player_query: Query<&Health, With<Player>>,
+    mut spawn_events: EventWriter<SpawnEvent>,
+    mut rng: ResMut<GameRng>,
+) {
+    director.spawn_cooldown.tick(time.delta());
+    if matches!(director.pacing, Pacing::Relax(_)) {
+        return;
+    }
+
+    // A jeep at full health earns the director its whole rate; a battered one gets about
+    // half the pressure.
+    let performance = player_query
+        .get_single()
+        .map_or(1.0, |health| 0.5 + 0.5 * health.fraction());
+    let rubber_band = if settings.adaptive_difficulty {
+        rubber_band(&stats)
+    } else {
+        1.0
+    };
+    director.budget = (director.budget
+        + BUDGET_RATE * performance * rubber_band * time.delta_seconds())
+    .min(MAX_BUDGET);
+
+    if !director.spawn_cooldown.finished() {
+        return;
+    }
+    let headroom = MAX_LIVE_THREAT - director.live_threat;
+    let kind = director.next;
+    if kind.threat() > director.budget || kind.threat() > headroom {
+        return;
+    }
+
+    director.budget -= kind.threat();
+    director.spawn_cooldown.reset();
+    director.next = EnemyKind::ALL
+        .choose_weighted(&mut rng.0, |kind| kind.weight())
+        .copied()
+        .unwrap_or(EnemyKind::Plane);
+    spawn_events.send(SpawnEvent(kind));
+}
+
+fn reset_director(mut director: ResMut<Director>) {
+    *director = Director::default();
+}
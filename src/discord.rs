@@ -0,0 +1,216 @@
+use std::{
+    io::{self, Read, Write},
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use bevy::prelude::*;
+use serde_json::{json, Value};
+
+use crate::{
+    director::{Director, WaveStartEvent},
+    localization::Locale,
+    profile::mode_key,
+    stats::RunStats,
+    GameState, Mission,
+};
+
+/// Rich presence is only reported when this names the game's Discord application.
+const APP_ID_VAR: &str = "BATTLE_JEEP_DISCORD_APP_ID";
+/// Discord listens on the first free one of these numbered sockets.
+const IPC_SOCKETS: u32 = 10;
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// What the player is up to, as shown on their Discord profile.
+struct Activity {
+    details: String,
+    state: String,
+    /// Unix time the run started, so Discord can show how long it's been going.
+    started: u64,
+}
+
+/// Hands presence updates to the thread that talks to Discord, so a slow or missing
+/// client never holds up a frame.
+#[derive(Resource)]
+struct Presence {
+    updates: Sender<Activity>,
+    run_started: u64,
+}
+
+pub struct DiscordPlugin;
+
+impl Plugin for DiscordPlugin {
+    fn build(&self, app: &mut App) {
+        let Ok(app_id) = std::env::var(APP_ID_VAR) else {
+            info!("{APP_ID_VAR} isn't set, so Discord rich presence is off");
+            return;
+        };
+        let (updates, activities) = mpsc::channel();
+        thread::spawn(move || report_presence(&app_id, activities));
+
+        app.insert_resource(Presence {
+            updates,
+            run_started: unix_time(),
+        })
+        .add_systems(OnEnter(GameState::Playing), start_run)
+        .add_systems(
+            Update,
+            report_run.run_if(in_state(GameState::Playing).and_then(on_event::<WaveStartEvent>())),
+        )
+        .add_systems(OnEnter(GameState::GameOver), report_run);
+    }
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs())
+}
+
+fn start_run(mut presence: ResMut<Presence>) {
+    presence.run_started = unix_time();
+}
+
+/// Sent at the start of every wave, the first one included, and when a run ends. Discord
+/// only shows a handful of updates a minute, so the score isn't sent every time it changes.
+fn report_run(
+    presence: Res<Presence>,
+    state: Res<State<GameState>>,
+    locale: Res<Locale>,
+    mission: Res<Mission>,
+    director: Res<Director>,
+    stats: Res<RunStats>,
+) {
+    let mode = locale
+        .get(&format!("mode-{}", mode_key(&mission)))
+        .to_string();
+    let details = match state.get() {
+        GameState::Playing => locale.format(
+            "presence-playing",
+            &[("mode", &mode), ("wave", &director.wave())],
+        ),
+        GameState::GameOver => locale.format("presence-game-over", &[("mode", &mode)]),
+    };
+    let activity = Activity {
+        details,
+        state: locale.format("presence-score", &[("score", &stats.score)]),
+        started: presence.run_started,
+    };
+    // The thread only goes away if it panicked, which it will have logged.
+    let _ = presence.updates.send(activity);
+}
+
+/// Connects to Discord when there's something to say, and again whenever the connection
+/// drops, for as long as the game runs.
+fn report_presence(app_id: &str, activities: Receiver<Activity>) {
+    let mut connection = None;
+    let mut nonce = 0_u64;
+    for activity in activities {
+        if connection.is_none() {
+            connection = connect(app_id)
+                .map_err(|error| debug!("couldn't reach Discord: {error}"))
+                .ok();
+        }
+        let Some(stream) = &mut connection else {
+            continue;
+        };
+        nonce += 1;
+        let command = json!({
+            "cmd": "SET_ACTIVITY",
+            "nonce": nonce.to_string(),
+            "args": {
+                "pid": std::process::id(),
+                "activity": {
+                    "details": activity.details,
+                    "state": activity.state,
+                    "timestamps": { "start": activity.started },
+                },
+            },
+        });
+        if let Err(error) = send_frame(stream, Opcode::Frame, &command) {
+            debug!("lost connection to Discord: {error}");
+            connection = None;
+        }
+    }
+}
+
+/// Kinds of message over Discord's IPC socket.
+#[derive(Clone, Copy)]
+enum Opcode {
+    Handshake = 0,
+    Frame = 1,
+}
+
+/// Each message is its opcode and length as little-endian `u32`s, then that much JSON.
+fn send_frame(stream: &mut impl Write, opcode: Opcode, payload: &Value) -> io::Result<()> {
+    let payload = payload.to_string();
+    let length = u32::try_from(payload.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "payload too long"))?;
+    stream.write_all(&(opcode as u32).to_le_bytes())?;
+    stream.write_all(&length.to_le_bytes())?;
+    stream.write_all(payload.as_bytes())?;
+    stream.flush()
+}
+
+fn read_frame(stream: &mut impl Read) -> io::Result<Value> {
+    let mut header = [0; 8];
+    stream.read_exact(&mut header)?;
+    let length = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+    let mut payload = vec![0; length as usize];
+    stream.read_exact(&mut payload)?;
+    serde_json::from_slice(&payload).map_err(io::Error::from)
+}
+
+/// Opens the first socket Discord answers on and introduces the game to it.
+fn connect(app_id: &str) -> io::Result<IpcStream> {
+    let mut last_error = io::Error::new(io::ErrorKind::NotFound, "no Discord socket");
+    for index in 0..IPC_SOCKETS {
+        match open_socket(index) {
+            Ok(mut stream) => {
+                send_frame(
+                    &mut stream,
+                    Opcode::Handshake,
+                    &json!({ "v": 1, "client_id": app_id }),
+                )?;
+                // Discord answers with a `READY` event, or an error and a closed socket.
+                let reply = read_frame(&mut stream)?;
+                if reply["evt"] != "READY" {
+                    return Err(io::Error::new(
+                        io::ErrorKind::ConnectionRefused,
+                        reply.to_string(),
+                    ));
+                }
+                return Ok(stream);
+            }
+            Err(error) => last_error = error,
+        }
+    }
+    Err(last_error)
+}
+
+#[cfg(unix)]
+type IpcStream = std::os::unix::net::UnixStream;
+
+#[cfg(unix)]
+fn open_socket(index: u32) -> io::Result<IpcStream> {
+    let dir = ["XDG_RUNTIME_DIR", "TMPDIR", "TMP", "TEMP"]
+        .into_iter()
+        .find_map(|var| std::env::var(var).ok())
+        .unwrap_or_else(|| "/tmp".to_string());
+    let stream =
+        IpcStream::connect(std::path::Path::new(&dir).join(format!("discord-ipc-{index}")))?;
+    stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+    Ok(stream)
+}
+
+#[cfg(windows)]
+type IpcStream = std::fs::File;
+
+#[cfg(windows)]
+fn open_socket(index: u32) -> io::Result<IpcStream> {
+    std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(format!(r"\\.\pipe\discord-ipc-{index}"))
+}
@@ -0,0 +1,362 @@
+use std::collections::VecDeque;
+
+use bevy::{
+    ecs::system::RunSystemOnce,
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    accessibility::AccessibilitySettings, camera::OFFSCREEN_MARGIN, localization::Locale,
+    pause::Paused, toast::ToastEvent, Collider, DamageEvent, DamageSource, GameSet, GameState,
+    Plane, Player, PLAY_AREA,
+};
+
+/// Seconds a drone stays up before its battery runs flat.
+const DRONE_LIFETIME: f32 = 20.0;
+/// It blinks, or fades, for this long before it goes, as a warning.
+const DRONE_WARNING: f32 = 3.0;
+/// How far behind the jeep the drone is, in seconds.
+const DRONE_DELAY: f32 = 0.35;
+/// Where the drone hovers relative to the jeep.
+const DRONE_HOVER: Vec2 = Vec2::new(-20.0, 70.0);
+const DRONE_BOB_HEIGHT: f32 = 4.0;
+const DRONE_BOB_RATE: f32 = 3.0;
+const DRONE_BODY_SIZE: Vec2 = Vec2::new(18.0, 6.0);
+const DRONE_ROTOR_SIZE: Vec2 = Vec2::new(10.0, 2.0);
+const DRONE_COLOR: Color = Color::rgb(0.3, 0.34, 0.38);
+const DRONE_ROTOR_COLOR: Color = Color::rgb(0.75, 0.78, 0.8);
+const DRONE_DEPTH: f32 = 0.3;
+
+const DRONE_FIRE_INTERVAL: f32 = 0.6;
+/// Planes further away than this are out of reach.
+const DRONE_RANGE: f32 = 420.0;
+const DRONE_SHOT_SPEED: f32 = 480.0;
+const DRONE_SHOT_DAMAGE: f32 = 0.5;
+const DRONE_SHOT_HALF_SIZE: Vec2 = Vec2::splat(2.5);
+const DRONE_SHOT_COLOR: Color = Color::rgb(0.55, 0.9, 1.0);
+
+/// Sent when the jeep picks up a drone, launching one or topping up the one already up.
+#[derive(Event)]
+pub struct DroneEvent;
+
+/// A little helper hovering over the jeep, following it a moment behind and taking
+/// potshots at the nearest plane.
+#[derive(Component, Clone, Serialize, Deserialize)]
+struct Drone {
+    lifetime: Timer,
+    weapon: Timer,
+    /// Where the jeep has been lately, oldest first, with the time it was there. The
+    /// times don't mean anything in another run, so a loaded drone starts it afresh.
+    #[serde(skip)]
+    trail: VecDeque<(f32, Vec2)>,
+}
+
+#[derive(Component, Clone, Serialize, Deserialize)]
+struct DroneShot {
+    velocity: Vec2,
+}
+
+pub struct DronePlugin;
+
+impl Plugin for DronePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DroneEvent>()
+            .add_systems(
+                Update,
+                (
+                    launch_drones.in_set(GameSet::Spawning),
+                    (follow_jeep, fire_drone, move_drone_shots)
+                        .chain()
+                        .in_set(GameSet::Movement),
+                    (
+                        drone_shot_collision.in_set(GameSet::Collision),
+                        expire_drones,
+                    ),
+                )
+                    .run_if(in_state(GameState::Playing).and_then(not(resource_exists::<Paused>))),
+            )
+            .add_systems(OnExit(GameState::GameOver), clear_drones);
+    }
+}
+
+fn launch_drones(
+    mut commands: Commands,
+    mut drone_events: EventReader<DroneEvent>,
+    mut drone_query: Query<&mut Drone>,
+    player_query: Query<&Transform, With<Player>>,
+    locale: Res<Locale>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    if drone_events.read().count() == 0 {
+        return;
+    }
+    toast_events.send(ToastEvent(locale.get("drone-launched").to_string()));
+    if let Ok(mut drone) = drone_query.get_single_mut() {
+        drone.lifetime.reset();
+        return;
+    }
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+
+    let position = player_transform.translation.truncate() + DRONE_HOVER;
+    spawn_drone(
+        &mut commands,
+        Transform::from_translation(position.extend(DRONE_DEPTH)),
+        Drone {
+            lifetime: Timer::from_seconds(DRONE_LIFETIME, TimerMode::Once),
+            weapon: Timer::from_seconds(DRONE_FIRE_INTERVAL, TimerMode::Repeating),
+            trail: VecDeque::new(),
+        },
+    );
+}
+
+fn spawn_drone(commands: &mut Commands, transform: Transform, drone: Drone) {
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: DRONE_COLOR,
+                    custom_size: Some(DRONE_BODY_SIZE),
+                    ..default()
+                },
+                transform,
+                ..default()
+            },
+            drone,
+        ))
+        .with_children(|parent| {
+            for side in [-1.0, 1.0] {
+                parent.spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color: DRONE_ROTOR_COLOR,
+                        custom_size: Some(DRONE_ROTOR_SIZE),
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(
+                        side * DRONE_BODY_SIZE.x / 2.0,
+                        DRONE_BODY_SIZE.y / 2.0 + DRONE_ROTOR_SIZE.y,
+                        0.01,
+                    ),
+                    ..default()
+                });
+            }
+        });
+}
+
+/// Plays back where the jeep was `DRONE_DELAY` ago, so the drone trails it rather than
+/// being bolted on.
+fn follow_jeep(
+    time: Res<Time>,
+    player_query: Query<&Transform, (With<Player>, Without<Drone>)>,
+    mut drone_query: Query<(&mut Transform, &mut Drone)>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let now = time.elapsed_seconds();
+    for (mut transform, mut drone) in &mut drone_query {
+        drone
+            .trail
+            .push_back((now, player_transform.translation.truncate()));
+        while drone
+            .trail
+            .get(1)
+            .is_some_and(|&(seen, _)| seen <= now - DRONE_DELAY)
+        {
+            drone.trail.pop_front();
+        }
+        let Some(&(_, followed)) = drone.trail.front() else {
+            continue;
+        };
+        let bob = (now * DRONE_BOB_RATE * std::f32::consts::TAU).sin() * DRONE_BOB_HEIGHT;
+        let position = followed + DRONE_HOVER + Vec2::Y * bob;
+        transform.translation = position.extend(DRONE_DEPTH);
+    }
+}
+
+fn fire_drone(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut drone_query: Query<(&Transform, &mut Drone)>,
+    plane_query: Query<&Transform, (With<Plane>, Without<Drone>)>,
+) {
+    for (transform, mut drone) in &mut drone_query {
+        drone.weapon.tick(time.delta());
+        if !drone.weapon.just_finished() {
+            continue;
+        }
+        let position = transform.translation.truncate();
+        let target = plane_query
+            .iter()
+            .map(|plane_transform| plane_transform.translation.truncate())
+            .filter(|target| target.distance(position) <= DRONE_RANGE)
+            .min_by(|a, b| a.distance(position).total_cmp(&b.distance(position)));
+        let Some(target) = target else {
+            continue;
+        };
+        commands.spawn(drone_shot(
+            Transform::from_translation(position.extend(DRONE_DEPTH)),
+            DroneShot {
+                velocity: (target - position).normalize_or_zero() * DRONE_SHOT_SPEED,
+            },
+        ));
+    }
+}
+
+fn drone_shot(transform: Transform, shot: DroneShot) -> impl Bundle {
+    (
+        SpriteBundle {
+            sprite: Sprite {
+                color: DRONE_SHOT_COLOR,
+                custom_size: Some(DRONE_SHOT_HALF_SIZE * 2.0),
+                ..default()
+            },
+            transform,
+            ..default()
+        },
+        shot,
+    )
+}
+
+fn move_drone_shots(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut shot_query: Query<(Entity, &mut Transform, &DroneShot)>,
+) {
+    let view = Rect::from_corners(-OFFSCREEN_MARGIN, PLAY_AREA + OFFSCREEN_MARGIN);
+    for (shot_entity, mut transform, shot) in &mut shot_query {
+        transform.translation += (shot.velocity * time.delta_seconds()).extend(0.0);
+        if !view.contains(transform.translation.truncate()) {
+            commands.entity(shot_entity).despawn();
+        }
+    }
+}
+
+fn drone_shot_collision(
+    mut commands: Commands,
+    shot_query: Query<(Entity, &Transform), With<DroneShot>>,
+    plane_query: Query<(Entity, &Transform, &Collider), With<Plane>>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    for (shot_entity, shot_transform) in &shot_query {
+        let shot_box = Aabb2d::new(shot_transform.translation.truncate(), DRONE_SHOT_HALF_SIZE);
+        let hit = plane_query.iter().find(|(_, plane_transform, collider)| {
+            shot_box.intersects(&Aabb2d::new(
+                plane_transform.translation.truncate(),
+                collider.half_size,
+            ))
+        });
+        if let Some((plane_entity, ..)) = hit {
+            commands.entity(shot_entity).despawn();
+            damage_events.send(DamageEvent {
+                target: plane_entity,
+                amount: DRONE_SHOT_DAMAGE,
+                source: DamageSource::Player,
+            });
+        }
+    }
+}
+
+/// The drone blinks as its battery runs down, then drops out of the fight. With flashes
+/// turned off it fades away instead.
+fn expire_drones(
+    mut commands: Commands,
+    time: Res<Time>,
+    accessibility: Res<AccessibilitySettings>,
+    mut drone_query: Query<(Entity, &mut Drone, &mut Visibility, &mut Sprite, &Children)>,
+    mut rotor_query: Query<&mut Sprite, Without<Drone>>,
+    locale: Res<Locale>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    for (drone_entity, mut drone, mut visibility, mut sprite, children) in &mut drone_query {
+        drone.lifetime.tick(time.delta());
+        if drone.lifetime.finished() {
+            commands.entity(drone_entity).despawn_recursive();
+            toast_events.send(ToastEvent(locale.get("drone-expired").to_string()));
+            continue;
+        }
+        let remaining = drone.lifetime.remaining_secs();
+        if !accessibility.flashes {
+            let alpha = (remaining / DRONE_WARNING).min(1.0);
+            sprite.color.set_a(alpha);
+            for &child in children {
+                if let Ok(mut rotor_sprite) = rotor_query.get_mut(child) {
+                    rotor_sprite.color.set_a(alpha);
+                }
+            }
+            *visibility = Visibility::Inherited;
+            continue;
+        }
+        let blink_off = remaining < DRONE_WARNING && (remaining * 8.0).fract() < 0.5;
+        *visibility = if blink_off {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+    }
+}
+
+fn clear_drones(
+    mut commands: Commands,
+    drone_query: Query<Entity, Or<(With<Drone>, With<DroneShot>)>>,
+) {
+    for entity in &drone_query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// What a saved run keeps of the drone and the shots it has in the air.
+#[derive(Serialize, Deserialize)]
+pub struct SavedDrone {
+    drones: Vec<(Transform, Drone)>,
+    shots: Vec<(Transform, DroneShot)>,
+}
+
+impl SavedDrone {
+    pub fn capture(world: &mut World) -> Self {
+        world.run_system_once(save_drone)
+    }
+
+    /// Swaps the drone in play, if any, for this one.
+    pub fn restore(self, world: &mut World) {
+        world.run_system_once_with(self, load_drone);
+    }
+}
+
+fn save_drone(
+    drone_query: Query<(&Transform, &Drone)>,
+    shot_query: Query<(&Transform, &DroneShot)>,
+) -> SavedDrone {
+    SavedDrone {
+        drones: drone_query
+            .iter()
+            .map(|(transform, drone)| (*transform, drone.clone()))
+            .collect(),
+        shots: shot_query
+            .iter()
+            .map(|(transform, shot)| (*transform, shot.clone()))
+            .collect(),
+    }
+}
+
+fn load_drone(
+    In(saved): In<SavedDrone>,
+    mut commands: Commands,
+    drone_query: Query<Entity, Or<(With<Drone>, With<DroneShot>)>>,
+) {
+    for entity in &drone_query {
+        commands.entity(entity).despawn_recursive();
+    }
+    for (transform, drone) in saved.drones {
+        spawn_drone(&mut commands, transform, drone);
+    }
+    commands.spawn_batch(
+        saved
+            .shots
+            .into_iter()
+            .map(|(transform, shot)| drone_shot(transform, shot)),
+    );
+}
@@ -0,0 +1,287 @@
+use bevy::{
+    ecs::system::RunSystemOnce,
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    convoys::{spawn_truck, truck_height},
+    debris::DebrisEvent,
+    explosion::ExplosionEvent,
+    health::Health,
+    hit_flash::HitFlashEvent,
+    localization::Locale,
+    pause::Paused,
+    terrain::Terrain,
+    Bomb, Collider, DamageSource, GameSet, GameState, Mission, BOMB_BLAST_DAMAGE, BOMB_HALF_SIZE,
+    PLAY_AREA,
+};
+
+/// Distance the convoy has to cover, in world units of scrolled terrain.
+const ROUTE_LENGTH: f32 = 8000.0;
+const CONVOY_SIZE: usize = 3;
+/// The convoy drives in the left half of the screen, leaving the jeep room to work.
+const CONVOY_LEAD_X: f32 = PLAY_AREA.x * 0.45;
+const CONVOY_SPACING: f32 = 90.0;
+const ROUTE_FONT_SIZE: f32 = 20.0;
+const ROUTE_TEXT_COLOR: Color = Color::WHITE;
+const ROUTE_PADDING: Val = Val::Px(12.0);
+
+/// How far along the route the convoy has got.
+#[derive(Resource, Default, Clone, Serialize, Deserialize)]
+struct Route {
+    travelled: f32,
+}
+
+/// How an escort mission ended, for the screen that follows it.
+#[derive(Resource, Clone, Copy, PartialEq, Eq)]
+pub enum EscortOutcome {
+    Delivered,
+    Lost,
+}
+
+/// A truck in the convoy being escorted. It keeps pace with the scrolling ground, so it
+/// holds its place on screen.
+#[derive(Component)]
+struct EscortTruck;
+
+/// The convoy and its progress readout, cleared away when play stops.
+#[derive(Component)]
+struct EscortScene;
+
+#[derive(Component)]
+struct RouteText;
+
+pub struct EscortPlugin;
+
+impl Plugin for EscortPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::Playing),
+            spawn_convoy.run_if(run_if_escort),
+        )
+        .add_systems(OnExit(GameState::Playing), despawn_convoy)
+        .add_systems(OnExit(GameState::GameOver), clear_outcome)
+        .add_systems(
+            Update,
+            (
+                (follow_road, advance_route).in_set(GameSet::Movement),
+                bomb_convoy.in_set(GameSet::Collision),
+                (check_route, update_route_text)
+                    .chain()
+                    .in_set(GameSet::Cleanup),
+            )
+                .run_if(
+                    in_state(GameState::Playing)
+                        .and_then(run_if_escort)
+                        .and_then(not(resource_exists::<Paused>)),
+                ),
+        );
+    }
+}
+
+fn run_if_escort(mission: Res<Mission>) -> bool {
+    mission.escort
+}
+
+fn spawn_convoy(mut commands: Commands, terrain: Res<Terrain>) {
+    commands.init_resource::<Route>();
+    for index in 0..CONVOY_SIZE {
+        let x = CONVOY_LEAD_X - index as f32 * CONVOY_SPACING;
+        let truck_entity = spawn_truck(&mut commands, &terrain, x, 1.0);
+        commands
+            .entity(truck_entity)
+            .insert((EscortTruck, EscortScene));
+    }
+    spawn_route_text(&mut commands);
+}
+
+fn spawn_route_text(commands: &mut Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    padding: UiRect::all(ROUTE_PADDING),
+                    ..default()
+                },
+                ..default()
+            },
+            EscortScene,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: ROUTE_FONT_SIZE,
+                        color: ROUTE_TEXT_COLOR,
+                        ..default()
+                    },
+                ),
+                RouteText,
+            ));
+        });
+}
+
+fn despawn_convoy(mut commands: Commands, scene_query: Query<Entity, With<EscortScene>>) {
+    for entity in &scene_query {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<Route>();
+}
+
+/// What a saved run keeps of the convoy being escorted and how far it's got.
+#[derive(Serialize, Deserialize)]
+pub struct SavedEscort {
+    /// `None` unless the run is an escort mission.
+    route: Option<Route>,
+    trucks: Vec<(Transform, Health)>,
+}
+
+impl SavedEscort {
+    pub fn capture(world: &mut World) -> Self {
+        world.run_system_once(save_escort)
+    }
+
+    /// Swaps the convoy in play for this one, and puts the route readout up or takes it
+    /// down to match.
+    pub fn restore(self, world: &mut World) {
+        world.run_system_once_with(self, load_escort);
+    }
+}
+
+fn save_escort(
+    route: Option<Res<Route>>,
+    truck_query: Query<(&Transform, &Health), With<EscortTruck>>,
+) -> SavedEscort {
+    SavedEscort {
+        route: route.map(|route| route.clone()),
+        trucks: truck_query
+            .iter()
+            .map(|(transform, health)| (*transform, health.clone()))
+            .collect(),
+    }
+}
+
+fn load_escort(
+    In(saved): In<SavedEscort>,
+    mut commands: Commands,
+    terrain: Res<Terrain>,
+    scene_query: Query<Entity, With<EscortScene>>,
+) {
+    for entity in &scene_query {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<Route>();
+    let Some(route) = saved.route else {
+        return;
+    };
+    commands.insert_resource(route);
+    for (transform, health) in saved.trucks {
+        let truck_entity = spawn_truck(&mut commands, &terrain, transform.translation.x, 1.0);
+        commands
+            .entity(truck_entity)
+            .insert((transform, health, EscortTruck, EscortScene));
+    }
+    spawn_route_text(&mut commands);
+}
+
+fn clear_outcome(mut commands: Commands) {
+    commands.remove_resource::<EscortOutcome>();
+}
+
+fn follow_road(terrain: Res<Terrain>, mut truck_query: Query<&mut Transform, With<EscortTruck>>) {
+    for mut transform in &mut truck_query {
+        transform.translation.y = truck_height(&terrain, transform.translation.x);
+    }
+}
+
+fn advance_route(time: Res<Time>, terrain: Res<Terrain>, mut route: ResMut<Route>) {
+    route.travelled += terrain.scroll_speed() * time.delta_seconds();
+}
+
+/// Bombs that land on a truck go off there instead of carrying on to the ground.
+fn bomb_convoy(
+    mut commands: Commands,
+    bomb_query: Query<(Entity, &Transform), With<Bomb>>,
+    mut truck_query: Query<(Entity, &Transform, &Collider, &mut Health), With<EscortTruck>>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+    mut hit_flash_events: EventWriter<HitFlashEvent>,
+    mut debris_events: EventWriter<DebrisEvent>,
+) {
+    for (bomb_entity, bomb_transform) in &bomb_query {
+        let bomb_box = Aabb2d::new(bomb_transform.translation.truncate(), BOMB_HALF_SIZE);
+        let hit = truck_query
+            .iter_mut()
+            .find(|(_, truck_transform, collider, _)| {
+                bomb_box.intersects(&Aabb2d::new(
+                    truck_transform.translation.truncate(),
+                    collider.half_size,
+                ))
+            });
+        let Some((truck_entity, truck_transform, _, mut health)) = hit else {
+            continue;
+        };
+
+        let position = truck_transform.translation.truncate();
+        commands.entity(bomb_entity).despawn();
+        explosion_events.send(ExplosionEvent {
+            position: bomb_transform.translation.truncate(),
+            radius: 48.0,
+            source: DamageSource::Enemy,
+        });
+        if health.damage(BOMB_BLAST_DAMAGE) {
+            commands.entity(truck_entity).despawn_recursive();
+            debris_events.send(DebrisEvent { position });
+        } else {
+            hit_flash_events.send(HitFlashEvent(truck_entity));
+        }
+    }
+}
+
+/// The mission is over once the convoy reaches the end of the route, or once there's no
+/// convoy left to get there.
+fn check_route(
+    mut commands: Commands,
+    route: Res<Route>,
+    truck_query: Query<(), With<EscortTruck>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let outcome = if truck_query.is_empty() {
+        EscortOutcome::Lost
+    } else if route.travelled >= ROUTE_LENGTH {
+        EscortOutcome::Delivered
+    } else {
+        return;
+    };
+    commands.insert_resource(outcome);
+    next_state.set(GameState::GameOver);
+}
+
+fn update_route_text(
+    route: Res<Route>,
+    locale: Res<Locale>,
+    truck_query: Query<(), With<EscortTruck>>,
+    mut text_query: Query<&mut Text, With<RouteText>>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+    let info = locale.format(
+        "escort-route",
+        &[
+            ("trucks", &truck_query.iter().len()),
+            ("size", &CONVOY_SIZE),
+            (
+                "percent",
+                &(route.travelled / ROUTE_LENGTH * 100.0).min(100.0).round(),
+            ),
+        ],
+    );
+    if text.sections[0].value != info {
+        text.sections[0].value = info;
+    }
+}
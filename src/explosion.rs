@@ -0,0 +1,93 @@
+use bevy::{
+    prelude::*,
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+};
+
+use crate::{settings::Settings, DamageSource};
+
+const EXPLOSION_DURATION: f32 = 0.5;
+
+/// Request a fireball at `position` that grows to `radius` and fades out. `source` is whose
+/// side set it off, which is passed on to anything it sets off in turn.
+#[derive(Event)]
+pub struct ExplosionEvent {
+    pub position: Vec2,
+    pub radius: f32,
+    pub source: DamageSource,
+}
+
+#[derive(Component)]
+struct Explosion {
+    timer: Timer,
+    radius: f32,
+}
+
+#[derive(Resource)]
+struct ExplosionMesh(Mesh2dHandle);
+
+pub struct ExplosionPlugin;
+
+impl Plugin for ExplosionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ExplosionEvent>()
+            .add_systems(Startup, setup_explosion_mesh)
+            .add_systems(Update, (spawn_explosions, animate_explosions));
+    }
+}
+
+fn setup_explosion_mesh(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>) {
+    commands.insert_resource(ExplosionMesh(Mesh2dHandle(meshes.add(Circle::new(1.0)))));
+}
+
+fn spawn_explosions(
+    mut commands: Commands,
+    mut explosion_events: EventReader<ExplosionEvent>,
+    explosion_mesh: Res<ExplosionMesh>,
+    settings: Res<Settings>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for explosion in explosion_events.read() {
+        commands.spawn((
+            MaterialMesh2dBundle {
+                mesh: explosion_mesh.0.clone(),
+                // Every explosion fades on its own, so each gets its own material.
+                material: materials.add(settings.palette.colors().explosion),
+                transform: Transform::from_translation(explosion.position.extend(1.0))
+                    .with_scale(Vec3::ZERO),
+                ..default()
+            },
+            Explosion {
+                timer: Timer::from_seconds(EXPLOSION_DURATION, TimerMode::Once),
+                radius: explosion.radius,
+            },
+        ));
+    }
+}
+
+fn animate_explosions(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut explosion_query: Query<(
+        Entity,
+        &mut Explosion,
+        &mut Transform,
+        &Handle<ColorMaterial>,
+    )>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (explosion_entity, mut explosion, mut explosion_transform, material) in &mut explosion_query
+    {
+        explosion.timer.tick(time.delta());
+        if explosion.timer.finished() {
+            commands.entity(explosion_entity).despawn();
+            continue;
+        }
+
+        let progress = explosion.timer.fraction();
+        let radius = explosion.radius * (0.2 + 0.8 * progress);
+        explosion_transform.scale = Vec3::new(radius, radius, 1.0);
+        if let Some(material) = materials.get_mut(material) {
+            material.color.set_a(1.0 - progress);
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use bevy::prelude::*;
+
+use crate::{
+    controls::{Action, Controls},
+    convoys::Civilian,
+    explosion::ExplosionEvent,
+    grading::{CountedShot, ShotHitEvent},
+    jeep_motion::ShotFiredEvent,
+    pause::Paused,
+    targeting::TargetLock,
+    DamageEvent, DamageSource, GameSet, GameState, Muzzle, Plane, Player, PlayerState, PLAY_AREA,
+};
+
+const FLAK_SIZE: f32 = 5.0;
+const FLAK_COLOR: Color = Color::rgb(0.95, 0.85, 0.55);
+
+const CANNON_COOLDOWN: f32 = 1.4;
+const CANNON_SHELL_SPEED: f32 = 420.0;
+/// Bursts are wider and hit harder than a turret's, to make up for the cooldown.
+const CANNON_BURST: FlakBurst = FlakBurst {
+    radius: 60.0,
+    damage: 1.5,
+};
+/// Where shells go off with nothing to aim at.
+const DEFAULT_FUSE_ALTITUDE: f32 = PLAY_AREA.y * 0.65;
+/// Planes within this far either side of the jeep count as overhead, for setting the fuse
+/// when nothing is locked.
+const OVERHEAD_RANGE: f32 = 200.0;
+/// Shells always climb at least this far, so none go off in the jeep's face.
+const MIN_FUSE_CLEARANCE: f32 = 80.0;
+const FUSE_MARKER_HALF_WIDTH: f32 = 10.0;
+const FUSE_MARKER_COLOR: Color = Color::rgba(0.95, 0.85, 0.55, 0.6);
+
+/// How big a bang a flak shell makes.
+#[derive(Clone, Copy)]
+pub struct FlakBurst {
+    pub radius: f32,
+    pub damage: f32,
+}
+
+/// When a flak shell goes off.
+pub enum Fuse {
+    /// After a set time in the air.
+    Timed(Timer),
+    /// Once it climbs to this height.
+    Altitude(f32),
+}
+
+/// A shell that bursts in the air, hurting every plane caught in the blast.
+#[derive(Component)]
+pub struct FlakShell {
+    pub velocity: Vec2,
+    pub fuse: Fuse,
+    pub burst: FlakBurst,
+}
+
+/// The jeep's flak cannon, and the height its next shell is fused for.
+#[derive(Resource)]
+struct FlakCannon {
+    cooldown: Timer,
+    fuse_altitude: f32,
+}
+
+impl Default for FlakCannon {
+    fn default() -> Self {
+        let mut cooldown = Timer::from_seconds(CANNON_COOLDOWN, TimerMode::Once);
+        cooldown.tick(cooldown.duration());
+        Self {
+            cooldown,
+            fuse_altitude: DEFAULT_FUSE_ALTITUDE,
+        }
+    }
+}
+
+pub struct FlakPlugin;
+
+impl Plugin for FlakPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FlakCannon>()
+            .add_systems(
+                Update,
+                (
+                    (set_fuse_altitude, fire_flak)
+                        .chain()
+                        .in_set(GameSet::Input),
+                    fly_flak.in_set(GameSet::Movement),
+                    burst_flak.in_set(GameSet::Collision),
+                )
+                    .run_if(in_state(GameState::Playing).and_then(not(resource_exists::<Paused>))),
+            )
+            .add_systems(
+                Update,
+                draw_fuse_marker.run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::GameOver), clear_flak);
+    }
+}
+
+/// A flak shell leaving `position`, ready to spawn.
+pub fn flak_shell(position: Vec2, velocity: Vec2, fuse: Fuse, burst: FlakBurst) -> impl Bundle {
+    (
+        SpriteBundle {
+            sprite: Sprite {
+                color: FLAK_COLOR,
+                custom_size: Some(Vec2::splat(FLAK_SIZE)),
+                ..default()
+            },
+            transform: Transform::from_translation(position.extend(1.0)),
+            ..default()
+        },
+        FlakShell {
+            velocity,
+            fuse,
+            burst,
+        },
+    )
+}
+
+/// The cannon is fused for the height of whatever the jeep is aiming at: the locked
+/// target, or else the nearest plane overhead. With neither it keeps the last height.
+fn set_fuse_altitude(
+    mut cannon: ResMut<FlakCannon>,
+    target_lock: Res<TargetLock>,
+    player_query: Query<&Transform, With<Player>>,
+    plane_query: Query<&Transform, (With<Plane>, Without<Player>)>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let player_x = player_transform.translation.x;
+    let locked = target_lock
+        .target
+        .and_then(|target| plane_query.get(target).ok());
+    let overhead = || {
+        plane_query
+            .iter()
+            .filter(|transform| (transform.translation.x - player_x).abs() <= OVERHEAD_RANGE)
+            .min_by(|a, b| {
+                let distance = |transform: &&Transform| (transform.translation.x - player_x).abs();
+                distance(a).total_cmp(&distance(b))
+            })
+    };
+    if let Some(target) = locked.or_else(overhead) {
+        cannon.fuse_altitude = target.translation.y;
+    }
+}
+
+/// The flak action (Down by default) fires a shell straight up, set to burst at the
+/// fuse height.
+fn fire_flak(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut controls: ResMut<Controls>,
+    mut cannon: ResMut<FlakCannon>,
+    muzzle: Muzzle,
+    mut shot_events: EventWriter<ShotFiredEvent>,
+) {
+    cannon.cooldown.tick(time.delta());
+    let Some(muzzle_position) = muzzle.position() else {
+        return;
+    };
+    if !controls.buffered(Action::Flak) || !cannon.cooldown.finished() {
+        return;
+    }
+
+    controls.consume(Action::Flak);
+    cannon.cooldown.reset();
+    let muzzle = muzzle_position.truncate();
+    commands.spawn((
+        flak_shell(
+            muzzle,
+            Vec2::Y * CANNON_SHELL_SPEED,
+            Fuse::Altitude(cannon.fuse_altitude.max(muzzle.y + MIN_FUSE_CLEARANCE)),
+            CANNON_BURST,
+        ),
+        CountedShot,
+    ));
+    shot_events.send(ShotFiredEvent::Flak);
+}
+
+fn fly_flak(time: Res<Time>, mut shell_query: Query<(&mut Transform, &mut FlakShell)>) {
+    for (mut transform, mut shell) in &mut shell_query {
+        transform.translation += (shell.velocity * time.delta_seconds()).extend(0.0);
+        match &mut shell.fuse {
+            Fuse::Timed(timer) => {
+                timer.tick(time.delta());
+            }
+            // Stops dead at its height rather than overshooting it on a long frame.
+            Fuse::Altitude(altitude) => {
+                transform.translation.y = transform.translation.y.min(*altitude);
+            }
+        }
+    }
+}
+
+/// Shells go off once their fuse says so, or if they leave the sky without going off.
+/// Anything in the burst is hurt, trucks included.
+fn burst_flak(
+    mut commands: Commands,
+    shell_query: Query<(Entity, &Transform, &FlakShell, Has<CountedShot>)>,
+    target_query: Query<(Entity, &Transform, Has<Civilian>), Or<(With<Plane>, With<Civilian>)>>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut hit_events: EventWriter<ShotHitEvent>,
+) {
+    for (shell_entity, transform, shell, counted) in &shell_query {
+        let position = transform.translation.truncate();
+        let fused = match &shell.fuse {
+            Fuse::Timed(timer) => timer.finished(),
+            Fuse::Altitude(altitude) => position.y >= *altitude,
+        };
+        if !fused && position.y <= PLAY_AREA.y {
+            continue;
+        }
+        commands.entity(shell_entity).despawn();
+        explosion_events.send(ExplosionEvent {
+            position,
+            radius: shell.burst.radius,
+            source: DamageSource::Player,
+        });
+        let mut hit_enemy = false;
+        for (target_entity, target_transform, is_civilian) in &target_query {
+            if target_transform.translation.truncate().distance(position) <= shell.burst.radius {
+                damage_events.send(DamageEvent {
+                    target: target_entity,
+                    amount: shell.burst.damage,
+                    source: DamageSource::Player,
+                });
+                hit_enemy |= !is_civilian;
+            }
+        }
+        if counted && hit_enemy {
+            hit_events.send(ShotHitEvent);
+        }
+    }
+}
+
+/// A tick over the jeep at the height the next shell will burst.
+fn draw_fuse_marker(
+    mut gizmos: Gizmos,
+    cannon: Res<FlakCannon>,
+    player_query: Query<(&Transform, &PlayerState), With<Player>>,
+) {
+    let Ok((player_transform, PlayerState::Alive)) = player_query.get_single() else {
+        return;
+    };
+    let center = Vec2::new(player_transform.translation.x, cannon.fuse_altitude);
+    gizmos.line_2d(
+        center - Vec2::X * FUSE_MARKER_HALF_WIDTH,
+        center + Vec2::X * FUSE_MARKER_HALF_WIDTH,
+        FUSE_MARKER_COLOR,
+    );
+}
+
+fn clear_flak(
+    mut commands: Commands,
+    mut cannon: ResMut<FlakCannon>,
+    shell_query: Query<Entity, With<FlakShell>>,
+) {
+    for shell_entity in &shell_query {
+        commands.entity(shell_entity).despawn();
+    }
+    *cannon = FlakCannon::default();
+}
@@ -0,0 +1,181 @@
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use crate::{
+    explosion::ExplosionEvent, missile::HomingMissile, rng::GameRng, DamageSource, GameState, Plane,
+};
+
+/// How close an incoming missile has to be before the plane reacts.
+const FLARE_TRIGGER_RANGE: f32 = 220.0;
+/// Chance a plane pops flares when it notices a missile.
+const FLARE_DEPLOY_CHANCE: f64 = 0.5;
+/// Chance a burst of flares pulls the missile off the plane.
+const FLARE_DECOY_CHANCE: f64 = 0.6;
+const FLARE_CHARGES: u32 = 2;
+const FLARE_COOLDOWN: f32 = 1.0;
+const FLARES_PER_BURST: usize = 3;
+const FLARE_LIFETIME: f32 = 1.5;
+const FLARE_GRAVITY: f32 = 150.0;
+const FLARE_SIZE: f32 = 6.0;
+const FLARE_COLOR: Color = Color::rgb(1.0, 0.95, 0.6);
+/// A missile chasing a flare detonates once it gets this close.
+const FLARE_BURN_RADIUS: f32 = 14.0;
+
+/// Flares a plane still has left to throw at incoming missiles.
+#[derive(Component)]
+struct FlareDispenser {
+    charges: u32,
+    cooldown: Timer,
+}
+
+#[derive(Component)]
+struct Flare {
+    velocity: Vec2,
+    lifetime: Timer,
+}
+
+pub struct FlaresPlugin;
+
+impl Plugin for FlaresPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                equip_flare_dispensers,
+                deploy_flares,
+                update_flares,
+                missiles_hit_flares,
+            )
+                .chain()
+                .run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(OnExit(GameState::GameOver), despawn_flares);
+    }
+}
+
+fn equip_flare_dispensers(mut commands: Commands, plane_query: Query<Entity, Added<Plane>>) {
+    for plane_entity in &plane_query {
+        commands.entity(plane_entity).try_insert(FlareDispenser {
+            charges: FLARE_CHARGES,
+            cooldown: Timer::from_seconds(FLARE_COOLDOWN, TimerMode::Once),
+        });
+    }
+}
+
+/// Planes with a missile closing in get a chance to pop flares that may steal its lock.
+fn deploy_flares(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut missile_query: Query<(&Transform, &mut HomingMissile)>,
+    mut plane_query: Query<(&Transform, &mut FlareDispenser), Without<HomingMissile>>,
+    mut rng: ResMut<GameRng>,
+) {
+    let rng = &mut rng.0;
+    for (_, mut dispenser) in &mut plane_query {
+        dispenser.cooldown.tick(time.delta());
+    }
+
+    for (missile_transform, mut missile) in &mut missile_query {
+        let Some(target) = missile.target else {
+            continue;
+        };
+        let Ok((plane_transform, mut dispenser)) = plane_query.get_mut(target) else {
+            continue;
+        };
+        let plane_position = plane_transform.translation.truncate();
+        let in_range = plane_position.distance(missile_transform.translation.truncate())
+            <= FLARE_TRIGGER_RANGE;
+        if !in_range || dispenser.charges == 0 || !dispenser.cooldown.finished() {
+            continue;
+        }
+
+        // Whether or not it panics, the plane gets one decision per cooldown.
+        dispenser.cooldown.reset();
+        if !rng.gen_bool(FLARE_DEPLOY_CHANCE) {
+            continue;
+        }
+        dispenser.charges -= 1;
+
+        let flares: Vec<Entity> = (0..FLARES_PER_BURST)
+            .map(|_| {
+                let velocity = Vec2::new(rng.gen_range(-120.0..40.0), rng.gen_range(-60.0..40.0));
+                commands
+                    .spawn((
+                        SpriteBundle {
+                            sprite: Sprite {
+                                color: FLARE_COLOR,
+                                custom_size: Some(Vec2::splat(FLARE_SIZE)),
+                                ..default()
+                            },
+                            transform: Transform::from_translation(plane_position.extend(1.5)),
+                            ..default()
+                        },
+                        Flare {
+                            velocity,
+                            lifetime: Timer::from_seconds(FLARE_LIFETIME, TimerMode::Once),
+                        },
+                    ))
+                    .id()
+            })
+            .collect();
+
+        if rng.gen_bool(FLARE_DECOY_CHANCE) {
+            missile.target = flares.choose(rng).copied();
+        }
+    }
+}
+
+fn update_flares(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut flare_query: Query<(Entity, &mut Transform, &mut Flare, &mut Sprite)>,
+) {
+    for (flare_entity, mut flare_transform, mut flare, mut sprite) in &mut flare_query {
+        flare.lifetime.tick(time.delta());
+        if flare.lifetime.finished() {
+            commands.entity(flare_entity).despawn();
+            continue;
+        }
+
+        flare.velocity.y -= FLARE_GRAVITY * time.delta_seconds();
+        flare_transform.translation += (flare.velocity * time.delta_seconds()).extend(0.0);
+        // Flicker as it burns out.
+        let flicker = (flare.lifetime.elapsed_secs() * 40.0).sin() * 0.25 + 0.75;
+        sprite
+            .color
+            .set_a(flicker * (1.0 - flare.lifetime.fraction()));
+    }
+}
+
+fn missiles_hit_flares(
+    mut commands: Commands,
+    missile_query: Query<(Entity, &Transform, &HomingMissile)>,
+    flare_query: Query<&Transform, With<Flare>>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+) {
+    for (missile_entity, missile_transform, missile) in &missile_query {
+        let Some(flare) = missile.target else {
+            continue;
+        };
+        let Ok(flare_transform) = flare_query.get(flare) else {
+            continue;
+        };
+
+        let position = missile_transform.translation.truncate();
+        if position.distance(flare_transform.translation.truncate()) <= FLARE_BURN_RADIUS {
+            commands.entity(missile_entity).despawn();
+            commands.entity(flare).despawn();
+            explosion_events.send(ExplosionEvent {
+                position,
+                radius: 20.0,
+                source: DamageSource::Player,
+            });
+        }
+    }
+}
+
+fn despawn_flares(mut commands: Commands, flare_query: Query<Entity, With<Flare>>) {
+    for flare_entity in &flare_query {
+        commands.entity(flare_entity).despawn();
+    }
+}
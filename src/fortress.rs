@@ -0,0 +1,597 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::{
+    ecs::{entity::EntityHashMap, query::QueryFilter, system::RunSystemOnce},
+    math::bounding::{Aabb2d, BoundingCircle, IntersectsVolume},
+    prelude::*,
+};
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ai::{update_ai_state, EnemyAi, SteersItself},
+    bonus::Hostile,
+    camera::OFFSCREEN_MARGIN,
+    director::WaveStartEvent,
+    explosion::ExplosionEvent,
+    grading::ShotHitEvent,
+    ground_units::Obstacle,
+    health::{Boss, Health},
+    localization::Locale,
+    missile::{HomingMissile, MISSILE_DAMAGE, MISSILE_HALF_SIZE},
+    pause::Paused,
+    repairs::BuildingDamageEvent,
+    rng::GameRng,
+    settings::Settings,
+    smoke_screen::InSmoke,
+    terrain::Terrain,
+    toast::ToastEvent,
+    trails::ENEMY_MISSILE_TRAIL,
+    Collider, DamageCause, DamageEvent, DamageSource, GameSet, GameState, Plane, PlaneSprites,
+    Player, PlayerDamageEvent, Rocket, PLAY_AREA, ROCKET_DAMAGE, ROCKET_HALF_SIZE,
+};
+
+/// A fortress comes in at the start of every this many waves.
+const FORTRESS_WAVE_INTERVAL: u32 = 5;
+const FORTRESS_SCALE: f32 = 4.0;
+const FORTRESS_HALF_SIZE: Vec2 = Vec2::new(64.0, 48.0);
+const FORTRESS_HEALTH: f32 = 30.0;
+/// Below half health it launches twice as often.
+const FORTRESS_PHASES: &[f32] = &[0.5];
+const FORTRESS_TINT: Color = Color::rgb(0.4, 0.4, 0.45);
+const FORTRESS_ALTITUDE: f32 = PLAY_AREA.y - 130.0;
+const FORTRESS_SPEED: f32 = 60.0;
+/// It cruises back and forth over this stretch once it has arrived.
+const FORTRESS_PATROL: std::ops::Range<f32> = 240.0..(PLAY_AREA.x - 240.0);
+const LAUNCH_INTERVAL: f32 = 6.0;
+
+const BOSS_MISSILE_SPEED: f32 = 45.0;
+const BOSS_MISSILE_HEALTH: f32 = 3.0;
+const BOSS_MISSILE_HALF_SIZE: Vec2 = Vec2::new(8.0, 14.0);
+const BOSS_MISSILE_SCALE: f32 = 3.0;
+const BOSS_MISSILE_TINT: Color = Color::rgb(0.9, 0.3, 0.25);
+/// A missile is on target once it's this close to where it's headed.
+const IMPACT_RADIUS: f32 = 8.0;
+const IMPACT_BLAST_RADIUS: f32 = 90.0;
+/// Taken out of the building it was aimed at, on top of what the blast knocks off.
+const IMPACT_BUILDING_DAMAGE: f32 = 2.0;
+const IMPACT_PLAYER_DAMAGE: f32 = 1.0;
+
+const MARKER_RADIUS: f32 = 18.0;
+const MARKER_FONT_SIZE: f32 = 22.0;
+/// The countdown sits this far above the spot the missile is headed for.
+const MARKER_OFFSET: f32 = 36.0;
+const MARKER_DEPTH: f32 = 30.0;
+
+/// A huge, slow bomber that crosses back and forth over the battlefield, firing missiles
+/// down at the buildings that have to be shot out of the sky before they land.
+#[derive(Component, Clone, Serialize, Deserialize)]
+pub struct Fortress {
+    /// Which way it's cruising across the screen.
+    heading: f32,
+    /// Set once it has flown in over the patrol stretch.
+    arrived: bool,
+    launcher: Timer,
+}
+
+/// Where a [`BossMissile`] is headed.
+#[derive(Clone, Serialize, Deserialize)]
+enum MissileTarget {
+    Building(Entity),
+    /// The building went off the edge, or there wasn't one, so it's making for a spot on
+    /// the ground that scrolls along with the terrain.
+    Ground(f32),
+}
+
+/// A big, slow missile fired by a fortress. It takes a few hits to bring down.
+#[derive(Component, Clone, Serialize, Deserialize)]
+struct BossMissile {
+    target: MissileTarget,
+    velocity: Vec2,
+    /// Set once it's reached its target, for the impact to be dealt with.
+    landed: bool,
+}
+
+/// Counts down the seconds until a missile lands, over the spot where it will.
+#[derive(Component)]
+struct CountdownMarker {
+    missile: Entity,
+}
+
+pub struct FortressPlugin;
+
+impl Plugin for FortressPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                spawn_fortresses.in_set(GameSet::Spawning),
+                (
+                    fly_fortresses.after(update_ai_state),
+                    launch_boss_missiles,
+                    steer_boss_missiles,
+                )
+                    .chain()
+                    .in_set(GameSet::Movement),
+                (hit_boss_missiles, land_boss_missiles).in_set(GameSet::Collision),
+            )
+                .run_if(in_state(GameState::Playing).and_then(not(resource_exists::<Paused>))),
+        )
+        .add_systems(
+            Update,
+            (announce_fortresses, update_countdown_markers).run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(OnExit(GameState::GameOver), clear_fortresses);
+    }
+}
+
+fn spawn_fortresses(
+    mut commands: Commands,
+    plane_sprites: PlaneSprites,
+    mut wave_start_events: EventReader<WaveStartEvent>,
+) {
+    let boss_wave = wave_start_events
+        .read()
+        .any(|WaveStartEvent(wave)| wave % FORTRESS_WAVE_INTERVAL == 0);
+    if !boss_wave {
+        return;
+    }
+
+    let x = PLAY_AREA.x + OFFSCREEN_MARGIN.x + FORTRESS_HALF_SIZE.x;
+    let transform = Transform::from_xyz(x, FORTRESS_ALTITUDE, 0.0).with_scale(Vec3::new(
+        FORTRESS_SCALE,
+        FORTRESS_SCALE,
+        0.0,
+    ));
+    spawn_fortress(
+        &mut commands,
+        &plane_sprites,
+        transform,
+        Fortress {
+            heading: -1.0,
+            arrived: false,
+            launcher: Timer::from_seconds(LAUNCH_INTERVAL, TimerMode::Repeating),
+        },
+        EnemyAi::new(FORTRESS_SPEED, FORTRESS_ALTITUDE).never_flees(),
+        Health::new(FORTRESS_HEALTH),
+    );
+}
+
+fn spawn_fortress(
+    commands: &mut Commands,
+    plane_sprites: &PlaneSprites,
+    transform: Transform,
+    fortress: Fortress,
+    ai: EnemyAi,
+    health: Health,
+) {
+    let mut sprite = plane_sprites.sprite(transform);
+    sprite.sprite.color = FORTRESS_TINT;
+    // Planes face left to begin with.
+    sprite.sprite.flip_x = fortress.heading > 0.0;
+    commands.spawn((
+        sprite,
+        Plane { number_of_bombs: 0 },
+        ai,
+        SteersItself,
+        health,
+        Collider {
+            half_size: FORTRESS_HALF_SIZE,
+        },
+        Boss {
+            name: "boss-fortress",
+            phases: FORTRESS_PHASES,
+        },
+        fortress,
+    ));
+}
+
+/// Tells the player what the fortress is after as it comes in.
+fn announce_fortresses(
+    fortress_query: Query<(), Added<Fortress>>,
+    locale: Res<Locale>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    for () in &fortress_query {
+        toast_events.send(ToastEvent(locale.get("fortress-incoming").to_string()));
+    }
+}
+
+/// Flies in from the right, then patrols, turning at each end of its stretch. While it's
+/// attacking it turns to stay over the jeep instead, within the same stretch. It's far too
+/// big to dodge rockets, so it holds its course while evading.
+fn fly_fortresses(
+    time: Res<Time>,
+    mut fortress_query: Query<(&mut Transform, &mut Sprite, &mut Fortress, &mut EnemyAi)>,
+    player_query: Query<&Transform, (With<Player>, Without<Fortress>)>,
+) {
+    let player_x = player_query
+        .get_single()
+        .ok()
+        .map(|player_transform| player_transform.translation.x);
+    for (mut transform, mut sprite, mut fortress, mut ai) in &mut fortress_query {
+        ai.velocity = Vec2::X * fortress.heading * ai.speed();
+        transform.translation.x += ai.velocity.x * time.delta_seconds();
+        let x = transform.translation.x;
+        if FORTRESS_PATROL.contains(&x) {
+            fortress.arrived = true;
+        }
+        if !fortress.arrived {
+            continue;
+        }
+        if x < FORTRESS_PATROL.start {
+            fortress.heading = 1.0;
+        } else if x > FORTRESS_PATROL.end {
+            fortress.heading = -1.0;
+        } else if let Some(player_x) = player_x.filter(|_| ai.is_attacking()) {
+            // Close enough overhead is left be, so it doesn't flip back and forth.
+            let offset = player_x - x;
+            if offset.abs() > FORTRESS_HALF_SIZE.x {
+                fortress.heading = offset.signum();
+            }
+        }
+        // Planes face left to begin with.
+        sprite.flip_x = fortress.heading > 0.0;
+    }
+}
+
+/// Fires a missile at a building on screen, or at the jeep's patch of ground if there
+/// are none. A jeep hidden in smoke can't be picked out, so the missile lands mid-field.
+fn launch_boss_missiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    mut fortress_query: Query<(&Transform, &Health, &Boss, &mut Fortress)>,
+    building_query: Query<(Entity, &Transform, &Obstacle)>,
+    player_query: Query<&Transform, (With<Player>, Without<InSmoke>)>,
+    mut rng: ResMut<GameRng>,
+) {
+    let rng = &mut rng.0;
+    for (fortress_transform, health, boss, mut fortress) in &mut fortress_query {
+        if !fortress.arrived {
+            continue;
+        }
+        let phase = boss
+            .phases
+            .iter()
+            .filter(|&&phase| health.fraction() < phase)
+            .count();
+        fortress.launcher.tick(time.delta() * (phase as u32 + 1));
+        if !fortress.launcher.just_finished() {
+            continue;
+        }
+
+        let buildings: Vec<Entity> = building_query
+            .iter()
+            .filter(|(_, transform, obstacle)| {
+                obstacle.height > 0.0 && (0.0..PLAY_AREA.x).contains(&transform.translation.x)
+            })
+            .map(|(building_entity, ..)| building_entity)
+            .collect();
+        let target = match buildings.choose(rng) {
+            Some(&building_entity) => MissileTarget::Building(building_entity),
+            None => MissileTarget::Ground(
+                player_query
+                    .get_single()
+                    .map_or(PLAY_AREA.x / 2.0, |transform| transform.translation.x),
+            ),
+        };
+
+        let position = fortress_transform.translation.truncate() - Vec2::Y * FORTRESS_HALF_SIZE.y;
+        spawn_boss_missile(
+            &mut commands,
+            &asset_server,
+            Transform::from_translation(position.extend(0.5)).with_scale(Vec3::new(
+                BOSS_MISSILE_SCALE,
+                BOSS_MISSILE_SCALE,
+                1.0,
+            )),
+            BossMissile {
+                target,
+                velocity: Vec2::NEG_Y * BOSS_MISSILE_SPEED,
+                landed: false,
+            },
+            Health::new(BOSS_MISSILE_HEALTH),
+        );
+    }
+}
+
+/// A missile, and the countdown marker that goes with it.
+fn spawn_boss_missile(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    transform: Transform,
+    missile: BossMissile,
+    health: Health,
+) {
+    let missile_entity = commands
+        .spawn((
+            SpriteBundle {
+                texture: asset_server.load("../assets/rocket.png"),
+                sprite: Sprite {
+                    color: BOSS_MISSILE_TINT,
+                    ..default()
+                },
+                transform,
+                ..default()
+            },
+            missile,
+            health,
+            Hostile,
+            ENEMY_MISSILE_TRAIL,
+        ))
+        .id();
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: MARKER_FONT_SIZE,
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(
+                transform.translation.truncate().extend(MARKER_DEPTH),
+            ),
+            ..default()
+        },
+        CountdownMarker {
+            missile: missile_entity,
+        },
+    ));
+}
+
+/// Where a missile is headed this frame: the roof of its building, or its spot on the
+/// ground. `None` if the building has just gone.
+fn aim_point<F: QueryFilter>(
+    target: &MissileTarget,
+    terrain: &Terrain,
+    building_query: &Query<(&Transform, &Obstacle), F>,
+) -> Option<Vec2> {
+    match *target {
+        MissileTarget::Building(building_entity) => {
+            building_query
+                .get(building_entity)
+                .ok()
+                .map(|(transform, obstacle)| {
+                    transform.translation.truncate() + Vec2::Y * obstacle.height
+                })
+        }
+        MissileTarget::Ground(x) => Some(Vec2::new(x, terrain.height_at(x))),
+    }
+}
+
+/// Missiles head straight for their target, which drifts left with the terrain like
+/// everything else on the ground.
+fn steer_boss_missiles(
+    time: Res<Time>,
+    terrain: Res<Terrain>,
+    mut missile_query: Query<(&mut Transform, &mut BossMissile)>,
+    building_query: Query<(&Transform, &Obstacle), Without<BossMissile>>,
+) {
+    let dt = time.delta_seconds();
+    for (mut transform, mut missile) in &mut missile_query {
+        if let MissileTarget::Building(building_entity) = missile.target {
+            if building_query.get(building_entity).is_err() {
+                missile.target = MissileTarget::Ground(transform.translation.x);
+            }
+        }
+        if let MissileTarget::Ground(x) = &mut missile.target {
+            *x -= terrain.scroll_speed() * dt;
+        }
+
+        let Some(target) = aim_point(&missile.target, &terrain, &building_query) else {
+            continue;
+        };
+        let to_target = target - transform.translation.truncate();
+        if to_target.length() <= IMPACT_RADIUS.max(BOSS_MISSILE_SPEED * dt) {
+            missile.landed = true;
+            continue;
+        }
+        missile.velocity = to_target.normalize() * BOSS_MISSILE_SPEED;
+        transform.translation += (missile.velocity * dt).extend(0.0);
+        // The rocket sprite points up.
+        let angle = missile.velocity.y.atan2(missile.velocity.x) - FRAC_PI_2;
+        transform.rotation = Quat::from_rotation_z(angle);
+    }
+}
+
+/// Rockets and missiles that hit a boss missile wear it down like any other enemy.
+fn hit_boss_missiles(
+    mut commands: Commands,
+    missile_query: Query<(Entity, &Transform), With<BossMissile>>,
+    shot_query: Query<
+        (Entity, &Transform, Has<HomingMissile>),
+        Or<(With<Rocket>, With<HomingMissile>)>,
+    >,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut hit_events: EventWriter<ShotHitEvent>,
+) {
+    let mut spent = Vec::new();
+    for (missile_entity, missile_transform) in &missile_query {
+        let missile_box = Aabb2d::new(
+            missile_transform.translation.truncate(),
+            BOSS_MISSILE_HALF_SIZE,
+        );
+        for (shot_entity, shot_transform, is_missile) in &shot_query {
+            let (half_size, damage) = if is_missile {
+                (MISSILE_HALF_SIZE, MISSILE_DAMAGE)
+            } else {
+                (ROCKET_HALF_SIZE, ROCKET_DAMAGE)
+            };
+            let shot_box = Aabb2d::new(shot_transform.translation.truncate(), half_size);
+            if spent.contains(&shot_entity) || !missile_box.intersects(&shot_box) {
+                continue;
+            }
+            spent.push(shot_entity);
+            commands.entity(shot_entity).despawn();
+            damage_events.send(DamageEvent {
+                target: missile_entity,
+                amount: damage,
+                source: DamageSource::Player,
+            });
+            hit_events.send(ShotHitEvent);
+        }
+    }
+}
+
+/// A missile that gets through blows a big hole in the building it was aimed at, and
+/// catches the jeep if it's close by.
+fn land_boss_missiles(
+    mut commands: Commands,
+    missile_query: Query<(Entity, &Transform, &BossMissile)>,
+    mut building_query: Query<&mut Health, (With<Obstacle>, Without<BossMissile>)>,
+    player_query: Query<(&Transform, &Collider), With<Player>>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+    mut building_damage_events: EventWriter<BuildingDamageEvent>,
+    mut player_damage_events: EventWriter<PlayerDamageEvent>,
+) {
+    for (missile_entity, transform, missile) in &missile_query {
+        if !missile.landed {
+            continue;
+        }
+        let position = transform.translation.truncate();
+        commands.entity(missile_entity).despawn_recursive();
+        explosion_events.send(ExplosionEvent {
+            position,
+            radius: IMPACT_BLAST_RADIUS,
+            source: DamageSource::Enemy,
+        });
+
+        if let MissileTarget::Building(building_entity) = missile.target {
+            if let Ok(mut health) = building_query.get_mut(building_entity) {
+                health.damage(IMPACT_BUILDING_DAMAGE);
+                building_damage_events.send(BuildingDamageEvent);
+            }
+        }
+        let Ok((player_transform, player_collider)) = player_query.get_single() else {
+            continue;
+        };
+        let blast = BoundingCircle::new(position, IMPACT_BLAST_RADIUS);
+        let player_box = Aabb2d::new(
+            player_transform.translation.truncate(),
+            player_collider.half_size,
+        );
+        if blast.intersects(&player_box) {
+            player_damage_events.send(PlayerDamageEvent {
+                amount: IMPACT_PLAYER_DAMAGE,
+                cause: DamageCause::BossMissile,
+            });
+        }
+    }
+}
+
+/// Rings the spot each missile is headed for and counts down the seconds to impact over
+/// it. Markers go once their missile does, whether it landed or was shot down.
+fn update_countdown_markers(
+    mut commands: Commands,
+    mut gizmos: Gizmos,
+    settings: Res<Settings>,
+    terrain: Res<Terrain>,
+    missile_query: Query<(&Transform, &BossMissile)>,
+    building_query: Query<(&Transform, &Obstacle), Without<CountdownMarker>>,
+    mut marker_query: Query<
+        (Entity, &CountdownMarker, &mut Transform, &mut Text),
+        Without<BossMissile>,
+    >,
+) {
+    let danger = settings.palette.colors().danger;
+    for (marker_entity, marker, mut marker_transform, mut text) in &mut marker_query {
+        let Ok((missile_transform, missile)) = missile_query.get(marker.missile) else {
+            commands.entity(marker_entity).despawn();
+            continue;
+        };
+        let Some(target) = aim_point(&missile.target, &terrain, &building_query) else {
+            continue;
+        };
+        let seconds =
+            target.distance(missile_transform.translation.truncate()) / BOSS_MISSILE_SPEED;
+        gizmos.circle_2d(target, MARKER_RADIUS, danger);
+        marker_transform.translation = (target + Vec2::Y * MARKER_OFFSET).extend(MARKER_DEPTH);
+        text.sections[0].value = format!("{}", seconds.ceil());
+        text.sections[0].style.color = danger;
+    }
+}
+
+fn clear_fortresses(
+    mut commands: Commands,
+    entity_query: Query<Entity, Or<(With<Fortress>, With<BossMissile>, With<CountdownMarker>)>>,
+) {
+    for entity in &entity_query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// What a saved run keeps of the fortresses in the sky and the missiles they've fired.
+#[derive(Serialize, Deserialize)]
+pub struct SavedFortresses {
+    fortresses: Vec<(Transform, Fortress, EnemyAi, Health)>,
+    missiles: Vec<(Transform, BossMissile, Health)>,
+}
+
+impl SavedFortresses {
+    pub fn capture(world: &mut World) -> Self {
+        world.run_system_once(save_fortresses)
+    }
+
+    /// Swaps the fortresses and missiles in play for these. The buildings have been put
+    /// back as new entities, so `buildings` maps the saved ones to them for the missiles
+    /// aimed at one.
+    pub fn restore(self, world: &mut World, buildings: EntityHashMap<Entity>) {
+        world.run_system_once_with((self, buildings), load_fortresses);
+    }
+}
+
+fn save_fortresses(
+    fortress_query: Query<(&Transform, &Fortress, &EnemyAi, &Health)>,
+    missile_query: Query<(&Transform, &BossMissile, &Health)>,
+) -> SavedFortresses {
+    SavedFortresses {
+        fortresses: fortress_query
+            .iter()
+            .map(|(transform, fortress, ai, health)| {
+                (*transform, fortress.clone(), ai.clone(), health.clone())
+            })
+            .collect(),
+        missiles: missile_query
+            .iter()
+            .map(|(transform, missile, health)| (*transform, missile.clone(), health.clone()))
+            .collect(),
+    }
+}
+
+fn load_fortresses(
+    In((saved, buildings)): In<(SavedFortresses, EntityHashMap<Entity>)>,
+    mut commands: Commands,
+    plane_sprites: PlaneSprites,
+    entity_query: Query<Entity, Or<(With<Fortress>, With<BossMissile>, With<CountdownMarker>)>>,
+) {
+    for entity in &entity_query {
+        commands.entity(entity).despawn_recursive();
+    }
+    for (transform, fortress, ai, health) in saved.fortresses {
+        spawn_fortress(
+            &mut commands,
+            &plane_sprites,
+            transform,
+            fortress,
+            ai,
+            health,
+        );
+    }
+    for (transform, mut missile, health) in saved.missiles {
+        if let MissileTarget::Building(building_entity) = missile.target {
+            missile.target = match buildings.get(&building_entity) {
+                Some(&building_entity) => MissileTarget::Building(building_entity),
+                None => MissileTarget::Ground(transform.translation.x),
+            };
+        }
+        spawn_boss_missile(
+            &mut commands,
+            &plane_sprites.asset_server,
+            transform,
+            missile,
+            health,
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use bevy::prelude::*;
+
+use crate::{
+    cosmetics::Garage, escort::EscortOutcome, localization::Localized, profile::ProfileNameEntry,
+    quit::QuitPrompt, stats_screen::StatsScreen, GameState, Mission,
+};
+
+#[derive(Component)]
+struct GameOverScreen;
+
+pub struct GameOverPlugin;
+
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::GameOver), spawn_game_over_screen)
+            .add_systems(OnExit(GameState::GameOver), despawn_game_over_screen)
+            .add_systems(
+                Update,
+                restart_run.run_if(
+                    in_state(GameState::GameOver)
+                        .and_then(not(resource_exists::<ProfileNameEntry>))
+                        .and_then(not(resource_exists::<Garage>))
+                        .and_then(not(resource_exists::<StatsScreen>))
+                        .and_then(not(resource_exists::<QuitPrompt>)),
+                ),
+            );
+    }
+}
+
+/// Escort missions that ended with the convoy delivered or lost say so in the heading.
+fn spawn_game_over_screen(mut commands: Commands, escort_outcome: Option<Res<EscortOutcome>>) {
+    let heading = match escort_outcome.as_deref() {
+        Some(EscortOutcome::Delivered) => "convoy-delivered",
+        Some(EscortOutcome::Lost) => "convoy-lost",
+        None => "game-over",
+    };
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            GameOverScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 64.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                Localized(heading),
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 24.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                Localized("game-over-prompt"),
+            ));
+        });
+}
+
+fn despawn_game_over_screen(
+    mut commands: Commands,
+    screen_query: Query<Entity, With<GameOverScreen>>,
+) {
+    for screen_entity in &screen_query {
+        commands.entity(screen_entity).despawn_recursive();
+    }
+}
+
+fn restart_run(
+    key_input: Res<ButtonInput<KeyCode>>,
+    mut mission: ResMut<Mission>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let next_mission = if key_input.just_pressed(KeyCode::Enter) {
+        Mission::default()
+    } else if key_input.just_pressed(KeyCode::KeyN) {
+        Mission {
+            night: true,
+            ..default()
+        }
+    } else if key_input.just_pressed(KeyCode::KeyC) {
+        Mission {
+            coastal: true,
+            ..default()
+        }
+    } else if key_input.just_pressed(KeyCode::KeyE) {
+        Mission {
+            escort: true,
+            ..default()
+        }
+    } else {
+        return;
+    };
+    *mission = next_mission;
+    next_state.set(GameState::Playing);
+}
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::{profile::mode_key, stats::RunStats, GameState, Mission, Player, PlayerState};
+
+/// How often the jeep's position is sampled for the ghost.
+const GHOST_SAMPLE_INTERVAL: f32 = 0.1;
+const GHOST_COLOR: Color = Color::rgba(0.8, 0.9, 1.0, 0.35);
+/// Just behind the live jeep so the two never fight over the same depth.
+const GHOST_DEPTH: f32 = -0.1;
+
+/// One sample of where the jeep was and how it was tilted.
+#[derive(Clone, Copy)]
+struct GhostFrame {
+    position: Vec2,
+    angle: f32,
+}
+
+struct GhostRun {
+    score: u32,
+    frames: Vec<GhostFrame>,
+}
+
+/// Best-scoring run so far for each mode, keyed by `mode_key`. The
+/// terrain is only generated once, so every run in a session shares its layout.
+#[derive(Resource, Default)]
+struct BestRuns(HashMap<&'static str, GhostRun>);
+
+/// Samples from the run in progress.
+#[derive(Resource)]
+struct GhostRecorder {
+    frames: Vec<GhostFrame>,
+    timer: Timer,
+}
+
+impl Default for GhostRecorder {
+    fn default() -> Self {
+        Self {
+            frames: Vec::new(),
+            timer: Timer::from_seconds(GHOST_SAMPLE_INTERVAL, TimerMode::Repeating),
+        }
+    }
+}
+
+/// Translucent jeep replaying the best run for the current mode.
+#[derive(Component)]
+struct Ghost {
+    elapsed: f32,
+}
+
+pub struct GhostPlugin;
+
+impl Plugin for GhostPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BestRuns>()
+            .init_resource::<GhostRecorder>()
+            .add_systems(OnEnter(GameState::Playing), start_ghost)
+            .add_systems(
+                Update,
+                (record_ghost, play_ghost).run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::Playing), despawn_ghost)
+            .add_systems(OnEnter(GameState::GameOver), keep_best_run);
+    }
+}
+
+fn start_ghost(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mission: Res<Mission>,
+    best_runs: Res<BestRuns>,
+    mut recorder: ResMut<GhostRecorder>,
+) {
+    *recorder = GhostRecorder::default();
+
+    let Some(best) = best_runs.0.get(mode_key(&mission)) else {
+        return;
+    };
+    let Some(first) = best.frames.first() else {
+        return;
+    };
+    commands.spawn((
+        SpriteBundle {
+            texture: asset_server.load("../assets/jeep.png"),
+            sprite: Sprite {
+                color: GHOST_COLOR,
+                ..default()
+            },
+            transform: Transform::from_translation(first.position.extend(GHOST_DEPTH))
+                .with_scale(Vec3::new(2.0, 2.0, 0.0)),
+            ..default()
+        },
+        Ghost { elapsed: 0.0 },
+    ));
+}
+
+fn record_ghost(
+    time: Res<Time>,
+    mut recorder: ResMut<GhostRecorder>,
+    player_query: Query<(&Transform, &PlayerState), With<Player>>,
+) {
+    let Ok((player_transform, player_state)) = player_query.get_single() else {
+        return;
+    };
+    recorder.timer.tick(time.delta());
+    if !recorder.timer.just_finished() {
+        return;
+    }
+
+    // While dead the previous sample is held, so the ghost waits where the jeep fell.
+    let frame = match player_state {
+        PlayerState::Alive => GhostFrame {
+            position: player_transform.translation.truncate(),
+            angle: player_transform.rotation.to_euler(EulerRot::XYZ).2,
+        },
+        PlayerState::Dead(_) => match recorder.frames.last() {
+            Some(&last) => last,
+            None => return,
+        },
+    };
+    recorder.frames.push(frame);
+}
+
+fn play_ghost(
+    mut commands: Commands,
+    time: Res<Time>,
+    mission: Res<Mission>,
+    best_runs: Res<BestRuns>,
+    mut ghost_query: Query<(Entity, &mut Transform, &mut Ghost)>,
+) {
+    let Ok((ghost_entity, mut ghost_transform, mut ghost)) = ghost_query.get_single_mut() else {
+        return;
+    };
+    let Some(best) = best_runs.0.get(mode_key(&mission)) else {
+        return;
+    };
+
+    ghost.elapsed += time.delta_seconds();
+    let sample = ghost.elapsed / GHOST_SAMPLE_INTERVAL;
+    let index = sample.floor() as usize;
+    let (Some(from), Some(to)) = (best.frames.get(index), best.frames.get(index + 1)) else {
+        // The best run ended here.
+        commands.entity(ghost_entity).despawn();
+        return;
+    };
+
+    let t = sample.fract();
+    ghost_transform.translation = from.position.lerp(to.position, t).extend(GHOST_DEPTH);
+    ghost_transform.rotation = Quat::from_rotation_z(from.angle + (to.angle - from.angle) * t);
+}
+
+fn despawn_ghost(mut commands: Commands, ghost_query: Query<Entity, With<Ghost>>) {
+    for ghost_entity in &ghost_query {
+        commands.entity(ghost_entity).despawn();
+    }
+}
+
+fn keep_best_run(
+    mission: Res<Mission>,
+    stats: Res<RunStats>,
+    mut recorder: ResMut<GhostRecorder>,
+    mut best_runs: ResMut<BestRuns>,
+) {
+    let beaten = best_runs
+        .0
+        .get(mode_key(&mission))
+        .is_none_or(|best| stats.score > best.score);
+    if beaten {
+        best_runs.0.insert(
+            mode_key(&mission),
+            GhostRun {
+                score: stats.score,
+                frames: std::mem::take(&mut recorder.frames),
+            },
+        );
+    }
+}
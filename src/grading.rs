@@ -0,0 +1,277 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    director::{Director, WaveEndEvent, WaveStartEvent},
+    jeep_motion::ShotFiredEvent,
+    localization::Locale,
+    profile::ActiveProfile,
+    EnemyKilledEvent, GameState, PlayerDamageEvent, PLAYER_HEALTH,
+};
+
+/// Kills a minute that earn full marks for speed.
+const TARGET_KILL_RATE: f32 = 20.0;
+/// Damage that takes away every mark for staying unhurt.
+const DAMAGE_ALLOWANCE: f32 = PLAYER_HEALTH;
+/// Lowest mark, out of 1, for each grade, best first.
+const GRADE_THRESHOLDS: [(Grade, f32); 3] = [(Grade::S, 0.85), (Grade::A, 0.7), (Grade::B, 0.5)];
+
+const CARD_DURATION: f32 = 5.0;
+const CARD_FONT_SIZE: f32 = 24.0;
+const CARD_GRADE_FONT_SIZE: f32 = 64.0;
+const CARD_TEXT_COLOR: Color = Color::WHITE;
+const CARD_BACKGROUND: Color = Color::rgba(0.1, 0.1, 0.15, 0.85);
+const CARD_TOP: Val = Val::Percent(40.0);
+const CARD_PADDING: Val = Val::Px(16.0);
+
+/// Sent the first time a shot that was counted when it was fired hits an enemy, for the
+/// wave's accuracy. Each shot counts once however much it hits.
+#[derive(Event)]
+pub struct ShotHitEvent;
+
+/// Put on a shell that was counted as a shot when it was fired, so its burst counts as a
+/// hit. A turret's shells aren't the player's shots and don't get one.
+#[derive(Component)]
+pub struct CountedShot;
+
+/// How well a wave went, best first.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Grade {
+    S,
+    A,
+    B,
+    C,
+}
+
+impl Grade {
+    /// Points the grade counts for in an average, from 3 for an S down to 0 for a C.
+    pub fn points(self) -> u32 {
+        match self {
+            Self::S => 3,
+            Self::A => 2,
+            Self::B => 1,
+            Self::C => 0,
+        }
+    }
+
+    /// The grade an average of `points` rounds to.
+    pub fn from_points(points: f32) -> Self {
+        match points.round() as u32 {
+            3.. => Self::S,
+            2 => Self::A,
+            1 => Self::B,
+            _ => Self::C,
+        }
+    }
+
+    pub fn letter(self) -> &'static str {
+        match self {
+            Self::S => "S",
+            Self::A => "A",
+            Self::B => "B",
+            Self::C => "C",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Self::S => Color::rgb(1.0, 0.85, 0.3),
+            Self::A => Color::rgb(0.5, 0.9, 0.5),
+            Self::B => Color::rgb(0.55, 0.75, 1.0),
+            Self::C => Color::rgb(0.75, 0.75, 0.75),
+        }
+    }
+}
+
+/// What the player did in the wave being played, for grading it at the end.
+#[derive(Resource, Default)]
+struct WaveRecord {
+    shots: u32,
+    hits: u32,
+    kills: u32,
+    damage_taken: f32,
+    time: f32,
+}
+
+impl WaveRecord {
+    fn accuracy(&self) -> f32 {
+        if self.shots == 0 {
+            return 0.0;
+        }
+        (self.hits as f32 / self.shots as f32).min(1.0)
+    }
+
+    /// Kills a minute.
+    fn kill_rate(&self) -> f32 {
+        self.kills as f32 / self.time.max(1.0) * 60.0
+    }
+
+    /// Accuracy, speed and damage taken are each marked out of 1 and count the same.
+    fn grade(&self) -> Grade {
+        let speed = (self.kill_rate() / TARGET_KILL_RATE).min(1.0);
+        let unhurt = 1.0 - (self.damage_taken / DAMAGE_ALLOWANCE).min(1.0);
+        let mark = (self.accuracy() + speed + unhurt) / 3.0;
+        GRADE_THRESHOLDS
+            .iter()
+            .find(|(_, threshold)| mark >= *threshold)
+            .map_or(Grade::C, |&(grade, _)| grade)
+    }
+}
+
+/// Shown between waves with how the last one went.
+#[derive(Component)]
+struct GradeCard {
+    timer: Timer,
+}
+
+pub struct GradingPlugin;
+
+impl Plugin for GradingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WaveRecord>()
+            .add_event::<ShotHitEvent>()
+            .add_systems(
+                Update,
+                (record_wave, grade_wave)
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(Update, dismiss_grade_card)
+            .add_systems(OnEnter(GameState::GameOver), clear_grade_card)
+            .add_systems(OnExit(GameState::GameOver), reset_wave_record);
+    }
+}
+
+fn record_wave(
+    time: Res<Time>,
+    mut record: ResMut<WaveRecord>,
+    mut wave_start_events: EventReader<WaveStartEvent>,
+    mut shot_events: EventReader<ShotFiredEvent>,
+    mut hit_events: EventReader<ShotHitEvent>,
+    mut killed_events: EventReader<EnemyKilledEvent>,
+    mut player_damage_events: EventReader<PlayerDamageEvent>,
+) {
+    if wave_start_events.read().count() > 0 {
+        *record = WaveRecord::default();
+    }
+    record.time += time.delta_seconds();
+    record.shots += shot_events.read().count() as u32;
+    record.hits += hit_events.read().count() as u32;
+    record.kills += killed_events
+        .read()
+        .filter(|killed| !killed.is_civilian)
+        .count() as u32;
+    record.damage_taken += player_damage_events
+        .read()
+        .map(|event| event.amount)
+        .sum::<f32>();
+}
+
+/// Grades the wave that just ended, puts the card up and adds the grade to the profile's
+/// average, which some unlocks ask for.
+fn grade_wave(
+    mut commands: Commands,
+    mut wave_end_events: EventReader<WaveEndEvent>,
+    record: Res<WaveRecord>,
+    director: Res<Director>,
+    locale: Res<Locale>,
+    mut profile: ResMut<ActiveProfile>,
+    card_query: Query<Entity, With<GradeCard>>,
+) {
+    if wave_end_events.read().count() == 0 {
+        return;
+    }
+    let grade = record.grade();
+    profile.0.stats.graded_waves += 1;
+    profile.0.stats.grade_points += grade.points();
+    profile.0.save();
+
+    for card_entity in &card_query {
+        commands.entity(card_entity).despawn_recursive();
+    }
+    let details = locale.format(
+        "grade-card",
+        &[
+            ("wave", &director.waves_cleared()),
+            ("accuracy", &(record.accuracy() * 100.0).round()),
+            ("speed", &record.kill_rate().round()),
+            ("damage", &record.damage_taken),
+        ],
+    );
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: CARD_TOP,
+                    width: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            GradeCard {
+                timer: Timer::from_seconds(CARD_DURATION, TimerMode::Once),
+            },
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        padding: UiRect::all(CARD_PADDING),
+                        ..default()
+                    },
+                    background_color: CARD_BACKGROUND.into(),
+                    ..default()
+                })
+                .with_children(|card| {
+                    card.spawn(TextBundle::from_section(
+                        grade.letter(),
+                        TextStyle {
+                            font_size: CARD_GRADE_FONT_SIZE,
+                            color: grade.color(),
+                            ..default()
+                        },
+                    ));
+                    card.spawn(
+                        TextBundle::from_section(
+                            details,
+                            TextStyle {
+                                font_size: CARD_FONT_SIZE,
+                                color: CARD_TEXT_COLOR,
+                                ..default()
+                            },
+                        )
+                        .with_text_justify(JustifyText::Center),
+                    );
+                });
+        });
+}
+
+/// The card comes down after a while, or as soon as the next wave starts.
+fn dismiss_grade_card(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut wave_start_events: EventReader<WaveStartEvent>,
+    mut card_query: Query<(Entity, &mut GradeCard)>,
+) {
+    let wave_started = wave_start_events.read().count() > 0;
+    for (card_entity, mut card) in &mut card_query {
+        card.timer.tick(time.delta());
+        if wave_started || card.timer.finished() {
+            commands.entity(card_entity).despawn_recursive();
+        }
+    }
+}
+
+fn clear_grade_card(mut commands: Commands, card_query: Query<Entity, With<GradeCard>>) {
+    for card_entity in &card_query {
+        commands.entity(card_entity).despawn_recursive();
+    }
+}
+
+fn reset_wave_record(mut record: ResMut<WaveRecord>) {
+    *record = WaveRecord::default();
+}
@@ -0,0 +1,387 @@
+use std::time::Duration;
+
+use bevy::{
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+};
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ai::{update_ai_state, AiState, EnemyAi, SteersItself},
+    camera::OFFSCREEN_MARGIN,
+    config::GameConfig,
+    director::{EnemyKind, SpawnEvent},
+    explosion::ExplosionEvent,
+    health::Health,
+    rng::GameRng,
+    terrain::Terrain,
+    Collider, DamageCause, DamageSource, GameSet, GameState, Player, PlayerDamageEvent,
+    PlayerState, PLAY_AREA,
+};
+
+const BUILDING_HALF_WIDTH: std::ops::Range<f32> = 24.0..48.0;
+const BUILDING_HEIGHT: std::ops::Range<f32> = 28.0..56.0;
+pub const BUILDING_COLOR: Color = Color::rgb(0.55, 0.45, 0.38);
+const CRATER_HALF_WIDTH: std::ops::Range<f32> = 20.0..36.0;
+const CRATER_DEPTH: f32 = 10.0;
+const CRATER_COLOR: Color = Color::rgb(0.45, 0.36, 0.22);
+/// In front of the ground mesh but behind the jeep.
+const OBSTACLE_DEPTH: f32 = -0.4;
+
+const INFANTRY_HALF_SIZE: Vec2 = Vec2::new(6.0, 12.0);
+const INFANTRY_COLOR: Color = Color::rgb(0.3, 0.35, 0.2);
+const INFANTRY_SPEED: std::ops::Range<f32> = 40.0..70.0;
+const INFANTRY_HEALTH: f32 = 2.0;
+const INFANTRY_GRAVITY: f32 = 1200.0;
+/// How much headroom a unit leaves above an obstacle it hops.
+const HOP_CLEARANCE: f32 = 8.0;
+/// Units start their hop once they're this close to the edge of an obstacle.
+const HOP_TRIGGER_DISTANCE: f32 = 6.0;
+/// Units queue up rather than walking into the back of one another.
+const INFANTRY_SPACING: f32 = 20.0;
+const INFANTRY_CONTACT_DAMAGE: f32 = 1.0;
+/// Props and units this far past the left edge of the view are despawned.
+const DESPAWN_MARGIN: f32 = 100.0;
+
+/// Something on the ground that ground units have to find a way past.
+#[derive(Component, Reflect, Clone, Serialize, Deserialize)]
+pub struct Obstacle {
+    pub half_width: f32,
+    /// Height above the ground surface at the obstacle's centre. Craters are zero height
+    /// but still have to be hopped, since walking into one would mean climbing back out.
+    pub height: f32,
+}
+
+impl Obstacle {
+    pub fn sprite(&self, transform: Transform) -> SpriteBundle {
+        let (color, size) = if self.height == 0.0 {
+            (CRATER_COLOR, Vec2::new(self.half_width * 2.0, CRATER_DEPTH))
+        } else {
+            (
+                BUILDING_COLOR,
+                Vec2::new(self.half_width * 2.0, self.height),
+            )
+        };
+        SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(size),
+                // Anchored at the bottom so buildings stand on the ground and craters
+                // sink into it.
+                anchor: bevy::sprite::Anchor::BottomCenter,
+                ..default()
+            },
+            transform,
+            ..default()
+        }
+    }
+
+    /// How far the sprite sits below the ground surface.
+    fn sink(&self) -> f32 {
+        if self.height == 0.0 {
+            CRATER_DEPTH
+        } else {
+            0.0
+        }
+    }
+}
+
+/// An enemy that walks along the terrain towards the jeep, steering over anything in
+/// its way with a planned hop instead of walking straight into it. Rockets and bomb
+/// blasts bring it down like anything else. Its [`EnemyAi`] decides which way it walks,
+/// at the AI's cruise speed.
+#[derive(Component, Reflect, Clone, Serialize, Deserialize)]
+pub struct GroundUnit {
+    /// Horizontal speed over the ground, relative to the scrolling terrain.
+    ground_velocity: f32,
+    vertical_velocity: f32,
+    grounded: bool,
+}
+
+impl GroundUnit {
+    pub fn sprite(transform: Transform) -> SpriteBundle {
+        SpriteBundle {
+            sprite: Sprite {
+                color: INFANTRY_COLOR,
+                custom_size: Some(INFANTRY_HALF_SIZE * 2.0),
+                ..default()
+            },
+            transform,
+            ..default()
+        }
+    }
+
+    pub fn new() -> Self {
+        Self {
+            ground_velocity: 0.0,
+            vertical_velocity: 0.0,
+            grounded: true,
+        }
+    }
+}
+
+#[derive(Resource)]
+struct ObstacleSpawnTimer(Timer);
+
+impl FromWorld for ObstacleSpawnTimer {
+    fn from_world(world: &mut World) -> Self {
+        let interval = world.resource::<GameConfig>().obstacle_interval;
+        Self(Timer::from_seconds(interval, TimerMode::Repeating))
+    }
+}
+
+pub struct GroundUnitsPlugin;
+
+impl Plugin for GroundUnitsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ObstacleSpawnTimer>()
+            .add_systems(
+                Update,
+                (
+                    (spawn_obstacles, spawn_infantry)
+                        .chain()
+                        .in_set(GameSet::Spawning),
+                    (
+                        scroll_obstacles,
+                        steer_ground_units.after(update_ai_state),
+                        move_ground_units,
+                    )
+                        .chain()
+                        .in_set(GameSet::Movement),
+                    ground_unit_contact.in_set(GameSet::Collision),
+                )
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::GameOver), despawn_ground_props);
+    }
+}
+
+/// Scenery isn't part of the threat budget, so it keeps to its own timer.
+fn spawn_obstacles(
+    mut commands: Commands,
+    time: Res<Time>,
+    terrain: Res<Terrain>,
+    mut spawn_timer: ResMut<ObstacleSpawnTimer>,
+    config: Res<GameConfig>,
+    mut rng: ResMut<GameRng>,
+) {
+    let rng = &mut rng.0;
+    if config.is_changed() {
+        spawn_timer
+            .0
+            .set_duration(Duration::from_secs_f32(config.obstacle_interval));
+    }
+    spawn_timer.0.tick(time.delta());
+
+    if spawn_timer.0.just_finished() {
+        let obstacle = if rng.gen_bool(0.5) {
+            Obstacle {
+                half_width: rng.gen_range(BUILDING_HALF_WIDTH),
+                height: rng.gen_range(BUILDING_HEIGHT),
+            }
+        } else {
+            Obstacle {
+                half_width: rng.gen_range(CRATER_HALF_WIDTH),
+                height: 0.0,
+            }
+        };
+        let x = PLAY_AREA.x + OFFSCREEN_MARGIN.x + obstacle.half_width;
+        let transform =
+            Transform::from_xyz(x, terrain.height_at(x) - obstacle.sink(), OBSTACLE_DEPTH);
+        commands.spawn((obstacle.sprite(transform), obstacle));
+    }
+}
+
+fn spawn_infantry(
+    mut commands: Commands,
+    terrain: Res<Terrain>,
+    mut spawn_events: EventReader<SpawnEvent>,
+    mut rng: ResMut<GameRng>,
+) {
+    let rng = &mut rng.0;
+
+    for _ in spawn_events
+        .read()
+        .filter(|event| event.0 == EnemyKind::Infantry)
+    {
+        let x = PLAY_AREA.x + OFFSCREEN_MARGIN.x + INFANTRY_HALF_SIZE.x;
+        commands.spawn((
+            GroundUnit::sprite(Transform::from_xyz(
+                x,
+                terrain.height_at(x) + INFANTRY_HALF_SIZE.y,
+                0.0,
+            )),
+            GroundUnit::new(),
+            EnemyAi::new(rng.gen_range(INFANTRY_SPEED), 0.0),
+            SteersItself,
+            Health::new(INFANTRY_HEALTH),
+            Collider {
+                half_size: INFANTRY_HALF_SIZE,
+            },
+        ));
+    }
+}
+
+/// Props are part of the landscape, so they drift left with the terrain.
+fn scroll_obstacles(
+    mut commands: Commands,
+    time: Res<Time>,
+    terrain: Res<Terrain>,
+    mut obstacle_query: Query<(Entity, &mut Transform, &Obstacle)>,
+) {
+    let despawn_x = -OFFSCREEN_MARGIN.x - DESPAWN_MARGIN;
+    for (obstacle_entity, mut obstacle_transform, obstacle) in &mut obstacle_query {
+        obstacle_transform.translation.x -= terrain.scroll_speed() * time.delta_seconds();
+        let x = obstacle_transform.translation.x;
+        obstacle_transform.translation.y = terrain.height_at(x) - obstacle.sink();
+        if x < despawn_x {
+            commands.entity(obstacle_entity).despawn_recursive();
+        }
+    }
+}
+
+/// Picks each grounded unit's next move: walk towards the jeep, wait behind the unit in
+/// front, or hop the obstacle ahead on an arc planned to clear it. Units that are fleeing
+/// turn and head the other way, and one in a rocket's line of fire throws itself flat.
+fn steer_ground_units(
+    mut unit_query: Query<(Entity, &Transform, &mut GroundUnit, &EnemyAi)>,
+    obstacle_query: Query<(&Transform, &Obstacle)>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let positions: Vec<(Entity, Vec2)> = unit_query
+        .iter()
+        .map(|(unit_entity, unit_transform, ..)| {
+            (unit_entity, unit_transform.translation.truncate())
+        })
+        .collect();
+
+    for (unit_entity, unit_transform, mut unit, ai) in &mut unit_query {
+        if !unit.grounded {
+            continue;
+        }
+        if matches!(ai.state, AiState::Evade { .. }) {
+            unit.ground_velocity = 0.0;
+            continue;
+        }
+        let position = unit_transform.translation.truncate();
+        let towards_jeep = (player_transform.translation.x - position.x).signum();
+        let direction = if ai.is_fleeing() {
+            -towards_jeep
+        } else {
+            towards_jeep
+        };
+        let leading_edge = position.x + direction * INFANTRY_HALF_SIZE.x;
+
+        // The nearest obstacle whose near edge is ahead of us.
+        let next_obstacle = obstacle_query
+            .iter()
+            .map(|(obstacle_transform, obstacle)| {
+                let near_edge = obstacle_transform.translation.x - direction * obstacle.half_width;
+                ((near_edge - leading_edge) * direction, obstacle)
+            })
+            .filter(|(distance, _)| *distance >= -INFANTRY_HALF_SIZE.x)
+            .min_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        if let Some((distance, obstacle)) = next_obstacle {
+            if distance <= HOP_TRIGGER_DISTANCE {
+                // Launch fast enough to clear the top, and cover the whole obstacle plus
+                // our own width while in the air.
+                let rise = obstacle.height + HOP_CLEARANCE;
+                let launch_speed = (2.0 * INFANTRY_GRAVITY * rise).sqrt();
+                let air_time = 2.0 * launch_speed / INFANTRY_GRAVITY;
+                let span = obstacle.half_width * 2.0 + INFANTRY_HALF_SIZE.x * 2.0 + distance;
+                unit.vertical_velocity = launch_speed;
+                unit.ground_velocity = direction * ai.speed().max(span / air_time);
+                unit.grounded = false;
+                continue;
+            }
+        }
+
+        let blocked = positions.iter().any(|(other_entity, other_position)| {
+            *other_entity != unit_entity
+                && (other_position.y - position.y).abs() < INFANTRY_HALF_SIZE.y
+                && (0.0..INFANTRY_SPACING).contains(&((other_position.x - position.x) * direction))
+        });
+        unit.ground_velocity = if blocked { 0.0 } else { direction * ai.speed() };
+    }
+}
+
+/// Units that walk out of view either way are gone for good.
+fn move_ground_units(
+    mut commands: Commands,
+    time: Res<Time>,
+    terrain: Res<Terrain>,
+    mut unit_query: Query<(Entity, &mut Transform, &mut GroundUnit)>,
+) {
+    let despawn_x = -OFFSCREEN_MARGIN.x - DESPAWN_MARGIN;
+    let retreat_x = PLAY_AREA.x + OFFSCREEN_MARGIN.x + DESPAWN_MARGIN;
+    for (unit_entity, mut unit_transform, mut unit) in &mut unit_query {
+        let dx = unit.ground_velocity - terrain.scroll_speed();
+        unit_transform.translation.x += dx * time.delta_seconds();
+        let x = unit_transform.translation.x;
+        let ground_y = terrain.height_at(x) + INFANTRY_HALF_SIZE.y;
+
+        if unit.grounded {
+            unit_transform.translation.y = ground_y;
+        } else {
+            unit.vertical_velocity -= INFANTRY_GRAVITY * time.delta_seconds();
+            unit_transform.translation.y += unit.vertical_velocity * time.delta_seconds();
+            if unit.vertical_velocity < 0.0 && unit_transform.translation.y <= ground_y {
+                unit_transform.translation.y = ground_y;
+                unit.vertical_velocity = 0.0;
+                unit.grounded = true;
+            }
+        }
+
+        if x < despawn_x || x > retreat_x {
+            commands.entity(unit_entity).despawn();
+        }
+    }
+}
+
+/// Infantry that reach the jeep set off their satchel charges.
+fn ground_unit_contact(
+    mut commands: Commands,
+    unit_query: Query<(Entity, &Transform, &Collider), With<GroundUnit>>,
+    player_query: Query<(&Transform, &Collider, &PlayerState), With<Player>>,
+    mut player_damage_events: EventWriter<PlayerDamageEvent>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+) {
+    let Ok((player_transform, player_collider, PlayerState::Alive)) = player_query.get_single()
+    else {
+        return;
+    };
+    let player_box = Aabb2d::new(
+        player_transform.translation.truncate(),
+        player_collider.half_size,
+    );
+
+    for (unit_entity, unit_transform, unit_collider) in &unit_query {
+        let position = unit_transform.translation.truncate();
+        if player_box.intersects(&Aabb2d::new(position, unit_collider.half_size)) {
+            commands.entity(unit_entity).despawn();
+            player_damage_events.send(PlayerDamageEvent {
+                amount: INFANTRY_CONTACT_DAMAGE,
+                cause: DamageCause::Infantry,
+            });
+            explosion_events.send(ExplosionEvent {
+                position,
+                radius: 24.0,
+                source: DamageSource::Enemy,
+            });
+        }
+    }
+}
+
+fn despawn_ground_props(
+    mut commands: Commands,
+    entity_query: Query<Entity, Or<(With<GroundUnit>, With<Obstacle>)>>,
+) {
+    for entity in &entity_query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,132 @@
+use bevy::{prelude::*, sprite::Anchor};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    convoys::Civilian, ground_units::Obstacle, settings::Settings, turrets::AaTurret, Player,
+};
+
+const HEALTH_BAR_SIZE: Vec2 = Vec2::new(40.0, 5.0);
+const HEALTH_BAR_OFFSET: f32 = 40.0;
+const HEALTH_BAR_BACKGROUND: Color = Color::rgba(0.0, 0.0, 0.0, 0.6);
+
+#[derive(Component, Reflect, Clone, Serialize, Deserialize)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+
+    /// Applies `amount` of damage and returns `true` if this killed the entity.
+    pub fn damage(&mut self, amount: f32) -> bool {
+        self.current = (self.current - amount).max(0.0);
+        self.is_dead()
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+
+    pub fn fraction(&self) -> f32 {
+        self.current / self.max
+    }
+}
+
+/// An enemy big enough to get its health shown across the top of the screen instead of
+/// in a bar over its head.
+#[derive(Component)]
+pub struct Boss {
+    /// Locale key of the boss's name.
+    pub name: &'static str,
+    /// Fractions of its health at which the boss changes how it fights, highest first.
+    pub phases: &'static [f32],
+}
+
+/// Added to an entity once its health bar has been spawned, pointing at the fill sprite.
+#[derive(Component)]
+struct HealthBarOwner {
+    fill: Entity,
+}
+
+pub struct HealthPlugin;
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (spawn_health_bars, update_health_bars).chain());
+    }
+}
+
+/// Multi-hit enemies only get a bar after they have been hurt for the first time. The
+/// jeep, its turrets, buildings and civilians have their health shown some other way, if
+/// at all.
+fn spawn_health_bars(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    health_query: Query<
+        (Entity, &Health, &Transform),
+        (
+            Changed<Health>,
+            Without<HealthBarOwner>,
+            Without<Boss>,
+            Without<Player>,
+            Without<AaTurret>,
+            Without<Obstacle>,
+            Without<Civilian>,
+        ),
+    >,
+) {
+    for (entity, health, transform) in &health_query {
+        if health.max <= 1.0 || health.current >= health.max || health.is_dead() {
+            continue;
+        }
+
+        // The bar is parented to the entity, so undo its scale to keep the bar a fixed size.
+        let parent_scale = transform.scale.truncate().max(Vec2::splat(f32::EPSILON));
+        let bar_transform = Transform::from_xyz(0.0, HEALTH_BAR_OFFSET / parent_scale.y, 1.0)
+            .with_scale((Vec2::ONE / parent_scale).extend(1.0));
+
+        let mut fill = Entity::PLACEHOLDER;
+        commands.entity(entity).with_children(|parent| {
+            parent
+                .spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color: HEALTH_BAR_BACKGROUND,
+                        custom_size: Some(HEALTH_BAR_SIZE),
+                        ..default()
+                    },
+                    transform: bar_transform,
+                    ..default()
+                })
+                .with_children(|bar| {
+                    fill = bar
+                        .spawn(SpriteBundle {
+                            sprite: Sprite {
+                                color: settings.palette.colors().health,
+                                custom_size: Some(HEALTH_BAR_SIZE),
+                                anchor: Anchor::CenterLeft,
+                                ..default()
+                            },
+                            transform: Transform::from_xyz(-HEALTH_BAR_SIZE.x / 2.0, 0.0, 0.1)
+                                .with_scale(Vec3::new(health.fraction(), 1.0, 1.0)),
+                            ..default()
+                        })
+                        .id();
+                });
+        });
+        commands.entity(entity).insert(HealthBarOwner { fill });
+    }
+}
+
+fn update_health_bars(
+    owner_query: Query<(&Health, &HealthBarOwner), Changed<Health>>,
+    mut fill_query: Query<&mut Transform>,
+) {
+    for (health, owner) in &owner_query {
+        if let Ok(mut fill_transform) = fill_query.get_mut(owner.fill) {
+            fill_transform.scale.x = health.fraction();
+        }
+    }
+}
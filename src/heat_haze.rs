@@ -0,0 +1,254 @@
+use bevy::{
+    core_pipeline::{
+        core_2d::graph::{Core2d, Node2d},
+        fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    },
+    ecs::query::QueryItem,
+    prelude::*,
+    render::{
+        extract_component::{
+            ComponentUniforms, DynamicUniformIndex, ExtractComponent, ExtractComponentPlugin,
+            UniformComponentPlugin,
+        },
+        render_graph::{
+            NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner,
+        },
+        render_resource::{
+            binding_types::{sampler, texture_2d, uniform_buffer},
+            BindGroupEntries, BindGroupLayout, BindGroupLayoutEntries, CachedRenderPipelineId,
+            ColorTargetState, ColorWrites, FragmentState, MultisampleState, Operations,
+            PipelineCache, PrimitiveState, RenderPassColorAttachment, RenderPassDescriptor,
+            RenderPipelineDescriptor, Sampler, SamplerBindingType, SamplerDescriptor, ShaderStages,
+            TextureFormat, TextureSampleType,
+        },
+        renderer::{RenderContext, RenderDevice},
+        texture::BevyDefault,
+        view::ViewTarget,
+        RenderApp,
+    },
+};
+
+use crate::{
+    settings::Settings, terrain::TERRAIN_BASE_HEIGHT, theme::Themes, Mission, GROUND_HEIGHT,
+};
+
+use self::uniform::HeatHaze;
+
+const HAZE_SHADER: &str = "shaders/heat_haze.wgsl";
+/// How far the haze rises above the average ground height.
+const HAZE_HEIGHT: f32 = 70.0;
+/// How far, in world units, the haze bends the picture at its strongest, down by the sand.
+const HAZE_STRENGTH: f32 = 1.5;
+
+/// `ShaderType` checks each field with a function it never calls, which only a module-wide
+/// allow can quieten.
+#[allow(dead_code)]
+mod uniform {
+    use bevy::{prelude::*, render::render_resource::ShaderType};
+
+    /// What the heat haze pass needs to know about the view it bends. Kept on the camera and
+    /// brought up to date every frame.
+    #[derive(Component, ShaderType, Clone, Copy, Default)]
+    pub struct HeatHaze {
+        /// World position of the top left corner of the view.
+        pub view_origin: Vec2,
+        /// Size of the view in world units.
+        pub view_size: Vec2,
+        /// World heights the haze rises from and has faded out by.
+        pub bottom: f32,
+        pub top: f32,
+        pub time: f32,
+        /// The pass is skipped altogether at 0.
+        pub strength: f32,
+    }
+}
+
+impl ExtractComponent for HeatHaze {
+    type QueryData = &'static Self;
+    type QueryFilter = ();
+    type Out = Self;
+
+    fn extract_component(haze: QueryItem<Self::QueryData>) -> Option<Self> {
+        (haze.strength > 0.0).then_some(*haze)
+    }
+}
+
+#[derive(RenderLabel, Debug, Clone, PartialEq, Eq, Hash)]
+struct HeatHazeLabel;
+
+pub struct HeatHazePlugin;
+
+impl Plugin for HeatHazePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            ExtractComponentPlugin::<HeatHaze>::default(),
+            UniformComponentPlugin::<HeatHaze>::default(),
+        ))
+        .add_systems(
+            Update,
+            (attach_heat_haze, toggle_heat_haze, show_heat_haze).chain(),
+        );
+
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        // After tonemapping, so the picture being bent is the one that ends up on screen,
+        // and before the UI is drawn over it.
+        render_app
+            .add_render_graph_node::<ViewNodeRunner<HeatHazeNode>>(Core2d, HeatHazeLabel)
+            .add_render_graph_edges(
+                Core2d,
+                (
+                    Node2d::Tonemapping,
+                    HeatHazeLabel,
+                    Node2d::EndMainPassPostProcessing,
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app.init_resource::<HeatHazePipeline>();
+    }
+}
+
+#[derive(Resource)]
+struct HeatHazePipeline {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+    pipeline_id: CachedRenderPipelineId,
+}
+
+impl FromWorld for HeatHazePipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let layout = render_device.create_bind_group_layout(
+            "heat_haze_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                    uniform_buffer::<HeatHaze>(true),
+                ),
+            ),
+        );
+        let sampler = render_device.create_sampler(&SamplerDescriptor::default());
+        let shader = world.resource::<AssetServer>().load(HAZE_SHADER);
+        let pipeline_id =
+            world
+                .resource_mut::<PipelineCache>()
+                .queue_render_pipeline(RenderPipelineDescriptor {
+                    label: Some("heat_haze_pipeline".into()),
+                    layout: vec![layout.clone()],
+                    vertex: fullscreen_shader_vertex_state(),
+                    fragment: Some(FragmentState {
+                        shader,
+                        shader_defs: vec![],
+                        entry_point: "fragment".into(),
+                        targets: vec![Some(ColorTargetState {
+                            format: TextureFormat::bevy_default(),
+                            blend: None,
+                            write_mask: ColorWrites::ALL,
+                        })],
+                    }),
+                    primitive: PrimitiveState::default(),
+                    depth_stencil: None,
+                    multisample: MultisampleState::default(),
+                    push_constant_ranges: vec![],
+                });
+        Self {
+            layout,
+            sampler,
+            pipeline_id,
+        }
+    }
+}
+
+/// Redraws the finished picture with the part above the ground bent by the haze.
+#[derive(Default)]
+struct HeatHazeNode;
+
+impl ViewNode for HeatHazeNode {
+    type ViewQuery = (&'static ViewTarget, &'static DynamicUniformIndex<HeatHaze>);
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (view_target, uniform_index): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let haze_pipeline = world.resource::<HeatHazePipeline>();
+        let Some(pipeline) = world
+            .resource::<PipelineCache>()
+            .get_render_pipeline(haze_pipeline.pipeline_id)
+        else {
+            return Ok(());
+        };
+        let Some(uniforms) = world.resource::<ComponentUniforms<HeatHaze>>().binding() else {
+            return Ok(());
+        };
+
+        let post_process = view_target.post_process_write();
+        let bind_group = render_context.render_device().create_bind_group(
+            "heat_haze_bind_group",
+            &haze_pipeline.layout,
+            &BindGroupEntries::sequential((post_process.source, &haze_pipeline.sampler, uniforms)),
+        );
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("heat_haze_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: post_process.destination,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[uniform_index.index()]);
+        render_pass.draw(0..3, 0..1);
+        Ok(())
+    }
+}
+
+fn attach_heat_haze(mut commands: Commands, camera_query: Query<Entity, Added<Camera2d>>) {
+    for camera_entity in &camera_query {
+        commands.entity(camera_entity).insert(HeatHaze::default());
+    }
+}
+
+/// `F4` turns the heat haze effect on and off.
+fn toggle_heat_haze(key_input: Res<ButtonInput<KeyCode>>, mut settings: ResMut<Settings>) {
+    if key_input.just_pressed(KeyCode::F4) {
+        settings.heat_haze = !settings.heat_haze;
+    }
+}
+
+/// Only themes with hot weather get the haze, and never at night. It follows the camera, so
+/// zooming and photo mode bend the same patch of air.
+fn show_heat_haze(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    themes: Res<Themes>,
+    mission: Res<Mission>,
+    mut camera_query: Query<(&mut HeatHaze, &OrthographicProjection, &GlobalTransform)>,
+) {
+    let shown = settings.heat_haze && themes.get(&settings.theme).heat_haze && !mission.night;
+    for (mut haze, projection, camera_transform) in &mut camera_query {
+        let area = projection.area;
+        *haze = HeatHaze {
+            view_origin: camera_transform.translation().truncate()
+                + Vec2::new(area.min.x, area.max.y),
+            view_size: area.size(),
+            bottom: GROUND_HEIGHT,
+            top: GROUND_HEIGHT + TERRAIN_BASE_HEIGHT + HAZE_HEIGHT,
+            time: time.elapsed_seconds_wrapped(),
+            strength: if shown { HAZE_STRENGTH } else { 0.0 },
+        };
+    }
+}
@@ -0,0 +1,68 @@
+use bevy::prelude::*;
+
+use crate::accessibility::AccessibilitySettings;
+
+const HIT_FLASH_DURATION: f32 = 0.1;
+const HIT_FLASH_COLOR: Color = Color::rgb(1.0, 0.25, 0.25);
+
+/// Flash the sprite of an entity that took damage but survived.
+#[derive(Event)]
+pub struct HitFlashEvent(pub Entity);
+
+#[derive(Component)]
+struct HitFlash {
+    timer: Timer,
+    original_color: Color,
+}
+
+pub struct HitFlashPlugin;
+
+impl Plugin for HitFlashPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<HitFlashEvent>()
+            .add_systems(Update, (start_hit_flashes, update_hit_flashes).chain());
+    }
+}
+
+fn start_hit_flashes(
+    mut commands: Commands,
+    accessibility: Res<AccessibilitySettings>,
+    mut hit_flash_events: EventReader<HitFlashEvent>,
+    mut sprite_query: Query<(&mut Sprite, Option<&mut HitFlash>)>,
+) {
+    if !accessibility.flashes {
+        hit_flash_events.clear();
+        return;
+    }
+    for HitFlashEvent(entity) in hit_flash_events.read() {
+        let Ok((mut sprite, hit_flash)) = sprite_query.get_mut(*entity) else {
+            continue;
+        };
+
+        // Already flashing: restart the timer but keep the color we need to restore.
+        if let Some(mut hit_flash) = hit_flash {
+            hit_flash.timer.reset();
+            continue;
+        }
+
+        commands.entity(*entity).try_insert(HitFlash {
+            timer: Timer::from_seconds(HIT_FLASH_DURATION, TimerMode::Once),
+            original_color: sprite.color,
+        });
+        sprite.color = HIT_FLASH_COLOR;
+    }
+}
+
+fn update_hit_flashes(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut hit_flash_query: Query<(Entity, &mut HitFlash, &mut Sprite)>,
+) {
+    for (entity, mut hit_flash, mut sprite) in &mut hit_flash_query {
+        hit_flash.timer.tick(time.delta());
+        if hit_flash.timer.finished() {
+            sprite.color = hit_flash.original_color;
+            commands.entity(entity).remove::<HitFlash>();
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+
+use crate::{accessibility::run_if_screen_shake, EnemyKilledEvent, GameState};
+
+/// How long the game all but stops for, in real seconds, when a boss goes down.
+const BOSS_HIT_STOP: f32 = 0.3;
+const MULTI_KILL_HIT_STOP: f32 = 0.1;
+/// Kills this close together count as one multi-kill.
+const MULTI_KILL_WINDOW: f32 = 0.35;
+const MULTI_KILL_COUNT: usize = 3;
+/// How fast the game runs during a hit-stop, against normal speed.
+const HIT_STOP_SPEED: f32 = 0.05;
+
+/// Asks for the game to freeze for a moment, for `duration` real seconds, to sell a big
+/// hit. Motion-sensitive players who turn screen shake off don't get it.
+#[derive(Event)]
+pub struct HitStopEvent {
+    pub duration: f32,
+}
+
+/// Real time left in the hit-stop under way, if any.
+#[derive(Resource, Default)]
+struct HitStop {
+    remaining: f32,
+}
+
+/// When each of the kills in the last moment happened, oldest first.
+#[derive(Resource, Default)]
+struct RecentKills(Vec<f32>);
+
+pub struct HitStopPlugin;
+
+impl Plugin for HitStopPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HitStop>()
+            .init_resource::<RecentKills>()
+            .add_event::<HitStopEvent>()
+            .add_systems(
+                Update,
+                (
+                    stop_on_big_kills.run_if(in_state(GameState::Playing)),
+                    start_hit_stop.run_if(run_if_screen_shake),
+                    release_hit_stop,
+                )
+                    .chain(),
+            )
+            .add_systems(OnExit(GameState::GameOver), forget_kills);
+    }
+}
+
+/// Bosses get a long hit-stop and a quick burst of kills a short one.
+fn stop_on_big_kills(
+    time: Res<Time>,
+    mut killed_events: EventReader<EnemyKilledEvent>,
+    mut recent_kills: ResMut<RecentKills>,
+    mut hit_stop_events: EventWriter<HitStopEvent>,
+) {
+    let now = time.elapsed_seconds();
+    recent_kills
+        .0
+        .retain(|&kill| now - kill < MULTI_KILL_WINDOW);
+    for killed in killed_events.read().filter(|killed| !killed.is_civilian) {
+        if killed.is_boss {
+            hit_stop_events.send(HitStopEvent {
+                duration: BOSS_HIT_STOP,
+            });
+        }
+        recent_kills.0.push(now);
+        if recent_kills.0.len() >= MULTI_KILL_COUNT {
+            recent_kills.0.clear();
+            hit_stop_events.send(HitStopEvent {
+                duration: MULTI_KILL_HIT_STOP,
+            });
+        }
+    }
+}
+
+fn start_hit_stop(
+    mut hit_stop_events: EventReader<HitStopEvent>,
+    mut hit_stop: ResMut<HitStop>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    let Some(duration) = hit_stop_events
+        .read()
+        .map(|event| event.duration)
+        .reduce(f32::max)
+    else {
+        return;
+    };
+    hit_stop.remaining = hit_stop.remaining.max(duration);
+    time.set_relative_speed(HIT_STOP_SPEED);
+}
+
+/// Counts the hit-stop down in real time, since game time is barely moving, and puts the
+/// game back to full speed once it's over. This runs in every state, so a run that ends
+/// mid-freeze doesn't leave the menus in slow motion.
+fn release_hit_stop(
+    real_time: Res<Time<Real>>,
+    mut hit_stop: ResMut<HitStop>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    if hit_stop.remaining <= 0.0 {
+        return;
+    }
+    hit_stop.remaining -= real_time.delta_seconds();
+    if hit_stop.remaining <= 0.0 {
+        time.set_relative_speed(1.0);
+    }
+}
+
+fn forget_kills(mut recent_kills: ResMut<RecentKills>) {
+    recent_kills.0.clear();
+}
@@ -0,0 +1,553 @@
+use bevy::{ecs::system::EntityCommands, prelude::*};
+
+use crate::{
+    config::GameConfig,
+    director::{Director, WaveStartEvent},
+    ground_units::GroundUnit,
+    health::{Boss, Health},
+    localization::{Locale, Localized},
+    missile::{MissileLauncher, MISSILE_COLOR},
+    settings::Settings,
+    stats::RunStats,
+    targeting::TargetLock,
+    Plane, Player, Rocket,
+};
+
+const HUD_FONT_SIZE: f32 = 20.0;
+const HUD_TEXT_COLOR: Color = Color::WHITE;
+const HUD_PADDING: Val = Val::Px(12.0);
+
+const WEAPON_BAR_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 0.5);
+const WEAPON_ICON_SIZE: Val = Val::Px(24.0);
+const WEAPON_SLOT_GAP: Val = Val::Px(8.0);
+const COOLDOWN_METER_SIZE: (Val, Val) = (Val::Px(60.0), Val::Px(6.0));
+const COOLDOWN_TRACK_COLOR: Color = Color::rgba(1.0, 1.0, 1.0, 0.2);
+const COOLDOWN_CHARGING_COLOR: Color = Color::GRAY;
+const ROCKET_SLOT_SIZE: (Val, Val) = (Val::Px(8.0), Val::Px(16.0));
+const ROCKET_SLOT_GAP: Val = Val::Px(4.0);
+const ROCKET_SLOT_SPENT_COLOR: Color = Color::rgba(1.0, 1.0, 1.0, 0.2);
+
+const BANNER_FONT_SIZE: f32 = 56.0;
+const BANNER_COLOR: Color = Color::rgb(1.0, 0.85, 0.3);
+const BANNER_DURATION: f32 = 2.5;
+/// The banner fades in and out over this share of its time on screen at each end.
+const BANNER_FADE: f32 = 0.2;
+/// The banner drops from the first of these heights to the second, as a percentage of the
+/// window from the top, while it fades in.
+const BANNER_TOP: (f32, f32) = (20.0, 28.0);
+
+const BOSS_BAR_TOP: Val = Val::Percent(8.0);
+const BOSS_BAR_WIDTH: Val = Val::Percent(60.0);
+const BOSS_BAR_HEIGHT: Val = Val::Px(16.0);
+const BOSS_BAR_BACKGROUND: Color = Color::rgba(0.0, 0.0, 0.0, 0.6);
+const BOSS_NAME_FONT_SIZE: f32 = 28.0;
+const PHASE_MARKER_WIDTH: Val = Val::Px(2.0);
+const PHASE_MARKER_COLOR: Color = Color::WHITE;
+
+#[derive(Component)]
+struct TargetInfoText;
+
+#[derive(Component)]
+struct WaveInfoText;
+
+/// Health of the boss being fought, across the top of the screen.
+#[derive(Component)]
+struct BossBar {
+    boss: Entity,
+}
+
+#[derive(Component)]
+struct BossBarFill;
+
+/// Text to announce across the screen for a moment, like the start of a new wave.
+#[derive(Event)]
+pub struct BannerEvent(pub String);
+
+#[derive(Component)]
+struct Banner {
+    timer: Timer,
+}
+
+/// A row of pips, one for each rocket that can be in the air at once.
+#[derive(Component)]
+struct RocketSlots;
+
+/// One pip in [`RocketSlots`], lit while another rocket can be fired.
+#[derive(Component)]
+struct RocketSlot;
+
+/// Stands in for the pips when there's no limit on rockets.
+#[derive(Component)]
+struct UnlimitedRocketsText;
+
+/// The filled part of the missile's cooldown meter.
+#[derive(Component)]
+struct CooldownFill;
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<BannerEvent>()
+            .add_systems(Startup, spawn_hud)
+            .add_systems(
+                Update,
+                (
+                    update_target_info,
+                    update_wave_info,
+                    update_rocket_slots,
+                    update_cooldown_meter,
+                    (announce_waves, show_banner, animate_banner).chain(),
+                    (show_boss_bar, update_boss_bar).chain(),
+                ),
+            );
+    }
+}
+
+fn spawn_hud(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let text_style = TextStyle {
+        font_size: HUD_FONT_SIZE,
+        color: HUD_TEXT_COLOR,
+        ..default()
+    };
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::FlexStart,
+                padding: UiRect::all(HUD_PADDING),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|hud| {
+            hud.spawn((
+                TextBundle::from_section("", text_style.clone()),
+                WaveInfoText,
+            ));
+            hud.spawn((
+                TextBundle::from_section("", text_style.clone()),
+                TargetInfoText,
+            ));
+        });
+
+    // The weapon bar is pinned to the bottom edge and centred across it, so it stays in
+    // place whatever shape the window is.
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(0.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(48.0),
+                padding: UiRect::all(HUD_PADDING),
+                ..default()
+            },
+            background_color: WEAPON_BAR_COLOR.into(),
+            ..default()
+        })
+        .with_children(|bar| {
+            weapon_slot(bar, &asset_server, Color::WHITE).with_children(|slot| {
+                slot.spawn((
+                    TextBundle::from_section("", text_style.clone()),
+                    Localized("hud-rockets"),
+                ));
+                slot.spawn((
+                    NodeBundle {
+                        style: Style {
+                            column_gap: ROCKET_SLOT_GAP,
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    RocketSlots,
+                ));
+                slot.spawn((
+                    TextBundle::from_section("", text_style.clone()),
+                    Localized("hud-ammo-unlimited"),
+                    UnlimitedRocketsText,
+                ));
+            });
+            weapon_slot(bar, &asset_server, MISSILE_COLOR).with_children(|slot| {
+                slot.spawn((
+                    TextBundle::from_section("", text_style.clone()),
+                    Localized("hud-missile"),
+                ));
+                slot.spawn(NodeBundle {
+                    style: Style {
+                        width: COOLDOWN_METER_SIZE.0,
+                        height: COOLDOWN_METER_SIZE.1,
+                        ..default()
+                    },
+                    background_color: COOLDOWN_TRACK_COLOR.into(),
+                    ..default()
+                })
+                .with_children(|meter| {
+                    meter.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(100.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: MISSILE_COLOR.into(),
+                            ..default()
+                        },
+                        CooldownFill,
+                    ));
+                });
+            });
+        });
+}
+
+/// A row in the weapon bar, led by the weapon's icon: its projectile in its own colour.
+fn weapon_slot<'a>(
+    bar: &'a mut ChildBuilder,
+    asset_server: &AssetServer,
+    tint: Color,
+) -> EntityCommands<'a> {
+    let mut slot = bar.spawn(NodeBundle {
+        style: Style {
+            align_items: AlignItems::Center,
+            column_gap: WEAPON_SLOT_GAP,
+            ..default()
+        },
+        ..default()
+    });
+    slot.with_children(|slot| {
+        slot.spawn(ImageBundle {
+            style: Style {
+                width: WEAPON_ICON_SIZE,
+                height: WEAPON_ICON_SIZE,
+                ..default()
+            },
+            image: UiImage::new(asset_server.load("../assets/rocket.png")),
+            background_color: tint.into(),
+            ..default()
+        });
+    });
+    slot
+}
+
+/// Lights one pip for each rocket the jeep can still fire before the ones in the air land,
+/// with as many pips as the config allows.
+fn update_rocket_slots(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    rocket_query: Query<(), With<Rocket>>,
+    slots_query: Query<(Entity, Option<&Children>), With<RocketSlots>>,
+    mut slot_query: Query<&mut BackgroundColor, With<RocketSlot>>,
+    mut unlimited_query: Query<&mut Style, With<UnlimitedRocketsText>>,
+) {
+    let Ok((slots_entity, slots)) = slots_query.get_single() else {
+        return;
+    };
+    let max = config.max_live_rockets as usize;
+    for mut style in &mut unlimited_query {
+        let display = if max == 0 {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        if style.display != display {
+            style.display = display;
+        }
+    }
+
+    let slots = slots.map_or(&[][..], |slots| &slots[..]);
+    if slots.len() != max {
+        commands
+            .entity(slots_entity)
+            .despawn_descendants()
+            .with_children(|row| {
+                for _ in 0..max {
+                    row.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: ROCKET_SLOT_SIZE.0,
+                                height: ROCKET_SLOT_SIZE.1,
+                                ..default()
+                            },
+                            background_color: HUD_TEXT_COLOR.into(),
+                            ..default()
+                        },
+                        RocketSlot,
+                    ));
+                }
+            });
+        return;
+    }
+
+    let free = max.saturating_sub(rocket_query.iter().len());
+    for (index, &slot) in slots.iter().enumerate() {
+        if let Ok(mut color) = slot_query.get_mut(slot) {
+            let lit = if index < free {
+                HUD_TEXT_COLOR
+            } else {
+                ROCKET_SLOT_SPENT_COLOR
+            };
+            if color.0 != lit {
+                color.0 = lit;
+            }
+        }
+    }
+}
+
+/// The missile meter fills up as the launcher cools down, and lights up once it's ready.
+fn update_cooldown_meter(
+    launcher: Res<MissileLauncher>,
+    mut fill_query: Query<(&mut Style, &mut BackgroundColor), With<CooldownFill>>,
+) {
+    if !launcher.is_changed() {
+        return;
+    }
+    let charge = launcher.charge();
+    for (mut style, mut color) in &mut fill_query {
+        style.width = Val::Percent(charge * 100.0);
+        color.0 = if charge >= 1.0 {
+            MISSILE_COLOR
+        } else {
+            COOLDOWN_CHARGING_COLOR
+        };
+    }
+}
+
+fn update_target_info(
+    target_lock: Res<TargetLock>,
+    locale: Res<Locale>,
+    target_query: Query<(&Transform, &Health)>,
+    player_query: Query<&Transform, With<Player>>,
+    mut text_query: Query<&mut Text, With<TargetInfoText>>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+
+    let target = target_lock
+        .target
+        .and_then(|target| target_query.get(target).ok());
+    let info = match (target, player_query.get_single()) {
+        (Some((target_transform, health)), Ok(player_transform)) => locale.format(
+            "hud-target",
+            &[
+                ("hp", &health.current.ceil()),
+                ("max", &health.max.ceil()),
+                (
+                    "range",
+                    &target_transform
+                        .translation
+                        .truncate()
+                        .distance(player_transform.translation.truncate())
+                        .round(),
+                ),
+            ],
+        ),
+        _ => locale.get("hud-no-target").to_string(),
+    };
+
+    if text.sections[0].value != info {
+        text.sections[0].value = info;
+    }
+}
+
+/// Wave number, enemies on the field and time played, top left.
+fn update_wave_info(
+    director: Res<Director>,
+    stats: Res<RunStats>,
+    locale: Res<Locale>,
+    plane_query: Query<(), With<Plane>>,
+    ground_unit_query: Query<(), With<GroundUnit>>,
+    mut text_query: Query<&mut Text, With<WaveInfoText>>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+
+    let seconds = stats.run_time as u32;
+    let info = locale.format(
+        "hud-wave-info",
+        &[
+            ("wave", &director.wave()),
+            (
+                "enemies",
+                &(plane_query.iter().len() + ground_unit_query.iter().len()),
+            ),
+            ("time", &format!("{}:{:02}", seconds / 60, seconds % 60)),
+        ],
+    );
+
+    if text.sections[0].value != info {
+        text.sections[0].value = info;
+    }
+}
+
+fn announce_waves(
+    locale: Res<Locale>,
+    mut wave_start_events: EventReader<WaveStartEvent>,
+    mut banner_events: EventWriter<BannerEvent>,
+) {
+    for WaveStartEvent(wave) in wave_start_events.read() {
+        banner_events.send(BannerEvent(locale.format("wave-banner", &[("wave", wave)])));
+    }
+}
+
+/// Only the latest banner is shown; it takes over from any still on screen.
+fn show_banner(
+    mut commands: Commands,
+    mut banner_events: EventReader<BannerEvent>,
+    banner_query: Query<Entity, With<Banner>>,
+) {
+    let Some(BannerEvent(text)) = banner_events.read().last() else {
+        return;
+    };
+    for banner_entity in &banner_query {
+        commands.entity(banner_entity).despawn_recursive();
+    }
+    commands.spawn((
+        TextBundle::from_section(
+            text.clone(),
+            TextStyle {
+                font_size: BANNER_FONT_SIZE,
+                color: BANNER_COLOR.with_a(0.0),
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(BANNER_TOP.0),
+            width: Val::Percent(100.0),
+            ..default()
+        })
+        .with_text_justify(JustifyText::Center),
+        Banner {
+            timer: Timer::from_seconds(BANNER_DURATION, TimerMode::Once),
+        },
+    ));
+}
+
+/// Drops the banner into place as it fades in, holds it, then fades it out.
+fn animate_banner(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut banner_query: Query<(Entity, &mut Banner, &mut Style, &mut Text)>,
+) {
+    for (banner_entity, mut banner, mut style, mut text) in &mut banner_query {
+        banner.timer.tick(time.delta());
+        if banner.timer.finished() {
+            commands.entity(banner_entity).despawn_recursive();
+            continue;
+        }
+        let progress = banner.timer.fraction();
+        let fade_in = (progress / BANNER_FADE).min(1.0);
+        let fade_out = ((1.0 - progress) / BANNER_FADE).min(1.0);
+        let drop = 1.0 - (1.0 - fade_in).powi(2);
+        style.top = Val::Percent(BANNER_TOP.0.lerp(BANNER_TOP.1, drop));
+        text.sections[0].style.color.set_a(fade_in.min(fade_out));
+    }
+}
+
+/// A boss coming in gets a bar with its name over it and a notch at each point where it
+/// changes phase. Only one boss is shown at a time.
+fn show_boss_bar(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    locale: Res<Locale>,
+    boss_query: Query<(Entity, &Boss, &Health), Added<Boss>>,
+    bar_query: Query<(), With<BossBar>>,
+) {
+    if !bar_query.is_empty() {
+        return;
+    }
+    let Some((boss_entity, boss, health)) = boss_query.iter().next() else {
+        return;
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: BOSS_BAR_TOP,
+                    width: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(6.0),
+                    ..default()
+                },
+                ..default()
+            },
+            BossBar { boss: boss_entity },
+        ))
+        .with_children(|bar| {
+            bar.spawn(TextBundle::from_section(
+                locale.get(boss.name),
+                TextStyle {
+                    font_size: BOSS_NAME_FONT_SIZE,
+                    color: HUD_TEXT_COLOR,
+                    ..default()
+                },
+            ));
+            bar.spawn(NodeBundle {
+                style: Style {
+                    width: BOSS_BAR_WIDTH,
+                    height: BOSS_BAR_HEIGHT,
+                    ..default()
+                },
+                background_color: BOSS_BAR_BACKGROUND.into(),
+                ..default()
+            })
+            .with_children(|track| {
+                track.spawn((
+                    NodeBundle {
+                        style: Style {
+                            width: Val::Percent(health.fraction() * 100.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        background_color: settings.palette.colors().health.into(),
+                        ..default()
+                    },
+                    BossBarFill,
+                ));
+                for phase in boss.phases {
+                    track.spawn(NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            left: Val::Percent(phase * 100.0),
+                            width: PHASE_MARKER_WIDTH,
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        background_color: PHASE_MARKER_COLOR.into(),
+                        ..default()
+                    });
+                }
+            });
+        });
+}
+
+/// Keeps the bar in step with the boss's health, and takes it down once the boss is gone.
+fn update_boss_bar(
+    mut commands: Commands,
+    bar_query: Query<(Entity, &BossBar)>,
+    health_query: Query<&Health, With<Boss>>,
+    mut fill_query: Query<&mut Style, With<BossBarFill>>,
+) {
+    for (bar_entity, bar) in &bar_query {
+        let Ok(health) = health_query.get(bar.boss) else {
+            commands.entity(bar_entity).despawn_recursive();
+            continue;
+        };
+        let width = Val::Percent(health.fraction() * 100.0);
+        for mut style in &mut fill_query {
+            if style.width != width {
+                style.width = width;
+            }
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use std::time::Duration;
+
+use bevy::{audio::Volume, prelude::*};
+use rand::prelude::*;
+
+use crate::{
+    audio_cues::{AudioCue, AudioCueEvent},
+    Collision, CollisionEvent, GameSet, GameState,
+};
+
+const SPARKS_PER_HIT: usize = 5;
+const SPARK_SPEED: f32 = 220.0;
+/// Sparks fly off within this many radians either side of the struck side's normal.
+const SPARK_SPREAD: f32 = 0.6;
+const SPARK_LIFETIME: f32 = 0.25;
+const SPARK_SIZE: Vec2 = Vec2::new(6.0, 2.0);
+const SPARK_COLOR: Color = Color::rgb(1.0, 0.85, 0.3);
+
+/// A rocket square on from below lifts the plane; one clipping the nose or tail only
+/// nudges it along.
+const DIRECT_KNOCKBACK: f32 = 120.0;
+const GLANCING_KNOCKBACK: f32 = 50.0;
+/// Rate at which knockback dies away, per second.
+const KNOCKBACK_DAMPING: f32 = 6.0;
+const MIN_KNOCKBACK_SPEED: f32 = 5.0;
+
+const DIRECT_HIT_TONE: f32 = 110.0;
+const GLANCING_HIT_TONE: f32 = 660.0;
+const HIT_TONE_DURATION: f32 = 0.08;
+const HIT_VOLUME: f32 = 0.3;
+
+#[derive(Component)]
+struct Spark {
+    velocity: Vec2,
+    lifetime: Timer,
+}
+
+/// Drift from being hit, on top of however the entity moves by itself.
+#[derive(Component)]
+struct Knockback(Vec2);
+
+/// A low thud for hits square on and a ping for those that clip the side.
+#[derive(Resource)]
+struct ImpactSounds {
+    direct: Handle<Pitch>,
+    glancing: Handle<Pitch>,
+}
+
+/// Hits on the nose or tail of a plane only catch its edge.
+fn is_glancing(side: Collision) -> bool {
+    matches!(side, Collision::Left | Collision::Right)
+}
+
+pub struct ImpactsPlugin;
+
+impl Plugin for ImpactsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_impact_sounds)
+            .add_systems(
+                Update,
+                (
+                    (play_impact_sounds, spawn_sparks, knock_back).in_set(GameSet::Cleanup),
+                    apply_knockback.in_set(GameSet::Movement),
+                )
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(Update, update_sparks);
+    }
+}
+
+fn setup_impact_sounds(mut commands: Commands, mut pitches: ResMut<Assets<Pitch>>) {
+    let duration = Duration::from_secs_f32(HIT_TONE_DURATION);
+    commands.insert_resource(ImpactSounds {
+        direct: pitches.add(Pitch::new(DIRECT_HIT_TONE, duration)),
+        glancing: pitches.add(Pitch::new(GLANCING_HIT_TONE, duration)),
+    });
+}
+
+fn play_impact_sounds(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    sounds: Res<ImpactSounds>,
+    mut cue_events: EventWriter<AudioCueEvent>,
+) {
+    for collision in collision_events.read() {
+        let (sound, cue) = if is_glancing(collision.side) {
+            (&sounds.glancing, AudioCue::GlancingHit)
+        } else {
+            (&sounds.direct, AudioCue::DirectHit)
+        };
+        cue_events.send(AudioCueEvent {
+            cue,
+            position: collision.point,
+        });
+        commands.spawn(PitchBundle {
+            source: sound.clone(),
+            settings: PlaybackSettings::DESPAWN.with_volume(Volume::new(HIT_VOLUME)),
+        });
+    }
+}
+
+/// Sparks spray back off whichever side of the target took the hit.
+fn spawn_sparks(mut commands: Commands, mut collision_events: EventReader<CollisionEvent>) {
+    let mut rng = thread_rng();
+    for collision in collision_events.read() {
+        let normal = collision.side.normal();
+        for _ in 0..SPARKS_PER_HIT {
+            let direction =
+                Vec2::from_angle(rng.gen_range(-SPARK_SPREAD..SPARK_SPREAD)).rotate(normal);
+            let velocity = direction * SPARK_SPEED * rng.gen_range(0.5..1.0);
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: SPARK_COLOR,
+                        custom_size: Some(SPARK_SIZE),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(collision.point.extend(0.6))
+                        .with_rotation(Quat::from_rotation_z(direction.to_angle())),
+                    ..default()
+                },
+                Spark {
+                    velocity,
+                    lifetime: Timer::from_seconds(SPARK_LIFETIME, TimerMode::Once),
+                },
+            ));
+        }
+    }
+}
+
+/// Pushes the target away from the side that was struck.
+fn knock_back(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut knockback_query: Query<&mut Knockback>,
+) {
+    for collision in collision_events.read() {
+        let speed = if is_glancing(collision.side) {
+            GLANCING_KNOCKBACK
+        } else {
+            DIRECT_KNOCKBACK
+        };
+        let push = -collision.side.normal() * speed;
+        if let Ok(mut knockback) = knockback_query.get_mut(collision.target) {
+            knockback.0 += push;
+        } else if let Some(mut target) = commands.get_entity(collision.target) {
+            // The hit may have been the last one, in which case the target is gone.
+            target.try_insert(Knockback(push));
+        }
+    }
+}
+
+fn apply_knockback(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut knockback_query: Query<(Entity, &mut Transform, &mut Knockback)>,
+) {
+    let dt = time.delta_seconds();
+    for (entity, mut transform, mut knockback) in &mut knockback_query {
+        transform.translation += (knockback.0 * dt).extend(0.0);
+        knockback.0 *= (1.0 - KNOCKBACK_DAMPING * dt).max(0.0);
+        if knockback.0.length() < MIN_KNOCKBACK_SPEED {
+            commands.entity(entity).remove::<Knockback>();
+        }
+    }
+}
+
+fn update_sparks(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut spark_query: Query<(Entity, &mut Spark, &mut Transform, &mut Sprite)>,
+) {
+    for (spark_entity, mut spark, mut transform, mut sprite) in &mut spark_query {
+        spark.lifetime.tick(time.delta());
+        if spark.lifetime.finished() {
+            commands.entity(spark_entity).despawn();
+            continue;
+        }
+        transform.translation += (spark.velocity * time.delta_seconds()).extend(0.0);
+        sprite.color.set_a(1.0 - spark.lifetime.fraction());
+    }
+}
@@ -0,0 +1,181 @@
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ai::EnemyAi, config::GameConfig, director::EnemyKind, health::Health, localization::Locale,
+    pause::Paused, pick_plane_lanes, plane_entry_x, rng::GameRng, settings::Settings, spread,
+    toast::ToastEvent, Collider, GameSet, GameState, Plane, PlaneLaunches, PlaneSprites,
+};
+
+const JAMMER_HEALTH: f32 = 3.0;
+/// Blue, and ringed with waves of interference, so it's easy to pick out.
+const JAMMER_TINT: Color = Color::rgb(0.45, 0.5, 0.8);
+/// The jammer hangs around longer than other planes.
+const JAMMER_SPEED: f32 = 0.6;
+const WAVE_INTERVAL: f32 = 0.8;
+const WAVE_COUNT: usize = 3;
+const WAVE_MAX_RADIUS: f32 = 90.0;
+const WAVE_ALPHA: f32 = 0.6;
+
+/// A plane that scrambles the jeep's off-screen warnings for as long as it's in the air.
+#[derive(Component)]
+pub struct Jammer;
+
+/// Whether the player has been told the warnings are jammed.
+#[derive(Resource, Default)]
+struct Jamming {
+    active: bool,
+}
+
+pub struct JammerPlugin;
+
+impl Plugin for JammerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Jamming>()
+            .add_systems(
+                Update,
+                (
+                    spawn_jammers
+                        .after(pick_plane_lanes)
+                        .in_set(GameSet::Spawning)
+                        .run_if(not(resource_exists::<Paused>)),
+                    announce_jamming,
+                    draw_jamming_waves,
+                )
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::GameOver), reset_jamming);
+    }
+}
+
+fn spawn_jammers(
+    mut commands: Commands,
+    plane_sprites: PlaneSprites,
+    launches: Res<PlaneLaunches>,
+    config: Res<GameConfig>,
+    mut rng: ResMut<GameRng>,
+) {
+    let rng = &mut rng.0;
+    for &(kind, altitude) in &launches.ready {
+        if kind != EnemyKind::Jammer {
+            continue;
+        }
+        let transform = Transform::from_xyz(plane_entry_x(), altitude, 0.0)
+            .with_scale(Vec3::new(2.0, 2.0, 0.0));
+        commands.spawn(jammer(
+            &plane_sprites,
+            transform,
+            EnemyAi::new(
+                spread(rng, config.plane_speed, config.plane_speed_spread) * JAMMER_SPEED,
+                altitude,
+            ),
+            Health::new(JAMMER_HEALTH),
+        ));
+    }
+}
+
+fn jammer(
+    plane_sprites: &PlaneSprites,
+    transform: Transform,
+    ai: EnemyAi,
+    health: Health,
+) -> impl Bundle {
+    let mut sprite = plane_sprites.sprite(transform);
+    sprite.sprite.color = JAMMER_TINT;
+    (
+        sprite,
+        Plane { number_of_bombs: 0 },
+        ai,
+        health,
+        Collider {
+            half_size: Vec2::splat(32.0),
+        },
+        Jammer,
+    )
+}
+
+/// Tells the player when the warnings go and when they come back.
+fn announce_jamming(
+    locale: Res<Locale>,
+    mut jamming: ResMut<Jamming>,
+    jammer_query: Query<(), With<Jammer>>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    let jammed = !jammer_query.is_empty();
+    if jammed == jamming.active {
+        return;
+    }
+    jamming.active = jammed;
+    let key = if jammed {
+        "warnings-jammed"
+    } else {
+        "warnings-restored"
+    };
+    toast_events.send(ToastEvent(locale.get(key).to_string()));
+}
+
+/// Jammers are cleared away with everything else between runs, without a word.
+fn reset_jamming(mut jamming: ResMut<Jamming>) {
+    jamming.active = false;
+}
+
+fn draw_jamming_waves(
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    jammer_query: Query<&Transform, With<Jammer>>,
+) {
+    let color = settings.palette.colors().danger;
+    let cycle = WAVE_INTERVAL * WAVE_COUNT as f32;
+    for jammer_transform in &jammer_query {
+        let center = jammer_transform.translation.truncate();
+        for wave in 0..WAVE_COUNT {
+            let age = (time.elapsed_seconds() + wave as f32 * WAVE_INTERVAL) % cycle / cycle;
+            gizmos.circle_2d(
+                center,
+                WAVE_MAX_RADIUS * age,
+                color.with_a(WAVE_ALPHA * (1.0 - age)),
+            );
+        }
+    }
+}
+
+/// What a saved run keeps of the jammers in the air.
+#[derive(Serialize, Deserialize)]
+pub struct SavedJammers(Vec<(Transform, EnemyAi, Health)>);
+
+impl SavedJammers {
+    pub fn capture(world: &mut World) -> Self {
+        world.run_system_once(save_jammers)
+    }
+
+    /// Swaps the jammers in play for these.
+    pub fn restore(self, world: &mut World) {
+        world.run_system_once_with(self, load_jammers);
+    }
+}
+
+fn save_jammers(
+    jammer_query: Query<(&Transform, &EnemyAi, &Health), With<Jammer>>,
+) -> SavedJammers {
+    SavedJammers(
+        jammer_query
+            .iter()
+            .map(|(transform, ai, health)| (*transform, ai.clone(), health.clone()))
+            .collect(),
+    )
+}
+
+fn load_jammers(
+    In(saved): In<SavedJammers>,
+    mut commands: Commands,
+    plane_sprites: PlaneSprites,
+    jammer_query: Query<Entity, With<Jammer>>,
+) {
+    for entity in &jammer_query {
+        commands.entity(entity).despawn_recursive();
+    }
+    for (transform, ai, health) in saved.0 {
+        commands.spawn(jammer(&plane_sprites, transform, ai, health));
+    }
+}
@@ -0,0 +1,186 @@
+use bevy::prelude::*;
+
+use crate::{
+    accessibility::run_if_screen_shake,
+    move_player,
+    pause::Paused,
+    player_vertical_movement,
+    tween::{Ease, Tween},
+    GameSet, GameState, Player,
+};
+
+/// How far back the jeep is shoved, in world units, and how far it tips up, in radians.
+const ROCKET_KICK: (f32, f32) = (3.0, 0.04);
+const MISSILE_KICK: (f32, f32) = (8.0, 0.12);
+const FLAK_KICK: (f32, f32) = (5.0, 0.07);
+const LASER_KICK: (f32, f32) = (1.0, 0.01);
+const ROCKET_KICK_DURATION: f32 = 0.15;
+const MISSILE_KICK_DURATION: f32 = 0.35;
+const FLAK_KICK_DURATION: f32 = 0.25;
+const LASER_KICK_DURATION: f32 = 0.1;
+
+/// How far the nose dips, in radians, while skidding at top speed.
+const SKID_LEAN: f32 = 0.08;
+
+const BOUNCE_HEIGHT: f32 = 1.5;
+/// Distance driven per bounce of the suspension.
+const BOUNCE_LENGTH: f32 = 60.0;
+/// Time for the bounce to build up when setting off, or settle when stopping.
+const BOUNCE_FADE: f32 = 0.2;
+
+/// Sent whenever the jeep fires, so it can kick back.
+#[derive(Event)]
+pub enum ShotFiredEvent {
+    Rocket,
+    Missile,
+    Flak,
+    /// The start of a burst of the laser.
+    Laser,
+}
+
+/// Knocked back by the last shot, settling over the tweens.
+#[derive(Component)]
+struct Recoil {
+    shove: Tween,
+    tilt: Tween,
+}
+
+#[derive(Component)]
+struct Suspension {
+    phase: f32,
+    amplitude: Tween,
+    last_x: f32,
+}
+
+/// Cosmetic offset added to the jeep on top of where gameplay puts it. It is taken off
+/// again before the jeep moves each frame, so gameplay never builds on it.
+#[derive(Component, Default)]
+struct Pose {
+    offset: Vec2,
+    tilt: f32,
+}
+
+pub struct JeepMotionPlugin;
+
+impl Plugin for JeepMotionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ShotFiredEvent>()
+            .add_systems(Update, equip_jeep)
+            .add_systems(
+                Update,
+                (
+                    clear_pose.before(move_player),
+                    (kick_back, pose_jeep)
+                        .chain()
+                        .after(player_vertical_movement)
+                        .run_if(run_if_screen_shake),
+                )
+                    .in_set(GameSet::Movement)
+                    .run_if(in_state(GameState::Playing).and_then(not(resource_exists::<Paused>))),
+            );
+    }
+}
+
+fn equip_jeep(
+    mut commands: Commands,
+    player_query: Query<(Entity, &Transform), (With<Player>, Without<Pose>)>,
+) {
+    for (player_entity, player_transform) in &player_query {
+        commands.entity(player_entity).insert((
+            Pose::default(),
+            Suspension {
+                phase: 0.0,
+                amplitude: Tween::new(0.0, 0.0, BOUNCE_FADE, Ease::Linear),
+                last_x: player_transform.translation.x,
+            },
+        ));
+    }
+}
+
+fn clear_pose(mut player_query: Query<(&mut Transform, &mut Pose)>) {
+    for (mut transform, mut pose) in &mut player_query {
+        transform.translation -= pose.offset.extend(0.0);
+        transform.rotation *= Quat::from_rotation_z(-pose.tilt);
+        *pose = Pose::default();
+    }
+}
+
+/// Heavier shots shove the jeep back further and for longer.
+fn kick_back(
+    mut commands: Commands,
+    mut shot_events: EventReader<ShotFiredEvent>,
+    player_query: Query<Entity, With<Pose>>,
+) {
+    let Some(shot) = shot_events.read().last() else {
+        return;
+    };
+    let Ok(player_entity) = player_query.get_single() else {
+        return;
+    };
+
+    let ((shove, tilt), duration) = match shot {
+        ShotFiredEvent::Rocket => (ROCKET_KICK, ROCKET_KICK_DURATION),
+        ShotFiredEvent::Missile => (MISSILE_KICK, MISSILE_KICK_DURATION),
+        ShotFiredEvent::Flak => (FLAK_KICK, FLAK_KICK_DURATION),
+        ShotFiredEvent::Laser => (LASER_KICK, LASER_KICK_DURATION),
+    };
+    commands.entity(player_entity).insert(Recoil {
+        shove: Tween::new(shove, 0.0, duration, Ease::QuadOut),
+        tilt: Tween::new(tilt, 0.0, duration, Ease::QuadOut),
+    });
+}
+
+/// Lays the recoil, a suspension bounce and any skid over the jeep's position for this frame.
+fn pose_jeep(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut player_query: Query<(
+        Entity,
+        &Player,
+        &mut Transform,
+        &mut Pose,
+        &mut Suspension,
+        Option<&mut Recoil>,
+    )>,
+) {
+    let Ok((player_entity, player, mut transform, mut pose, mut suspension, recoil)) =
+        player_query.get_single_mut()
+    else {
+        return;
+    };
+
+    let x = transform.translation.x;
+    let driving = player.grounded && x != suspension.last_x;
+    suspension.phase += (x - suspension.last_x).abs() / BOUNCE_LENGTH * std::f32::consts::TAU;
+    suspension.last_x = x;
+    let target = if driving { 1.0 } else { 0.0 };
+    if suspension.amplitude.target() != target {
+        let current = suspension.amplitude.value();
+        suspension.amplitude = Tween::new(current, target, BOUNCE_FADE, Ease::Linear);
+    }
+    suspension.amplitude.tick(time.delta());
+    let bounce = suspension.phase.sin() * BOUNCE_HEIGHT * suspension.amplitude.value();
+
+    let (shove, tilt) = match recoil {
+        Some(mut recoil) => {
+            recoil.shove.tick(time.delta());
+            recoil.tilt.tick(time.delta());
+            if recoil.shove.finished() && recoil.tilt.finished() {
+                commands.entity(player_entity).remove::<Recoil>();
+            }
+            (recoil.shove.value(), recoil.tilt.value())
+        }
+        None => (0.0, 0.0),
+    };
+
+    pose.offset = (transform.rotation * Vec3::new(-shove, bounce, 0.0)).truncate();
+    // The nose dips towards the way the jeep is sliding, less as it slows.
+    let skid = if player.skidding && player.grounded {
+        -SKID_LEAN * player.horizontal_velocity / player.movement_speed.max(1.0)
+    } else {
+        0.0
+    };
+    pose.tilt = tilt + skid;
+    transform.translation += pose.offset.extend(0.0);
+    transform.rotation *= Quat::from_rotation_z(pose.tilt);
+}
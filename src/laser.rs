@@ -0,0 +1,274 @@
+use bevy::{
+    math::bounding::{Aabb2d, RayCast2d},
+    prelude::*,
+};
+
+use crate::{
+    controls::{Action, Controls},
+    cosmetics::Unlock,
+    grading::ShotHitEvent,
+    jeep_motion::ShotFiredEvent,
+    localization::Locale,
+    pause::Paused,
+    profile::ActiveProfile,
+    targeting::TargetLock,
+    toast::ToastEvent,
+    Collider, DamageEvent, DamageSource, GameSet, GameState, Muzzle, Plane, Player, PlayerState,
+};
+
+const LASER_UNLOCK: Unlock = Unlock::LifetimeKills(300);
+/// Key the laser is recorded under in the profile's unlocks once earned.
+const LASER_UNLOCK_KEY: &str = "weapon:laser";
+
+const LASER_DPS: f32 = 2.5;
+/// How far the beam reaches when it doesn't hit anything.
+const LASER_RANGE: f32 = 900.0;
+const BEAM_WIDTH: f32 = 3.0;
+const BEAM_COLOR: Color = Color::rgb(1.0, 0.25, 0.35);
+/// Over the planes and the jeep, so the beam is never lost behind what it's hitting.
+const BEAM_DEPTH: f32 = 2.0;
+
+/// Heat is kept between 0 and 1. A full second and a half of firing overheats the laser,
+/// and it cools again in a little over two.
+const HEAT_RATE: f32 = 1.0 / 1.5;
+const COOL_RATE: f32 = 1.0 / 2.2;
+/// Once overheated, the laser won't fire again until it's cooled down to this.
+const OVERHEAT_RECOVERY: f32 = 0.3;
+const HEAT_GAUGE_OFFSET: f32 = 40.0;
+const HEAT_GAUGE_HALF_WIDTH: f32 = 18.0;
+const HEAT_GAUGE_COLD: Color = Color::rgb(1.0, 0.85, 0.3);
+const HEAT_GAUGE_HOT: Color = Color::rgb(1.0, 0.2, 0.1);
+
+#[derive(Resource, Default)]
+struct Laser {
+    heat: f32,
+    overheated: bool,
+    firing: bool,
+    /// Whether the beam is on a plane this frame.
+    on_target: bool,
+    /// Whether the burst under way has touched a plane yet, or `None` between bursts.
+    burst: Option<bool>,
+}
+
+/// The beam, stretched from the turret to whatever it hits each frame it's firing.
+#[derive(Component)]
+struct LaserBeam;
+
+pub struct LaserPlugin;
+
+impl Plugin for LaserPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Laser>()
+            .add_systems(Startup, spawn_beam)
+            .add_systems(Update, grant_laser)
+            .add_systems(
+                Update,
+                (heat_laser, fire_laser, count_laser_shots, draw_heat_gauge)
+                    .chain()
+                    .in_set(GameSet::Collision)
+                    .run_if(in_state(GameState::Playing).and_then(not(resource_exists::<Paused>))),
+            )
+            .add_systems(OnExit(GameState::Playing), hide_beam)
+            .add_systems(OnExit(GameState::GameOver), cool_laser);
+    }
+}
+
+fn spawn_beam(mut commands: Commands) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: BEAM_COLOR,
+                anchor: bevy::sprite::Anchor::BottomCenter,
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        LaserBeam,
+    ));
+}
+
+/// The laser is earned like a cosmetic and kept on the profile from then on.
+fn grant_laser(
+    mut profile: ResMut<ActiveProfile>,
+    locale: Res<Locale>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    if !profile.is_changed()
+        || !LASER_UNLOCK.is_met(&profile.0)
+        || profile.0.unlocks.iter().any(|key| key == LASER_UNLOCK_KEY)
+    {
+        return;
+    }
+    profile.0.unlocks.push(LASER_UNLOCK_KEY.to_string());
+    profile.0.save();
+    toast_events.send(ToastEvent(locale.get("laser-unlocked").to_string()));
+}
+
+/// Where a beam from `origin` along `direction` first meets a plane, and which plane.
+fn cast_beam(
+    origin: Vec2,
+    direction: Direction2d,
+    plane_query: &Query<(Entity, &Transform, &Collider), With<Plane>>,
+) -> (Option<Entity>, f32) {
+    let ray = RayCast2d::new(origin, direction, LASER_RANGE);
+    plane_query
+        .iter()
+        .filter_map(|(plane_entity, transform, collider)| {
+            let plane_box = Aabb2d::new(transform.translation.truncate(), collider.half_size);
+            ray.aabb_intersection_at(&plane_box)
+                .map(|distance| (Some(plane_entity), distance))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .unwrap_or((None, LASER_RANGE))
+}
+
+/// Holding the laser action fires it while it's unlocked and not overheated. Firing heats
+/// it up, and letting go cools it down again.
+fn heat_laser(
+    time: Res<Time>,
+    controls: Res<Controls>,
+    profile: Res<ActiveProfile>,
+    mut laser: ResMut<Laser>,
+    player_query: Query<&PlayerState, With<Player>>,
+    locale: Res<Locale>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    laser.firing = false;
+    let trigger = controls.pressed(Action::Laser);
+    if trigger && !profile.0.unlocks.iter().any(|key| key == LASER_UNLOCK_KEY) {
+        if controls.just_pressed(Action::Laser) {
+            toast_events.send(ToastEvent(locale.format(
+                "laser-locked",
+                &[("requirement", &LASER_UNLOCK.describe(&locale))],
+            )));
+        }
+        return;
+    }
+    let alive = matches!(player_query.get_single(), Ok(PlayerState::Alive));
+    if !trigger || !alive || laser.overheated {
+        laser.heat = (laser.heat - COOL_RATE * time.delta_seconds()).max(0.0);
+        if laser.heat <= OVERHEAT_RECOVERY {
+            laser.overheated = false;
+        }
+        return;
+    }
+    laser.firing = true;
+    laser.heat = (laser.heat + HEAT_RATE * time.delta_seconds()).min(1.0);
+    if laser.heat >= 1.0 {
+        laser.overheated = true;
+        toast_events.send(ToastEvent(locale.get("laser-overheated").to_string()));
+    }
+}
+
+/// The beam goes at the locked target if there is one, or else straight up, and burns the
+/// first plane in its path.
+fn fire_laser(
+    time: Res<Time>,
+    mut laser: ResMut<Laser>,
+    target_lock: Res<TargetLock>,
+    muzzle: Muzzle,
+    plane_query: Query<(Entity, &Transform, &Collider), With<Plane>>,
+    mut beam_query: Query<
+        (&mut Transform, &mut Sprite, &mut Visibility),
+        (With<LaserBeam>, Without<Plane>),
+    >,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    let Ok((mut beam_transform, mut beam_sprite, mut beam_visibility)) =
+        beam_query.get_single_mut()
+    else {
+        return;
+    };
+    let Some(muzzle_position) = muzzle.position() else {
+        return;
+    };
+    if !laser.firing {
+        *beam_visibility = Visibility::Hidden;
+        laser.on_target = false;
+        return;
+    }
+
+    let muzzle = muzzle_position.truncate();
+    let direction = target_lock
+        .target
+        .and_then(|target| plane_query.get(target).ok())
+        .and_then(|(_, transform, _)| {
+            Direction2d::new(transform.translation.truncate() - muzzle).ok()
+        })
+        .unwrap_or(Direction2d::Y);
+    let (hit, length) = cast_beam(muzzle, direction, &plane_query);
+    laser.on_target = hit.is_some();
+    if let Some(plane_entity) = hit {
+        damage_events.send(DamageEvent {
+            target: plane_entity,
+            amount: LASER_DPS * time.delta_seconds(),
+            source: DamageSource::Player,
+        });
+    }
+
+    *beam_visibility = Visibility::Visible;
+    beam_sprite.custom_size = Some(Vec2::new(BEAM_WIDTH, length));
+    *beam_transform = Transform::from_translation(muzzle.extend(BEAM_DEPTH))
+        .with_rotation(Quat::from_rotation_arc_2d(Vec2::Y, *direction));
+}
+
+/// Each burst of the beam counts as one shot, and as one hit once it's touched a plane.
+fn count_laser_shots(
+    mut laser: ResMut<Laser>,
+    mut shot_events: EventWriter<ShotFiredEvent>,
+    mut hit_events: EventWriter<ShotHitEvent>,
+) {
+    if !laser.firing {
+        laser.burst = None;
+        return;
+    }
+    if laser.burst.is_none() {
+        shot_events.send(ShotFiredEvent::Laser);
+    }
+    let touched = laser.burst.unwrap_or(false);
+    if laser.on_target && !touched {
+        hit_events.send(ShotHitEvent);
+    }
+    laser.burst = Some(touched || laser.on_target);
+}
+
+/// A bar over the jeep fills and reddens as the laser heats up.
+fn draw_heat_gauge(
+    mut gizmos: Gizmos,
+    laser: Res<Laser>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    if laser.heat <= 0.0 {
+        return;
+    }
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let left = player_transform.translation.truncate()
+        + Vec2::new(-HEAT_GAUGE_HALF_WIDTH, HEAT_GAUGE_OFFSET);
+    let [cold_r, cold_g, cold_b, _] = HEAT_GAUGE_COLD.as_rgba_f32();
+    let [hot_r, hot_g, hot_b, _] = HEAT_GAUGE_HOT.as_rgba_f32();
+    let heat = laser.heat;
+    let color = Color::rgb(
+        cold_r + (hot_r - cold_r) * heat,
+        cold_g + (hot_g - cold_g) * heat,
+        cold_b + (hot_b - cold_b) * heat,
+    );
+    gizmos.line_2d(
+        left,
+        left + Vec2::X * HEAT_GAUGE_HALF_WIDTH * 2.0 * heat,
+        color,
+    );
+}
+
+/// Nothing else switches the beam off once play stops, so it's put away here.
+fn hide_beam(mut beam_query: Query<&mut Visibility, With<LaserBeam>>) {
+    for mut visibility in &mut beam_query {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+fn cool_laser(mut laser: ResMut<Laser>) {
+    *laser = Laser::default();
+}
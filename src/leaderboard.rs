@@ -0,0 +1,262 @@
+use std::{fs, path::Path};
+
+use bevy::{
+    prelude::*,
+    tasks::{block_on, futures_lite::future, IoTaskPool, Task},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{localization::Locale, profile::ActiveProfile, stats::RunStats, GameState};
+
+/// Overridden by `BATTLE_JEEP_LEADERBOARD_URL`.
+const DEFAULT_ENDPOINT: &str = "http://localhost:8080/leaderboard";
+/// Last fetched board and any scores still waiting to be submitted.
+const CACHE_PATH: &str = "leaderboard_cache.json";
+const FETCH_LIMIT: usize = 100;
+/// How many of the fetched entries fit on the game over screen.
+const DISPLAYED_ENTRIES: usize = 10;
+const REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub name: String,
+    pub score: u32,
+}
+
+/// Where scores are submitted and fetched from. `POST`ing an entry submits it and
+/// `GET ?limit=n` returns the top `n` entries as JSON.
+#[derive(Resource)]
+struct LeaderboardConfig {
+    endpoint: String,
+}
+
+impl Default for LeaderboardConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: std::env::var("BATTLE_JEEP_LEADERBOARD_URL")
+                .unwrap_or_else(|_| DEFAULT_ENDPOINT.to_string()),
+        }
+    }
+}
+
+/// The top scores as last seen, plus scores set while the server couldn't be reached.
+/// Both survive restarts through the cache file, so the board still shows something
+/// offline and nothing is lost before it can be sent.
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct Leaderboard {
+    entries: Vec<LeaderboardEntry>,
+    pending: Vec<LeaderboardEntry>,
+    #[serde(skip)]
+    offline: bool,
+}
+
+impl Leaderboard {
+    fn load() -> Self {
+        fs::read_to_string(CACHE_PATH)
+            .ok()
+            .and_then(|cache| serde_json::from_str(&cache).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        match serde_json::to_string(self) {
+            Ok(cache) => {
+                if let Err(error) = fs::write(Path::new(CACHE_PATH), cache) {
+                    warn!("couldn't write leaderboard cache: {error}");
+                }
+            }
+            Err(error) => warn!("couldn't serialize leaderboard cache: {error}"),
+        }
+    }
+}
+
+struct SyncResult {
+    /// How many of the pending scores made it to the server.
+    submitted: usize,
+    entries: Result<Vec<LeaderboardEntry>, String>,
+}
+
+/// A submit-then-fetch round trip running on the IO task pool.
+#[derive(Resource)]
+struct LeaderboardSync(Task<SyncResult>);
+
+#[derive(Component)]
+struct LeaderboardPanel;
+
+#[derive(Component)]
+struct LeaderboardText;
+
+pub struct LeaderboardPlugin;
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LeaderboardConfig>()
+            .insert_resource(Leaderboard::load())
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                (submit_score, spawn_leaderboard_text),
+            )
+            .add_systems(
+                Update,
+                (finish_sync, update_leaderboard_text)
+                    .chain()
+                    .run_if(in_state(GameState::GameOver)),
+            )
+            .add_systems(OnExit(GameState::GameOver), despawn_leaderboard_text);
+    }
+}
+
+/// Queues the run's score and starts syncing with the server.
+fn submit_score(
+    mut commands: Commands,
+    config: Res<LeaderboardConfig>,
+    stats: Res<RunStats>,
+    profile: Res<ActiveProfile>,
+    mut leaderboard: ResMut<Leaderboard>,
+    sync: Option<Res<LeaderboardSync>>,
+) {
+    if stats.score > 0 {
+        leaderboard.pending.push(LeaderboardEntry {
+            name: profile.0.name.clone(),
+            score: stats.score,
+        });
+        leaderboard.save();
+    }
+    // A sync still in flight picks the new score up next time.
+    if sync.is_some() {
+        return;
+    }
+
+    let endpoint = config.endpoint.clone();
+    let pending = leaderboard.pending.clone();
+    let task = IoTaskPool::get().spawn(async move { sync_leaderboard(&endpoint, &pending) });
+    commands.insert_resource(LeaderboardSync(task));
+}
+
+/// Blocking HTTP round trip; only ever run on the IO task pool.
+fn sync_leaderboard(endpoint: &str, pending: &[LeaderboardEntry]) -> SyncResult {
+    let agent = ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build();
+
+    let submitted = pending
+        .iter()
+        .take_while(|entry| agent.post(endpoint).send_json(entry).is_ok())
+        .count();
+    let entries = agent
+        .get(endpoint)
+        .query("limit", &FETCH_LIMIT.to_string())
+        .call()
+        .map_err(|error| error.to_string())
+        .and_then(|response| {
+            response
+                .into_json::<Vec<LeaderboardEntry>>()
+                .map_err(|error| error.to_string())
+        });
+
+    SyncResult { submitted, entries }
+}
+
+fn finish_sync(
+    mut commands: Commands,
+    sync: Option<ResMut<LeaderboardSync>>,
+    mut leaderboard: ResMut<Leaderboard>,
+) {
+    let Some(mut sync) = sync else {
+        return;
+    };
+    let Some(result) = block_on(future::poll_once(&mut sync.0)) else {
+        return;
+    };
+    commands.remove_resource::<LeaderboardSync>();
+
+    let submitted = result.submitted.min(leaderboard.pending.len());
+    leaderboard.pending.drain(..submitted);
+    match result.entries {
+        Ok(mut entries) => {
+            entries.truncate(FETCH_LIMIT);
+            leaderboard.entries = entries;
+            leaderboard.offline = false;
+        }
+        Err(error) => {
+            warn!("leaderboard unavailable, showing cached scores: {error}");
+            leaderboard.offline = true;
+        }
+    }
+    leaderboard.save();
+}
+
+fn spawn_leaderboard_text(mut commands: Commands) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::FlexEnd,
+                padding: UiRect::bottom(Val::Px(32.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(LeaderboardPanel)
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                LeaderboardText,
+            ));
+        });
+}
+
+fn update_leaderboard_text(
+    leaderboard: Res<Leaderboard>,
+    sync: Option<Res<LeaderboardSync>>,
+    locale: Res<Locale>,
+    mut text_query: Query<&mut Text, With<LeaderboardText>>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+
+    let mut board = locale.get("leaderboard-title").to_string();
+    if sync.is_some() {
+        board.push_str(locale.get("leaderboard-updating"));
+    } else if leaderboard.offline {
+        board.push_str(locale.get("leaderboard-offline"));
+    }
+    for (rank, entry) in leaderboard
+        .entries
+        .iter()
+        .take(DISPLAYED_ENTRIES)
+        .enumerate()
+    {
+        board.push_str(&format!(
+            "\n{:>2}. {:<12} {:>7}",
+            rank + 1,
+            entry.name,
+            entry.score
+        ));
+    }
+    if leaderboard.entries.is_empty() {
+        board.push('\n');
+        board.push_str(locale.get("leaderboard-empty"));
+    }
+
+    if text.sections[0].value != board {
+        text.sections[0].value = board;
+    }
+}
+
+fn despawn_leaderboard_text(
+    mut commands: Commands,
+    panel_query: Query<Entity, With<LeaderboardPanel>>,
+) {
+    for panel_entity in &panel_query {
+        commands.entity(panel_entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,189 @@
+use std::{collections::HashMap, fmt::Display, fs};
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{mods::ModPacks, settings::Settings};
+
+/// Every `.ron` file in here is a string table, named after its file stem.
+const LOCALE_DIR: &str = "assets/locales";
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// A language's UI strings as read from its string table.
+#[derive(Deserialize)]
+struct LocaleFile {
+    name: String,
+    strings: HashMap<String, String>,
+}
+
+struct Language {
+    /// File stem of the string table, which is what settings refer to.
+    id: String,
+    name: String,
+    strings: HashMap<String, String>,
+}
+
+/// Every language found on disk, sorted by id.
+#[derive(Resource)]
+pub struct Languages {
+    packs: Vec<Language>,
+}
+
+impl FromWorld for Languages {
+    /// String tables in mod packs are read after the game's own. One for a language that
+    /// is already known adds to its strings and replaces any it has in common.
+    fn from_world(world: &mut World) -> Self {
+        let dirs = world.get_resource::<ModPacks>().map_or_else(
+            || vec![LOCALE_DIR.into()],
+            |packs| packs.data_dirs(LOCALE_DIR),
+        );
+        let mut languages: Vec<Language> = Vec::new();
+        let found = dirs
+            .iter()
+            .flat_map(fs::read_dir)
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? != "ron" {
+                    return None;
+                }
+                let id = path.file_stem()?.to_str()?.to_string();
+                let table = fs::read_to_string(&path).ok()?;
+                match ron::from_str::<LocaleFile>(&table) {
+                    Ok(table) => Some(Language {
+                        id,
+                        name: table.name,
+                        strings: table.strings,
+                    }),
+                    Err(error) => {
+                        warn!("skipping language {}: {error}", path.display());
+                        None
+                    }
+                }
+            });
+        for language in found {
+            match languages.iter_mut().find(|known| known.id == language.id) {
+                Some(known) => {
+                    known.name = language.name;
+                    known.strings.extend(language.strings);
+                }
+                None => languages.push(language),
+            }
+        }
+        languages.sort_by(|a, b| a.id.cmp(&b.id));
+        Self { packs: languages }
+    }
+}
+
+impl Languages {
+    fn get(&self, id: &str) -> Option<&Language> {
+        self.packs.iter().find(|language| language.id == id)
+    }
+
+    fn next_after(&self, id: &str) -> Option<&Language> {
+        let next = self
+            .packs
+            .iter()
+            .position(|language| language.id == id)
+            .map_or(0, |index| (index + 1) % self.packs.len());
+        self.packs.get(next)
+    }
+
+    /// The strings for `id`, with English filling in whatever its table leaves out.
+    fn locale(&self, id: &str) -> Locale {
+        let mut strings = self
+            .get(DEFAULT_LANGUAGE)
+            .map(|english| english.strings.clone())
+            .unwrap_or_default();
+        let language = self.get(id);
+        if let Some(language) = language {
+            strings.extend(language.strings.clone());
+        }
+        Locale {
+            id: id.to_string(),
+            name: language.map_or_else(|| id.to_string(), |language| language.name.clone()),
+            strings,
+        }
+    }
+}
+
+/// The UI strings of the language in use.
+#[derive(Resource)]
+pub struct Locale {
+    id: String,
+    pub name: String,
+    strings: HashMap<String, String>,
+}
+
+impl Locale {
+    /// The string for `key`, or the key itself when no table has it, so a missing string
+    /// shows up on screen rather than as a blank.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings.get(key).map_or(key, String::as_str)
+    }
+
+    /// The string for `key` with each `{name}` in it replaced by its argument.
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        args.iter()
+            .fold(self.get(key).to_string(), |text, (name, value)| {
+                text.replace(&format!("{{{name}}}"), &value.to_string())
+            })
+    }
+}
+
+impl FromWorld for Locale {
+    fn from_world(world: &mut World) -> Self {
+        let id = world
+            .get_resource::<Settings>()
+            .map_or(DEFAULT_LANGUAGE, |settings| settings.language.as_str())
+            .to_string();
+        world.init_resource::<Languages>();
+        world.resource::<Languages>().locale(&id)
+    }
+}
+
+/// Text that shows the string for this key, and changes with the language.
+#[derive(Component)]
+pub struct Localized(pub &'static str);
+
+pub struct LocalizationPlugin;
+
+impl Plugin for LocalizationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Locale>().add_systems(
+            Update,
+            (cycle_language, apply_language, localize_texts).chain(),
+        );
+    }
+}
+
+/// Ctrl + `L` switches to the next language.
+fn cycle_language(
+    key_input: Res<ButtonInput<KeyCode>>,
+    languages: Res<Languages>,
+    mut settings: ResMut<Settings>,
+) {
+    if !key_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+        || !key_input.just_pressed(KeyCode::KeyL)
+    {
+        return;
+    }
+    if let Some(language) = languages.next_after(&settings.language) {
+        settings.language = language.id.clone();
+    }
+}
+
+fn apply_language(settings: Res<Settings>, languages: Res<Languages>, mut locale: ResMut<Locale>) {
+    if locale.id != settings.language {
+        *locale = languages.locale(&settings.language);
+    }
+}
+
+fn localize_texts(locale: Res<Locale>, mut text_query: Query<(&Localized, &mut Text)>) {
+    for (Localized(key), mut text) in &mut text_query {
+        let value = locale.get(key);
+        if text.sections[0].value != value {
+            text.sections[0].value = value.to_string();
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use std::sync::OnceLock;
+
+use bevy::{
+    input::common_conditions::input_just_pressed,
+    log::{
+        tracing_subscriber::{filter::LevelFilter, prelude::*, reload},
+        BoxedSubscriber, Level,
+    },
+    prelude::*,
+};
+
+use crate::{localization::Locale, profile::ProfileNameEntry, toast::ToastEvent};
+
+/// Swaps the level [`reloadable_level`] lets through while the game runs.
+static LEVEL_HANDLE: OnceLock<reload::Handle<LevelFilter, BoxedSubscriber>> = OnceLock::new();
+/// What the log starts at, set from `--log-level` before the log is put together.
+static STARTING_LEVEL: OnceLock<Level> = OnceLock::new();
+
+/// The most verbose log messages shown right now.
+#[derive(Resource)]
+struct LogLevel(Level);
+
+pub struct LogLevelPlugin;
+
+impl Plugin for LogLevelPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LogLevel(starting_level())).add_systems(
+            Update,
+            cycle_log_level.run_if(
+                input_just_pressed(KeyCode::Backquote)
+                    .and_then(not(resource_exists::<ProfileNameEntry>)),
+            ),
+        );
+    }
+}
+
+/// Sets the level the log starts at. Has to be called before the log plugin is built.
+pub fn start_at(level: Level) {
+    STARTING_LEVEL.get_or_init(|| level);
+}
+
+/// `RUST_LOG` takes precedence, so it isn't cut short until the level is changed.
+fn starting_level() -> Level {
+    if std::env::var_os("RUST_LOG").is_some() {
+        return Level::TRACE;
+    }
+    STARTING_LEVEL.get().copied().unwrap_or(Level::INFO)
+}
+
+/// Puts a filter in front of the rest of the log that can be changed while the game runs.
+/// Meant for [`LogPlugin::update_subscriber`](bevy::log::LogPlugin::update_subscriber),
+/// with the log plugin's own level left at `TRACE` so this is the one that counts.
+pub fn reloadable_level(subscriber: BoxedSubscriber) -> BoxedSubscriber {
+    let (filter, handle) = reload::Layer::new(LevelFilter::from_level(starting_level()));
+    LEVEL_HANDLE.get_or_init(|| handle);
+    Box::new(subscriber.with(filter))
+}
+
+/// `` ` `` steps through the levels, from errors only up to everything, then round again.
+fn cycle_log_level(
+    mut log_level: ResMut<LogLevel>,
+    locale: Res<Locale>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    log_level.0 = match log_level.0 {
+        Level::ERROR => Level::WARN,
+        Level::WARN => Level::INFO,
+        Level::INFO => Level::DEBUG,
+        Level::DEBUG => Level::TRACE,
+        _ => Level::ERROR,
+    };
+    if let Some(handle) = LEVEL_HANDLE.get() {
+        if let Err(error) = handle.reload(LevelFilter::from_level(log_level.0)) {
+            warn!("couldn't change the log level: {error}");
+            return;
+        }
+    }
+    toast_events.send(ToastEvent(locale.format(
+        "log-level",
+        &[("level", &log_level.0.as_str().to_lowercase())],
+    )));
+}
@@ -1,142 +1,776 @@
+// Systems must never panic because an entity they expect is missing (the player can be
+// despawned, the window closed, ...). Use `get_single()` with `let ... else` or a run
+// condition instead of unwrapping query results.
+#![deny(clippy::unwrap_used)]
+// Bevy queries and system params routinely trip this lint.
+#![allow(clippy::type_complexity)]
+
+mod accessibility;
+mod ai;
+mod ambience;
+mod armor;
+mod audio_cues;
+mod balloons;
+mod bomber;
+mod bonus;
+mod camera;
+mod cli;
+mod clouds;
+mod coast;
+mod config;
+mod controls;
+mod convoys;
+mod cosmetics;
+mod crash;
+mod craters;
+mod debris;
+#[cfg(feature = "dev-tools")]
+mod dev_tools;
+mod director;
+#[cfg(feature = "discord")]
+mod discord;
+mod drone;
+mod escort;
+mod explosion;
+mod flak;
+mod flares;
+mod fortress;
+mod game_over;
+mod ghost;
+mod grading;
+mod ground_units;
+mod health;
+mod heat_haze;
+mod hit_flash;
+mod hit_stop;
+mod hud;
+mod impacts;
+mod jammer;
+mod jeep_motion;
+mod laser;
+#[cfg(feature = "online")]
+mod leaderboard;
+mod localization;
+mod log_level;
+mod mines;
+mod missile;
+mod mods;
+mod night;
+mod palette;
+mod pause;
+mod perfect_wave;
+mod photo_mode;
+mod profile;
+mod props;
+mod quit;
+mod rank;
+mod repairs;
+mod rng;
+mod score_popups;
+mod settings;
+mod smoke_screen;
+mod smoke_trails;
+mod snapshot;
+mod sound;
+mod stats;
+mod stats_screen;
+mod streaks;
+mod supply;
+mod targeting;
+mod telemetry;
+mod terrain;
+mod theme;
+mod timers;
+mod toast;
+mod trails;
+mod turrets;
+mod tween;
+mod warnings;
+mod wave_events;
+
+use accessibility::{AccessibilityPlugin, AccessibilitySettings};
+use ai::{AiPlugin, EnemyAi};
+use ambience::AmbiencePlugin;
+use armor::ArmorPlugin;
+use audio_cues::AudioCuesPlugin;
+use balloons::BalloonsPlugin;
 use bevy::{
-    math::bounding::{Aabb2d, Bounded2d, BoundingVolume, IntersectsVolume},
+    ecs::system::SystemParam,
+    math::bounding::{Aabb2d, BoundingCircle, BoundingVolume, IntersectsVolume},
     prelude::*,
-    window::PrimaryWindow,
 };
-use bevy_rapier2d::na::ComplexField;
+use bomber::BomberPlugin;
+use bonus::BonusPlugin;
+use camera::{CameraPlugin, OFFSCREEN_MARGIN};
+use clap::Parser;
+use cli::Cli;
+use clouds::CloudsPlugin;
+use coast::{CoastPlugin, Gunboat};
+use config::{ConfigPlugin, GameConfig};
+use controls::{Action, Controls, ControlsPlugin};
+use convoys::{Civilian, ConvoysPlugin};
+use cosmetics::{Cosmetics, CosmeticsPlugin};
+use crash::CrashPlugin;
+use craters::{crater_speed_factor, CratersPlugin};
+use debris::{DebrisEvent, DebrisPlugin};
+#[cfg(feature = "dev-tools")]
+use dev_tools::DevToolsPlugin;
+use director::{DirectorPlugin, EnemyKind, SpawnEvent};
+#[cfg(feature = "discord")]
+use discord::DiscordPlugin;
+use drone::DronePlugin;
+use escort::EscortPlugin;
+use explosion::{ExplosionEvent, ExplosionPlugin};
+use flak::FlakPlugin;
+use flares::FlaresPlugin;
+use fortress::FortressPlugin;
+use game_over::GameOverPlugin;
+use ghost::GhostPlugin;
+use grading::{GradingPlugin, ShotHitEvent};
+use ground_units::{GroundUnit, GroundUnitsPlugin};
+use health::{Boss, Health, HealthPlugin};
+use heat_haze::HeatHazePlugin;
+use hit_flash::{HitFlashEvent, HitFlashPlugin};
+use hit_stop::HitStopPlugin;
+use hud::HudPlugin;
+use impacts::ImpactsPlugin;
+use jammer::JammerPlugin;
+use jeep_motion::{JeepMotionPlugin, ShotFiredEvent};
+use laser::LaserPlugin;
+#[cfg(feature = "online")]
+use leaderboard::LeaderboardPlugin;
+use localization::LocalizationPlugin;
+use log_level::LogLevelPlugin;
+use mines::MinesPlugin;
+use missile::MissilePlugin;
+use mods::ModsPlugin;
+use night::{InDarkness, NightPlugin};
+use palette::{Outlined, PalettePlugin};
+use pause::{PausePlugin, Paused};
+use perfect_wave::PerfectWavePlugin;
+use photo_mode::PhotoModePlugin;
+use profile::ProfilePlugin;
+use props::PropsPlugin;
+use quit::QuitPlugin;
 use rand::prelude::*;
+use rank::RankPlugin;
+use repairs::RepairsPlugin;
+use rng::GameRng;
+use score_popups::{ScorePopupEvent, ScorePopupsPlugin};
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsPlugin};
+use smoke_screen::SmokeScreenPlugin;
+use smoke_trails::SmokeTrailsPlugin;
+use snapshot::SnapshotPlugin;
+use sound::SoundPlugin;
+use stats::{RunStats, StatsPlugin};
+use stats_screen::StatsScreenPlugin;
+use std::collections::VecDeque;
+use streaks::StreaksPlugin;
+use supply::SupplyPlugin;
+use targeting::TargetingPlugin;
+use telemetry::TelemetryPlugin;
+use terrain::{Terrain, TerrainPlugin};
+use theme::{Theme, ThemePlugin, Themes};
+use timers::{BombTimer, TimersPlugin};
+use toast::ToastPlugin;
+use trails::TrailsPlugin;
+use turrets::TurretsPlugin;
+use warnings::WarningsPlugin;
+use wave_events::WaveEventsPlugin;
 
-#[derive(Component)]
+const SKY_COLOR: Color = Color::rgb(0.53, 0.75, 0.92);
+/// Size of the 16:9 battlefield in world units. It is scaled to fit the window, so the
+/// game plays the same whatever the window's size or shape.
+const PLAY_AREA: Vec2 = Vec2::new(1280.0, 720.0);
+const PLAYER_LIVES: u32 = 3;
+const PLAYER_HEALTH: f32 = 3.0;
+const GROUND_HEIGHT: f32 = 0.0;
+const PLAYER_HALF_SIZE: Vec2 = Vec2::splat(32.0);
+const ROCKET_HALF_SIZE: Vec2 = Vec2::splat(4.0);
+/// Top of the turret barrel on the jeep sprite, before the jeep is scaled up. The
+/// [`Turret`] is put here, and shots leave from it rather than from the middle of the jeep.
+const TURRET_OFFSET: Vec3 = Vec3::new(0.0, 16.0, 0.0);
+const MUZZLE_FLASH_SIZE: Vec2 = Vec2::new(6.0, 8.0);
+const MUZZLE_FLASH_COLOR: Color = Color::rgb(1.0, 0.9, 0.4);
+const MUZZLE_FLASH_DURATION: f32 = 0.06;
+const BOMB_HALF_SIZE: Vec2 = Vec2::splat(16.0);
+const PLANE_HEALTH: f32 = 2.0;
+/// How far below the top of the play area planes fly.
+const PLANE_FLIGHT_OFFSET: f32 = 100.0;
+/// Planes this far outside the play area have left the battle and are despawned.
+const PLANE_DESPAWN_MARGIN: f32 = 64.0;
+/// Planes start this far past the right edge so the warning arrows show before they arrive.
+const PLANE_ENTRY_MARGIN: f32 = 150.0;
+/// Planes come in on one of this many lanes, stacked down from the flight altitude.
+const PLANE_LANES: usize = 3;
+const PLANE_LANE_SPACING: f32 = 72.0;
+/// A lane is taken while a plane on it is still this close to where planes come in.
+const PLANE_ENTRY_SPACING: f32 = 160.0;
+const ROCKET_DAMAGE: f32 = 1.0;
+const BOMB_DIRECT_HIT_DAMAGE: f32 = PLAYER_HEALTH;
+const BOMB_BLAST_RADIUS: f32 = 80.0;
+const BOMB_BLAST_DAMAGE: f32 = 1.0;
+/// Planes shot down further than this from the jeep score a long-shot bonus...
+const LONG_SHOT_DISTANCE: f32 = 350.0;
+/// ...of this many points for every pixel beyond it.
+const LONG_SHOT_POINTS: f32 = 0.5;
+
+#[derive(States, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum GameState {
+    #[default]
+    Playing,
+    GameOver,
+}
+
+/// Stages of a frame of gameplay, run in this order so that what the player does, what
+/// spawns and what moves is all settled before anything is checked for hits, and the
+/// fallout of those hits is dealt with last. Commands are applied between stages.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+enum GameSet {
+    /// Reading the player's controls.
+    Input,
+    Spawning,
+    Movement,
+    Collision,
+    /// Damage, deaths, respawns and clearing out whatever has left the battle.
+    Cleanup,
+}
+
+/// Conditions for the current run.
+#[derive(Resource, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct Mission {
+    /// Night missions darken the sky and only planes in the searchlight can be hit.
+    night: bool,
+    /// Coastal missions run along the shore, with gunboats shelling the beach.
+    coastal: bool,
+    /// Escort missions follow a convoy along a fixed route, and end when it gets through
+    /// or is wiped out.
+    escort: bool,
+}
+
+#[derive(Component, Reflect, Clone, Serialize, Deserialize)]
 struct Player {
     movement_speed: f32,
+    jump_speed: f32,
+    /// Positive to the right. The jeep speeds up and slows down rather than changing
+    /// speed at once.
+    #[serde(default)]
+    horizontal_velocity: f32,
+    /// Driving against its own momentum, so it slides before it turns round.
+    #[serde(default)]
+    skidding: bool,
+    vertical_velocity: f32,
+    grounded: bool,
+    lives: u32,
+}
+
+#[derive(Component, Reflect, Default, Clone, Serialize, Deserialize)]
+enum PlayerState {
+    #[default]
+    Alive,
+    /// Blown up and waiting to respawn; input is ignored until the timer finishes.
+    Dead(Timer),
 }
 
+/// The jeep's gun mount, a child of the jeep so it follows it over the dunes.
 #[derive(Component)]
+struct Turret;
+
+#[derive(Component)]
+struct MuzzleFlash {
+    lifetime: Timer,
+}
+
+#[derive(Component, Reflect, Clone, Serialize, Deserialize)]
 struct Rocket {
     movement_speed: f32,
 }
 
-#[derive(Component)]
+#[derive(Component, Reflect, Clone, Serialize, Deserialize)]
 struct Plane {
-    movement_speed: f32,
-    bomb_spawn_timer: Timer,
-    number_of_bombs: i32,
+    /// Bombs left to drop. Once they're gone the plane just flies on.
+    number_of_bombs: u32,
 }
 
-#[derive(Component)]
+/// Aircraft the director has asked for that are yet to take off, and the lanes the ones
+/// about to spawn this frame are coming in on.
+#[derive(Resource, Default)]
+struct PlaneLaunches {
+    waiting: VecDeque<EnemyKind>,
+    ready: Vec<(EnemyKind, f32)>,
+}
+
+#[derive(Component, Reflect, Clone, Serialize, Deserialize)]
 struct Bomb {
     falling_speed: f32,
 }
 
-#[derive(Resource)]
-struct PlaneSpawnTimer {
-    timer: Timer,
+#[derive(Component, Clone, Serialize, Deserialize)]
+struct Collider {
+    half_size: Vec2,
 }
 
-impl Default for PlaneSpawnTimer {
-    fn default() -> Self {
-        Self {
-            timer: Timer::from_seconds(2.0, TimerMode::Repeating),
-        }
-    }
+/// A rocket struck a plane.
+#[derive(Event)]
+struct CollisionEvent {
+    target: Entity,
+    /// Where on the target the rocket struck.
+    point: Vec2,
+    side: Collision,
 }
 
-#[derive(Component)]
-struct Collider;
+/// Whose side a hit or a blast came from, so the player is only held to account for what
+/// their own weapons did.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum DamageSource {
+    Player,
+    Enemy,
+}
+
+/// Damage dealt to an enemy or a civilian, by the player's weapons or by a blast.
+#[derive(Event)]
+struct DamageEvent {
+    target: Entity,
+    amount: f32,
+    source: DamageSource,
+}
 
-#[derive(Event, Default)]
-struct CollisionEvent;
+/// Sent when a bomb reaches the ground, before it goes off.
+#[derive(Event)]
+struct BombImpactEvent {
+    position: Vec2,
+}
+
+#[derive(Event)]
+struct PlayerDamageEvent {
+    amount: f32,
+    cause: DamageCause,
+}
+
+/// Sent when the player's weapons destroy something.
+#[derive(Event)]
+struct EnemyKilledEvent {
+    position: Vec2,
+    is_plane: bool,
+    is_civilian: bool,
+    is_boss: bool,
+    source: DamageSource,
+}
+
+/// What hurt the jeep.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+enum DamageCause {
+    BombHit,
+    BombBlast,
+    Infantry,
+    Shelling,
+    Barrel,
+    Mine,
+    BossMissile,
+}
+
+/// Sent when the jeep loses a life, with whatever hit it hardest that frame.
+#[derive(Event)]
+struct PlayerKilledEvent {
+    cause: DamageCause,
+}
 
 fn main() {
-    App::new()
-        .add_plugins(DefaultPlugins)
-        .init_resource::<PlaneSpawnTimer>()
-        .add_event::<CollisionEvent>()
-        .add_systems(Startup, (setup_camera, spawn_player))
-        .add_systems(
-            Update,
+    let cli = Cli::parse();
+    let mut app = App::new();
+    // The panic hook goes in first, so crashes while the rest starts up are reported too.
+    app.add_plugins(CrashPlugin);
+    // Mod packs change where assets are read from, so they go in before the asset server.
+    app.add_plugins(ModsPlugin);
+    // Spawners roll their first intervals as they're added, so the seed goes in first.
+    app.insert_resource(cli.rng());
+    app.add_plugins((
+        AccessibilityPlugin,
+        AudioCuesPlugin,
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: Some(cli.window()),
+                ..default()
+            })
+            .set(cli.log()),
+        ConfigPlugin,
+        ControlsPlugin,
+        LocalizationPlugin,
+        LogLevelPlugin,
+        PalettePlugin,
+        PausePlugin,
+        PhotoModePlugin,
+        QuitPlugin,
+        SettingsPlugin,
+        SnapshotPlugin,
+        TimersPlugin,
+        ToastPlugin,
+    ))
+    // Enemies and the rules of the fight.
+    .add_plugins((
+        AiPlugin,
+        ConvoysPlugin,
+        CosmeticsPlugin,
+        DirectorPlugin,
+        FlaresPlugin,
+        GameOverPlugin,
+        GhostPlugin,
+        GroundUnitsPlugin,
+        HealthPlugin,
+        MissilePlugin,
+        ProfilePlugin,
+        RankPlugin,
+        StatsPlugin,
+        TargetingPlugin,
+        TelemetryPlugin,
+    ))
+    // Missions and the special events during them.
+    .add_plugins((
+        BomberPlugin,
+        BonusPlugin,
+        CoastPlugin,
+        EscortPlugin,
+        FortressPlugin,
+        JammerPlugin,
+        MinesPlugin,
+        NightPlugin,
+        RepairsPlugin,
+        SupplyPlugin,
+        WaveEventsPlugin,
+    ))
+    // Scenery and effects.
+    .add_plugins((
+        AmbiencePlugin,
+        BalloonsPlugin,
+        CloudsPlugin,
+        CratersPlugin,
+        DebrisPlugin,
+        ExplosionPlugin,
+        HeatHazePlugin,
+        HitFlashPlugin,
+        ImpactsPlugin,
+        JeepMotionPlugin,
+        PropsPlugin,
+        SmokeTrailsPlugin,
+        TerrainPlugin,
+        ThemePlugin,
+        TrailsPlugin,
+    ))
+    // Camera and UI.
+    .add_plugins((
+        CameraPlugin,
+        HudPlugin,
+        ScorePopupsPlugin,
+        StatsScreenPlugin,
+        WarningsPlugin,
+    ))
+    // The jeep's upgrades and kit.
+    .add_plugins((
+        ArmorPlugin,
+        DronePlugin,
+        FlakPlugin,
+        LaserPlugin,
+        SmokeScreenPlugin,
+        TurretsPlugin,
+    ))
+    // Streaks and other rewards for playing well.
+    .add_plugins((
+        GradingPlugin,
+        HitStopPlugin,
+        PerfectWavePlugin,
+        StreaksPlugin,
+    ))
+    // Sound.
+    .add_plugins(SoundPlugin)
+    .insert_resource(ClearColor(SKY_COLOR))
+    .init_state::<GameState>()
+    .insert_resource(cli.mission())
+    .add_event::<CollisionEvent>()
+    .add_event::<DamageEvent>()
+    .add_event::<PlayerDamageEvent>()
+    .add_event::<BombImpactEvent>()
+    .add_event::<EnemyKilledEvent>()
+    .add_event::<PlayerKilledEvent>()
+    .init_resource::<PlaneLaunches>()
+    .add_systems(Startup, spawn_player)
+    .configure_sets(
+        Update,
+        (
+            GameSet::Input,
+            GameSet::Spawning,
+            GameSet::Movement,
+            GameSet::Collision,
+            GameSet::Cleanup,
+        )
+            .chain(),
+    )
+    .add_systems(
+        Update,
+        (
+            fire_rocket
+                .run_if(run_if_rocket_slot_free)
+                .in_set(GameSet::Input),
+            ((pick_plane_lanes, spawn_planes).chain(), spawn_bombs).in_set(GameSet::Spawning),
             (
-                move_player,
-                fire_rocket,
-                spawn_planes,
-                spawn_bombs,
-                plane_spawn_timer_update,
-                bomb_spawn_timer_update,
-                plane_update.run_if(run_if_planes),
-                bomb_spawn_timer_update.run_if(run_if_planes),
+                apply_player_tuning,
+                (move_player, player_vertical_movement).chain(),
                 rocket_update.run_if(run_if_rockets),
                 update_bombs.run_if(run_if_bombs),
-            ),
+            )
+                .in_set(GameSet::Movement),
+            (
+                rocket_collision.run_if(run_if_rockets_and_targets),
+                bomb_collision.run_if(run_if_bombs),
+                detonate_bombs,
+            )
+                .in_set(GameSet::Collision),
+            (
+                (apply_damage, destroy_enemies).chain(),
+                damage_player,
+                plane_update.run_if(run_if_planes),
+                update_player_state,
+                update_muzzle_flashes,
+            )
+                .in_set(GameSet::Cleanup),
         )
-        .add_systems(FixedUpdate, rocket_collision.run_if(run_if_rockets_and_planes))
-        .run();
-}
-
-fn setup_camera(mut commands: Commands, window_query: Query<&Window, With<PrimaryWindow>>) {
-    let window = window_query.get_single().unwrap();
-
-    commands.spawn(Camera2dBundle {
-        transform: Transform::from_xyz(window.width() / 2.0, window.height() / 2.0, 0.0),
-        ..Default::default()
-    });
+            .run_if(in_state(GameState::Playing).and_then(not(resource_exists::<Paused>))),
+    )
+    .add_systems(OnExit(GameState::GameOver), reset_run);
+    if let Some(terrain) = cli.terrain() {
+        app.insert_resource(terrain);
+    }
+    #[cfg(feature = "online")]
+    app.add_plugins(LeaderboardPlugin);
+    #[cfg(feature = "dev-tools")]
+    app.add_plugins(DevToolsPlugin);
+    #[cfg(feature = "discord")]
+    app.add_plugins(DiscordPlugin);
+    app.run();
 }
 
 fn spawn_player(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    window_query: Query<&Window, With<PrimaryWindow>>,
+    terrain: Res<Terrain>,
+    cosmetics: Res<Cosmetics>,
+    config: Res<GameConfig>,
 ) {
-    let window = window_query.get_single().unwrap();
-    commands.spawn((
-        SpriteBundle {
-            texture: asset_server.load("../assets/jeep.png"),
-            transform: Transform::from_xyz(window.width() / 2.0, 32.0, 0.0)
+    let spawn_x = PLAY_AREA.x / 2.0;
+    let skin = cosmetics.jeep();
+    commands
+        .spawn((
+            SpriteBundle {
+                texture: asset_server.load(skin.texture),
+                sprite: Sprite {
+                    color: skin.tint,
+                    ..default()
+                },
+                transform: Transform::from_xyz(
+                    spawn_x,
+                    terrain.height_at(spawn_x) + PLAYER_HALF_SIZE.y,
+                    0.0,
+                )
                 .with_scale(Vec3::new(2.0, 2.0, 0.0)),
-            ..default()
-        },
-        Player {
-            movement_speed: 500.0,
-        },
-        Collider,
-    ));
+                ..default()
+            },
+            Player {
+                movement_speed: config.player_speed,
+                jump_speed: config.player_jump_speed,
+                horizontal_velocity: 0.0,
+                skidding: false,
+                vertical_velocity: 0.0,
+                grounded: true,
+                lives: PLAYER_LIVES,
+            },
+            PlayerState::Alive,
+            Health::new(PLAYER_HEALTH),
+            Collider {
+                half_size: PLAYER_HALF_SIZE,
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                SpatialBundle::from_transform(Transform::from_translation(TURRET_OFFSET)),
+                Turret,
+            ));
+        });
+}
+
+/// Where shots leave the jeep. Read off the turret itself, so the muzzle is wherever the
+/// jeep's mount actually is.
+#[derive(SystemParam)]
+struct Muzzle<'w, 's> {
+    player_query: Query<'w, 's, &'static PlayerState, With<Player>>,
+    turret_query: Query<'w, 's, &'static GlobalTransform, With<Turret>>,
 }
 
+impl Muzzle<'_, '_> {
+    /// The muzzle in world space, or `None` while the jeep is down.
+    fn position(&self) -> Option<Vec3> {
+        let Ok(PlayerState::Alive) = self.player_query.get_single() else {
+            return None;
+        };
+        self.turret_query
+            .get_single()
+            .ok()
+            .map(GlobalTransform::translation)
+    }
+}
+
+/// Picks up edits to the jeep's tuning in the game config.
+fn apply_player_tuning(config: Res<GameConfig>, mut player_query: Query<&mut Player>) {
+    if !config.is_changed() {
+        return;
+    }
+    for mut player in &mut player_query {
+        player.movement_speed = config.player_speed;
+        player.jump_speed = config.player_jump_speed;
+    }
+}
+
+/// The jeep has some weight to it: it builds up to speed, rolls to a stop and skids when
+/// it's driven the other way, all at rates taken from the config. Craters bog it down.
 fn move_player(
-    mut player_query: Query<(&mut Transform, &Player), With<Player>>,
-    key_input: Res<ButtonInput<KeyCode>>,
+    mut player_query: Query<(&mut Transform, &mut Player, &PlayerState), With<Player>>,
+    controls: Res<Controls>,
     time: Res<Time>,
+    config: Res<GameConfig>,
+    terrain: Res<Terrain>,
 ) {
-    let (mut player_transform, player) = player_query.get_single_mut().unwrap();
+    let Ok((mut player_transform, mut player, PlayerState::Alive)) = player_query.get_single_mut()
+    else {
+        return;
+    };
 
     let mut direction = 0.0;
-    if key_input.pressed(KeyCode::ArrowLeft) {
+    if controls.pressed(Action::MoveLeft) {
         direction += -1.0;
     }
-    if key_input.pressed(KeyCode::ArrowRight) {
+    if controls.pressed(Action::MoveRight) {
         direction += 1.0;
     }
-    player_transform.translation.x += player.movement_speed * direction * time.delta_seconds();
+    let traction = if player.grounded {
+        crater_speed_factor(&terrain, player_transform.translation.x)
+    } else {
+        1.0
+    };
+    let target = player.movement_speed * traction * direction;
+    let velocity = player.horizontal_velocity;
+    player.skidding = direction != 0.0 && velocity * direction < 0.0;
+    let rate = if player.skidding {
+        config.player_skid_deceleration
+    } else if direction == 0.0 || velocity.abs() > target.abs() {
+        config.player_deceleration
+    } else {
+        config.player_acceleration
+    };
+    let change =
+        (target - velocity).clamp(-rate * time.delta_seconds(), rate * time.delta_seconds());
+    player.horizontal_velocity += change;
+    player_transform.translation.x += player.horizontal_velocity * time.delta_seconds();
+}
+
+/// Keeps the jeep glued to the terrain while grounded and handles jumping off it.
+fn player_vertical_movement(
+    mut player_query: Query<(&mut Transform, &mut Player, &PlayerState), With<Player>>,
+    controls: Res<Controls>,
+    time: Res<Time>,
+    terrain: Res<Terrain>,
+    config: Res<GameConfig>,
+) {
+    let Ok((mut player_transform, mut player, player_state)) = player_query.get_single_mut() else {
+        return;
+    };
+
+    let x = player_transform.translation.x;
+    let ground_y = terrain.height_at(x) + PLAYER_HALF_SIZE.y;
+    if player.grounded {
+        if matches!(player_state, PlayerState::Alive) && controls.just_pressed(Action::Jump) {
+            player.vertical_velocity = player.jump_speed;
+            player.grounded = false;
+        } else {
+            player_transform.translation.y = ground_y;
+            player_transform.rotation = Quat::from_rotation_z(terrain.angle_at(x));
+            return;
+        }
+    }
+
+    player.vertical_velocity -= config.player_gravity * time.delta_seconds();
+    player_transform.translation.y += player.vertical_velocity * time.delta_seconds();
+    if player_transform.translation.y <= ground_y {
+        player_transform.translation.y = ground_y;
+        player.vertical_velocity = 0.0;
+        player.grounded = true;
+    }
 }
 
 fn fire_rocket(
-    player_query: Query<&Transform, With<Player>>,
+    muzzle: Muzzle,
+    turret_query: Query<Entity, With<Turret>>,
     mut commands: Commands,
-    key_input: Res<ButtonInput<KeyCode>>,
+    mut controls: ResMut<Controls>,
     asset_server: Res<AssetServer>,
+    config: Res<GameConfig>,
+    mut shot_events: EventWriter<ShotFiredEvent>,
 ) {
-    let player_transform = player_query.get_single().unwrap();
-    let player_loc: Vec3 = player_transform.translation;
-    if key_input.just_pressed(KeyCode::Space) {
-        commands.spawn((
-            SpriteBundle {
-                texture: asset_server.load("../assets/rocket.png"),
-                transform: Transform::from_translation(player_loc),
-                ..default()
-            },
-            Rocket {
-                movement_speed: 600.0,
-            },
-        ));
+    let Some(muzzle_position) = muzzle.position() else {
+        return;
+    };
+    if !controls.buffered(Action::Fire) {
+        return;
+    }
+    controls.consume(Action::Fire);
+
+    commands.spawn((
+        rocket_sprite(&asset_server, Transform::from_translation(muzzle_position)),
+        Rocket {
+            movement_speed: config.rocket_speed,
+        },
+    ));
+    shot_events.send(ShotFiredEvent::Rocket);
+    if let Ok(turret_entity) = turret_query.get_single() {
+        commands.entity(turret_entity).with_children(|parent| {
+            parent.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: MUZZLE_FLASH_COLOR,
+                        custom_size: Some(MUZZLE_FLASH_SIZE),
+                        ..default()
+                    },
+                    ..default()
+                },
+                MuzzleFlash {
+                    lifetime: Timer::from_seconds(MUZZLE_FLASH_DURATION, TimerMode::Once),
+                },
+            ));
+        });
+    }
+}
+
+fn update_muzzle_flashes(
+    mut commands: Commands,
+    time: Res<Time>,
+    accessibility: Res<AccessibilitySettings>,
+    mut flash_query: Query<(Entity, &mut MuzzleFlash, &mut Visibility)>,
+) {
+    for (flash_entity, mut flash, mut visibility) in &mut flash_query {
+        if !accessibility.flashes {
+            *visibility = Visibility::Hidden;
+        }
+        flash.lifetime.tick(time.delta());
+        if flash.lifetime.finished() {
+            commands.entity(flash_entity).despawn_recursive();
+        }
+    }
+}
+
+fn rocket_sprite(asset_server: &AssetServer, transform: Transform) -> SpriteBundle {
+    SpriteBundle {
+        texture: asset_server.load("../assets/rocket.png"),
+        transform,
+        ..default()
     }
 }
 
@@ -144,11 +778,9 @@ fn rocket_update(
     mut commands: Commands,
     time: Res<Time>,
     mut rocket_query: Query<(&mut Transform, Entity, &Rocket), With<Rocket>>,
-    window_query: Query<&Window, With<PrimaryWindow>>,
 ) {
-    let window = window_query.get_single().unwrap();
     for (mut rocket_transform, rocket_entity, rocket) in &mut rocket_query {
-        if rocket_transform.translation.y < window.height() {
+        if rocket_transform.translation.y < PLAY_AREA.y + OFFSCREEN_MARGIN.y {
             rocket_transform.translation.y += rocket.movement_speed * time.delta_seconds();
         } else {
             commands.entity(rocket_entity).despawn();
@@ -156,43 +788,144 @@ fn rocket_update(
     }
 }
 
+/// Where a new plane appears, before its altitude is picked.
+fn plane_entry_x() -> f32 {
+    PLAY_AREA.x + OFFSCREEN_MARGIN.x + PLANE_ENTRY_MARGIN
+}
+
+/// Gives each plane the director asks for a lane nobody else is coming in on, highest
+/// first. When every lane is busy the plane waits its turn, rather than spawning on top
+/// of another one.
+fn pick_plane_lanes(
+    mut spawn_events: EventReader<SpawnEvent>,
+    mut launches: ResMut<PlaneLaunches>,
+    plane_query: Query<&Transform, With<Plane>>,
+) {
+    let aircraft = spawn_events
+        .read()
+        .map(|event| event.0)
+        .filter(|kind| kind.is_aircraft());
+    launches.waiting.extend(aircraft);
+    launches.ready.clear();
+
+    let entry_x = plane_entry_x();
+    while let Some(&kind) = launches.waiting.front() {
+        let free_lane = (0..PLANE_LANES)
+            .map(|lane| PLAY_AREA.y - PLANE_FLIGHT_OFFSET - lane as f32 * PLANE_LANE_SPACING)
+            .find(|&altitude| {
+                let on_lane = |y: f32| (y - altitude).abs() < PLANE_LANE_SPACING / 2.0;
+                !launches.ready.iter().any(|&(_, picked)| on_lane(picked))
+                    && !plane_query.iter().any(|plane_transform| {
+                        (plane_transform.translation.x - entry_x).abs() < PLANE_ENTRY_SPACING
+                            && on_lane(plane_transform.translation.y)
+                    })
+            });
+        let Some(altitude) = free_lane else {
+            break;
+        };
+        launches.ready.push((kind, altitude));
+        launches.waiting.pop_front();
+    }
+}
+
 fn spawn_planes(
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    window_query: Query<&Window, With<PrimaryWindow>>,
-    plane_spawn_timer: Res<PlaneSpawnTimer>,
+    plane_sprites: PlaneSprites,
+    launches: Res<PlaneLaunches>,
+    config: Res<GameConfig>,
+    mut rng: ResMut<GameRng>,
 ) {
-    let window = window_query.get_single().unwrap();
-    if plane_spawn_timer.timer.finished() {
+    let rng = &mut rng.0;
+    for &(kind, altitude) in &launches.ready {
+        if kind != EnemyKind::Plane {
+            continue;
+        }
+        let transform = Transform::from_xyz(plane_entry_x(), altitude, 0.0)
+            .with_scale(Vec3::new(2.0, 2.0, 0.0));
         commands.spawn((
-            SpriteBundle {
-                texture: asset_server.load("../assets/plane.png"),
-                transform: Transform::from_xyz(window.width(), window.height() - 100.0, 0.0)
-                    .with_scale(Vec3::new(2.0, 2.0, 0.0)),
-                ..default()
-            },
+            plane_sprites.sprite(transform),
             Plane {
-                movement_speed: 100.0,
-                bomb_spawn_timer: Timer::from_seconds(2.0, TimerMode::Repeating),
-                number_of_bombs: 1,
+                number_of_bombs: spread_count(rng, config.plane_bombs),
+            },
+            BombTimer::new(spread(
+                rng,
+                config.plane_bomb_interval,
+                config.plane_bomb_interval_spread,
+            )),
+            EnemyAi::new(
+                spread(rng, config.plane_speed, config.plane_speed_spread),
+                altitude,
+            ),
+            Health::new(PLANE_HEALTH),
+            Collider {
+                half_size: Vec2::splat(32.0),
             },
-            Collider,
         ));
     }
 }
 
-fn plane_update(
-    mut commands: Commands,
-    time: Res<Time>,
-    mut plane_query: Query<(&mut Transform, Entity, &Plane), With<Plane>>,
-    window_query: Query<&Window, With<PrimaryWindow>>,
-) {
-    let window = window_query.get_single().unwrap();
-    for (mut plane_transform, plane_entity, plane) in &mut plane_query {
-        if plane_transform.translation.y < window.height() {
-            plane_transform.translation.x -= plane.movement_speed * time.delta_seconds();
-        } else {
-            commands.entity(plane_entity).despawn();
+/// `value`, off by up to `share` of itself either way.
+fn spread(rng: &mut impl Rng, value: f32, share: f32) -> f32 {
+    let share = share.clamp(0.0, 1.0);
+    value * rng.gen_range(1.0 - share..=1.0 + share)
+}
+
+/// Anything from the first to the second of `range`, whichever way round they're given.
+fn spread_count(rng: &mut impl Rng, (low, high): (u32, u32)) -> u32 {
+    rng.gen_range(low.min(high)..=low.max(high))
+}
+
+/// Everything a plane's sprite is made from, for systems that spawn them.
+#[derive(SystemParam)]
+struct PlaneSprites<'w> {
+    asset_server: Res<'w, AssetServer>,
+    cosmetics: Res<'w, Cosmetics>,
+    settings: Res<'w, Settings>,
+    themes: Res<'w, Themes>,
+}
+
+impl PlaneSprites<'_> {
+    fn sprite(&self, transform: Transform) -> SpriteBundle {
+        plane_sprite(
+            &self.asset_server,
+            &self.cosmetics,
+            self.themes.get(&self.settings.theme),
+            transform,
+        )
+    }
+}
+
+/// Planes wear the selected livery, unless the theme swaps in its own texture.
+fn plane_sprite(
+    asset_server: &AssetServer,
+    cosmetics: &Cosmetics,
+    theme: &Theme,
+    transform: Transform,
+) -> SpriteBundle {
+    let livery = cosmetics.plane();
+    let texture = theme
+        .plane_texture
+        .clone()
+        .unwrap_or_else(|| livery.texture.to_string());
+    SpriteBundle {
+        texture: asset_server.load(texture),
+        sprite: Sprite {
+            color: livery.tint,
+            ..default()
+        },
+        transform,
+        ..default()
+    }
+}
+
+/// Movement is handled by the enemy AI; this only clears out planes that have flown off.
+fn plane_update(mut commands: Commands, plane_query: Query<(&Transform, Entity), With<Plane>>) {
+    let margin = OFFSCREEN_MARGIN + PLANE_DESPAWN_MARGIN;
+    for (plane_transform, plane_entity) in &plane_query {
+        let escaped = plane_transform.translation.x < -margin.x
+            || plane_transform.translation.y > PLAY_AREA.y + margin.y;
+        if escaped {
+            commands.entity(plane_entity).despawn_recursive();
         }
     }
 }
@@ -200,35 +933,109 @@ fn plane_update(
 fn spawn_bombs(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    plane_query: Query<(&Transform, &Plane), With<Plane>>,
+    mut plane_query: Query<(&Transform, &BombTimer, &EnemyAi, &mut Plane)>,
+    settings: Res<Settings>,
+    themes: Res<Themes>,
+    config: Res<GameConfig>,
 ) {
-    for (plane_transform, plane) in plane_query.iter() {
-        if plane.bomb_spawn_timer.finished() {
+    let theme = themes.get(&settings.theme);
+    for (plane_transform, bomb_timer, ai, mut plane) in &mut plane_query {
+        if ai.is_attacking() && bomb_timer.0.finished() && plane.number_of_bombs > 0 {
+            plane.number_of_bombs -= 1;
             commands.spawn((
-                SpriteBundle {
-                    texture: asset_server.load("../assets/bomb.png"),
-                    transform: Transform::from_translation(plane_transform.translation)
+                bomb_sprite(
+                    &asset_server,
+                    theme,
+                    Transform::from_translation(plane_transform.translation)
                         .with_scale(Vec3::new(2.0, 2.0, 0.0)),
-                    ..default()
-                },
+                ),
                 Bomb {
-                    falling_speed: 100.0,
+                    falling_speed: config.bomb_falling_speed,
+                },
+                Outlined {
+                    half_size: BOMB_HALF_SIZE,
                 },
             ));
         }
     }
 }
 
+fn bomb_sprite(asset_server: &AssetServer, theme: &Theme, transform: Transform) -> SpriteBundle {
+    SpriteBundle {
+        texture: asset_server.load(theme.bomb_texture.clone()),
+        transform,
+        ..default()
+    }
+}
+
 fn update_bombs(
     mut commands: Commands,
     time: Res<Time>,
     mut bomb_query: Query<(&mut Transform, Entity, &Bomb), With<Bomb>>,
+    mut impact_events: EventWriter<BombImpactEvent>,
+    terrain: Res<Terrain>,
 ) {
     for (mut bomb_transform, bomb_entity, bomb) in &mut bomb_query {
-        if bomb_transform.translation.y > -16.0 {
+        let ground_y = terrain.height_at(bomb_transform.translation.x);
+        if bomb_transform.translation.y > ground_y + BOMB_HALF_SIZE.y {
             bomb_transform.translation.y -= bomb.falling_speed * time.delta_seconds();
-        } else {
-            commands.entity(bomb_entity).despawn();
+            continue;
+        }
+
+        commands.entity(bomb_entity).despawn();
+        impact_events.send(BombImpactEvent {
+            position: bomb_transform.translation.truncate(),
+        });
+    }
+}
+
+/// Bombs go off on impact, so a near miss can still catch the jeep in the blast.
+/// Bombs go off where they land, catching the jeep and any infantry close by.
+fn detonate_bombs(
+    mut impact_events: EventReader<BombImpactEvent>,
+    player_query: Query<(&Transform, &Collider), With<Player>>,
+    unit_query: Query<(Entity, &Transform, &Collider), With<GroundUnit>>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+    mut player_damage_events: EventWriter<PlayerDamageEvent>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    for &BombImpactEvent {
+        position: blast_center,
+    } in impact_events.read()
+    {
+        explosion_events.send(ExplosionEvent {
+            position: blast_center,
+            radius: BOMB_BLAST_RADIUS,
+            source: DamageSource::Enemy,
+        });
+
+        let blast = BoundingCircle::new(blast_center, BOMB_BLAST_RADIUS);
+        for (unit_entity, unit_transform, unit_collider) in &unit_query {
+            let unit_box = Aabb2d::new(
+                unit_transform.translation.truncate(),
+                unit_collider.half_size,
+            );
+            if blast.intersects(&unit_box) {
+                damage_events.send(DamageEvent {
+                    target: unit_entity,
+                    amount: BOMB_BLAST_DAMAGE,
+                    source: DamageSource::Enemy,
+                });
+            }
+        }
+
+        let Ok((player_transform, player_collider)) = player_query.get_single() else {
+            continue;
+        };
+        let player_box = Aabb2d::new(
+            player_transform.translation.truncate(),
+            player_collider.half_size,
+        );
+        if blast.intersects(&player_box) {
+            player_damage_events.send(PlayerDamageEvent {
+                amount: BOMB_BLAST_DAMAGE,
+                cause: DamageCause::BombBlast,
+            });
         }
     }
 }
@@ -236,33 +1043,257 @@ fn update_bombs(
 fn rocket_collision(
     mut commands: Commands,
     rocket_query: Query<(Entity, &Transform), With<Rocket>>,
-    collider_query: Query<(Entity, &Transform, Option<&Plane>), With<Collider>>,
+    collider_query: Query<
+        (
+            Entity,
+            &Transform,
+            &Collider,
+            Has<Plane>,
+            Has<Gunboat>,
+            Has<GroundUnit>,
+            Has<Civilian>,
+            Has<InDarkness>,
+        ),
+        Without<Player>,
+    >,
     mut collision_events: EventWriter<CollisionEvent>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut hit_events: EventWriter<ShotHitEvent>,
 ) {
+    let _span = debug_span!("rocket_collision", rockets = rocket_query.iter().len()).entered();
     for (rocket_entity, rocket_transform) in rocket_query.iter() {
-        for (collider_entity, collider_transform, plane) in &collider_query {
+        for (
+            collider_entity,
+            collider_transform,
+            collider,
+            is_plane,
+            is_gunboat,
+            is_ground_unit,
+            is_civilian,
+            in_darkness,
+        ) in &collider_query
+        {
+            if in_darkness {
+                continue;
+            }
+
+            let collider_box = Aabb2d::new(
+                collider_transform.translation.truncate(),
+                collider.half_size,
+            );
             let collision = is_collision(
-                Aabb2d::new(
-                    rocket_transform.translation.truncate(),
-                    rocket_transform.scale.truncate() / 2.0,
-                ),
-                Aabb2d::new(
-                    collider_transform.translation.truncate(),
-                    collider_transform.scale.truncate() / 2.,
-                ),
+                Aabb2d::new(rocket_transform.translation.truncate(), ROCKET_HALF_SIZE),
+                collider_box,
             );
 
-            if let Some(collision) = collision {
-                collision_events.send_default();
-                if plane.is_some() {
-                    commands.entity(collider_entity).despawn();
-                    commands.entity(rocket_entity).despawn();
+            if let Some(side) = collision {
+                // Trucks on the road can be hit too, though the player pays for it.
+                if !is_plane && !is_gunboat && !is_ground_unit && !is_civilian {
+                    continue;
                 }
+
+                commands.entity(rocket_entity).despawn();
+                collision_events.send(CollisionEvent {
+                    target: collider_entity,
+                    point: collider_box.closest_point(rocket_transform.translation.truncate()),
+                    side,
+                });
+                damage_events.send(DamageEvent {
+                    target: collider_entity,
+                    amount: ROCKET_DAMAGE,
+                    source: DamageSource::Player,
+                });
+                if !is_civilian {
+                    hit_events.send(ShotHitEvent);
+                }
+                break;
             }
         }
     }
 }
 
+/// Applies damage to enemies, destroying them when their health runs out. Civilians take
+/// damage the same way, but every hit the player lands on one costs them instead of
+/// scoring. Civilians caught in the enemy's blasts aren't held against the player.
+fn apply_damage(
+    mut commands: Commands,
+    mut damage_events: EventReader<DamageEvent>,
+    mut health_query: Query<
+        (
+            &Transform,
+            &mut Health,
+            Has<Civilian>,
+            Has<Plane>,
+            Has<Boss>,
+        ),
+        Without<Player>,
+    >,
+    mut hit_flash_events: EventWriter<HitFlashEvent>,
+    mut killed_events: EventWriter<EnemyKilledEvent>,
+    mut stats: ResMut<RunStats>,
+) {
+    for damage in damage_events.read() {
+        let Ok((transform, mut health, is_civilian, is_plane, is_boss)) =
+            health_query.get_mut(damage.target)
+        else {
+            continue;
+        };
+        if health.is_dead() {
+            continue;
+        }
+        if is_civilian && damage.source == DamageSource::Player {
+            stats.record_civilian_hit();
+        }
+
+        if !health.damage(damage.amount) {
+            hit_flash_events.send(HitFlashEvent(damage.target));
+            continue;
+        }
+
+        commands.entity(damage.target).despawn_recursive();
+        killed_events.send(EnemyKilledEvent {
+            position: transform.translation.truncate(),
+            is_plane,
+            is_civilian,
+            is_boss,
+            source: damage.source,
+        });
+    }
+}
+
+/// Blows up whatever was destroyed and scores it. Planes brought down from far away earn
+/// a bonus for the risk of the long shot, on top of the usual points.
+fn destroy_enemies(
+    mut killed_events: EventReader<EnemyKilledEvent>,
+    player_query: Query<&Transform, With<Player>>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+    mut debris_events: EventWriter<DebrisEvent>,
+    mut popup_events: EventWriter<ScorePopupEvent>,
+    mut stats: ResMut<RunStats>,
+) {
+    let player_position = player_query
+        .get_single()
+        .ok()
+        .map(|transform| transform.translation.truncate());
+    for killed in killed_events.read() {
+        let position = killed.position;
+        explosion_events.send(ExplosionEvent {
+            position,
+            radius: 40.0,
+            source: killed.source,
+        });
+        debris_events.send(DebrisEvent { position });
+        if killed.is_civilian {
+            continue;
+        }
+
+        let points = stats.record_kill();
+        let long_shot = match player_position {
+            Some(player_position) if killed.is_plane => {
+                let excess = (position.distance(player_position) - LONG_SHOT_DISTANCE).max(0.0);
+                (excess * LONG_SHOT_POINTS).round() as u32
+            }
+            _ => 0,
+        };
+        stats.score += long_shot;
+        popup_events.send(ScorePopupEvent {
+            position,
+            points,
+            long_shot,
+        });
+    }
+}
+
+fn bomb_collision(
+    mut commands: Commands,
+    bomb_query: Query<(Entity, &Transform), With<Bomb>>,
+    player_query: Query<(&Transform, &Collider, &PlayerState), With<Player>>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+    mut player_damage_events: EventWriter<PlayerDamageEvent>,
+) {
+    let Ok((player_transform, player_collider, PlayerState::Alive)) = player_query.get_single()
+    else {
+        return;
+    };
+
+    let player_box = Aabb2d::new(
+        player_transform.translation.truncate(),
+        player_collider.half_size,
+    );
+    for (bomb_entity, bomb_transform) in &bomb_query {
+        let bomb_box = Aabb2d::new(bomb_transform.translation.truncate(), BOMB_HALF_SIZE);
+        if !bomb_box.intersects(&player_box) {
+            continue;
+        }
+
+        commands.entity(bomb_entity).despawn();
+        explosion_events.send(ExplosionEvent {
+            position: bomb_transform.translation.truncate(),
+            radius: 48.0,
+            source: DamageSource::Enemy,
+        });
+        player_damage_events.send(PlayerDamageEvent {
+            amount: BOMB_DIRECT_HIT_DAMAGE,
+            cause: DamageCause::BombHit,
+        });
+        return;
+    }
+}
+
+fn damage_player(
+    mut player_damage_events: EventReader<PlayerDamageEvent>,
+    mut player_query: Query<(
+        Entity,
+        &Transform,
+        &mut Health,
+        &mut Player,
+        &mut PlayerState,
+        &mut Visibility,
+    )>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+    mut hit_flash_events: EventWriter<HitFlashEvent>,
+    mut killed_events: EventWriter<PlayerKilledEvent>,
+    mut stats: ResMut<RunStats>,
+    config: Res<GameConfig>,
+) {
+    let hits: Vec<&PlayerDamageEvent> = player_damage_events.read().collect();
+    let amount: f32 = hits.iter().map(|hit| hit.amount).sum();
+    let Ok((
+        player_entity,
+        player_transform,
+        mut health,
+        mut player,
+        mut player_state,
+        mut player_visibility,
+    )) = player_query.get_single_mut()
+    else {
+        return;
+    };
+    if amount <= 0.0 || !matches!(*player_state, PlayerState::Alive) {
+        return;
+    }
+
+    if !health.damage(amount) {
+        hit_flash_events.send(HitFlashEvent(player_entity));
+        return;
+    }
+
+    player.lives = player.lives.saturating_sub(1);
+    stats.record_death();
+    if let Some(hardest) = hits.iter().max_by(|a, b| a.amount.total_cmp(&b.amount)) {
+        killed_events.send(PlayerKilledEvent {
+            cause: hardest.cause,
+        });
+    }
+    *player_state = PlayerState::Dead(Timer::from_seconds(config.respawn_delay, TimerMode::Once));
+    *player_visibility = Visibility::Hidden;
+    explosion_events.send(ExplosionEvent {
+        position: player_transform.translation.truncate(),
+        radius: 48.0,
+        source: DamageSource::Enemy,
+    });
+}
+
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 enum Collision {
     Left,
@@ -271,9 +1302,21 @@ enum Collision {
     Bottom,
 }
 
+impl Collision {
+    /// Outward direction of the side that was struck.
+    fn normal(self) -> Vec2 {
+        match self {
+            Collision::Left => Vec2::NEG_X,
+            Collision::Right => Vec2::X,
+            Collision::Top => Vec2::Y,
+            Collision::Bottom => Vec2::NEG_Y,
+        }
+    }
+}
+
 fn is_collision(colliding: Aabb2d, collider: Aabb2d) -> Option<Collision> {
     if !&colliding.intersects(&collider) {
-        println!("rocket: {:?}, plane: {:?}", colliding, collider);
+        trace!("no collision between {colliding:?} and {collider:?}");
         return None;
     }
 
@@ -294,22 +1337,88 @@ fn is_collision(colliding: Aabb2d, collider: Aabb2d) -> Option<Collision> {
     Some(side)
 }
 
-fn plane_spawn_timer_update(mut plane_spawn_timer: ResMut<PlaneSpawnTimer>, time: Res<Time>) {
-    plane_spawn_timer.timer.tick(time.delta());
+fn update_player_state(
+    time: Res<Time>,
+    mut player_query: Query<(
+        &mut Transform,
+        &mut Player,
+        &mut PlayerState,
+        &mut Health,
+        &mut Visibility,
+    )>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Ok(player_item) = player_query.get_single_mut() else {
+        return;
+    };
+    let (mut player_transform, mut player, mut player_state, mut health, mut player_visibility) =
+        player_item;
+    let PlayerState::Dead(respawn_timer) = player_state.as_mut() else {
+        return;
+    };
+
+    respawn_timer.tick(time.delta());
+    if !respawn_timer.finished() {
+        return;
+    }
+
+    if player.lives == 0 {
+        next_state.set(GameState::GameOver);
+        return;
+    }
+
+    player_transform.translation.x = PLAY_AREA.x / 2.0;
+    player.horizontal_velocity = 0.0;
+    player.skidding = false;
+    player.vertical_velocity = 0.0;
+    player.grounded = true;
+    health.current = health.max;
+    *player_visibility = Visibility::Visible;
+    *player_state = PlayerState::Alive;
 }
 
-fn bomb_spawn_timer_update(
-    mut bomb_spawn_timer_query: Query<&mut Plane, With<Plane>>,
-    time: Res<Time>,
+/// Clears the field and restores the jeep so a new run can start after game over.
+fn reset_run(
+    mut commands: Commands,
+    mut launches: ResMut<PlaneLaunches>,
+    entity_query: Query<Entity, Or<(With<Plane>, With<Bomb>, With<Rocket>)>>,
+    mut player_query: Query<(
+        &mut Transform,
+        &mut Player,
+        &mut PlayerState,
+        &mut Health,
+        &mut Visibility,
+    )>,
 ) {
-    for mut plane in bomb_spawn_timer_query.iter_mut() {
-        plane.bomb_spawn_timer.tick(time.delta());
+    for entity in &entity_query {
+        commands.entity(entity).despawn_recursive();
     }
+    *launches = PlaneLaunches::default();
+
+    let Ok(player_item) = player_query.get_single_mut() else {
+        return;
+    };
+    let (mut player_transform, mut player, mut player_state, mut health, mut player_visibility) =
+        player_item;
+    player_transform.translation.x = PLAY_AREA.x / 2.0;
+    player.horizontal_velocity = 0.0;
+    player.skidding = false;
+    player.vertical_velocity = 0.0;
+    player.grounded = true;
+    player.lives = PLAYER_LIVES;
+    health.current = health.max;
+    *player_state = PlayerState::Alive;
+    *player_visibility = Visibility::Visible;
 }
+
 fn run_if_rockets(rocket_query: Query<(), With<Rocket>>) -> bool {
     !rocket_query.is_empty()
 }
 
+fn run_if_rocket_slot_free(rocket_query: Query<(), With<Rocket>>, config: Res<GameConfig>) -> bool {
+    config.max_live_rockets == 0 || rocket_query.iter().len() < config.max_live_rockets as usize
+}
+
 fn run_if_planes(plane_query: Query<(), With<Plane>>) -> bool {
     !plane_query.is_empty()
 }
@@ -317,13 +1426,11 @@ fn run_if_planes(plane_query: Query<(), With<Plane>>) -> bool {
 fn run_if_bombs(bomb_query: Query<(), With<Bomb>>) -> bool {
     !bomb_query.is_empty()
 }
-fn run_if_rockets_and_planes(
-    plane_query: Query<(), With<Plane>>,
+
+/// Rockets only need checking while there's something around for them to hit.
+fn run_if_rockets_and_targets(
+    target_query: Query<(), Or<(With<Plane>, With<Gunboat>, With<GroundUnit>, With<Civilian>)>>,
     rocket_query: Query<(), With<Rocket>>,
 ) -> bool {
-    if !rocket_query.is_empty() && !plane_query.is_empty() {
-        true
-    } else {
-        false
-    }
+    !rocket_query.is_empty() && !target_query.is_empty()
 }
@@ -0,0 +1,381 @@
+use bevy::{
+    ecs::system::RunSystemOnce,
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+};
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    accessibility::AccessibilitySettings,
+    ai::EnemyAi,
+    bonus::Hostile,
+    camera::OFFSCREEN_MARGIN,
+    config::GameConfig,
+    director::EnemyKind,
+    explosion::ExplosionEvent,
+    health::Health,
+    missile::{HomingMissile, MISSILE_HALF_SIZE},
+    pause::Paused,
+    pick_plane_lanes, plane_entry_x,
+    rng::GameRng,
+    settings::Settings,
+    spread,
+    terrain::Terrain,
+    Collider, DamageCause, DamageSource, GameSet, GameState, Plane, PlaneLaunches, PlaneSprites,
+    Player, PlayerDamageEvent, PlayerState, Rocket, PLANE_HEALTH, PLAY_AREA, ROCKET_HALF_SIZE,
+};
+
+/// Sand-coloured, so it's told apart from the planes that bomb the jeep directly.
+const MINE_LAYER_TINT: Color = Color::rgb(0.85, 0.75, 0.5);
+const MINE_LAYER_LOAD: std::ops::RangeInclusive<u32> = 2..=4;
+const MINE_DROP_INTERVAL: f32 = 1.5;
+/// Mines are only laid this far inside the play area, so they land where the jeep can
+/// get to them.
+const MINE_DROP_MARGIN: f32 = 120.0;
+
+const MINE_HALF_SIZE: Vec2 = Vec2::new(8.0, 4.0);
+const MINE_COLOR: Color = Color::rgb(0.25, 0.25, 0.22);
+const MINE_FALL_SPEED: f32 = 140.0;
+/// How long a mine lies in wait before it rusts through and goes quiet.
+const MINE_LIFETIME: f32 = 14.0;
+/// Its light blinks this many times a second at first, and faster as it runs out.
+const MINE_BLINK_RATE: f32 = 2.0;
+const MINE_DAMAGE: f32 = 2.0;
+const MINE_BLAST_RADIUS: f32 = 50.0;
+/// A defused mine only pops.
+const DEFUSE_BLAST_RADIUS: f32 = 12.0;
+/// Just behind the jeep, so it drives over them rather than under.
+const MINE_DEPTH: f32 = -0.2;
+
+/// A plane that lays mines along the ground instead of bombing the jeep.
+#[derive(Component, Clone, Serialize, Deserialize)]
+struct MineLayer {
+    mines_left: u32,
+    drop_timer: Timer,
+}
+
+/// A mine on its way down or lying on the ground. Once armed it goes off under the jeep,
+/// unless a rocket or missile sets it off first.
+#[derive(Component, Clone, Serialize, Deserialize)]
+pub struct Mine {
+    state: MineState,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+enum MineState {
+    Falling,
+    Armed(Timer),
+}
+
+impl Mine {
+    /// Armed mines sit on the ground, where the jeep can lock on to them.
+    pub fn is_armed(&self) -> bool {
+        matches!(self.state, MineState::Armed(_))
+    }
+}
+
+pub struct MinesPlugin;
+
+impl Plugin for MinesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                (spawn_mine_layers.after(pick_plane_lanes), lay_mines).in_set(GameSet::Spawning),
+                (move_mines, blink_mines).chain().in_set(GameSet::Movement),
+                (defuse_mines, mine_contact)
+                    .chain()
+                    .in_set(GameSet::Collision),
+            )
+                .run_if(in_state(GameState::Playing).and_then(not(resource_exists::<Paused>))),
+        )
+        .add_systems(OnExit(GameState::GameOver), clear_mines);
+    }
+}
+
+fn spawn_mine_layers(
+    mut commands: Commands,
+    plane_sprites: PlaneSprites,
+    launches: Res<PlaneLaunches>,
+    config: Res<GameConfig>,
+    mut rng: ResMut<GameRng>,
+) {
+    let rng = &mut rng.0;
+    for &(kind, altitude) in &launches.ready {
+        if kind != EnemyKind::MineLayer {
+            continue;
+        }
+        let transform = Transform::from_xyz(plane_entry_x(), altitude, 0.0)
+            .with_scale(Vec3::new(2.0, 2.0, 0.0));
+        spawn_mine_layer(
+            &mut commands,
+            &plane_sprites,
+            transform,
+            EnemyAi::new(
+                spread(rng, config.plane_speed, config.plane_speed_spread),
+                altitude,
+            ),
+            Health::new(PLANE_HEALTH),
+            MineLayer {
+                mines_left: rng.gen_range(MINE_LAYER_LOAD),
+                drop_timer: Timer::from_seconds(MINE_DROP_INTERVAL, TimerMode::Repeating),
+            },
+        );
+    }
+}
+
+fn spawn_mine_layer(
+    commands: &mut Commands,
+    plane_sprites: &PlaneSprites,
+    transform: Transform,
+    ai: EnemyAi,
+    health: Health,
+    layer: MineLayer,
+) {
+    let mut sprite = plane_sprites.sprite(transform);
+    sprite.sprite.color = MINE_LAYER_TINT;
+    commands.spawn((
+        sprite,
+        Plane { number_of_bombs: 0 },
+        ai,
+        health,
+        Collider {
+            half_size: Vec2::splat(32.0),
+        },
+        layer,
+    ));
+}
+
+/// Mine layers drop their load at a steady rate while they're over the battlefield.
+fn lay_mines(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut layer_query: Query<(&Transform, &mut MineLayer)>,
+) {
+    for (layer_transform, mut layer) in &mut layer_query {
+        let x = layer_transform.translation.x;
+        if layer.mines_left == 0 || !(MINE_DROP_MARGIN..PLAY_AREA.x - MINE_DROP_MARGIN).contains(&x)
+        {
+            continue;
+        }
+        layer.drop_timer.tick(time.delta());
+        if !layer.drop_timer.just_finished() {
+            continue;
+        }
+        layer.mines_left -= 1;
+        commands.spawn(mine(
+            Transform::from_xyz(x, layer_transform.translation.y, MINE_DEPTH),
+            Mine {
+                state: MineState::Falling,
+            },
+        ));
+    }
+}
+
+fn mine(transform: Transform, mine: Mine) -> impl Bundle {
+    (
+        SpriteBundle {
+            sprite: Sprite {
+                color: MINE_COLOR,
+                custom_size: Some(MINE_HALF_SIZE * 2.0),
+                ..default()
+            },
+            transform,
+            ..default()
+        },
+        mine,
+        Hostile,
+    )
+}
+
+/// Falling mines drop until they reach the ground and arm. Armed ones are part of the
+/// landscape, so they drift left with the terrain until they expire.
+fn move_mines(
+    mut commands: Commands,
+    time: Res<Time>,
+    terrain: Res<Terrain>,
+    mut mine_query: Query<(Entity, &mut Transform, &mut Mine)>,
+) {
+    let despawn_x = -OFFSCREEN_MARGIN.x - MINE_HALF_SIZE.x;
+    for (mine_entity, mut transform, mut mine) in &mut mine_query {
+        match &mut mine.state {
+            MineState::Falling => {
+                let ground = terrain.height_at(transform.translation.x) + MINE_HALF_SIZE.y;
+                transform.translation.y -= MINE_FALL_SPEED * time.delta_seconds();
+                if transform.translation.y <= ground {
+                    transform.translation.y = ground;
+                    mine.state =
+                        MineState::Armed(Timer::from_seconds(MINE_LIFETIME, TimerMode::Once));
+                }
+            }
+            MineState::Armed(lifetime) => {
+                lifetime.tick(time.delta());
+                transform.translation.x -= terrain.scroll_speed() * time.delta_seconds();
+                let x = transform.translation.x;
+                transform.translation.y = terrain.height_at(x) + MINE_HALF_SIZE.y;
+                if lifetime.finished() || x < despawn_x {
+                    commands.entity(mine_entity).despawn();
+                }
+            }
+        }
+    }
+}
+
+/// Armed mines blink faster as they near the end of their lifetime. With flashes turned
+/// off they glow a steady danger colour instead.
+fn blink_mines(
+    settings: Res<Settings>,
+    accessibility: Res<AccessibilitySettings>,
+    mut mine_query: Query<(&Mine, &mut Sprite)>,
+) {
+    let danger = settings.palette.colors().danger;
+    for (mine, mut sprite) in &mut mine_query {
+        let MineState::Armed(lifetime) = &mine.state else {
+            continue;
+        };
+        let elapsed = lifetime.elapsed_secs();
+        let phase = elapsed * MINE_BLINK_RATE * (1.0 + lifetime.fraction());
+        sprite.color = if !accessibility.flashes || phase.fract() < 0.5 {
+            danger
+        } else {
+            MINE_COLOR
+        };
+    }
+}
+
+/// A rocket or missile that hits a mine sets it off before the jeep can.
+fn defuse_mines(
+    mut commands: Commands,
+    mine_query: Query<(Entity, &Transform), With<Mine>>,
+    shot_query: Query<
+        (Entity, &Transform, Has<HomingMissile>),
+        Or<(With<Rocket>, With<HomingMissile>)>,
+    >,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+) {
+    let mut spent = Vec::new();
+    for (mine_entity, mine_transform) in &mine_query {
+        let position = mine_transform.translation.truncate();
+        let mine_box = Aabb2d::new(position, MINE_HALF_SIZE);
+        let hit = shot_query
+            .iter()
+            .find(|&(shot_entity, shot_transform, is_missile)| {
+                let half_size = if is_missile {
+                    MISSILE_HALF_SIZE
+                } else {
+                    ROCKET_HALF_SIZE
+                };
+                !spent.contains(&shot_entity)
+                    && mine_box.intersects(&Aabb2d::new(
+                        shot_transform.translation.truncate(),
+                        half_size,
+                    ))
+            });
+        if let Some((shot_entity, ..)) = hit {
+            spent.push(shot_entity);
+            commands.entity(shot_entity).despawn();
+            commands.entity(mine_entity).despawn();
+            explosion_events.send(ExplosionEvent {
+                position,
+                radius: DEFUSE_BLAST_RADIUS,
+                source: DamageSource::Player,
+            });
+        }
+    }
+}
+
+fn mine_contact(
+    mut commands: Commands,
+    mine_query: Query<(Entity, &Transform, &Mine)>,
+    player_query: Query<(&Transform, &Collider, &PlayerState), With<Player>>,
+    mut player_damage_events: EventWriter<PlayerDamageEvent>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+) {
+    let Ok((player_transform, player_collider, PlayerState::Alive)) = player_query.get_single()
+    else {
+        return;
+    };
+    let player_box = Aabb2d::new(
+        player_transform.translation.truncate(),
+        player_collider.half_size,
+    );
+
+    for (mine_entity, mine_transform, mine) in &mine_query {
+        let position = mine_transform.translation.truncate();
+        if mine.is_armed() && player_box.intersects(&Aabb2d::new(position, MINE_HALF_SIZE)) {
+            commands.entity(mine_entity).despawn();
+            player_damage_events.send(PlayerDamageEvent {
+                amount: MINE_DAMAGE,
+                cause: DamageCause::Mine,
+            });
+            explosion_events.send(ExplosionEvent {
+                position,
+                radius: MINE_BLAST_RADIUS,
+                source: DamageSource::Enemy,
+            });
+        }
+    }
+}
+
+fn clear_mines(mut commands: Commands, mine_query: Query<Entity, With<Mine>>) {
+    for mine_entity in &mine_query {
+        commands.entity(mine_entity).despawn();
+    }
+}
+
+/// What a saved run keeps of the mine layers overhead and the mines they've dropped.
+#[derive(Serialize, Deserialize)]
+pub struct SavedMines {
+    layers: Vec<(Transform, EnemyAi, Health, MineLayer)>,
+    mines: Vec<(Transform, Mine)>,
+}
+
+impl SavedMines {
+    pub fn capture(world: &mut World) -> Self {
+        world.run_system_once(save_mines)
+    }
+
+    /// Swaps the mine layers and mines in play for these.
+    pub fn restore(self, world: &mut World) {
+        world.run_system_once_with(self, load_mines);
+    }
+}
+
+fn save_mines(
+    layer_query: Query<(&Transform, &EnemyAi, &Health, &MineLayer)>,
+    mine_query: Query<(&Transform, &Mine)>,
+) -> SavedMines {
+    SavedMines {
+        layers: layer_query
+            .iter()
+            .map(|(transform, ai, health, layer)| {
+                (*transform, ai.clone(), health.clone(), layer.clone())
+            })
+            .collect(),
+        mines: mine_query
+            .iter()
+            .map(|(transform, mine)| (*transform, mine.clone()))
+            .collect(),
+    }
+}
+
+fn load_mines(
+    In(saved): In<SavedMines>,
+    mut commands: Commands,
+    plane_sprites: PlaneSprites,
+    entity_query: Query<Entity, Or<(With<MineLayer>, With<Mine>)>>,
+) {
+    for entity in &entity_query {
+        commands.entity(entity).despawn_recursive();
+    }
+    for (transform, ai, health, layer) in saved.layers {
+        spawn_mine_layer(&mut commands, &plane_sprites, transform, ai, health, layer);
+    }
+    commands.spawn_batch(
+        saved
+            .mines
+            .into_iter()
+            .map(|(transform, saved_mine)| mine(transform, saved_mine)),
+    );
+}
@@ -0,0 +1,200 @@
+use bevy::{
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+};
+
+use crate::{
+    camera::OFFSCREEN_MARGIN,
+    coast::Gunboat,
+    controls::{Action, Controls},
+    grading::ShotHitEvent,
+    jeep_motion::ShotFiredEvent,
+    night::InDarkness,
+    pause::Paused,
+    targeting::TargetLock,
+    Collider, DamageEvent, DamageSource, GameSet, GameState, Muzzle, Plane, PLAY_AREA,
+};
+
+const MISSILE_SPEED: f32 = 450.0;
+/// Maximum rate the missile can turn towards its target, in radians per second.
+const MISSILE_TURN_RATE: f32 = 3.0;
+pub const MISSILE_DAMAGE: f32 = 2.0;
+const MISSILE_COOLDOWN: f32 = 1.5;
+pub const MISSILE_HALF_SIZE: Vec2 = Vec2::splat(6.0);
+pub const MISSILE_COLOR: Color = Color::rgb(1.0, 0.7, 0.3);
+/// Missiles that drift this far outside the play area are despawned.
+const MISSILE_DESPAWN_MARGIN: f32 = 100.0;
+
+/// A missile that steers towards the plane that was locked when it was fired.
+#[derive(Component)]
+pub struct HomingMissile {
+    pub target: Option<Entity>,
+    velocity: Vec2,
+}
+
+#[derive(Resource)]
+pub struct MissileLauncher {
+    cooldown: Timer,
+}
+
+impl MissileLauncher {
+    /// How far the launcher is through its cooldown, from 0 just after firing to 1 when
+    /// it is ready again.
+    pub fn charge(&self) -> f32 {
+        self.cooldown.fraction()
+    }
+
+    /// Makes the launcher ready to fire straight away.
+    pub fn recharge(&mut self) {
+        let duration = self.cooldown.duration();
+        self.cooldown.tick(duration);
+    }
+}
+
+impl Default for MissileLauncher {
+    fn default() -> Self {
+        let mut launcher = Self {
+            cooldown: Timer::from_seconds(MISSILE_COOLDOWN, TimerMode::Once),
+        };
+        launcher.recharge();
+        launcher
+    }
+}
+
+pub struct MissilePlugin;
+
+impl Plugin for MissilePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MissileLauncher>()
+            .add_systems(
+                Update,
+                (
+                    (tick_missile_cooldown, fire_missile)
+                        .chain()
+                        .in_set(GameSet::Input)
+                        .run_if(not(resource_exists::<Paused>)),
+                    steer_missiles.in_set(GameSet::Movement),
+                    missile_collision
+                        .in_set(GameSet::Collision)
+                        .run_if(not(resource_exists::<Paused>)),
+                )
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::GameOver), despawn_missiles);
+    }
+}
+
+fn tick_missile_cooldown(time: Res<Time>, mut launcher: ResMut<MissileLauncher>) {
+    launcher.cooldown.tick(time.delta());
+}
+
+/// The missile action (X by default) launches a homing missile at the locked target.
+fn fire_missile(
+    mut commands: Commands,
+    mut controls: ResMut<Controls>,
+    asset_server: Res<AssetServer>,
+    target_lock: Res<TargetLock>,
+    mut launcher: ResMut<MissileLauncher>,
+    muzzle: Muzzle,
+    mut shot_events: EventWriter<ShotFiredEvent>,
+) {
+    let Some(muzzle_position) = muzzle.position() else {
+        return;
+    };
+    if !controls.buffered(Action::Missile) || !launcher.cooldown.finished() {
+        return;
+    }
+
+    controls.consume(Action::Missile);
+    launcher.cooldown.reset();
+    commands.spawn((
+        SpriteBundle {
+            texture: asset_server.load("../assets/rocket.png"),
+            sprite: Sprite {
+                color: MISSILE_COLOR,
+                ..default()
+            },
+            transform: Transform::from_translation(muzzle_position)
+                .with_scale(Vec3::new(1.5, 1.5, 1.0)),
+            ..default()
+        },
+        HomingMissile {
+            target: target_lock.target,
+            velocity: Vec2::Y * MISSILE_SPEED,
+        },
+    ));
+    shot_events.send(ShotFiredEvent::Missile);
+}
+
+fn steer_missiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut missile_query: Query<(Entity, &mut Transform, &mut HomingMissile)>,
+    target_query: Query<&Transform, Without<HomingMissile>>,
+) {
+    for (missile_entity, mut missile_transform, mut missile) in &mut missile_query {
+        let position = missile_transform.translation.truncate();
+        if let Some(target_transform) = missile
+            .target
+            .and_then(|target| target_query.get(target).ok())
+        {
+            let desired = target_transform.translation.truncate() - position;
+            let turn = missile.velocity.angle_between(desired);
+            let max_turn = MISSILE_TURN_RATE * time.delta_seconds();
+            missile.velocity =
+                Vec2::from_angle(turn.clamp(-max_turn, max_turn)).rotate(missile.velocity);
+        }
+
+        missile_transform.translation += (missile.velocity * time.delta_seconds()).extend(0.0);
+        // The rocket sprite points up the y axis.
+        missile_transform.rotation = Quat::from_rotation_z(
+            missile.velocity.y.atan2(missile.velocity.x) - std::f32::consts::FRAC_PI_2,
+        );
+
+        let margin = OFFSCREEN_MARGIN + MISSILE_DESPAWN_MARGIN;
+        let outside = position.x < -margin.x
+            || position.x > PLAY_AREA.x + margin.x
+            || position.y < -margin.y
+            || position.y > PLAY_AREA.y + margin.y;
+        if outside {
+            commands.entity(missile_entity).despawn();
+        }
+    }
+}
+
+fn missile_collision(
+    mut commands: Commands,
+    missile_query: Query<(Entity, &Transform), With<HomingMissile>>,
+    plane_query: Query<
+        (Entity, &Transform, &Collider),
+        (Or<(With<Plane>, With<Gunboat>)>, Without<InDarkness>),
+    >,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut hit_events: EventWriter<ShotHitEvent>,
+) {
+    for (missile_entity, missile_transform) in &missile_query {
+        let missile_box = Aabb2d::new(missile_transform.translation.truncate(), MISSILE_HALF_SIZE);
+        let hit = plane_query.iter().find(|(_, plane_transform, collider)| {
+            missile_box.intersects(&Aabb2d::new(
+                plane_transform.translation.truncate(),
+                collider.half_size,
+            ))
+        });
+
+        if let Some((plane_entity, ..)) = hit {
+            commands.entity(missile_entity).despawn();
+            damage_events.send(DamageEvent {
+                target: plane_entity,
+                amount: MISSILE_DAMAGE,
+                source: DamageSource::Player,
+            });
+            hit_events.send(ShotHitEvent);
+        }
+    }
+}
+
+fn despawn_missiles(mut commands: Commands, missile_query: Query<Entity, With<HomingMissile>>) {
+    for missile_entity in &missile_query {
+        commands.entity(missile_entity).despawn();
+    }
+}
@@ -0,0 +1,286 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Component, Path, PathBuf},
+    time::Duration,
+};
+
+use bevy::{
+    asset::io::{
+        file::FileAssetReader, AssetReader, AssetReaderError, AssetSource, AssetSourceId,
+        PathStream, Reader, VecReader,
+    },
+    prelude::*,
+    utils::BoxedFuture,
+};
+
+use crate::{localization::Locale, toast::ToastEvent};
+
+/// Every folder in here is a data pack, laid out like the `assets` folder. A file in a pack
+/// replaces the game's own file at the same place, so a pack can swap in textures, sounds
+/// or its own `game_config.ron`, and add themes and string tables of its own.
+const MOD_DIR: &str = "mods";
+/// Names the packs to load, one per line, in the order they're loaded. When this exists
+/// packs it doesn't list are left out; without it every pack loads, in name order.
+const LOAD_ORDER_FILE: &str = "mods/load_order.txt";
+const ASSET_DIR: &str = "assets";
+
+#[derive(Clone)]
+struct ModPack {
+    name: String,
+    dir: PathBuf,
+}
+
+/// The data packs in use, in load order. Packs later in the order win over earlier ones.
+#[derive(Resource, Clone)]
+pub struct ModPacks {
+    packs: Vec<ModPack>,
+    /// Packs named in the load order that aren't there.
+    missing: Vec<String>,
+    /// Packs that are there but left out of the load order.
+    left_out: Vec<String>,
+}
+
+impl ModPacks {
+    fn scan() -> Self {
+        let mut found: Vec<String> = fs::read_dir(MOD_DIR)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                entry.file_type().ok()?.is_dir().then_some(())?;
+                entry.file_name().to_str().map(str::to_string)
+            })
+            .collect();
+        found.sort();
+
+        let (names, missing, left_out) = match fs::read_to_string(LOAD_ORDER_FILE) {
+            Ok(load_order) => {
+                let listed: Vec<String> = load_order
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                    .map(str::to_string)
+                    .collect();
+                let (names, missing): (Vec<String>, Vec<String>) =
+                    listed.into_iter().partition(|name| found.contains(name));
+                let left_out = found
+                    .into_iter()
+                    .filter(|name| !names.contains(name))
+                    .collect();
+                (names, missing, left_out)
+            }
+            Err(_) => (found, Vec::new(), Vec::new()),
+        };
+
+        Self {
+            packs: names
+                .into_iter()
+                .map(|name| ModPack {
+                    dir: Path::new(MOD_DIR).join(&name),
+                    name,
+                })
+                .collect(),
+            missing,
+            left_out,
+        }
+    }
+
+    /// `base_dir` under `assets`, followed by the same folder in each pack, in load order.
+    pub fn data_dirs(&self, base_dir: &str) -> Vec<PathBuf> {
+        let relative = Path::new(base_dir)
+            .strip_prefix(ASSET_DIR)
+            .unwrap_or(Path::new(base_dir));
+        std::iter::once(PathBuf::from(base_dir))
+            .chain(self.packs.iter().map(|pack| pack.dir.join(relative)))
+            .collect()
+    }
+
+    /// The pack file standing in for the asset at `path`, if any. `path` is relative to the
+    /// asset folder, the way it's handed to the asset server.
+    fn override_for(&self, path: &Path) -> Option<PathBuf> {
+        let relative = asset_relative(path)?;
+        self.packs
+            .iter()
+            .rev()
+            .map(|pack| pack.dir.join(&relative))
+            .find(|file| file.is_file())
+    }
+
+    /// Whether the game or any pack has a file at `path`, relative to the working
+    /// directory like `assets/music/calm.ogg`.
+    pub fn has_file(&self, path: &Path) -> bool {
+        path.is_file()
+            || path
+                .strip_prefix(ASSET_DIR)
+                .is_ok_and(|relative| self.override_for(relative).is_some())
+    }
+
+    /// Logs every file more than one pack provides, along with which one wins, and
+    /// returns how many there are.
+    fn report_conflicts(&self) -> usize {
+        let mut providers: BTreeMap<PathBuf, Vec<&str>> = BTreeMap::new();
+        for pack in &self.packs {
+            for file in files_under(&pack.dir) {
+                if let Ok(relative) = file.strip_prefix(&pack.dir) {
+                    providers
+                        .entry(relative.to_path_buf())
+                        .or_default()
+                        .push(&pack.name);
+                }
+            }
+        }
+        let mut conflicts = 0;
+        for (file, packs) in &providers {
+            if let [.., winner] = packs.as_slice() {
+                if packs.len() > 1 {
+                    conflicts += 1;
+                    warn!(
+                        "mod conflict: {} is in packs {}; using the one from `{winner}`",
+                        file.display(),
+                        packs.join(", "),
+                    );
+                } else if Path::new(ASSET_DIR).join(file).is_file() {
+                    info!("mod pack `{winner}` replaces {}", file.display());
+                }
+            }
+        }
+        conflicts
+    }
+}
+
+/// Every file under `dir`, however deep.
+fn files_under(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    files
+}
+
+/// Asset paths climb out of and back into the asset folder, like `../assets/jeep.png`, so
+/// this works out where they really point inside it.
+fn asset_relative(path: &Path) -> Option<PathBuf> {
+    let mut parts = vec![ASSET_DIR.into()];
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_os_string()),
+            Component::ParentDir => {
+                parts.pop()?;
+            }
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    let full: PathBuf = parts.into_iter().collect();
+    full.strip_prefix(ASSET_DIR).ok().map(Path::to_path_buf)
+}
+
+/// Reads each asset from the latest pack that has it, and from the game's own assets
+/// otherwise.
+struct ModAssetReader {
+    assets: FileAssetReader,
+    packs: ModPacks,
+}
+
+impl AssetReader for ModAssetReader {
+    fn read<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<Box<Reader<'a>>, AssetReaderError>> {
+        let Some(file) = self.packs.override_for(path) else {
+            return self.assets.read(path);
+        };
+        Box::pin(async move {
+            let reader: Box<Reader> = Box::new(VecReader::new(fs::read(file)?));
+            Ok(reader)
+        })
+    }
+
+    fn read_meta<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<Box<Reader<'a>>, AssetReaderError>> {
+        self.assets.read_meta(path)
+    }
+
+    fn read_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<Box<PathStream>, AssetReaderError>> {
+        self.assets.read_directory(path)
+    }
+
+    fn is_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<bool, AssetReaderError>> {
+        self.assets.is_directory(path)
+    }
+}
+
+/// Has to be added before `DefaultPlugins`, since it takes over how assets are read.
+pub struct ModsPlugin;
+
+impl Plugin for ModsPlugin {
+    fn build(&self, app: &mut App) {
+        let packs = ModPacks::scan();
+        let reader_packs = packs.clone();
+        app.register_asset_source(
+            AssetSourceId::Default,
+            AssetSource::build()
+                .with_reader(move || {
+                    Box::new(ModAssetReader {
+                        assets: FileAssetReader::new(ASSET_DIR),
+                        packs: reader_packs.clone(),
+                    })
+                })
+                .with_writer(AssetSource::get_default_writer(ASSET_DIR.to_string()))
+                .with_watcher(AssetSource::get_default_watcher(
+                    ASSET_DIR.to_string(),
+                    Duration::from_millis(300),
+                ))
+                .with_watch_warning(AssetSource::get_default_watch_warning()),
+        )
+        .insert_resource(packs)
+        .add_systems(Startup, announce_mods);
+    }
+}
+
+/// Lets the player know their packs were picked up, and whether any of them clash. The
+/// packs are found before logging is set up, so this is where they're logged as well.
+fn announce_mods(
+    packs: Res<ModPacks>,
+    locale: Res<Locale>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    for pack in &packs.packs {
+        info!("loaded mod pack `{}`", pack.name);
+    }
+    for name in &packs.missing {
+        warn!("mod pack `{name}` is in the load order but isn't in {MOD_DIR}");
+    }
+    for name in &packs.left_out {
+        info!("mod pack `{name}` isn't in the load order, so it's left out");
+    }
+    if packs.packs.is_empty() {
+        return;
+    }
+    toast_events.send(ToastEvent(
+        locale.format("mods-loaded", &[("count", &packs.packs.len())]),
+    ));
+    let conflicts = packs.report_conflicts();
+    if conflicts > 0 {
+        toast_events.send(ToastEvent(
+            locale.format("mods-conflicts", &[("count", &conflicts)]),
+        ));
+    }
+}
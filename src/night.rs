@@ -0,0 +1,198 @@
+use bevy::{
+    ecs::system::RunSystemOnce,
+    prelude::*,
+    render::{mesh::Indices, render_asset::RenderAssetUsages, render_resource::PrimitiveTopology},
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+};
+
+use crate::{camera::OFFSCREEN_MARGIN, GameState, Mission, Plane, Player, PLAY_AREA};
+
+const NIGHT_OVERLAY_COLOR: Color = Color::rgba(0.0, 0.0, 0.08, 0.8);
+const NIGHT_OVERLAY_DEPTH: f32 = 5.0;
+const SEARCHLIGHT_COLOR: Color = Color::rgba(1.0, 0.95, 0.7, 0.25);
+const SEARCHLIGHT_RANGE: f32 = 700.0;
+const SEARCHLIGHT_HALF_ANGLE: f32 = 0.22;
+/// Furthest the beam can swing away from straight up, in radians.
+const SEARCHLIGHT_MAX_ANGLE: f32 = 1.2;
+const SEARCHLIGHT_TURN_SPEED: f32 = 1.5;
+const SEARCHLIGHT_ARC_SEGMENTS: u32 = 12;
+/// Lit planes are lifted above the overlay so they show up in full color.
+const LIT_PLANE_DEPTH: f32 = NIGHT_OVERLAY_DEPTH + 0.5;
+
+#[derive(Component)]
+struct NightScene;
+
+#[derive(Component)]
+struct Searchlight {
+    /// Angle of the beam from straight up, positive to the left.
+    angle: f32,
+}
+
+/// A plane outside the searchlight beam during a night mission. Rockets pass straight
+/// through it.
+#[derive(Component)]
+pub struct InDarkness;
+
+pub struct NightPlugin;
+
+impl Plugin for NightPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::Playing),
+            spawn_night_scene.run_if(run_if_night),
+        )
+        .add_systems(OnExit(GameState::Playing), despawn_night_scene)
+        .add_systems(
+            Update,
+            (aim_searchlight, illuminate_planes)
+                .chain()
+                .run_if(in_state(GameState::Playing).and_then(run_if_night)),
+        );
+    }
+}
+
+fn run_if_night(mission: Res<Mission>) -> bool {
+    mission.night
+}
+
+/// Puts the night sky up or takes it down to match the mission, for a run loaded in the
+/// middle of play.
+pub fn reset_night_scene(world: &mut World) {
+    world.run_system_once(despawn_night_scene);
+    if world.resource::<Mission>().night {
+        world.run_system_once(spawn_night_scene);
+    }
+}
+
+fn spawn_night_scene(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: NIGHT_OVERLAY_COLOR,
+                custom_size: Some(PLAY_AREA + OFFSCREEN_MARGIN * 2.0),
+                ..default()
+            },
+            transform: Transform::from_xyz(
+                PLAY_AREA.x / 2.0,
+                PLAY_AREA.y / 2.0,
+                NIGHT_OVERLAY_DEPTH,
+            ),
+            ..default()
+        },
+        NightScene,
+    ));
+
+    commands.spawn((
+        MaterialMesh2dBundle {
+            mesh: Mesh2dHandle(meshes.add(searchlight_mesh())),
+            material: materials.add(SEARCHLIGHT_COLOR),
+            transform: Transform::from_xyz(0.0, 0.0, NIGHT_OVERLAY_DEPTH + 0.1),
+            ..default()
+        },
+        Searchlight { angle: 0.0 },
+        NightScene,
+    ));
+}
+
+/// A circular sector with its apex at the origin, pointing up the y axis.
+fn searchlight_mesh() -> Mesh {
+    let mut positions = vec![[0.0, 0.0, 0.0]];
+    for segment in 0..=SEARCHLIGHT_ARC_SEGMENTS {
+        let t = segment as f32 / SEARCHLIGHT_ARC_SEGMENTS as f32;
+        let angle = -SEARCHLIGHT_HALF_ANGLE + 2.0 * SEARCHLIGHT_HALF_ANGLE * t;
+        positions.push([
+            -angle.sin() * SEARCHLIGHT_RANGE,
+            angle.cos() * SEARCHLIGHT_RANGE,
+            0.0,
+        ]);
+    }
+    let indices = (1..=SEARCHLIGHT_ARC_SEGMENTS)
+        .flat_map(|segment| [0, segment, segment + 1])
+        .collect();
+    let vertex_count = positions.len();
+
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0, 0.0, 1.0]; vertex_count])
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, vec![[0.0, 0.0]; vertex_count])
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+fn despawn_night_scene(
+    mut commands: Commands,
+    scene_query: Query<Entity, With<NightScene>>,
+    mut plane_query: Query<(Entity, &mut Transform), With<InDarkness>>,
+) {
+    for scene_entity in &scene_query {
+        commands.entity(scene_entity).despawn();
+    }
+    for (plane_entity, mut plane_transform) in &mut plane_query {
+        plane_transform.translation.z = 0.0;
+        commands.entity(plane_entity).remove::<InDarkness>();
+    }
+}
+
+/// Q and E swing the beam left and right; it always follows the jeep.
+fn aim_searchlight(
+    time: Res<Time>,
+    key_input: Res<ButtonInput<KeyCode>>,
+    mut searchlight_query: Query<(&mut Transform, &mut Searchlight)>,
+    player_query: Query<&Transform, (With<Player>, Without<Searchlight>)>,
+) {
+    let Ok((mut searchlight_transform, mut searchlight)) = searchlight_query.get_single_mut()
+    else {
+        return;
+    };
+
+    let mut direction = 0.0;
+    if key_input.pressed(KeyCode::KeyQ) {
+        direction += 1.0;
+    }
+    if key_input.pressed(KeyCode::KeyE) {
+        direction -= 1.0;
+    }
+    searchlight.angle = (searchlight.angle
+        + direction * SEARCHLIGHT_TURN_SPEED * time.delta_seconds())
+    .clamp(-SEARCHLIGHT_MAX_ANGLE, SEARCHLIGHT_MAX_ANGLE);
+
+    if let Ok(player_transform) = player_query.get_single() {
+        searchlight_transform.translation.x = player_transform.translation.x;
+        searchlight_transform.translation.y = player_transform.translation.y;
+    }
+    searchlight_transform.rotation = Quat::from_rotation_z(searchlight.angle);
+}
+
+fn illuminate_planes(
+    mut commands: Commands,
+    searchlight_query: Query<(&Transform, &Searchlight)>,
+    mut plane_query: Query<
+        (Entity, &mut Transform, Has<InDarkness>),
+        (With<Plane>, Without<Searchlight>),
+    >,
+) {
+    let Ok((searchlight_transform, searchlight)) = searchlight_query.get_single() else {
+        return;
+    };
+    let apex = searchlight_transform.translation.truncate();
+    let beam_direction = Vec2::from_angle(searchlight.angle + std::f32::consts::FRAC_PI_2);
+
+    for (plane_entity, mut plane_transform, in_darkness) in &mut plane_query {
+        let to_plane = plane_transform.translation.truncate() - apex;
+        let lit = to_plane.length() <= SEARCHLIGHT_RANGE
+            && beam_direction.angle_between(to_plane).abs() <= SEARCHLIGHT_HALF_ANGLE;
+
+        plane_transform.translation.z = if lit { LIT_PLANE_DEPTH } else { 0.0 };
+        if lit && in_darkness {
+            commands.entity(plane_entity).remove::<InDarkness>();
+        } else if !lit && !in_darkness {
+            commands.entity(plane_entity).try_insert(InDarkness);
+        }
+    }
+}
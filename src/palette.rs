@@ -0,0 +1,112 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{settings::Settings, GameState};
+
+const OUTLINE_COLOR: Color = Color::WHITE;
+const OUTLINE_SHADOW_COLOR: Color = Color::BLACK;
+/// Gap left between a sprite and the outline drawn around it.
+const OUTLINE_PADDING: f32 = 3.0;
+
+/// Colour scheme used for the effects and readouts that tell friend from foe.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum ColorPalette {
+    #[default]
+    Standard,
+    /// For protanopia and deuteranopia: warnings go orange, health goes blue.
+    RedGreen,
+    /// For tritanopia: warnings go magenta, health goes teal.
+    BlueYellow,
+}
+
+impl ColorPalette {
+    fn next(self) -> Self {
+        match self {
+            Self::Standard => Self::RedGreen,
+            Self::RedGreen => Self::BlueYellow,
+            Self::BlueYellow => Self::Standard,
+        }
+    }
+
+    pub fn colors(self) -> Palette {
+        // Picked from the Okabe-Ito set, which stays distinct under each kind of
+        // colour blindness.
+        match self {
+            Self::Standard => Palette {
+                danger: Color::rgb(1.0, 0.3, 0.2),
+                health: Color::rgb(0.2, 0.9, 0.2),
+                explosion: Color::rgb(1.0, 0.6, 0.1),
+                highlight: Color::rgb(1.0, 0.9, 0.2),
+            },
+            Self::RedGreen => Palette {
+                danger: Color::rgb(0.9, 0.6, 0.0),
+                health: Color::rgb(0.35, 0.7, 0.9),
+                explosion: Color::rgb(0.95, 0.9, 0.25),
+                highlight: Color::rgb(0.8, 0.6, 0.7),
+            },
+            Self::BlueYellow => Palette {
+                danger: Color::rgb(0.85, 0.2, 0.5),
+                health: Color::rgb(0.0, 0.6, 0.5),
+                explosion: Color::rgb(0.85, 0.35, 0.2),
+                highlight: Color::rgb(0.95, 0.95, 0.95),
+            },
+        }
+    }
+}
+
+/// The colours a palette gives each role.
+pub struct Palette {
+    /// Threat warnings and target locks.
+    pub danger: Color,
+    pub health: Color,
+    pub explosion: Color,
+    /// Aim assist markers.
+    pub highlight: Color,
+}
+
+/// Something drawn with a box around it in high-contrast mode, so it stands out from
+/// busy backgrounds.
+#[derive(Component)]
+pub struct Outlined {
+    pub half_size: Vec2,
+}
+
+pub struct PalettePlugin;
+
+impl Plugin for PalettePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (cycle_palette, toggle_high_contrast))
+            .add_systems(
+                Update,
+                draw_outlines.run_if(in_state(GameState::Playing).and_then(run_if_high_contrast)),
+            );
+    }
+}
+
+fn run_if_high_contrast(settings: Res<Settings>) -> bool {
+    settings.high_contrast
+}
+
+/// `F6` steps through the colour palettes.
+fn cycle_palette(key_input: Res<ButtonInput<KeyCode>>, mut settings: ResMut<Settings>) {
+    if key_input.just_pressed(KeyCode::F6) {
+        settings.palette = settings.palette.next();
+    }
+}
+
+/// `F7` turns the high-contrast outlines on and off.
+fn toggle_high_contrast(key_input: Res<ButtonInput<KeyCode>>, mut settings: ResMut<Settings>) {
+    if key_input.just_pressed(KeyCode::F7) {
+        settings.high_contrast = !settings.high_contrast;
+    }
+}
+
+/// A light box with a dark one just outside it reads against both sky and ground.
+fn draw_outlines(mut gizmos: Gizmos, outlined_query: Query<(&GlobalTransform, &Outlined)>) {
+    for (transform, outlined) in &outlined_query {
+        let position = transform.translation().truncate();
+        let size = (outlined.half_size + OUTLINE_PADDING) * 2.0;
+        gizmos.rect_2d(position, 0.0, size, OUTLINE_COLOR);
+        gizmos.rect_2d(position, 0.0, size + 2.0, OUTLINE_SHADOW_COLOR);
+    }
+}
@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+
+use crate::{
+    crash::CrashNotice, localization::Localized, photo_mode::PhotoMode, quit::QuitPrompt, GameState,
+};
+
+/// Present while the game is paused. Virtual time is stopped as well, so anything driven
+/// by `Time` freezes by itself; this is for the systems that act on input.
+#[derive(Resource)]
+pub struct Paused;
+
+#[derive(Component)]
+struct PauseMenu;
+
+/// Line under the pause menu where the result of the last action is shown.
+#[derive(Component)]
+pub struct PauseNotice;
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            toggle_pause.run_if(
+                in_state(GameState::Playing)
+                    .and_then(not(resource_exists::<QuitPrompt>))
+                    .and_then(not(resource_exists::<PhotoMode>))
+                    .and_then(not(resource_exists::<CrashNotice>)),
+            ),
+        );
+    }
+}
+
+/// Escape pauses and resumes the game.
+fn toggle_pause(
+    mut commands: Commands,
+    key_input: Res<ButtonInput<KeyCode>>,
+    paused: Option<Res<Paused>>,
+    mut time: ResMut<Time<Virtual>>,
+    menu_query: Query<Entity, With<PauseMenu>>,
+) {
+    if !key_input.just_pressed(KeyCode::Escape) {
+        return;
+    }
+
+    if paused.is_some() {
+        commands.remove_resource::<Paused>();
+        time.unpause();
+        for menu_entity in &menu_query {
+            commands.entity(menu_entity).despawn_recursive();
+        }
+        return;
+    }
+
+    commands.insert_resource(Paused);
+    time.pause();
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(12.0),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.5).into(),
+                ..default()
+            },
+            PauseMenu,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 64.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                Localized("paused"),
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 24.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                Localized("pause-controls"),
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::rgb(1.0, 0.9, 0.5),
+                        ..default()
+                    },
+                ),
+                PauseNotice,
+            ));
+        });
+}
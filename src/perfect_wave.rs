@@ -0,0 +1,44 @@
+use bevy::prelude::*;
+
+use crate::{
+    director::{Director, WaveEndEvent},
+    hud::BannerEvent,
+    localization::Locale,
+    stats::RunStats,
+    toast::ToastEvent,
+    GameState,
+};
+
+/// Bonus for a perfect wave, for each wave played so far, so later waves are worth more.
+const PERFECT_WAVE_POINTS: u32 = 200;
+
+pub struct PerfectWavePlugin;
+
+impl Plugin for PerfectWavePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            reward_perfect_waves.run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+/// A wave the director saw through without any damage to the jeep or the buildings gets a
+/// banner and a bonus.
+fn reward_perfect_waves(
+    mut wave_end_events: EventReader<WaveEndEvent>,
+    director: Res<Director>,
+    locale: Res<Locale>,
+    mut stats: ResMut<RunStats>,
+    mut banner_events: EventWriter<BannerEvent>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    for _ in wave_end_events.read().filter(|event| event.perfect) {
+        let points = PERFECT_WAVE_POINTS * director.waves_cleared();
+        stats.score += points;
+        banner_events.send(BannerEvent(locale.get("perfect-wave-banner").to_string()));
+        toast_events.send(ToastEvent(
+            locale.format("perfect-wave", &[("points", &points)]),
+        ));
+    }
+}
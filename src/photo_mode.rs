@@ -0,0 +1,202 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+    render::view::screenshot::ScreenshotManager,
+    window::PrimaryWindow,
+};
+
+use crate::{
+    camera::Letterbox, crash::CrashNotice, localization::Locale, pause::Paused, quit::QuitPrompt,
+    toast::ToastEvent, GameState, PLAY_AREA,
+};
+
+/// Photos are saved in here as `photo-<unix time>.png`.
+const PHOTO_DIR: &str = "screenshots";
+/// Play area widths the camera pans across per second.
+const PAN_SPEED: f32 = 0.6;
+/// How far past the play area the camera may wander, as a share of its size.
+const PAN_LIMIT: f32 = 0.5;
+const MIN_PHOTO_ZOOM: f32 = 0.5;
+const MAX_PHOTO_ZOOM: f32 = 4.0;
+/// Zoom factor per notch of the mouse wheel.
+const PHOTO_ZOOM_STEP: f32 = 1.1;
+/// Touchpads scroll in pixels; this many count as one notch.
+const PIXELS_PER_NOTCH: f32 = 40.0;
+
+/// Present while the player is lining up a photo from the pause menu. The UI is tucked
+/// away and the camera is free to roam, while the game itself stays frozen.
+#[derive(Resource, Default)]
+pub struct PhotoMode {
+    /// UI that was hidden for the photo, to be shown again afterwards. Anything that keeps
+    /// showing itself again is only recorded once.
+    hidden_ui: HashSet<Entity>,
+}
+
+pub struct PhotoModePlugin;
+
+impl Plugin for PhotoModePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                enter_photo_mode.run_if(
+                    in_state(GameState::Playing)
+                        .and_then(resource_exists::<Paused>)
+                        .and_then(not(resource_exists::<PhotoMode>))
+                        .and_then(not(resource_exists::<QuitPrompt>))
+                        .and_then(not(resource_exists::<CrashNotice>)),
+                ),
+                (hide_ui, move_photo_camera, take_photo, leave_photo_mode)
+                    .chain()
+                    .run_if(resource_exists::<PhotoMode>),
+            ),
+        );
+    }
+}
+
+/// `P` on the pause menu switches to photo mode.
+fn enter_photo_mode(
+    mut commands: Commands,
+    key_input: Res<ButtonInput<KeyCode>>,
+    mut letterbox_query: Query<&mut Visibility, With<Letterbox>>,
+) {
+    if !key_input.just_pressed(KeyCode::KeyP) {
+        return;
+    }
+    commands.init_resource::<PhotoMode>();
+    // The camera can look beyond the play area, so the bars would only get in the way.
+    for mut visibility in &mut letterbox_query {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+/// Keeps every piece of UI out of the shot, including any that turns up while in photo
+/// mode.
+fn hide_ui(
+    mut photo_mode: ResMut<PhotoMode>,
+    mut ui_query: Query<(Entity, &mut Visibility), (With<Node>, Without<Parent>)>,
+) {
+    for (ui_entity, mut visibility) in &mut ui_query {
+        if *visibility != Visibility::Hidden {
+            *visibility = Visibility::Hidden;
+            photo_mode.hidden_ui.insert(ui_entity);
+        }
+    }
+}
+
+/// The arrow keys or WASD pan and the mouse wheel zooms, in real time since the game's
+/// clock is stopped.
+fn move_photo_camera(
+    time: Res<Time<Real>>,
+    key_input: Res<ButtonInput<KeyCode>>,
+    mut wheel_events: EventReader<MouseWheel>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<Camera2d>>,
+) {
+    let Ok((mut camera_transform, mut projection)) = camera_query.get_single_mut() else {
+        return;
+    };
+
+    let notches: f32 = wheel_events
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / PIXELS_PER_NOTCH,
+        })
+        .sum();
+    if notches != 0.0 {
+        let zoom = (1.0 / projection.scale * PHOTO_ZOOM_STEP.powf(notches))
+            .clamp(MIN_PHOTO_ZOOM, MAX_PHOTO_ZOOM);
+        projection.scale = 1.0 / zoom;
+    }
+
+    let mut direction = Vec2::ZERO;
+    for (keys, step) in [
+        ([KeyCode::ArrowLeft, KeyCode::KeyA], Vec2::NEG_X),
+        ([KeyCode::ArrowRight, KeyCode::KeyD], Vec2::X),
+        ([KeyCode::ArrowDown, KeyCode::KeyS], Vec2::NEG_Y),
+        ([KeyCode::ArrowUp, KeyCode::KeyW], Vec2::Y),
+    ] {
+        if key_input.any_pressed(keys) {
+            direction += step;
+        }
+    }
+    if direction == Vec2::ZERO {
+        return;
+    }
+
+    // Zoomed in, the same key press moves the same distance across the screen.
+    let pan = direction * PAN_SPEED * PLAY_AREA.x * projection.scale * time.delta_seconds();
+    let limit = PLAY_AREA * PAN_LIMIT;
+    let position = (camera_transform.translation.truncate() + pan).clamp(-limit, PLAY_AREA + limit);
+    camera_transform.translation = position.extend(camera_transform.translation.z);
+}
+
+/// Enter saves what's on screen.
+fn take_photo(
+    key_input: Res<ButtonInput<KeyCode>>,
+    locale: Res<Locale>,
+    window_query: Query<Entity, With<PrimaryWindow>>,
+    mut screenshot_manager: ResMut<ScreenshotManager>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    if !key_input.just_pressed(KeyCode::Enter) {
+        return;
+    }
+    let Ok(window_entity) = window_query.get_single() else {
+        return;
+    };
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs());
+    let path = PathBuf::from(PHOTO_DIR).join(format!("photo-{time}.png"));
+
+    let result = fs::create_dir_all(PHOTO_DIR)
+        .map_err(|error| error.to_string())
+        .and_then(|()| {
+            screenshot_manager
+                .save_screenshot_to_disk(window_entity, &path)
+                .map_err(|error| error.to_string())
+        });
+    match result {
+        Ok(()) => {
+            info!("saving photo to {}", path.display());
+            toast_events.send(ToastEvent(
+                locale.format("photo-saved", &[("path", &path.display())]),
+            ));
+        }
+        Err(error) => warn!("couldn't take photo {}: {error}", path.display()),
+    }
+}
+
+/// Escape or `P` goes back to the pause menu, with the camera where it was.
+fn leave_photo_mode(
+    mut commands: Commands,
+    key_input: Res<ButtonInput<KeyCode>>,
+    photo_mode: Res<PhotoMode>,
+    mut visibility_query: Query<&mut Visibility>,
+    letterbox_query: Query<Entity, With<Letterbox>>,
+    mut camera_query: Query<&mut Transform, With<Camera2d>>,
+) {
+    // Skip the key that opened photo mode.
+    if photo_mode.is_added() || !key_input.any_just_pressed([KeyCode::Escape, KeyCode::KeyP]) {
+        return;
+    }
+
+    commands.remove_resource::<PhotoMode>();
+    for entity in photo_mode.hidden_ui.iter().copied().chain(&letterbox_query) {
+        if let Ok(mut visibility) = visibility_query.get_mut(entity) {
+            *visibility = Visibility::Inherited;
+        }
+    }
+    // The zoom is put back by the camera itself once photo mode is over.
+    if let Ok(mut camera_transform) = camera_query.get_single_mut() {
+        camera_transform.translation = (PLAY_AREA / 2.0).extend(camera_transform.translation.z);
+    }
+}
@@ -0,0 +1,496 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use bevy::{input::keyboard::KeyboardInput, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    accessibility::AccessibilitySettings,
+    cosmetics::{Cosmetics, Garage},
+    director::WaveEndEvent,
+    grading::Grade,
+    localization::Locale,
+    quit::QuitPrompt,
+    settings::Settings,
+    stats::{RunStats, WeaponShots},
+    stats_screen::StatsScreen,
+    theme::Themes,
+    toast::ToastEvent,
+    GameState, Mission,
+};
+
+/// Each profile is saved as `<name>.json` in here.
+const PROFILE_DIR: &str = "profiles";
+/// Remembers which profile was picked last, so it's selected again on startup.
+const LAST_PROFILE_FILE: &str = "last_profile";
+const DEFAULT_PROFILE_NAME: &str = "Player";
+const MAX_NAME_LENGTH: usize = 12;
+/// Experience earned for every run played, on top of what it earns for kills.
+const XP_PER_RUN: u32 = 25;
+const XP_PER_KILL: u32 = 10;
+/// Credits earned for every kill, to spend on armour in the garage.
+const CREDITS_PER_KILL: u32 = 10;
+
+/// Totals across every run played on a profile.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LifetimeStats {
+    pub runs: u32,
+    pub kills: u32,
+    pub deaths: u32,
+    /// Waves graded, and the points their grades add up to.
+    pub graded_waves: u32,
+    pub grade_points: u32,
+    /// Seconds played, not counting time spent paused.
+    pub play_time: f32,
+    pub shots: WeaponShots,
+    /// Most kills in a row without the jeep being hurt, in any run.
+    pub best_streak: u32,
+}
+
+impl LifetimeStats {
+    /// The grade waves average out at, once there's been one to grade.
+    pub fn average_grade(&self) -> Option<Grade> {
+        (self.graded_waves > 0)
+            .then(|| Grade::from_points(self.grade_points as f32 / self.graded_waves as f32))
+    }
+}
+
+/// Everything saved for one player.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub name: String,
+    /// Best score per mode, keyed by `mode_key`.
+    pub high_scores: HashMap<String, u32>,
+    pub unlocks: Vec<String>,
+    pub stats: LifetimeStats,
+    /// Experience from every run, which decides the player's rank.
+    pub xp: u32,
+    /// Earned for kills and spent in the garage.
+    pub credits: u32,
+    /// Index into `ARMOR_TIERS` of the armour fitted to the jeep.
+    pub armor: usize,
+    pub settings: Settings,
+    pub accessibility: AccessibilitySettings,
+    pub cosmetics: Cosmetics,
+}
+
+impl Profile {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..default()
+        }
+    }
+
+    fn path(name: &str) -> PathBuf {
+        PathBuf::from(PROFILE_DIR).join(format!("{name}.json"))
+    }
+
+    fn load(name: &str) -> Self {
+        fs::read_to_string(Self::path(name))
+            .ok()
+            .and_then(|save| serde_json::from_str::<Profile>(&save).ok())
+            .map(|profile| Self {
+                name: name.to_string(),
+                ..profile
+            })
+            .unwrap_or_else(|| Self::new(name))
+    }
+
+    pub fn save(&self) {
+        let result = fs::create_dir_all(PROFILE_DIR)
+            .map_err(|error| error.to_string())
+            .and_then(|()| serde_json::to_string_pretty(self).map_err(|error| error.to_string()))
+            .and_then(|save| {
+                fs::write(Self::path(&self.name), save).map_err(|error| error.to_string())
+            });
+        if let Err(error) = result {
+            warn!("couldn't save profile {}: {error}", self.name);
+        }
+    }
+}
+
+/// Which high score table a run counts towards.
+pub fn mode_key(mission: &Mission) -> &'static str {
+    if mission.escort {
+        return "escort";
+    }
+    match (mission.coastal, mission.night) {
+        (false, false) => "day",
+        (false, true) => "night",
+        (true, false) => "coast",
+        (true, true) => "coast_night",
+    }
+}
+
+/// Names of every saved profile, sorted.
+fn profile_names() -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(PROFILE_DIR)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            (path.extension()? == "json")
+                .then(|| path.file_stem()?.to_str().map(str::to_string))
+                .flatten()
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+/// The profile being played. It is written back to disk whenever something in it
+/// changes.
+#[derive(Resource)]
+pub struct ActiveProfile(pub Profile);
+
+impl Default for ActiveProfile {
+    fn default() -> Self {
+        let name = fs::read_to_string(PathBuf::from(PROFILE_DIR).join(LAST_PROFILE_FILE))
+            .ok()
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| DEFAULT_PROFILE_NAME.to_string());
+        Self(Profile::load(&name))
+    }
+}
+
+impl ActiveProfile {
+    fn switch_to(&mut self, name: &str) {
+        self.0.save();
+        self.0 = Profile::load(name);
+        self.0.save();
+        if let Err(error) = fs::write(PathBuf::from(PROFILE_DIR).join(LAST_PROFILE_FILE), name) {
+            warn!("couldn't remember last profile: {error}");
+        }
+    }
+}
+
+/// How much of the run in progress has already been added to the profile, so banking it
+/// again only adds what's new.
+#[derive(Resource, Default)]
+struct BankedRun {
+    counted: bool,
+    kills: u32,
+    deaths: u32,
+    run_time: f32,
+    shots: WeaponShots,
+    /// Whether the run has already been cheered for setting a new high score.
+    high_score_beaten: bool,
+}
+
+/// Name being typed for a new profile. While this exists the game over screen's other
+/// keys are ignored.
+#[derive(Resource, Default)]
+pub struct ProfileNameEntry(String);
+
+#[derive(Component)]
+struct ProfilePanel;
+
+#[derive(Component)]
+struct ProfileText;
+
+pub struct ProfilePlugin;
+
+impl Plugin for ProfilePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActiveProfile>()
+            .init_resource::<BankedRun>()
+            .add_systems(PreUpdate, load_profile_preferences)
+            .add_systems(
+                Update,
+                (
+                    switch_profile.run_if(
+                        not(resource_exists::<ProfileNameEntry>)
+                            .and_then(not(resource_exists::<QuitPrompt>)),
+                    ),
+                    enter_profile_name.run_if(resource_exists::<ProfileNameEntry>),
+                    update_profile_text,
+                )
+                    .chain()
+                    .run_if(
+                        in_state(GameState::GameOver)
+                            .and_then(not(resource_exists::<Garage>))
+                            .and_then(not(resource_exists::<StatsScreen>)),
+                    ),
+            )
+            .add_systems(Update, save_profile_preferences)
+            .add_systems(
+                Update,
+                record_run
+                    .run_if(in_state(GameState::Playing).and_then(on_event::<WaveEndEvent>())),
+            )
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                (record_run, spawn_profile_panel),
+            )
+            .add_systems(
+                OnExit(GameState::GameOver),
+                (despawn_profile_panel, reset_banked_run),
+            );
+    }
+}
+
+/// Copies the settings and cosmetics of a newly selected profile into their resources.
+fn load_profile_preferences(
+    profile: Res<ActiveProfile>,
+    mut settings: ResMut<Settings>,
+    mut accessibility: ResMut<AccessibilitySettings>,
+    mut cosmetics: ResMut<Cosmetics>,
+    mut loaded: Local<Option<String>>,
+) {
+    if loaded.as_deref() == Some(profile.0.name.as_str()) {
+        return;
+    }
+    *settings = profile.0.settings.clone();
+    *accessibility = profile.0.accessibility.clone();
+    *cosmetics = profile.0.cosmetics.clone();
+    *loaded = Some(profile.0.name.clone());
+}
+
+/// Settings and cosmetics live in the profile, so any tweak is saved straight away.
+fn save_profile_preferences(
+    settings: Res<Settings>,
+    accessibility: Res<AccessibilitySettings>,
+    cosmetics: Res<Cosmetics>,
+    mut profile: ResMut<ActiveProfile>,
+) {
+    if !settings.is_changed() && !accessibility.is_changed() && !cosmetics.is_changed() {
+        return;
+    }
+    profile.0.settings = settings.clone();
+    profile.0.accessibility = accessibility.clone();
+    profile.0.cosmetics = cosmetics.clone();
+    profile.0.save();
+}
+
+/// Adds the run so far to the profile's totals and best scores, and saves it. Returns
+/// `true` the first time the run beats a high score that was already on the board.
+fn bank_run(
+    profile: &mut Profile,
+    mission: &Mission,
+    stats: &RunStats,
+    banked: &mut BankedRun,
+) -> bool {
+    if !banked.counted {
+        profile.stats.runs += 1;
+        profile.xp += XP_PER_RUN;
+        banked.counted = true;
+    }
+    // Loading a saved run can rewind the tally, which mustn't take anything back off the
+    // profile or count it twice.
+    let new_kills = stats.kills.saturating_sub(banked.kills);
+    profile.stats.kills += new_kills;
+    profile.xp += new_kills * XP_PER_KILL;
+    profile.credits += new_kills * CREDITS_PER_KILL;
+    profile.stats.deaths += stats.deaths.saturating_sub(banked.deaths);
+    banked.kills = banked.kills.max(stats.kills);
+    banked.deaths = banked.deaths.max(stats.deaths);
+    let new_time = (stats.run_time - banked.run_time).max(0.0);
+    profile.stats.play_time += new_time;
+    banked.run_time += new_time;
+    let new_shots = stats.shots.since(&banked.shots);
+    profile.stats.shots += new_shots;
+    banked.shots += new_shots;
+    profile.stats.best_streak = profile.stats.best_streak.max(stats.best_streak);
+    let high_score = profile
+        .high_scores
+        .entry(mode_key(mission).to_string())
+        .or_default();
+    let beaten = !banked.high_score_beaten && *high_score > 0 && stats.score > *high_score;
+    banked.high_score_beaten |= beaten;
+    *high_score = (*high_score).max(stats.score);
+    profile.save();
+    beaten
+}
+
+/// Runs at the end of every wave as well as at game over, so quitting or crashing mid-run
+/// loses at most the wave being played. Unlocks earned along the way are granted straight
+/// away.
+fn record_run(
+    mission: Res<Mission>,
+    stats: Res<RunStats>,
+    locale: Res<Locale>,
+    mut banked: ResMut<BankedRun>,
+    mut profile: ResMut<ActiveProfile>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    if bank_run(&mut profile.0, &mission, &stats, &mut banked) {
+        toast_events.send(ToastEvent(
+            locale.format("toast-high-score", &[("score", &stats.score)]),
+        ));
+    }
+}
+
+fn reset_banked_run(mut banked: ResMut<BankedRun>) {
+    *banked = BankedRun::default();
+}
+
+/// `P` moves to the next saved profile; Ctrl + `P` starts naming a new one.
+fn switch_profile(
+    mut commands: Commands,
+    key_input: Res<ButtonInput<KeyCode>>,
+    mut profile: ResMut<ActiveProfile>,
+) {
+    if !key_input.just_pressed(KeyCode::KeyP) {
+        return;
+    }
+    if key_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        commands.init_resource::<ProfileNameEntry>();
+        return;
+    }
+
+    let names = profile_names();
+    let next = names
+        .iter()
+        .position(|name| *name == profile.0.name)
+        .map_or(0, |index| (index + 1) % names.len().max(1));
+    let Some(name) = names.get(next) else {
+        return;
+    };
+    if *name != profile.0.name {
+        profile.switch_to(name);
+    }
+}
+
+/// Letters and digits type the name, Backspace deletes, Enter creates the profile and
+/// Escape gives up.
+fn enter_profile_name(
+    mut commands: Commands,
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut entry: ResMut<ProfileNameEntry>,
+    mut profile: ResMut<ActiveProfile>,
+) {
+    // Skip the Ctrl + P that opened the prompt.
+    if entry.is_added() {
+        keyboard_events.clear();
+        return;
+    }
+    for event in keyboard_events.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+        match event.key_code {
+            KeyCode::Enter => {
+                let name = entry.0.trim().to_string();
+                if !name.is_empty() {
+                    profile.switch_to(&name);
+                }
+                commands.remove_resource::<ProfileNameEntry>();
+                return;
+            }
+            KeyCode::Escape => {
+                commands.remove_resource::<ProfileNameEntry>();
+                return;
+            }
+            KeyCode::Backspace => {
+                entry.0.pop();
+            }
+            key_code => {
+                if let Some(character) = key_character(key_code) {
+                    if entry.0.len() < MAX_NAME_LENGTH {
+                        entry.0.push(character);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Characters allowed in profile names, which double as file names.
+fn key_character(key_code: KeyCode) -> Option<char> {
+    use KeyCode::*;
+    let letters = [
+        KeyA, KeyB, KeyC, KeyD, KeyE, KeyF, KeyG, KeyH, KeyI, KeyJ, KeyK, KeyL, KeyM, KeyN, KeyO,
+        KeyP, KeyQ, KeyR, KeyS, KeyT, KeyU, KeyV, KeyW, KeyX, KeyY, KeyZ,
+    ];
+    let digits = [
+        Digit0, Digit1, Digit2, Digit3, Digit4, Digit5, Digit6, Digit7, Digit8, Digit9,
+    ];
+    if let Some(index) = letters.iter().position(|&key| key == key_code) {
+        return char::from_u32('A' as u32 + index as u32);
+    }
+    if let Some(index) = digits.iter().position(|&key| key == key_code) {
+        return char::from_digit(index as u32, 10);
+    }
+    None
+}
+
+fn spawn_profile_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::FlexStart,
+                    align_items: AlignItems::FlexStart,
+                    padding: UiRect::all(Val::Px(12.0)),
+                    ..default()
+                },
+                ..default()
+            },
+            ProfilePanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                ProfileText,
+            ));
+        });
+}
+
+fn update_profile_text(
+    profile: Res<ActiveProfile>,
+    mission: Res<Mission>,
+    entry: Option<Res<ProfileNameEntry>>,
+    themes: Res<Themes>,
+    locale: Res<Locale>,
+    mut text_query: Query<&mut Text, With<ProfileText>>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+
+    let info = match entry {
+        Some(entry) => locale.format("profile-new", &[("name", &entry.0)]),
+        None => locale.format(
+            "profile-info",
+            &[
+                ("name", &profile.0.name),
+                ("mode", &locale.get(&format!("mode-{}", mode_key(&mission)))),
+                (
+                    "best",
+                    &profile
+                        .0
+                        .high_scores
+                        .get(mode_key(&mission))
+                        .copied()
+                        .unwrap_or_default(),
+                ),
+                ("runs", &profile.0.stats.runs),
+                ("kills", &profile.0.stats.kills),
+                ("theme", &themes.get(&profile.0.settings.theme).name),
+                ("language", &locale.name),
+            ],
+        ),
+    };
+
+    if text.sections[0].value != info {
+        text.sections[0].value = info;
+    }
+}
+
+fn despawn_profile_panel(mut commands: Commands, panel_query: Query<Entity, With<ProfilePanel>>) {
+    for panel_entity in &panel_query {
+        commands.entity(panel_entity).despawn_recursive();
+    }
+}
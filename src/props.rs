@@ -0,0 +1,325 @@
+use bevy::{
+    ecs::system::RunSystemOnce,
+    math::bounding::{Aabb2d, BoundingCircle, BoundingVolume, IntersectsVolume},
+    prelude::*,
+    sprite::Anchor,
+};
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    camera::OFFSCREEN_MARGIN, debris::DebrisEvent, explosion::ExplosionEvent, health::Health,
+    pause::Paused, rng::GameRng, terrain::Terrain, Collider, DamageCause, DamageEvent,
+    DamageSource, GameSet, GameState, Player, PlayerDamageEvent, PLAY_AREA,
+};
+
+const PROP_INTERVAL: std::ops::Range<f32> = 3.0..6.0;
+const CRATE_SIZE: Vec2 = Vec2::splat(20.0);
+const CRATE_COLOR: Color = Color::rgb(0.6, 0.44, 0.24);
+const BARREL_SIZE: Vec2 = Vec2::new(14.0, 20.0);
+const BARREL_COLOR: Color = Color::rgb(0.75, 0.18, 0.12);
+const PALM_TRUNK_SIZE: Vec2 = Vec2::new(6.0, 54.0);
+const PALM_TRUNK_COLOR: Color = Color::rgb(0.5, 0.36, 0.2);
+const PALM_FRONDS_SIZE: Vec2 = Vec2::new(44.0, 12.0);
+const PALM_FRONDS_COLOR: Color = Color::rgb(0.22, 0.5, 0.2);
+/// Behind the jeep and the ground units, in front of the buildings.
+const PROP_DEPTH: f32 = -0.35;
+/// Props this far past the left edge of the view are despawned.
+const DESPAWN_MARGIN: f32 = 100.0;
+
+/// A lit barrel goes up a moment after the blast that set it off, so a row of them
+/// goes off one after another rather than all at once.
+const BARREL_FUSE: f32 = 0.15;
+const BARREL_BLAST_RADIUS: f32 = 70.0;
+const BARREL_BLAST_DAMAGE: f32 = 1.0;
+
+/// Scenery on the ground that doesn't survive being caught in an explosion.
+#[derive(Component, Clone, Copy, Serialize, Deserialize)]
+enum Prop {
+    Crate,
+    /// Goes off itself when caught in a blast, hurting anything close by.
+    Barrel,
+    PalmTree,
+}
+
+impl Prop {
+    fn size(self) -> Vec2 {
+        match self {
+            Prop::Crate => CRATE_SIZE,
+            Prop::Barrel => BARREL_SIZE,
+            Prop::PalmTree => PALM_TRUNK_SIZE + Vec2::Y * PALM_FRONDS_SIZE.y,
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Prop::Crate => CRATE_COLOR,
+            Prop::Barrel => BARREL_COLOR,
+            Prop::PalmTree => PALM_TRUNK_COLOR,
+        }
+    }
+
+    /// The bounds of the prop standing on the ground at `base`.
+    fn bounds(self, base: Vec2) -> Aabb2d {
+        let size = self.size();
+        Aabb2d::new(base + Vec2::Y * size.y / 2.0, size / 2.0)
+    }
+}
+
+/// A barrel that's been caught in a blast and is about to go off. Its own blast is put
+/// down to whoever set off the one that lit it.
+#[derive(Component)]
+struct Lit {
+    fuse: Timer,
+    source: DamageSource,
+}
+
+#[derive(Resource)]
+struct PropSpawner(Timer);
+
+impl FromWorld for PropSpawner {
+    fn from_world(world: &mut World) -> Self {
+        Self(random_interval(&mut world.resource_mut::<GameRng>().0))
+    }
+}
+
+fn random_interval(rng: &mut impl Rng) -> Timer {
+    Timer::from_seconds(rng.gen_range(PROP_INTERVAL), TimerMode::Once)
+}
+
+pub struct PropsPlugin;
+
+impl Plugin for PropsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PropSpawner>()
+            .add_systems(
+                Update,
+                (
+                    spawn_props.in_set(GameSet::Spawning),
+                    scroll_props.in_set(GameSet::Movement),
+                    (wreck_props, burn_fuses).chain().in_set(GameSet::Cleanup),
+                )
+                    .run_if(in_state(GameState::Playing).and_then(not(resource_exists::<Paused>))),
+            )
+            .add_systems(OnExit(GameState::GameOver), despawn_props);
+    }
+}
+
+fn spawn_props(
+    mut commands: Commands,
+    time: Res<Time>,
+    terrain: Res<Terrain>,
+    mut spawner: ResMut<PropSpawner>,
+    mut rng: ResMut<GameRng>,
+) {
+    let rng = &mut rng.0;
+    spawner.0.tick(time.delta());
+    if !spawner.0.finished() {
+        return;
+    }
+    spawner.0 = random_interval(rng);
+
+    let prop = *[Prop::Crate, Prop::Barrel, Prop::PalmTree]
+        .choose(rng)
+        .unwrap_or(&Prop::Crate);
+    let x = PLAY_AREA.x + OFFSCREEN_MARGIN.x + prop.size().x;
+    spawn_prop(
+        &mut commands,
+        Transform::from_xyz(x, terrain.height_at(x), PROP_DEPTH),
+        prop,
+    );
+}
+
+fn spawn_prop(commands: &mut Commands, transform: Transform, prop: Prop) {
+    let size = match prop {
+        Prop::PalmTree => PALM_TRUNK_SIZE,
+        _ => prop.size(),
+    };
+    let mut entity = commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: prop.color(),
+                custom_size: Some(size),
+                // Anchored at the bottom so props stand on the ground.
+                anchor: Anchor::BottomCenter,
+                ..default()
+            },
+            transform,
+            ..default()
+        },
+        prop,
+    ));
+    if let Prop::PalmTree = prop {
+        entity.with_children(|parent| {
+            parent.spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: PALM_FRONDS_COLOR,
+                    custom_size: Some(PALM_FRONDS_SIZE),
+                    anchor: Anchor::BottomCenter,
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, PALM_TRUNK_SIZE.y, 0.0),
+                ..default()
+            });
+        });
+    }
+}
+
+/// Props are part of the landscape, so they drift left with the terrain.
+fn scroll_props(
+    mut commands: Commands,
+    time: Res<Time>,
+    terrain: Res<Terrain>,
+    mut prop_query: Query<(Entity, &mut Transform), With<Prop>>,
+) {
+    let despawn_x = -OFFSCREEN_MARGIN.x - DESPAWN_MARGIN;
+    for (prop_entity, mut prop_transform) in &mut prop_query {
+        prop_transform.translation.x -= terrain.scroll_speed() * time.delta_seconds();
+        let x = prop_transform.translation.x;
+        prop_transform.translation.y = terrain.height_at(x);
+        if x < despawn_x {
+            commands.entity(prop_entity).despawn_recursive();
+        }
+    }
+}
+
+/// Props caught in an explosion are knocked to pieces, apart from barrels, which are lit.
+fn wreck_props(
+    mut commands: Commands,
+    mut explosion_events: EventReader<ExplosionEvent>,
+    prop_query: Query<(Entity, &Transform, &Prop), Without<Lit>>,
+    mut debris_events: EventWriter<DebrisEvent>,
+) {
+    let explosions: Vec<(BoundingCircle, DamageSource)> = explosion_events
+        .read()
+        .map(|explosion| {
+            (
+                BoundingCircle::new(explosion.position, explosion.radius),
+                explosion.source,
+            )
+        })
+        .collect();
+    if explosions.is_empty() {
+        return;
+    }
+
+    for (prop_entity, prop_transform, prop) in &prop_query {
+        let base = prop_transform.translation.truncate();
+        let bounds = prop.bounds(base);
+        let Some(&(_, source)) = explosions
+            .iter()
+            .find(|(blast, _)| blast.intersects(&bounds))
+        else {
+            continue;
+        };
+
+        match prop {
+            Prop::Barrel => {
+                commands.entity(prop_entity).insert(Lit {
+                    fuse: Timer::from_seconds(BARREL_FUSE, TimerMode::Once),
+                    source,
+                });
+            }
+            Prop::Crate | Prop::PalmTree => {
+                commands.entity(prop_entity).despawn_recursive();
+                debris_events.send(DebrisEvent {
+                    position: bounds.center(),
+                });
+            }
+        }
+    }
+}
+
+/// Lit barrels blow up, hurting enemies and the jeep alike if they're close enough. The
+/// blast goes on to wreck or light any props around it.
+fn burn_fuses(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut barrel_query: Query<(Entity, &Transform, &mut Lit)>,
+    target_query: Query<(Entity, &Transform, &Collider, Has<Player>), With<Health>>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut player_damage_events: EventWriter<PlayerDamageEvent>,
+) {
+    for (barrel_entity, barrel_transform, mut fuse) in &mut barrel_query {
+        fuse.fuse.tick(time.delta());
+        if !fuse.fuse.finished() {
+            continue;
+        }
+
+        let position = Prop::Barrel
+            .bounds(barrel_transform.translation.truncate())
+            .center();
+        commands.entity(barrel_entity).despawn_recursive();
+        explosion_events.send(ExplosionEvent {
+            position,
+            radius: BARREL_BLAST_RADIUS,
+            source: fuse.source,
+        });
+
+        let blast = BoundingCircle::new(position, BARREL_BLAST_RADIUS);
+        for (target, target_transform, collider, is_player) in &target_query {
+            let target_box =
+                Aabb2d::new(target_transform.translation.truncate(), collider.half_size);
+            if !blast.intersects(&target_box) {
+                continue;
+            }
+            if is_player {
+                player_damage_events.send(PlayerDamageEvent {
+                    amount: BARREL_BLAST_DAMAGE,
+                    cause: DamageCause::Barrel,
+                });
+            } else {
+                damage_events.send(DamageEvent {
+                    target,
+                    amount: BARREL_BLAST_DAMAGE,
+                    source: fuse.source,
+                });
+            }
+        }
+    }
+}
+
+fn despawn_props(mut commands: Commands, prop_query: Query<Entity, With<Prop>>) {
+    for prop_entity in &prop_query {
+        commands.entity(prop_entity).despawn_recursive();
+    }
+}
+
+/// What a saved run keeps of the props along the ground. Barrels that were about to go
+/// off are put back unlit.
+#[derive(Serialize, Deserialize)]
+pub struct SavedProps(Vec<(Transform, Prop)>);
+
+impl SavedProps {
+    pub fn capture(world: &mut World) -> Self {
+        world.run_system_once(save_props)
+    }
+
+    /// Swaps the props in play for these.
+    pub fn restore(self, world: &mut World) {
+        world.run_system_once_with(self, load_props);
+    }
+}
+
+fn save_props(prop_query: Query<(&Transform, &Prop)>) -> SavedProps {
+    SavedProps(
+        prop_query
+            .iter()
+            .map(|(transform, &prop)| (*transform, prop))
+            .collect(),
+    )
+}
+
+fn load_props(
+    In(saved): In<SavedProps>,
+    mut commands: Commands,
+    prop_query: Query<Entity, With<Prop>>,
+) {
+    for prop_entity in &prop_query {
+        commands.entity(prop_entity).despawn_recursive();
+    }
+    for (transform, prop) in saved.0 {
+        spawn_prop(&mut commands, transform, prop);
+    }
+}
@@ -0,0 +1,129 @@
+use bevy::{app::AppExit, prelude::*};
+
+use crate::{
+    cosmetics::Garage, crash::CrashNotice, localization::Localized, pause::Paused,
+    photo_mode::PhotoMode, profile::ProfileNameEntry, stats_screen::StatsScreen, GameState,
+};
+
+/// Present while the game is asking whether the player really wants to quit.
+#[derive(Resource)]
+pub struct QuitPrompt;
+
+#[derive(Component)]
+struct QuitDialog;
+
+pub struct QuitPlugin;
+
+impl Plugin for QuitPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                ask_to_quit.run_if(
+                    not(resource_exists::<QuitPrompt>)
+                        .and_then(not(resource_exists::<ProfileNameEntry>))
+                        .and_then(not(resource_exists::<Garage>))
+                        .and_then(not(resource_exists::<StatsScreen>))
+                        .and_then(not(resource_exists::<PhotoMode>))
+                        .and_then(not(resource_exists::<CrashNotice>)),
+                ),
+                answer_quit_prompt.run_if(resource_exists::<QuitPrompt>),
+            ),
+        );
+    }
+}
+
+/// Escape on the game over screen, or `Q` on the pause menu, asks before quitting. Quitting
+/// mid-run throws the run away, so the dialog warns about that.
+fn ask_to_quit(
+    mut commands: Commands,
+    key_input: Res<ButtonInput<KeyCode>>,
+    state: Res<State<GameState>>,
+    paused: Option<Res<Paused>>,
+) {
+    let in_run = match state.get() {
+        GameState::GameOver if key_input.just_pressed(KeyCode::Escape) => false,
+        GameState::Playing if paused.is_some() && key_input.just_pressed(KeyCode::KeyQ) => true,
+        _ => return,
+    };
+
+    commands.insert_resource(QuitPrompt);
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(12.0),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.8).into(),
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            QuitDialog,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 48.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                Localized("quit-title"),
+            ));
+            if in_run {
+                parent.spawn((
+                    TextBundle::from_section(
+                        "",
+                        TextStyle {
+                            font_size: 24.0,
+                            color: Color::rgb(1.0, 0.5, 0.4),
+                            ..default()
+                        },
+                    ),
+                    Localized("quit-run-lost"),
+                ));
+            }
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 24.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                Localized("quit-controls"),
+            ));
+        });
+}
+
+/// `Y` quits; `N` or Escape goes back to where the player was.
+fn answer_quit_prompt(
+    mut commands: Commands,
+    key_input: Res<ButtonInput<KeyCode>>,
+    prompt: Res<QuitPrompt>,
+    dialog_query: Query<Entity, With<QuitDialog>>,
+    mut exit_events: EventWriter<AppExit>,
+) {
+    // Skip the key that opened the prompt.
+    if prompt.is_added() {
+        return;
+    }
+    if key_input.just_pressed(KeyCode::KeyY) {
+        exit_events.send(AppExit);
+    } else if key_input.any_just_pressed([KeyCode::KeyN, KeyCode::Escape]) {
+        commands.remove_resource::<QuitPrompt>();
+        for dialog_entity in &dialog_query {
+            commands.entity(dialog_entity).despawn_recursive();
+        }
+    }
+}
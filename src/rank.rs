@@ -0,0 +1,227 @@
+use bevy::prelude::*;
+
+use crate::{localization::Locale, profile::ActiveProfile, GameState};
+
+const PROGRESS_BAR_SIZE: Vec2 = Vec2::new(320.0, 10.0);
+const PROGRESS_BAR_BACKGROUND: Color = Color::rgba(0.0, 0.0, 0.0, 0.6);
+const PROGRESS_BAR_FILL: Color = Color::rgb(0.95, 0.8, 0.2);
+const RANK_FONT_SIZE: f32 = 20.0;
+const PROMOTION_FONT_SIZE: f32 = 32.0;
+const PROMOTION_COLOR: Color = Color::rgb(0.95, 0.8, 0.2);
+
+/// A step in the player's rank, earned with experience from every run on a profile.
+pub struct Rank {
+    /// Locale key of the rank's title.
+    pub title: &'static str,
+    /// Experience needed to reach it.
+    pub xp: u32,
+}
+
+pub const RANKS: &[Rank] = &[
+    Rank {
+        title: "rank-private",
+        xp: 0,
+    },
+    Rank {
+        title: "rank-corporal",
+        xp: 300,
+    },
+    Rank {
+        title: "rank-sergeant",
+        xp: 1000,
+    },
+    Rank {
+        title: "rank-lieutenant",
+        xp: 2500,
+    },
+    Rank {
+        title: "rank-captain",
+        xp: 5000,
+    },
+    Rank {
+        title: "rank-major",
+        xp: 10000,
+    },
+    Rank {
+        title: "rank-colonel",
+        xp: 20000,
+    },
+];
+
+/// Index into `RANKS` of the highest rank `xp` is enough for.
+pub fn rank_index(xp: u32) -> usize {
+    RANKS.iter().rposition(|rank| xp >= rank.xp).unwrap_or(0)
+}
+
+/// How far `xp` is from the current rank towards the next, or 1 at the top rank.
+fn progress(xp: u32) -> f32 {
+    let index = rank_index(xp);
+    let Some(next) = RANKS.get(index + 1) else {
+        return 1.0;
+    };
+    let floor = RANKS[index].xp;
+    (xp - floor) as f32 / (next.xp - floor) as f32
+}
+
+/// The rank each profile had when the current run started, to tell if it was promoted.
+#[derive(Resource, Default)]
+struct StartingRank {
+    profile: String,
+    rank: usize,
+}
+
+#[derive(Component)]
+struct RankPanel;
+
+#[derive(Component)]
+struct RankText;
+
+#[derive(Component)]
+struct PromotionText;
+
+#[derive(Component)]
+struct ProgressFill;
+
+pub struct RankPlugin;
+
+impl Plugin for RankPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StartingRank>()
+            .add_systems(Startup, remember_starting_rank)
+            .add_systems(OnEnter(GameState::GameOver), spawn_rank_panel)
+            .add_systems(
+                OnExit(GameState::GameOver),
+                (despawn_rank_panel, remember_starting_rank),
+            )
+            .add_systems(
+                Update,
+                update_rank_panel.run_if(in_state(GameState::GameOver)),
+            );
+    }
+}
+
+fn remember_starting_rank(profile: Res<ActiveProfile>, mut starting_rank: ResMut<StartingRank>) {
+    *starting_rank = StartingRank {
+        profile: profile.0.name.clone(),
+        rank: rank_index(profile.0.xp),
+    };
+}
+
+/// The player's rank and progress to the next one, along the bottom of the game over
+/// screen, with a line announcing any promotion earned in the run just played.
+fn spawn_rank_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::FlexEnd,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(6.0),
+                    padding: UiRect::all(Val::Px(24.0)),
+                    ..default()
+                },
+                ..default()
+            },
+            RankPanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: PROMOTION_FONT_SIZE,
+                        color: PROMOTION_COLOR,
+                        ..default()
+                    },
+                ),
+                PromotionText,
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: RANK_FONT_SIZE,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                RankText,
+            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(PROGRESS_BAR_SIZE.x),
+                        height: Val::Px(PROGRESS_BAR_SIZE.y),
+                        ..default()
+                    },
+                    background_color: PROGRESS_BAR_BACKGROUND.into(),
+                    ..default()
+                })
+                .with_children(|bar| {
+                    bar.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(0.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: PROGRESS_BAR_FILL.into(),
+                            ..default()
+                        },
+                        ProgressFill,
+                    ));
+                });
+        });
+}
+
+fn update_rank_panel(
+    profile: Res<ActiveProfile>,
+    starting_rank: Res<StartingRank>,
+    locale: Res<Locale>,
+    mut rank_text_query: Query<&mut Text, (With<RankText>, Without<PromotionText>)>,
+    mut promotion_text_query: Query<&mut Text, With<PromotionText>>,
+    mut fill_query: Query<&mut Style, With<ProgressFill>>,
+) {
+    let xp = profile.0.xp;
+    let index = rank_index(xp);
+    let title = locale.get(RANKS[index].title);
+    let info = match RANKS.get(index + 1) {
+        Some(next) => locale.format(
+            "rank-info",
+            &[("rank", &title), ("xp", &xp), ("next", &next.xp)],
+        ),
+        None => locale.format("rank-info-top", &[("rank", &title), ("xp", &xp)]),
+    };
+    // Only a promotion on the profile that played the run counts.
+    let promotion = if starting_rank.profile == profile.0.name && index > starting_rank.rank {
+        locale.format("promoted", &[("rank", &title.to_uppercase())])
+    } else {
+        String::new()
+    };
+
+    if let Ok(mut text) = rank_text_query.get_single_mut() {
+        if text.sections[0].value != info {
+            text.sections[0].value = info;
+        }
+    }
+    if let Ok(mut text) = promotion_text_query.get_single_mut() {
+        if text.sections[0].value != promotion {
+            text.sections[0].value = promotion;
+        }
+    }
+    for mut style in &mut fill_query {
+        let width = Val::Percent(progress(xp) * 100.0);
+        if style.width != width {
+            style.width = width;
+        }
+    }
+}
+
+fn despawn_rank_panel(mut commands: Commands, panel_query: Query<Entity, With<RankPanel>>) {
+    for panel_entity in &panel_query {
+        commands.entity(panel_entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,289 @@
+use bevy::{
+    math::bounding::{Aabb2d, BoundingCircle, IntersectsVolume},
+    prelude::*,
+};
+use rand::prelude::*;
+
+use crate::{
+    camera::OFFSCREEN_MARGIN,
+    controls::{Action, Controls},
+    director::Director,
+    explosion::ExplosionEvent,
+    ground_units::{Obstacle, BUILDING_COLOR},
+    health::Health,
+    localization::Locale,
+    pause::Paused,
+    rng::GameRng,
+    terrain::Terrain,
+    toast::ToastEvent,
+    Collider, GameSet, GameState, Player, PlayerState, PLAY_AREA,
+};
+
+const KIT_INTERVAL: std::ops::Range<f32> = 25.0..45.0;
+const KIT_HALF_SIZE: Vec2 = Vec2::splat(9.0);
+const KIT_COLOR: Color = Color::rgb(0.9, 0.9, 0.88);
+const KIT_CROSS_COLOR: Color = Color::rgb(0.8, 0.15, 0.15);
+const KIT_CROSS_THICKNESS: f32 = 4.0;
+/// Health the jeep gets back from a kit.
+const KIT_REPAIR: f32 = 1.0;
+/// Just behind the jeep, so it drives over the kit rather than under it.
+const KIT_DEPTH: f32 = -0.2;
+
+const BUILDING_HEALTH: f32 = 4.0;
+/// Anything going off against a building knocks this much out of it.
+const BUILDING_BLAST_DAMAGE: f32 = 1.0;
+/// What's left of a building with no health, blackened by the fires.
+const RUIN_COLOR: Color = Color::rgb(0.22, 0.2, 0.19);
+/// Health put back into a building each second the jeep spends working on it.
+const REPAIR_RATE: f32 = 0.5;
+/// How far from a building's walls the jeep can be and still work on it.
+const REPAIR_REACH: f32 = 24.0;
+
+/// Sent whenever a building is damaged.
+#[derive(Event)]
+pub struct BuildingDamageEvent;
+
+/// A kit lying on the ground that patches the jeep up when it drives over it.
+#[derive(Component)]
+struct RepairKit;
+
+#[derive(Resource)]
+struct KitSpawner(Timer);
+
+impl FromWorld for KitSpawner {
+    fn from_world(world: &mut World) -> Self {
+        Self(random_interval(&mut world.resource_mut::<GameRng>().0))
+    }
+}
+
+fn random_interval(rng: &mut impl Rng) -> Timer {
+    Timer::from_seconds(rng.gen_range(KIT_INTERVAL), TimerMode::Once)
+}
+
+pub struct RepairsPlugin;
+
+impl Plugin for RepairsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<KitSpawner>()
+            .add_event::<BuildingDamageEvent>()
+            .add_systems(
+                Update,
+                (
+                    (spawn_repair_kits, fortify_buildings).in_set(GameSet::Spawning),
+                    scroll_repair_kits.in_set(GameSet::Movement),
+                    (pick_up_repair_kits, damage_buildings).in_set(GameSet::Collision),
+                    (repair_buildings, shade_buildings).chain(),
+                )
+                    .run_if(in_state(GameState::Playing).and_then(not(resource_exists::<Paused>))),
+            )
+            .add_systems(OnExit(GameState::GameOver), clear_repair_kits);
+    }
+}
+
+/// Kits turn up at the far edge of the battlefield now and then, and are carried along
+/// with the ground like everything else on it.
+fn spawn_repair_kits(
+    mut commands: Commands,
+    time: Res<Time>,
+    terrain: Res<Terrain>,
+    mut spawner: ResMut<KitSpawner>,
+    mut rng: ResMut<GameRng>,
+) {
+    let rng = &mut rng.0;
+    spawner.0.tick(time.delta());
+    if !spawner.0.finished() {
+        return;
+    }
+    spawner.0 = random_interval(rng);
+
+    let x = PLAY_AREA.x + OFFSCREEN_MARGIN.x + KIT_HALF_SIZE.x;
+    let arm = Vec2::new(KIT_HALF_SIZE.x * 1.4, KIT_CROSS_THICKNESS);
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: KIT_COLOR,
+                    custom_size: Some(KIT_HALF_SIZE * 2.0),
+                    ..default()
+                },
+                transform: Transform::from_xyz(
+                    x,
+                    terrain.height_at(x) + KIT_HALF_SIZE.y,
+                    KIT_DEPTH,
+                ),
+                ..default()
+            },
+            RepairKit,
+        ))
+        .with_children(|parent| {
+            for size in [arm, arm.yx()] {
+                parent.spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color: KIT_CROSS_COLOR,
+                        custom_size: Some(size),
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(0.0, 0.0, 0.01),
+                    ..default()
+                });
+            }
+        });
+}
+
+fn scroll_repair_kits(
+    mut commands: Commands,
+    time: Res<Time>,
+    terrain: Res<Terrain>,
+    mut kit_query: Query<(Entity, &mut Transform), With<RepairKit>>,
+) {
+    let despawn_x = -OFFSCREEN_MARGIN.x - KIT_HALF_SIZE.x;
+    for (kit_entity, mut transform) in &mut kit_query {
+        transform.translation.x -= terrain.scroll_speed() * time.delta_seconds();
+        let x = transform.translation.x;
+        transform.translation.y = terrain.height_at(x) + KIT_HALF_SIZE.y;
+        if x < despawn_x {
+            commands.entity(kit_entity).despawn_recursive();
+        }
+    }
+}
+
+fn pick_up_repair_kits(
+    mut commands: Commands,
+    kit_query: Query<(Entity, &Transform), With<RepairKit>>,
+    mut player_query: Query<(&Transform, &Collider, &PlayerState, &mut Health), With<Player>>,
+    locale: Res<Locale>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    let Ok((player_transform, player_collider, PlayerState::Alive, mut health)) =
+        player_query.get_single_mut()
+    else {
+        return;
+    };
+    let player_box = Aabb2d::new(
+        player_transform.translation.truncate(),
+        player_collider.half_size,
+    );
+
+    for (kit_entity, kit_transform) in &kit_query {
+        let kit_box = Aabb2d::new(kit_transform.translation.truncate(), KIT_HALF_SIZE);
+        if player_box.intersects(&kit_box) {
+            commands.entity(kit_entity).despawn_recursive();
+            health.current = (health.current + KIT_REPAIR).min(health.max);
+            toast_events.send(ToastEvent(locale.get("repair-kit-picked-up").to_string()));
+        }
+    }
+}
+
+/// Buildings can be knocked about and patched up again, so they get health as they
+/// appear. Craters are already as damaged as they'll get.
+fn fortify_buildings(
+    mut commands: Commands,
+    obstacle_query: Query<(Entity, &Obstacle), Added<Obstacle>>,
+) {
+    for (obstacle_entity, obstacle) in &obstacle_query {
+        if obstacle.height > 0.0 {
+            commands
+                .entity(obstacle_entity)
+                .insert(Health::new(BUILDING_HEALTH));
+        }
+    }
+}
+
+/// The box a building fills, from the ground at its base up to its roof.
+fn building_box(transform: &Transform, obstacle: &Obstacle) -> Aabb2d {
+    let half_size = Vec2::new(obstacle.half_width, obstacle.height / 2.0);
+    Aabb2d::new(
+        transform.translation.truncate() + Vec2::Y * half_size.y,
+        half_size,
+    )
+}
+
+/// Bombs, mines and anything shot down on top of a building take chunks out of it.
+/// Buildings are never levelled, so whatever's left still has to be hopped.
+fn damage_buildings(
+    mut explosion_events: EventReader<ExplosionEvent>,
+    mut building_query: Query<(&Transform, &Obstacle, &mut Health)>,
+    mut building_damage_events: EventWriter<BuildingDamageEvent>,
+) {
+    for explosion in explosion_events.read() {
+        let blast = BoundingCircle::new(explosion.position, explosion.radius);
+        for (transform, obstacle, mut health) in &mut building_query {
+            if !health.is_dead() && blast.intersects(&building_box(transform, obstacle)) {
+                health.damage(BUILDING_BLAST_DAMAGE);
+                building_damage_events.send(BuildingDamageEvent);
+            }
+        }
+    }
+}
+
+/// Holding repair beside a damaged building slowly patches it up, but only in the
+/// breather between waves; in the middle of one the player is told to wait.
+fn repair_buildings(
+    time: Res<Time>,
+    controls: Res<Controls>,
+    director: Res<Director>,
+    player_query: Query<(&Transform, &Collider, &PlayerState), With<Player>>,
+    mut building_query: Query<(&Transform, &Obstacle, &mut Health)>,
+    locale: Res<Locale>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    if !controls.pressed(Action::Repair) {
+        return;
+    }
+    let Ok((player_transform, player_collider, PlayerState::Alive)) = player_query.get_single()
+    else {
+        return;
+    };
+    if !director.is_between_waves() {
+        if controls.just_pressed(Action::Repair) {
+            toast_events.send(ToastEvent(locale.get("repair-wait").to_string()));
+        }
+        return;
+    }
+
+    let reach = Aabb2d::new(
+        player_transform.translation.truncate(),
+        player_collider.half_size + Vec2::splat(REPAIR_REACH),
+    );
+    let nearest = building_query
+        .iter_mut()
+        .filter(|(transform, obstacle, health)| {
+            health.current < health.max && reach.intersects(&building_box(transform, obstacle))
+        })
+        .min_by(|(a, ..), (b, ..)| {
+            let distance = |transform: &Transform| {
+                (transform.translation.x - player_transform.translation.x).abs()
+            };
+            distance(a).total_cmp(&distance(b))
+        });
+    let Some((_, _, mut health)) = nearest else {
+        return;
+    };
+    health.current = (health.current + REPAIR_RATE * time.delta_seconds()).min(health.max);
+    if health.current >= health.max {
+        toast_events.send(ToastEvent(locale.get("building-repaired").to_string()));
+    }
+}
+
+/// Damaged buildings darken towards a blackened ruin.
+fn shade_buildings(
+    mut building_query: Query<(&Health, &mut Sprite), (With<Obstacle>, Changed<Health>)>,
+) {
+    for (health, mut sprite) in &mut building_query {
+        let damage = 1.0 - health.fraction();
+        let [r, g, b, a] = BUILDING_COLOR.as_rgba_f32();
+        let [ruin_r, ruin_g, ruin_b, _] = RUIN_COLOR.as_rgba_f32();
+        sprite.color = Color::rgba(
+            r + (ruin_r - r) * damage,
+            g + (ruin_g - g) * damage,
+            b + (ruin_b - b) * damage,
+            a,
+        );
+    }
+}
+
+fn clear_repair_kits(mut commands: Commands, kit_query: Query<Entity, With<RepairKit>>) {
+    for kit_entity in &kit_query {
+        commands.entity(kit_entity).despawn_recursive();
+    }
+}
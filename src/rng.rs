@@ -0,0 +1,15 @@
+use bevy::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+/// Where the fight draws its randomness from: when and where enemies turn up, what they
+/// do and what they drop. It's seeded from `--seed` when one is given, so a seeded run
+/// plays out the same way for as long as the player does. Effects that don't change the
+/// fight, like sparks, debris and smoke, still use `thread_rng`.
+#[derive(Resource)]
+pub struct GameRng(pub StdRng);
+
+impl GameRng {
+    pub fn new(seed: Option<u64>) -> Self {
+        Self(seed.map_or_else(StdRng::from_entropy, StdRng::seed_from_u64))
+    }
+}
@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+
+use crate::{localization::Locale, settings::Settings, GameState};
+
+const POPUP_DURATION: f32 = 1.0;
+/// Popups float up by this much over their lifetime.
+const POPUP_RISE: f32 = 40.0;
+const POPUP_FONT_SIZE: f32 = 20.0;
+/// Under the audio captions, so a caption for the same kill stays readable.
+const POPUP_DEPTH: f32 = 39.0;
+
+/// Points scored somewhere on the battlefield, shown floating up from that spot.
+#[derive(Event)]
+pub struct ScorePopupEvent {
+    pub position: Vec2,
+    pub points: u32,
+    /// Extra points for shooting the target down from a long way off, if any.
+    pub long_shot: u32,
+}
+
+#[derive(Component)]
+struct ScorePopup {
+    timer: Timer,
+    start_y: f32,
+    color: Color,
+}
+
+pub struct ScorePopupsPlugin;
+
+impl Plugin for ScorePopupsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ScorePopupEvent>()
+            .add_systems(
+                Update,
+                show_score_popups.run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(Update, fade_score_popups);
+    }
+}
+
+fn show_score_popups(
+    mut commands: Commands,
+    locale: Res<Locale>,
+    settings: Res<Settings>,
+    mut popup_events: EventReader<ScorePopupEvent>,
+) {
+    let colors = settings.palette.colors();
+    for event in popup_events.read() {
+        let mut label = format!("+{}", event.points);
+        // Long shots are called out in the highlight colour so they're noticed.
+        let color = if event.long_shot > 0 {
+            label.push('\n');
+            label.push_str(&locale.format("popup-long-shot", &[("points", &event.long_shot)]));
+            colors.highlight
+        } else {
+            Color::WHITE
+        };
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    label,
+                    TextStyle {
+                        font_size: POPUP_FONT_SIZE,
+                        color,
+                        ..default()
+                    },
+                )
+                .with_justify(JustifyText::Center),
+                transform: Transform::from_translation(event.position.extend(POPUP_DEPTH)),
+                ..default()
+            },
+            ScorePopup {
+                timer: Timer::from_seconds(POPUP_DURATION, TimerMode::Once),
+                start_y: event.position.y,
+                color,
+            },
+        ));
+    }
+}
+
+fn fade_score_popups(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut popup_query: Query<(Entity, &mut ScorePopup, &mut Transform, &mut Text)>,
+) {
+    for (popup_entity, mut popup, mut transform, mut text) in &mut popup_query {
+        popup.timer.tick(time.delta());
+        if popup.timer.finished() {
+            commands.entity(popup_entity).despawn();
+            continue;
+        }
+        let progress = popup.timer.fraction();
+        transform.translation.y = popup.start_y + POPUP_RISE * progress;
+        text.sections[0].style.color = popup.color.with_a(1.0 - progress);
+    }
+}
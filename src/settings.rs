@@ -0,0 +1,140 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    controls::InputPreset, localization::DEFAULT_LANGUAGE, palette::ColorPalette,
+    profile::ProfileNameEntry, theme::DEFAULT_THEME, PLAY_AREA,
+};
+
+/// The HUD and menus are laid out against the play area's size.
+const UI_REFERENCE_RESOLUTION: Vec2 = PLAY_AREA;
+const UI_SCALE_STEP: f32 = 0.1;
+const MIN_UI_SCALE: f32 = 0.5;
+const MAX_UI_SCALE: f32 = 3.0;
+
+#[derive(Resource, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// User multiplier applied on top of the window-derived UI scale.
+    pub ui_scale: f32,
+    /// Aim assist showing where to stand to hit the nearest plane.
+    pub lead_indicator: bool,
+    /// Let the spawn director ease off after repeated deaths and push harder during
+    /// flawless stretches.
+    pub adaptive_difficulty: bool,
+    /// Id of the visual theme pack in use.
+    pub theme: String,
+    /// Camera magnification, changed with the mouse wheel.
+    pub camera_zoom: f32,
+    /// Visual effect: shimmering air over the ground, in themes hot enough for it.
+    pub heat_haze: bool,
+    /// Colours for warnings, health and explosions, with colour-blind friendly options.
+    pub palette: ColorPalette,
+    /// Outline bombs and pickups so they stand out.
+    pub high_contrast: bool,
+    /// Bindings the jeep is driven with.
+    pub input_preset: InputPreset,
+    /// Id of the language the interface is shown in.
+    pub language: String,
+    /// Holding fire keeps firing, as fast as each weapon allows.
+    pub autofire: bool,
+    /// Keep anonymous balance numbers from each run. Off until the player turns it on.
+    pub telemetry: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            ui_scale: 1.0,
+            lead_indicator: false,
+            adaptive_difficulty: false,
+            theme: DEFAULT_THEME.to_string(),
+            camera_zoom: 1.0,
+            heat_haze: true,
+            palette: ColorPalette::Standard,
+            high_contrast: false,
+            input_preset: InputPreset::Standard,
+            language: DEFAULT_LANGUAGE.to_string(),
+            autofire: false,
+            telemetry: false,
+        }
+    }
+}
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>().add_systems(
+            Update,
+            (
+                (adjust_ui_scale, apply_ui_scale).chain(),
+                toggle_lead_indicator.run_if(not(resource_exists::<ProfileNameEntry>)),
+                toggle_adaptive_difficulty,
+            ),
+        );
+    }
+}
+
+/// `L` turns the lead indicator aim assist on and off. Ctrl + `L` is left to the language.
+fn toggle_lead_indicator(key_input: Res<ButtonInput<KeyCode>>, mut settings: ResMut<Settings>) {
+    if key_input.just_pressed(KeyCode::KeyL)
+        && !key_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+    {
+        settings.lead_indicator = !settings.lead_indicator;
+    }
+}
+
+/// `F2` turns adaptive difficulty on and off.
+fn toggle_adaptive_difficulty(
+    key_input: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<Settings>,
+) {
+    if key_input.just_pressed(KeyCode::F2) {
+        settings.adaptive_difficulty = !settings.adaptive_difficulty;
+    }
+}
+
+/// Ctrl + `=` / `-` grows or shrinks the interface, Ctrl + `0` resets it.
+fn adjust_ui_scale(key_input: Res<ButtonInput<KeyCode>>, mut settings: ResMut<Settings>) {
+    if !key_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+
+    let mut ui_scale = settings.ui_scale;
+    if key_input.just_pressed(KeyCode::Equal) {
+        ui_scale += UI_SCALE_STEP;
+    }
+    if key_input.just_pressed(KeyCode::Minus) {
+        ui_scale -= UI_SCALE_STEP;
+    }
+    if key_input.just_pressed(KeyCode::Digit0) {
+        ui_scale = 1.0;
+    }
+    ui_scale = ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+
+    if ui_scale != settings.ui_scale {
+        settings.ui_scale = ui_scale;
+    }
+}
+
+/// Bevy already lays UI out in logical pixels, so the OS scale factor is handled for us.
+/// On top of that we scale relative to the reference resolution so text stays readable
+/// on both large and small windows, then apply the user's preference.
+fn apply_ui_scale(
+    settings: Res<Settings>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+
+    let window_scale = (window.width() / UI_REFERENCE_RESOLUTION.x)
+        .min(window.height() / UI_REFERENCE_RESOLUTION.y);
+    let scale = window_scale * settings.ui_scale;
+
+    if ui_scale.0 != scale {
+        ui_scale.0 = scale;
+    }
+}
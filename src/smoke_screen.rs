@@ -0,0 +1,206 @@
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use crate::{
+    controls::{Action, Controls},
+    localization::Locale,
+    pause::Paused,
+    terrain::Terrain,
+    toast::ToastEvent,
+    GameSet, GameState, Player, PlayerState,
+};
+
+/// Canisters the jeep starts each run with.
+const SMOKE_CANISTERS: u32 = 2;
+const SMOKE_LIFETIME: f32 = 8.0;
+/// Seconds the cloud takes to billow out to full size, and to thin away at the end.
+const SMOKE_BILLOW: f32 = 1.0;
+const SMOKE_FADE: f32 = 2.0;
+/// The jeep is hidden while it's this close to the middle of a cloud.
+const SMOKE_RADIUS: f32 = 110.0;
+/// Drift against the scrolling ground, so the jeep has to keep up with the cloud.
+const SMOKE_WIND: f32 = 15.0;
+/// The cloud's middle hangs this far above the ground where it was popped.
+const SMOKE_HEIGHT: f32 = 50.0;
+const SMOKE_PUFFS: usize = 16;
+const SMOKE_PUFF_SIZE: std::ops::Range<f32> = 40.0..80.0;
+const SMOKE_ALPHA: f32 = 0.85;
+const SMOKE_GREY: f32 = 0.82;
+/// In front of the jeep, so it's actually covered.
+const SMOKE_DEPTH: f32 = 0.6;
+
+/// The jeep is somewhere in the smoke, so enemies can't pick it out to aim at.
+#[derive(Component)]
+pub struct InSmoke;
+
+/// Smoke canisters the jeep has left this run.
+#[derive(Resource)]
+struct SmokeCanisters(u32);
+
+impl Default for SmokeCanisters {
+    fn default() -> Self {
+        Self(SMOKE_CANISTERS)
+    }
+}
+
+/// A cloud of smoke drifting over the battlefield, made up of puffs around its middle.
+#[derive(Component)]
+struct SmokeScreen {
+    lifetime: Timer,
+}
+
+#[derive(Component)]
+struct SmokePuff {
+    offset: Vec2,
+}
+
+pub struct SmokeScreenPlugin;
+
+impl Plugin for SmokeScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SmokeCanisters>()
+            .add_systems(
+                Update,
+                (
+                    pop_smoke.in_set(GameSet::Input),
+                    (drift_smoke, billow_smoke)
+                        .chain()
+                        .in_set(GameSet::Movement),
+                    conceal_jeep.in_set(GameSet::Collision),
+                )
+                    .run_if(in_state(GameState::Playing).and_then(not(resource_exists::<Paused>))),
+            )
+            .add_systems(OnExit(GameState::GameOver), clear_smoke);
+    }
+}
+
+fn pop_smoke(
+    mut commands: Commands,
+    controls: Res<Controls>,
+    terrain: Res<Terrain>,
+    mut canisters: ResMut<SmokeCanisters>,
+    player_query: Query<(&Transform, &PlayerState), With<Player>>,
+    locale: Res<Locale>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    if !controls.just_pressed(Action::Smoke) {
+        return;
+    }
+    let Ok((player_transform, PlayerState::Alive)) = player_query.get_single() else {
+        return;
+    };
+    if canisters.0 == 0 {
+        toast_events.send(ToastEvent(locale.get("smoke-empty").to_string()));
+        return;
+    }
+    canisters.0 -= 1;
+    toast_events.send(ToastEvent(
+        locale.format("smoke-popped", &[("left", &canisters.0)]),
+    ));
+
+    let mut rng = thread_rng();
+    let x = player_transform.translation.x;
+    commands
+        .spawn((
+            SpatialBundle::from_transform(Transform::from_xyz(
+                x,
+                terrain.height_at(x) + SMOKE_HEIGHT,
+                SMOKE_DEPTH,
+            )),
+            SmokeScreen {
+                lifetime: Timer::from_seconds(SMOKE_LIFETIME, TimerMode::Once),
+            },
+        ))
+        .with_children(|parent| {
+            for _ in 0..SMOKE_PUFFS {
+                let offset = Vec2::new(
+                    rng.gen_range(-1.0..1.0) * SMOKE_RADIUS,
+                    rng.gen_range(-0.6..0.6) * SMOKE_HEIGHT,
+                );
+                let grey = SMOKE_GREY + rng.gen_range(-0.08..0.08);
+                parent.spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::rgba(grey, grey, grey, SMOKE_ALPHA),
+                            custom_size: Some(Vec2::splat(rng.gen_range(SMOKE_PUFF_SIZE))),
+                            ..default()
+                        },
+                        transform: Transform::from_scale(Vec3::ZERO)
+                            .with_rotation(Quat::from_rotation_z(rng.gen_range(0.0..1.0))),
+                        ..default()
+                    },
+                    SmokePuff { offset },
+                ));
+            }
+        });
+}
+
+fn drift_smoke(
+    mut commands: Commands,
+    time: Res<Time>,
+    terrain: Res<Terrain>,
+    mut smoke_query: Query<(Entity, &mut Transform, &mut SmokeScreen)>,
+) {
+    for (smoke_entity, mut transform, mut smoke) in &mut smoke_query {
+        smoke.lifetime.tick(time.delta());
+        if smoke.lifetime.finished() {
+            commands.entity(smoke_entity).despawn_recursive();
+            continue;
+        }
+        transform.translation.x -= (terrain.scroll_speed() + SMOKE_WIND) * time.delta_seconds();
+    }
+}
+
+/// Puffs swell out from the canister, then thin away as the cloud runs out.
+fn billow_smoke(
+    smoke_query: Query<(&SmokeScreen, &Children)>,
+    mut puff_query: Query<(&SmokePuff, &mut Transform, &mut Sprite)>,
+) {
+    for (smoke, children) in &smoke_query {
+        let elapsed = smoke.lifetime.elapsed_secs();
+        let growth = (elapsed / SMOKE_BILLOW).min(1.0);
+        let fade = (smoke.lifetime.remaining_secs() / SMOKE_FADE).min(1.0);
+        let mut puffs = puff_query.iter_many_mut(children);
+        while let Some((puff, mut transform, mut sprite)) = puffs.fetch_next() {
+            transform.translation = (puff.offset * growth).extend(0.0);
+            transform.scale = Vec3::splat(growth);
+            sprite.color.set_a(SMOKE_ALPHA * fade);
+        }
+    }
+}
+
+/// Sitting in a cloud that's yet to thin out hides the jeep from anything aiming at it.
+fn conceal_jeep(
+    mut commands: Commands,
+    smoke_query: Query<(&Transform, &SmokeScreen)>,
+    player_query: Query<(Entity, &Transform, Has<InSmoke>), With<Player>>,
+) {
+    let Ok((player_entity, player_transform, concealed)) = player_query.get_single() else {
+        return;
+    };
+    let position = player_transform.translation.truncate();
+    let hidden = smoke_query.iter().any(|(transform, smoke)| {
+        smoke.lifetime.remaining_secs() > SMOKE_FADE / 2.0
+            && transform.translation.truncate().distance(position) <= SMOKE_RADIUS
+    });
+    if hidden && !concealed {
+        commands.entity(player_entity).insert(InSmoke);
+    } else if !hidden && concealed {
+        commands.entity(player_entity).remove::<InSmoke>();
+    }
+}
+
+fn clear_smoke(
+    mut commands: Commands,
+    mut canisters: ResMut<SmokeCanisters>,
+    smoke_query: Query<Entity, With<SmokeScreen>>,
+    player_query: Query<Entity, With<InSmoke>>,
+) {
+    for smoke_entity in &smoke_query {
+        commands.entity(smoke_entity).despawn_recursive();
+    }
+    for player_entity in &player_query {
+        commands.entity(player_entity).remove::<InSmoke>();
+    }
+    *canisters = SmokeCanisters::default();
+}
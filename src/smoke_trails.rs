@@ -0,0 +1,122 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use crate::{health::Health, GameState, Plane};
+
+/// Puffs per second from a plane that's one hit from going down. Less damaged planes
+/// smoke less.
+const MAX_PUFF_RATE: f32 = 14.0;
+const PUFF_LIFETIME: f32 = 1.2;
+const PUFF_START_SIZE: f32 = 8.0;
+/// How many times its starting size a puff is by the time it's gone.
+const PUFF_GROWTH: f32 = 3.0;
+const PUFF_RISE_SPEED: f32 = 30.0;
+const PUFF_ALPHA: f32 = 0.6;
+/// Puffs come out of the back of the plane, which flies leftwards.
+const EXHAUST_OFFSET: Vec2 = Vec2::new(28.0, 4.0);
+/// Just behind the planes, but in front of the ground.
+const PUFF_Z: f32 = -0.1;
+/// Light grey for a scratched plane, darkening to near black as it's worn down.
+const LIGHT_SMOKE_GREY: f32 = 0.75;
+const DARK_SMOKE_GREY: f32 = 0.15;
+
+/// Trails smoke from a damaged plane, so its remaining health can be read at a glance.
+#[derive(Component, Default)]
+struct Smoking {
+    /// Puffs owed but not yet let out, carried over between frames.
+    pending: f32,
+}
+
+#[derive(Component)]
+struct SmokePuff {
+    lifetime: Timer,
+    color: Color,
+}
+
+pub struct SmokeTrailsPlugin;
+
+impl Plugin for SmokeTrailsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (start_smoking, emit_smoke, drift_smoke)
+                .chain()
+                .run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(OnExit(GameState::GameOver), clear_smoke);
+    }
+}
+
+fn start_smoking(
+    mut commands: Commands,
+    plane_query: Query<(Entity, &Health), (With<Plane>, Without<Smoking>, Changed<Health>)>,
+) {
+    for (plane_entity, health) in &plane_query {
+        if health.current < health.max {
+            commands.entity(plane_entity).try_insert(Smoking::default());
+        }
+    }
+}
+
+fn emit_smoke(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut plane_query: Query<(&Transform, &Health, &mut Smoking)>,
+) {
+    let mut rng = thread_rng();
+    for (plane_transform, health, mut smoking) in &mut plane_query {
+        let damage = 1.0 - health.fraction();
+        smoking.pending += MAX_PUFF_RATE * damage * time.delta_seconds();
+        let exhaust = plane_transform.translation.truncate() + EXHAUST_OFFSET;
+        let grey = LIGHT_SMOKE_GREY + (DARK_SMOKE_GREY - LIGHT_SMOKE_GREY) * damage;
+        let color = Color::rgb(grey, grey, grey);
+        while smoking.pending >= 1.0 {
+            smoking.pending -= 1.0;
+            let jitter = Vec2::new(rng.gen_range(-4.0..4.0), rng.gen_range(-4.0..4.0));
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: color.with_a(PUFF_ALPHA),
+                        custom_size: Some(Vec2::splat(PUFF_START_SIZE)),
+                        ..default()
+                    },
+                    transform: Transform::from_translation((exhaust + jitter).extend(PUFF_Z))
+                        .with_rotation(Quat::from_rotation_z(rng.gen_range(0.0..TAU))),
+                    ..default()
+                },
+                SmokePuff {
+                    lifetime: Timer::from_seconds(PUFF_LIFETIME, TimerMode::Once),
+                    color,
+                },
+            ));
+        }
+    }
+}
+
+/// Puffs hang where they were let out, rising and spreading as they thin away.
+fn drift_smoke(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut puff_query: Query<(Entity, &mut SmokePuff, &mut Transform, &mut Sprite)>,
+) {
+    for (puff_entity, mut puff, mut transform, mut sprite) in &mut puff_query {
+        puff.lifetime.tick(time.delta());
+        if puff.lifetime.finished() {
+            commands.entity(puff_entity).despawn();
+            continue;
+        }
+        let age = puff.lifetime.fraction();
+        transform.translation.y += PUFF_RISE_SPEED * time.delta_seconds();
+        let size = 1.0 + (PUFF_GROWTH - 1.0) * age;
+        transform.scale = Vec3::new(size, size, 1.0);
+        sprite.color = puff.color.with_a(PUFF_ALPHA * (1.0 - age));
+    }
+}
+
+fn clear_smoke(mut commands: Commands, puff_query: Query<Entity, With<SmokePuff>>) {
+    for puff_entity in &puff_query {
+        commands.entity(puff_entity).despawn();
+    }
+}
@@ -0,0 +1,349 @@
+use std::{fs, path::PathBuf};
+
+use bevy::{
+    ecs::entity::EntityHashMap, hierarchy::despawn_with_children_recursive,
+    input::common_conditions::input_just_pressed, prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ai::{EnemyAi, SteersItself},
+    balloons::SavedBalloons,
+    bomb_sprite,
+    bomber::{Bomber, SavedBombers},
+    bonus::SavedBonus,
+    coast::SavedCoast,
+    convoys::SavedTrucks,
+    cosmetics::Cosmetics,
+    crash::CrashNotice,
+    director::Director,
+    drone::SavedDrone,
+    escort::SavedEscort,
+    fortress::SavedFortresses,
+    ground_units::{GroundUnit, Obstacle},
+    health::Health,
+    jammer::{Jammer, SavedJammers},
+    localization::Locale,
+    mines::SavedMines,
+    night::reset_night_scene,
+    pause::{PauseNotice, Paused},
+    photo_mode::PhotoMode,
+    plane_sprite,
+    profile::ActiveProfile,
+    props::SavedProps,
+    rocket_sprite,
+    settings::Settings,
+    stats::RunStats,
+    terrain::Terrain,
+    theme::Themes,
+    timers::BombTimer,
+    toast::ToastEvent,
+    turrets::SavedTurrets,
+    wave_events::SavedWaveEvents,
+    Bomb, Collider, GameState, Mission, Plane, Player, PlayerState, Rocket,
+};
+
+/// Each profile's saved run is kept as `<name>.json` in here.
+const SAVE_DIR: &str = "saves";
+
+/// Everything needed to pick a run back up where it was left, special events and bosses
+/// included. Short-lived effects (the jeep's missiles, flares and explosions) aren't kept.
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    mission: Mission,
+    stats: RunStats,
+    director: Director,
+    terrain: Terrain,
+    player: (Transform, Player, PlayerState, Health),
+    /// The ordinary planes. The other kinds are kept with the rest of what they bring.
+    planes: Vec<(Transform, Plane, BombTimer, EnemyAi, Health, Collider)>,
+    bombs: Vec<(Transform, Bomb)>,
+    rockets: Vec<(Transform, Rocket)>,
+    infantry: Vec<(Transform, GroundUnit, EnemyAi, Health, Collider)>,
+    /// With the entity each was, so missiles aimed at one can be pointed at it again.
+    obstacles: Vec<(Entity, Transform, Obstacle)>,
+    bombers: SavedBombers,
+    jammers: SavedJammers,
+    coast: SavedCoast,
+    balloons: SavedBalloons,
+    props: SavedProps,
+    trucks: SavedTrucks,
+    escort: SavedEscort,
+    bonus: SavedBonus,
+    fortresses: SavedFortresses,
+    mines: SavedMines,
+    turrets: SavedTurrets,
+    drone: SavedDrone,
+    wave_events: SavedWaveEvents,
+}
+
+impl Snapshot {
+    fn capture(world: &mut World) -> Option<Self> {
+        let player = world
+            .query::<(&Transform, &Player, &PlayerState, &Health)>()
+            .get_single(world)
+            .ok()
+            .map(|(transform, player, state, health)| {
+                (*transform, player.clone(), state.clone(), health.clone())
+            })?;
+        let planes = world
+            .query_filtered::<(&Transform, &Plane, &BombTimer, &EnemyAi, &Health, &Collider), (
+                Without<Bomber>,
+                Without<Jammer>,
+            )>()
+            .iter(world)
+            .map(|(transform, plane, bomb_timer, ai, health, collider)| {
+                (
+                    *transform,
+                    plane.clone(),
+                    bomb_timer.clone(),
+                    ai.clone(),
+                    health.clone(),
+                    collider.clone(),
+                )
+            })
+            .collect();
+        let bombs = world
+            .query::<(&Transform, &Bomb)>()
+            .iter(world)
+            .map(|(transform, bomb)| (*transform, bomb.clone()))
+            .collect();
+        let rockets = world
+            .query::<(&Transform, &Rocket)>()
+            .iter(world)
+            .map(|(transform, rocket)| (*transform, rocket.clone()))
+            .collect();
+        let infantry = world
+            .query::<(&Transform, &GroundUnit, &EnemyAi, &Health, &Collider)>()
+            .iter(world)
+            .map(|(transform, unit, ai, health, collider)| {
+                (
+                    *transform,
+                    unit.clone(),
+                    ai.clone(),
+                    health.clone(),
+                    collider.clone(),
+                )
+            })
+            .collect();
+        let obstacles = world
+            .query::<(Entity, &Transform, &Obstacle)>()
+            .iter(world)
+            .map(|(entity, transform, obstacle)| (entity, *transform, obstacle.clone()))
+            .collect();
+
+        Some(Self {
+            mission: world.resource::<Mission>().clone(),
+            stats: world.resource::<RunStats>().clone(),
+            director: world.resource::<Director>().clone(),
+            terrain: world.resource::<Terrain>().clone(),
+            player,
+            planes,
+            bombs,
+            rockets,
+            infantry,
+            obstacles,
+            bombers: SavedBombers::capture(world),
+            jammers: SavedJammers::capture(world),
+            coast: SavedCoast::capture(world),
+            balloons: SavedBalloons::capture(world),
+            props: SavedProps::capture(world),
+            trucks: SavedTrucks::capture(world),
+            escort: SavedEscort::capture(world),
+            bonus: SavedBonus::capture(world),
+            fortresses: SavedFortresses::capture(world),
+            mines: SavedMines::capture(world),
+            turrets: SavedTurrets::capture(world),
+            drone: SavedDrone::capture(world),
+            wave_events: SavedWaveEvents::capture(world),
+        })
+    }
+
+    /// Replaces the run in progress with this one.
+    fn restore(self, world: &mut World) {
+        let saved_entities: Vec<Entity> = world
+            .query_filtered::<Entity, Or<(
+                With<Plane>,
+                With<Bomb>,
+                With<Rocket>,
+                With<GroundUnit>,
+                With<Obstacle>,
+            )>>()
+            .iter(world)
+            .collect();
+        for entity in saved_entities {
+            despawn_with_children_recursive(world, entity);
+        }
+
+        world.insert_resource(self.mission);
+        world.insert_resource(self.stats);
+        world.insert_resource(self.director);
+        world.insert_resource(self.terrain);
+
+        let (transform, player, state, health) = self.player;
+        let visibility = match state {
+            PlayerState::Alive => Visibility::Visible,
+            PlayerState::Dead(_) => Visibility::Hidden,
+        };
+        if let Ok(player_entity) = world
+            .query_filtered::<Entity, With<Player>>()
+            .get_single(world)
+        {
+            world
+                .entity_mut(player_entity)
+                .insert((transform, player, state, health, visibility));
+        }
+
+        let asset_server = world.resource::<AssetServer>().clone();
+        let cosmetics = world.resource::<Cosmetics>().clone();
+        let settings = world.resource::<Settings>().clone();
+        let themes = world.resource::<Themes>();
+        let theme = themes.get(&settings.theme);
+        let planes: Vec<_> = self
+            .planes
+            .into_iter()
+            .map(|(transform, plane, bomb_timer, ai, health, collider)| {
+                (
+                    plane_sprite(&asset_server, &cosmetics, theme, transform),
+                    plane,
+                    bomb_timer,
+                    ai,
+                    health,
+                    collider,
+                )
+            })
+            .collect();
+        let bombs: Vec<_> = self
+            .bombs
+            .into_iter()
+            .map(|(transform, bomb)| (bomb_sprite(&asset_server, theme, transform), bomb))
+            .collect();
+
+        world.spawn_batch(planes);
+        world.spawn_batch(bombs);
+        world.spawn_batch(
+            self.rockets
+                .into_iter()
+                .map(|(transform, rocket)| (rocket_sprite(&asset_server, transform), rocket)),
+        );
+        world.spawn_batch(self.infantry.into_iter().map(
+            |(transform, unit, ai, health, collider)| {
+                (
+                    GroundUnit::sprite(transform),
+                    unit,
+                    ai,
+                    SteersItself,
+                    health,
+                    collider,
+                )
+            },
+        ));
+        let buildings: EntityHashMap<Entity> = self
+            .obstacles
+            .into_iter()
+            .map(|(saved_entity, transform, obstacle)| {
+                let entity = world.spawn((obstacle.sprite(transform), obstacle)).id();
+                (saved_entity, entity)
+            })
+            .collect();
+
+        self.bombers.restore(world);
+        self.jammers.restore(world);
+        self.coast.restore(world);
+        self.balloons.restore(world);
+        self.props.restore(world);
+        self.trucks.restore(world);
+        self.escort.restore(world);
+        self.bonus.restore(world);
+        self.fortresses.restore(world, buildings);
+        self.mines.restore(world);
+        self.turrets.restore(world);
+        self.drone.restore(world);
+        self.wave_events.restore(world);
+        reset_night_scene(world);
+    }
+}
+
+fn snapshot_path(world: &World) -> PathBuf {
+    let name = &world.resource::<ActiveProfile>().0.name;
+    PathBuf::from(SAVE_DIR).join(format!("{name}.json"))
+}
+
+pub struct SnapshotPlugin;
+
+impl Plugin for SnapshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                save_snapshot.run_if(input_just_pressed(KeyCode::F5)),
+                load_snapshot.run_if(input_just_pressed(KeyCode::F9)),
+            )
+                .run_if(
+                    in_state(GameState::Playing)
+                        .and_then(resource_exists::<Paused>)
+                        .and_then(not(resource_exists::<PhotoMode>))
+                        .and_then(not(resource_exists::<CrashNotice>)),
+                ),
+        );
+    }
+}
+
+/// `F5` in the pause menu writes the run to the active profile's save slot.
+fn save_snapshot(world: &mut World) {
+    let Some(snapshot) = Snapshot::capture(world) else {
+        return;
+    };
+    let path = snapshot_path(world);
+    let result = fs::create_dir_all(SAVE_DIR)
+        .map_err(|error| error.to_string())
+        .and_then(|()| serde_json::to_string(&snapshot).map_err(|error| error.to_string()))
+        .and_then(|save| fs::write(&path, save).map_err(|error| error.to_string()));
+
+    match result {
+        Ok(()) => {
+            let toast = world.resource::<Locale>().get("run-saved").to_string();
+            world.send_event(ToastEvent(toast));
+            set_notice(world, String::new());
+        }
+        Err(error) => {
+            warn!("couldn't save run to {}: {error}", path.display());
+            show_notice(world, "run-save-failed");
+        }
+    }
+}
+
+/// `F9` in the pause menu swaps the run in progress for the saved one. The game stays
+/// paused so the player can get their bearings before carrying on.
+fn load_snapshot(world: &mut World) {
+    let path = snapshot_path(world);
+    let snapshot = fs::read_to_string(&path)
+        .map_err(|error| error.to_string())
+        .and_then(|save| {
+            serde_json::from_str::<Snapshot>(&save).map_err(|error| error.to_string())
+        });
+
+    let notice = match snapshot {
+        Ok(snapshot) => {
+            snapshot.restore(world);
+            "run-loaded"
+        }
+        Err(error) => {
+            info!("no saved run at {}: {error}", path.display());
+            "no-saved-run"
+        }
+    };
+    show_notice(world, notice);
+}
+
+fn show_notice(world: &mut World, key: &str) {
+    let notice = world.resource::<Locale>().get(key).to_string();
+    set_notice(world, notice);
+}
+
+fn set_notice(world: &mut World, notice: String) {
+    let mut notice_query = world.query_filtered::<&mut Text, With<PauseNotice>>();
+    for mut text in notice_query.iter_mut(world) {
+        text.sections[0].value.clone_from(&notice);
+    }
+}
@@ -0,0 +1,358 @@
+use std::{path::Path, time::Duration};
+
+use bevy::{
+    audio::{DefaultSpatialScale, SpatialScale, Volume},
+    prelude::*,
+};
+
+use crate::{
+    audio_cues::{AudioCue, AudioCueEvent},
+    camera::view_rect,
+    mods::ModPacks,
+    pause::Paused,
+    settings::Settings,
+    streaks::StreakEvent,
+    supply::SupplyPlane,
+    Bomb, GameState, Plane, Player,
+};
+
+/// World units per unit of audio distance. Sounds are at full volume within this of the
+/// jeep and fall off with distance beyond it.
+const AUDIO_DISTANCE: f32 = 200.0;
+/// Distance between the jeep's ears, before its sprite scale is applied, so sounds pan
+/// with which side of it they are on.
+const EAR_GAP: f32 = 100.0;
+
+const WHISTLE_TONE: f32 = 1400.0;
+const WHISTLE_VOLUME: f32 = 0.15;
+/// The whistle drops to this fraction of its pitch as the bomb closes in on the jeep.
+const WHISTLE_LOWEST_PITCH: f32 = 0.55;
+/// Beyond this distance from the jeep the whistle keeps its highest pitch.
+const WHISTLE_RANGE: f32 = 800.0;
+/// Bombs whistling this close to the jeep get a caption for players who can't hear it.
+const WHISTLE_CUE_RANGE: f32 = 300.0;
+
+const ENGINE_TONE: f32 = 90.0;
+const ENGINE_VOLUME: f32 = 0.08;
+/// Friendly supply planes drone higher than enemy ones, so they can be told apart by ear.
+const SUPPLY_ENGINE_TONE: f32 = 150.0;
+
+/// The announcer's chime for a kill streak.
+const FANFARE_TONE: f32 = 880.0;
+const FANFARE_LENGTH: Duration = Duration::from_millis(300);
+const FANFARE_VOLUME: f32 = 0.2;
+
+/// Music stems are `<stem>.ogg` files in here, or in the same place in a mod pack. Any
+/// that are missing are stood in for by a steady tone, so the layering can still be heard.
+const MUSIC_DIR: &str = "assets/music";
+const MUSIC_VOLUME: f32 = 0.4;
+/// Stand-in tones are much harsher than music, so they're mixed in well below it.
+const PLACEHOLDER_MUSIC_VOLUME: f32 = 0.05;
+/// How long a stem takes to fade all the way in or out.
+const MUSIC_FADE_TIME: f32 = 2.0;
+/// Each bomb in the air counts for this much of a plane towards the threat.
+const BOMB_THREAT: f32 = 0.5;
+/// Threat at which the tense and frantic stems start to come in, and are fully in.
+const TENSE_THREAT: (f32, f32) = (2.0, 5.0);
+const FRANTIC_THREAT: (f32, f32) = (6.0, 10.0);
+
+/// A layer of the soundtrack. The calm stem always plays; the others are mixed in on top
+/// of it as the fight heats up. Stems need to be the same length for their loops to stay
+/// in time.
+#[derive(Component, Clone, Copy)]
+enum MusicStem {
+    Calm,
+    Tense,
+    Frantic,
+}
+
+impl MusicStem {
+    const ALL: [Self; 3] = [Self::Calm, Self::Tense, Self::Frantic];
+
+    fn file_name(self) -> &'static str {
+        match self {
+            Self::Calm => "calm.ogg",
+            Self::Tense => "tense.ogg",
+            Self::Frantic => "frantic.ogg",
+        }
+    }
+
+    /// Pitch of the tone played in place of a missing stem. Together they build up a
+    /// chord: the root, its fifth, then the octave.
+    fn placeholder_tone(self) -> f32 {
+        match self {
+            Self::Calm => 110.0,
+            Self::Tense => 165.0,
+            Self::Frantic => 220.0,
+        }
+    }
+
+    /// How loud the stem should be at `threat`, from 0 to 1.
+    fn mix(self, threat: f32) -> f32 {
+        let fade_in =
+            |(start, full): (f32, f32)| ((threat - start) / (full - start)).clamp(0.0, 1.0);
+        match self {
+            Self::Calm => 1.0,
+            Self::Tense => fade_in(TENSE_THREAT),
+            Self::Frantic => fade_in(FRANTIC_THREAT),
+        }
+    }
+}
+
+#[derive(Resource)]
+struct Sounds {
+    whistle: Handle<Pitch>,
+    engine: Handle<Pitch>,
+    supply_engine: Handle<Pitch>,
+    fanfare: Handle<Pitch>,
+}
+
+/// The drone of a plane's engine. It is kept apart from the plane so it can be panned by
+/// where the plane is on screen, and stops once the plane is gone.
+#[derive(Component)]
+struct EngineSound {
+    plane: Entity,
+}
+
+/// A bomb whose whistle has already been captioned.
+#[derive(Component)]
+struct WhistleCued;
+
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(DefaultSpatialScale(SpatialScale::new_2d(
+            1.0 / AUDIO_DISTANCE,
+        )))
+        .add_systems(Startup, (setup_sounds, start_music))
+        .add_systems(
+            Update,
+            (
+                equip_listener,
+                (whistle_bombs, tune_whistles)
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+                (start_engines, place_engines).chain(),
+                play_fanfares.run_if(in_state(GameState::Playing)),
+                hold_sounds,
+                mix_music,
+            ),
+        );
+    }
+}
+
+fn setup_sounds(mut commands: Commands, mut pitches: ResMut<Assets<Pitch>>) {
+    commands.insert_resource(Sounds {
+        // A whole number of cycles, so the tone loops without a click.
+        whistle: pitches.add(Pitch::new(WHISTLE_TONE, Duration::from_secs(1))),
+        engine: pitches.add(Pitch::new(ENGINE_TONE, Duration::from_secs(1))),
+        supply_engine: pitches.add(Pitch::new(SUPPLY_ENGINE_TONE, Duration::from_secs(1))),
+        fanfare: pitches.add(Pitch::new(FANFARE_TONE, FANFARE_LENGTH)),
+    });
+}
+
+/// Spatial sounds are heard from where the jeep is.
+fn equip_listener(
+    mut commands: Commands,
+    player_query: Query<Entity, (With<Player>, Without<SpatialListener>)>,
+) {
+    for player_entity in &player_query {
+        commands
+            .entity(player_entity)
+            .insert(SpatialListener::new(EAR_GAP));
+    }
+}
+
+/// Every falling bomb whistles from where it is, so it can be placed by ear.
+fn whistle_bombs(
+    mut commands: Commands,
+    sounds: Res<Sounds>,
+    bomb_query: Query<Entity, Added<Bomb>>,
+) {
+    for bomb_entity in &bomb_query {
+        commands.entity(bomb_entity).try_insert(PitchBundle {
+            source: sounds.whistle.clone(),
+            settings: PlaybackSettings::LOOP
+                .with_volume(Volume::new(WHISTLE_VOLUME))
+                .with_spatial(true),
+        });
+    }
+}
+
+/// The closer a bomb is to the jeep the lower its whistle, like one falling right on top
+/// of you.
+fn tune_whistles(
+    mut commands: Commands,
+    player_query: Query<&Transform, With<Player>>,
+    bomb_query: Query<(Entity, &Transform, &SpatialAudioSink, Has<WhistleCued>), With<Bomb>>,
+    mut cue_events: EventWriter<AudioCueEvent>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let player_position = player_transform.translation.truncate();
+
+    for (bomb_entity, bomb_transform, sink, cued) in &bomb_query {
+        let bomb_position = bomb_transform.translation.truncate();
+        let distance = bomb_position.distance(player_position);
+        let closeness = 1.0 - (distance / WHISTLE_RANGE).min(1.0);
+        sink.set_speed(1.0_f32.lerp(WHISTLE_LOWEST_PITCH, closeness));
+
+        if !cued && distance < WHISTLE_CUE_RANGE {
+            commands.entity(bomb_entity).try_insert(WhistleCued);
+            cue_events.send(AudioCueEvent {
+                cue: AudioCue::BombWhistle,
+                position: bomb_position,
+            });
+        }
+    }
+}
+
+fn start_engines(
+    mut commands: Commands,
+    sounds: Res<Sounds>,
+    plane_query: Query<
+        (Entity, &Transform, Has<SupplyPlane>),
+        Or<(Added<Plane>, Added<SupplyPlane>)>,
+    >,
+) {
+    for (plane_entity, plane_transform, is_supply) in &plane_query {
+        let source = if is_supply {
+            &sounds.supply_engine
+        } else {
+            &sounds.engine
+        };
+        commands.spawn((
+            PitchBundle {
+                source: source.clone(),
+                settings: PlaybackSettings::LOOP
+                    .with_volume(Volume::new(ENGINE_VOLUME))
+                    .with_spatial(true),
+            },
+            SpatialBundle::from_transform(*plane_transform),
+            EngineSound {
+                plane: plane_entity,
+            },
+        ));
+    }
+}
+
+/// Sets each engine's sound off from the jeep by how far its plane is from the middle of
+/// the screen across, and from the jeep up, so it pans with the plane's place on screen
+/// and fades as the plane gets further away.
+fn place_engines(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    player_query: Query<&Transform, (With<Player>, Without<EngineSound>)>,
+    plane_query: Query<&Transform, (Or<(With<Plane>, With<SupplyPlane>)>, Without<EngineSound>)>,
+    mut engine_query: Query<(Entity, &EngineSound, &mut Transform)>,
+) {
+    let listener = player_query
+        .get_single()
+        .map_or(Vec2::ZERO, |player_transform| {
+            player_transform.translation.truncate()
+        });
+    let center_x = view_rect(&settings).center().x;
+
+    for (engine_entity, engine, mut engine_transform) in &mut engine_query {
+        let Ok(plane_transform) = plane_query.get(engine.plane) else {
+            commands.entity(engine_entity).despawn();
+            continue;
+        };
+        let plane = plane_transform.translation;
+        let offset = Vec2::new(plane.x - center_x, plane.y - listener.y);
+        engine_transform.translation = (listener + offset).extend(plane.z);
+    }
+}
+
+/// Streaks are announced to the player rather than heard from anywhere in particular, so
+/// the chime isn't spatial.
+fn play_fanfares(
+    mut commands: Commands,
+    sounds: Res<Sounds>,
+    mut streak_events: EventReader<StreakEvent>,
+) {
+    for _ in streak_events.read() {
+        commands.spawn(PitchBundle {
+            source: sounds.fanfare.clone(),
+            settings: PlaybackSettings::DESPAWN.with_volume(Volume::new(FANFARE_VOLUME)),
+        });
+    }
+}
+
+fn start_music(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    packs: Res<ModPacks>,
+    mut pitches: ResMut<Assets<Pitch>>,
+) {
+    for stem in MusicStem::ALL {
+        let path = Path::new(MUSIC_DIR).join(stem.file_name());
+        if !packs.has_file(&path) {
+            commands.spawn((
+                PitchBundle {
+                    // A whole number of cycles, so the tone loops without a click.
+                    source: pitches
+                        .add(Pitch::new(stem.placeholder_tone(), Duration::from_secs(1))),
+                    settings: PlaybackSettings::LOOP
+                        .with_volume(Volume::new(stem.mix(0.0) * PLACEHOLDER_MUSIC_VOLUME)),
+                },
+                stem,
+            ));
+            continue;
+        }
+        commands.spawn((
+            AudioBundle {
+                source: asset_server.load(format!("../{}", path.display())),
+                settings: PlaybackSettings::LOOP
+                    .with_volume(Volume::new(stem.mix(0.0) * MUSIC_VOLUME)),
+            },
+            stem,
+        ));
+    }
+}
+
+/// Fades each stem towards its share of the mix for the number of planes and bombs about.
+/// Real time is used so the music keeps settling while the game is paused.
+fn mix_music(
+    time: Res<Time<Real>>,
+    state: Res<State<GameState>>,
+    plane_query: Query<(), With<Plane>>,
+    bomb_query: Query<(), With<Bomb>>,
+    stem_query: Query<(&MusicStem, &AudioSink, Has<Handle<Pitch>>)>,
+) {
+    let threat = if *state.get() == GameState::Playing {
+        plane_query.iter().len() as f32 + bomb_query.iter().len() as f32 * BOMB_THREAT
+    } else {
+        0.0
+    };
+    for (stem, sink, placeholder) in &stem_query {
+        let full_volume = if placeholder {
+            PLACEHOLDER_MUSIC_VOLUME
+        } else {
+            MUSIC_VOLUME
+        };
+        let step = full_volume / MUSIC_FADE_TIME * time.delta_seconds();
+        let volume = sink.volume();
+        let target = stem.mix(threat) * full_volume;
+        if volume != target {
+            sink.set_volume(volume + (target - volume).clamp(-step, step));
+        }
+    }
+}
+
+/// Looping sounds only play while the fight is on, not over the pause menu or the game
+/// over screen.
+fn hold_sounds(
+    state: Res<State<GameState>>,
+    paused: Option<Res<Paused>>,
+    sink_query: Query<&SpatialAudioSink>,
+) {
+    let audible = *state.get() == GameState::Playing && paused.is_none();
+    for sink in &sink_query {
+        if sink.is_paused() == audible {
+            sink.toggle();
+        }
+    }
+}
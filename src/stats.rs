@@ -0,0 +1,177 @@
+use std::ops::AddAssign;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{jeep_motion::ShotFiredEvent, GameState, PlayerDamageEvent};
+
+/// Points for destroying an enemy.
+const KILL_SCORE: u32 = 100;
+/// Points lost for hurting a civilian.
+const CIVILIAN_PENALTY: u32 = 300;
+/// Kills this close together keep a combo going.
+const COMBO_WINDOW: f32 = 3.0;
+/// Every this many kills in a combo adds one to the score multiplier.
+const COMBO_STEP: u32 = 5;
+/// How long it takes for one death to stop counting as recent.
+const RECENT_DEATH_WINDOW: f32 = 45.0;
+
+/// Shots fired with each of the jeep's weapons.
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WeaponShots {
+    pub rockets: u32,
+    pub missiles: u32,
+    pub flak: u32,
+    /// Bursts of the laser, each counted as one shot.
+    pub laser: u32,
+}
+
+impl WeaponShots {
+    /// Shots fired since `earlier`. Loading a saved run can leave fewer than there were,
+    /// which counts as none.
+    pub fn since(&self, earlier: &Self) -> Self {
+        Self {
+            rockets: self.rockets.saturating_sub(earlier.rockets),
+            missiles: self.missiles.saturating_sub(earlier.missiles),
+            flak: self.flak.saturating_sub(earlier.flak),
+            laser: self.laser.saturating_sub(earlier.laser),
+        }
+    }
+
+    /// Locale key of the weapon fired most, once any of them have been.
+    pub fn favorite(&self) -> Option<&'static str> {
+        [
+            (self.rockets, "weapon-rockets"),
+            (self.missiles, "weapon-missiles"),
+            (self.flak, "weapon-flak"),
+            (self.laser, "weapon-laser"),
+        ]
+        .into_iter()
+        .filter(|&(shots, _)| shots > 0)
+        .max_by_key(|&(shots, _)| shots)
+        .map(|(_, weapon)| weapon)
+    }
+}
+
+impl AddAssign for WeaponShots {
+    fn add_assign(&mut self, other: Self) {
+        self.rockets += other.rockets;
+        self.missiles += other.missiles;
+        self.flak += other.flak;
+        self.laser += other.laser;
+    }
+}
+
+/// Running tally of how the current run is going.
+#[derive(Resource, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunStats {
+    pub score: u32,
+    pub kills: u32,
+    pub deaths: u32,
+    /// Seconds since the jeep last took any damage.
+    pub time_since_hit: f32,
+    /// Deaths weighted towards the last minute or so; each one wears off over
+    /// `RECENT_DEATH_WINDOW` seconds.
+    pub recent_deaths: f32,
+    /// Kills in a row, each within `COMBO_WINDOW` of the last.
+    pub combo: u32,
+    /// Seconds left to make the next kill before the combo is lost.
+    pub combo_time_left: f32,
+    /// Seconds played so far, not counting time spent paused.
+    pub run_time: f32,
+    pub shots: WeaponShots,
+    /// Most kills in a row without the jeep being hurt.
+    pub best_streak: u32,
+}
+
+impl RunStats {
+    /// Returns the points the kill scored.
+    pub fn record_kill(&mut self) -> u32 {
+        let points = KILL_SCORE * self.combo_multiplier();
+        self.kills += 1;
+        self.score += points;
+        self.combo += 1;
+        self.combo_time_left = COMBO_WINDOW;
+        points
+    }
+
+    /// Hurting a civilian costs points and throws away the combo.
+    pub fn record_civilian_hit(&mut self) {
+        self.score = self.score.saturating_sub(CIVILIAN_PENALTY);
+        self.combo = 0;
+        self.combo_time_left = 0.0;
+    }
+
+    pub fn combo_multiplier(&self) -> u32 {
+        1 + self.combo / COMBO_STEP
+    }
+
+    pub fn record_death(&mut self) {
+        self.deaths += 1;
+        self.recent_deaths += 1.0;
+    }
+}
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunStats>()
+            .add_systems(
+                Update,
+                (
+                    track_run_time,
+                    track_damage_taken,
+                    track_shots,
+                    expire_combo,
+                )
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::GameOver), reset_stats);
+    }
+}
+
+fn track_run_time(time: Res<Time>, mut stats: ResMut<RunStats>) {
+    stats.run_time += time.delta_seconds();
+}
+
+fn track_damage_taken(
+    time: Res<Time>,
+    mut stats: ResMut<RunStats>,
+    mut player_damage_events: EventReader<PlayerDamageEvent>,
+) {
+    let dt = time.delta_seconds();
+    stats.recent_deaths = (stats.recent_deaths - dt / RECENT_DEATH_WINDOW).max(0.0);
+    if player_damage_events.read().any(|event| event.amount > 0.0) {
+        stats.time_since_hit = 0.0;
+    } else {
+        stats.time_since_hit += dt;
+    }
+}
+
+fn track_shots(mut stats: ResMut<RunStats>, mut shot_events: EventReader<ShotFiredEvent>) {
+    for shot in shot_events.read() {
+        match shot {
+            ShotFiredEvent::Rocket => stats.shots.rockets += 1,
+            ShotFiredEvent::Missile => stats.shots.missiles += 1,
+            ShotFiredEvent::Flak => stats.shots.flak += 1,
+            ShotFiredEvent::Laser => stats.shots.laser += 1,
+        }
+    }
+}
+
+fn expire_combo(time: Res<Time>, mut stats: ResMut<RunStats>) {
+    if stats.combo == 0 {
+        return;
+    }
+    stats.combo_time_left -= time.delta_seconds();
+    if stats.combo_time_left <= 0.0 {
+        stats.combo = 0;
+    }
+}
+
+fn reset_stats(mut stats: ResMut<RunStats>) {
+    *stats = RunStats::default();
+}
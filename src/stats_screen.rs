@@ -0,0 +1,140 @@
+use bevy::prelude::*;
+
+use crate::{
+    cosmetics::Garage,
+    localization::{Locale, Localized},
+    profile::{ActiveProfile, ProfileNameEntry},
+    quit::QuitPrompt,
+    GameState,
+};
+
+/// Open while the player looks over their profile's lifetime stats on the game over
+/// screen.
+#[derive(Resource)]
+pub struct StatsScreen;
+
+#[derive(Component)]
+struct StatsPanel;
+
+pub struct StatsScreenPlugin;
+
+impl Plugin for StatsScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                open_stats_screen.run_if(
+                    not(resource_exists::<StatsScreen>)
+                        .and_then(not(resource_exists::<Garage>))
+                        .and_then(not(resource_exists::<ProfileNameEntry>))
+                        .and_then(not(resource_exists::<QuitPrompt>)),
+                ),
+                close_stats_screen.run_if(resource_exists::<StatsScreen>),
+            )
+                .chain()
+                .run_if(in_state(GameState::GameOver)),
+        )
+        .add_systems(OnExit(GameState::GameOver), despawn_stats_screen);
+    }
+}
+
+/// `S` opens the stats screen. Nothing on it changes while it's open, so it's written out
+/// once.
+fn open_stats_screen(
+    mut commands: Commands,
+    key_input: Res<ButtonInput<KeyCode>>,
+    profile: Res<ActiveProfile>,
+    locale: Res<Locale>,
+) {
+    if !key_input.just_pressed(KeyCode::KeyS) {
+        return;
+    }
+    commands.insert_resource(StatsScreen);
+
+    let stats = &profile.0.stats;
+    let minutes = (stats.play_time / 60.0) as u32;
+    let play_time = locale.format(
+        "stats-play-time",
+        &[("hours", &(minutes / 60)), ("minutes", &(minutes % 60))],
+    );
+    let favorite = locale.get(stats.shots.favorite().unwrap_or("stats-no-favorite"));
+    let summary = locale.format(
+        "stats-summary",
+        &[
+            ("kills", &stats.kills),
+            ("runs", &stats.runs),
+            ("deaths", &stats.deaths),
+            ("time", &play_time),
+            ("weapon", &favorite),
+            ("streak", &stats.best_streak),
+        ],
+    );
+
+    let label_style = TextStyle {
+        font_size: 24.0,
+        color: Color::WHITE,
+        ..default()
+    };
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(16.0),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.85).into(),
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            StatsPanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 48.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                Localized("stats-screen"),
+            ));
+            parent.spawn(
+                TextBundle::from_section(summary, label_style.clone())
+                    .with_text_justify(JustifyText::Center),
+            );
+            parent.spawn((
+                TextBundle::from_section("", label_style),
+                Localized("stats-controls"),
+            ));
+        });
+}
+
+/// `S` again goes back to the game over screen.
+fn close_stats_screen(
+    mut commands: Commands,
+    key_input: Res<ButtonInput<KeyCode>>,
+    stats_screen: Res<StatsScreen>,
+    panel_query: Query<Entity, With<StatsPanel>>,
+) {
+    if !key_input.just_pressed(KeyCode::KeyS) || stats_screen.is_added() {
+        return;
+    }
+    commands.remove_resource::<StatsScreen>();
+    for panel_entity in &panel_query {
+        commands.entity(panel_entity).despawn_recursive();
+    }
+}
+
+fn despawn_stats_screen(mut commands: Commands, panel_query: Query<Entity, With<StatsPanel>>) {
+    commands.remove_resource::<StatsScreen>();
+    for panel_entity in &panel_query {
+        commands.entity(panel_entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,130 @@
+use bevy::prelude::*;
+
+use crate::{
+    audio_cues::{AudioCue, AudioCueEvent},
+    hud::BannerEvent,
+    localization::Locale,
+    missile::MissileLauncher,
+    stats::RunStats,
+    toast::ToastEvent,
+    EnemyKilledEvent, GameState, PlayerDamageEvent,
+};
+
+/// A number of kills in a row, without the jeep being hurt, worth announcing.
+struct Milestone {
+    kills: u32,
+    /// Locale key of the banner it gets.
+    banner: &'static str,
+    points: u32,
+}
+
+const MILESTONES: &[Milestone] = &[
+    Milestone {
+        kills: 5,
+        banner: "streak-5",
+        points: 250,
+    },
+    Milestone {
+        kills: 10,
+        banner: "streak-10",
+        points: 500,
+    },
+    Milestone {
+        kills: 20,
+        banner: "streak-20",
+        points: 1000,
+    },
+];
+
+/// Sent when a streak reaches one of the milestones.
+#[derive(Event)]
+pub struct StreakEvent {
+    milestone: &'static Milestone,
+    /// Where the kill that reached it happened.
+    position: Vec2,
+}
+
+/// Kills since the jeep last took damage.
+#[derive(Resource, Default)]
+struct Streak {
+    kills: u32,
+}
+
+pub struct StreaksPlugin;
+
+impl Plugin for StreaksPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Streak>()
+            .add_event::<StreakEvent>()
+            .add_systems(
+                Update,
+                (count_streak, reward_streaks)
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::GameOver), reset_streak);
+    }
+}
+
+/// Any damage to the jeep ends the streak, even a hit that comes in the same frame as a
+/// kill.
+fn count_streak(
+    mut killed_events: EventReader<EnemyKilledEvent>,
+    mut player_damage_events: EventReader<PlayerDamageEvent>,
+    mut streak: ResMut<Streak>,
+    mut stats: ResMut<RunStats>,
+    mut streak_events: EventWriter<StreakEvent>,
+) {
+    if player_damage_events.read().any(|event| event.amount > 0.0) {
+        killed_events.clear();
+        streak.kills = 0;
+        return;
+    }
+    for killed in killed_events.read().filter(|killed| !killed.is_civilian) {
+        streak.kills += 1;
+        stats.best_streak = stats.best_streak.max(streak.kills);
+        if let Some(milestone) = MILESTONES
+            .iter()
+            .find(|milestone| milestone.kills == streak.kills)
+        {
+            streak_events.send(StreakEvent {
+                milestone,
+                position: killed.position,
+            });
+        }
+    }
+}
+
+/// Each milestone gets a banner, a bonus and a missile ready to fire, with the fanfare
+/// captioned where the kill was.
+fn reward_streaks(
+    mut streak_events: EventReader<StreakEvent>,
+    locale: Res<Locale>,
+    mut stats: ResMut<RunStats>,
+    mut launcher: ResMut<MissileLauncher>,
+    mut banner_events: EventWriter<BannerEvent>,
+    mut toast_events: EventWriter<ToastEvent>,
+    mut cue_events: EventWriter<AudioCueEvent>,
+) {
+    for &StreakEvent {
+        milestone,
+        position,
+    } in streak_events.read()
+    {
+        stats.score += milestone.points;
+        launcher.recharge();
+        banner_events.send(BannerEvent(locale.get(milestone.banner).to_string()));
+        toast_events.send(ToastEvent(locale.format(
+            "streak-reward",
+            &[("kills", &milestone.kills), ("points", &milestone.points)],
+        )));
+        cue_events.send(AudioCueEvent {
+            cue: AudioCue::Fanfare,
+            position,
+        });
+    }
+}
+
+fn reset_streak(mut streak: ResMut<Streak>) {
+    streak.kills = 0;
+}
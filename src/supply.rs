@@ -0,0 +1,346 @@
+use bevy::{
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+};
+use rand::prelude::*;
+
+use crate::{
+    audio_cues::{AudioCue, AudioCueEvent},
+    camera::OFFSCREEN_MARGIN,
+    convoys::Civilian,
+    drone::DroneEvent,
+    health::Health,
+    localization::Locale,
+    pause::Paused,
+    rng::GameRng,
+    rocket_collision,
+    settings::Settings,
+    terrain::Terrain,
+    toast::ToastEvent,
+    Collider, DamageEvent, DamageSource, GameSet, GameState, PlaneSprites, Player, PlayerState,
+    Rocket, PLAY_AREA, ROCKET_DAMAGE, ROCKET_HALF_SIZE,
+};
+
+const SUPPLY_INTERVAL: std::ops::Range<f32> = 40.0..60.0;
+/// Above the lanes the enemy planes fly on.
+const SUPPLY_ALTITUDE: f32 = PLAY_AREA.y - 50.0;
+/// Enemy planes fly leftwards, so the supply plane coming the other way is the first sign
+/// it's a friend.
+const SUPPLY_PLANE_SPEED: f32 = 170.0;
+/// Pale, where enemy planes wear their livery.
+const SUPPLY_PLANE_TINT: Color = Color::rgb(0.92, 0.94, 0.96);
+const SUPPLY_PLANE_HEALTH: f32 = 2.0;
+/// The plane starts and finishes this far past the edge of the view.
+const SUPPLY_PLANE_MARGIN: f32 = 100.0;
+/// The crate goes out somewhere over this stretch of the battlefield.
+const DROP_RANGE: std::ops::Range<f32> = 200.0..(PLAY_AREA.x - 200.0);
+const FRIEND_MARKER_OFFSET: f32 = 44.0;
+const FRIEND_MARKER_SIZE: f32 = 8.0;
+
+const CRATE_HALF_SIZE: Vec2 = Vec2::splat(10.0);
+const CRATE_COLOR: Color = Color::rgb(0.55, 0.4, 0.2);
+/// Painted grey so the drone stands out from the usual supplies.
+const DRONE_CRATE_COLOR: Color = Color::rgb(0.45, 0.5, 0.55);
+/// Chance a crate has a drone in it rather than supplies.
+const DRONE_CRATE_CHANCE: f64 = 0.35;
+const PARACHUTE_SIZE: Vec2 = Vec2::new(40.0, 12.0);
+const PARACHUTE_COLOR: Color = Color::rgb(0.95, 0.95, 0.9);
+const PARACHUTE_HEIGHT: f32 = 30.0;
+const CRATE_FALL_SPEED: f32 = 70.0;
+/// How long a crate waits on the ground to be picked up.
+const CRATE_LIFETIME: f32 = 12.0;
+/// Health the jeep gets back from a crate.
+const CRATE_REPAIR: f32 = 1.0;
+/// Just behind the jeep, so it drives over the crate rather than under it.
+const CRATE_DEPTH: f32 = -0.2;
+
+/// A friendly plane flying over to parachute a supply crate down to the jeep. Shooting
+/// it calls the drop off and costs points, like any other civilian.
+#[derive(Component)]
+pub struct SupplyPlane {
+    drop_x: f32,
+    /// Set once the crate is out, or the drop has been called off.
+    done: bool,
+}
+
+#[derive(Component)]
+struct SupplyCrate {
+    /// Counts down once the crate is on the ground.
+    lifetime: Option<Timer>,
+    /// Holds a drone to help the jeep out, instead of a repair.
+    drone: bool,
+}
+
+#[derive(Resource)]
+struct SupplySpawner(Timer);
+
+impl FromWorld for SupplySpawner {
+    fn from_world(world: &mut World) -> Self {
+        Self(random_interval(&mut world.resource_mut::<GameRng>().0))
+    }
+}
+
+fn random_interval(rng: &mut impl Rng) -> Timer {
+    Timer::from_seconds(rng.gen_range(SUPPLY_INTERVAL), TimerMode::Once)
+}
+
+pub struct SupplyPlugin;
+
+impl Plugin for SupplyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SupplySpawner>()
+            .add_systems(
+                Update,
+                (
+                    spawn_supply_planes.in_set(GameSet::Spawning),
+                    (fly_supply_planes, drop_crates, move_crates)
+                        .chain()
+                        .in_set(GameSet::Movement),
+                    (hit_supply_planes.before(rocket_collision), pick_up_crates)
+                        .in_set(GameSet::Collision),
+                )
+                    .run_if(in_state(GameState::Playing).and_then(not(resource_exists::<Paused>))),
+            )
+            .add_systems(
+                Update,
+                (announce_supply_planes, mark_friends).run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::GameOver), clear_supplies);
+    }
+}
+
+fn spawn_supply_planes(
+    mut commands: Commands,
+    time: Res<Time>,
+    plane_sprites: PlaneSprites,
+    mut spawner: ResMut<SupplySpawner>,
+    mut rng: ResMut<GameRng>,
+) {
+    let rng = &mut rng.0;
+    spawner.0.tick(time.delta());
+    if !spawner.0.finished() {
+        return;
+    }
+    spawner.0 = random_interval(rng);
+
+    let position = Vec2::new(-OFFSCREEN_MARGIN.x - SUPPLY_PLANE_MARGIN, SUPPLY_ALTITUDE);
+    let transform =
+        Transform::from_translation(position.extend(0.0)).with_scale(Vec3::new(2.0, 2.0, 0.0));
+    let mut sprite = plane_sprites.sprite(transform);
+    sprite.sprite.color = SUPPLY_PLANE_TINT;
+    sprite.sprite.flip_x = true;
+    commands.spawn((
+        sprite,
+        SupplyPlane {
+            drop_x: rng.gen_range(DROP_RANGE),
+            done: false,
+        },
+        Civilian,
+        Health::new(SUPPLY_PLANE_HEALTH),
+        Collider {
+            half_size: Vec2::splat(32.0),
+        },
+    ));
+}
+
+/// Warns the player off shooting the supply plane as it comes in, with a caption for its
+/// engine for those who can't hear it.
+fn announce_supply_planes(
+    plane_query: Query<&Transform, Added<SupplyPlane>>,
+    locale: Res<Locale>,
+    mut toast_events: EventWriter<ToastEvent>,
+    mut cue_events: EventWriter<AudioCueEvent>,
+) {
+    for plane_transform in &plane_query {
+        toast_events.send(ToastEvent(locale.get("supply-incoming").to_string()));
+        cue_events.send(AudioCueEvent {
+            cue: AudioCue::SupplyPlane,
+            position: plane_transform.translation.truncate(),
+        });
+    }
+}
+
+fn fly_supply_planes(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut plane_query: Query<(Entity, &mut Transform), With<SupplyPlane>>,
+) {
+    let despawn_x = PLAY_AREA.x + OFFSCREEN_MARGIN.x + SUPPLY_PLANE_MARGIN;
+    for (plane_entity, mut transform) in &mut plane_query {
+        transform.translation.x += SUPPLY_PLANE_SPEED * time.delta_seconds();
+        if transform.translation.x > despawn_x {
+            commands.entity(plane_entity).despawn_recursive();
+        }
+    }
+}
+
+fn drop_crates(
+    mut commands: Commands,
+    mut plane_query: Query<(&Transform, &mut SupplyPlane)>,
+    mut rng: ResMut<GameRng>,
+) {
+    for (plane_transform, mut plane) in &mut plane_query {
+        if plane.done || plane_transform.translation.x < plane.drop_x {
+            continue;
+        }
+        plane.done = true;
+        let drone = rng.0.gen_bool(DRONE_CRATE_CHANCE);
+        commands
+            .spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: if drone {
+                            DRONE_CRATE_COLOR
+                        } else {
+                            CRATE_COLOR
+                        },
+                        custom_size: Some(CRATE_HALF_SIZE * 2.0),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(
+                        plane_transform.translation.truncate().extend(CRATE_DEPTH),
+                    ),
+                    ..default()
+                },
+                SupplyCrate {
+                    lifetime: None,
+                    drone,
+                },
+            ))
+            .with_children(|parent| {
+                parent.spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color: PARACHUTE_COLOR,
+                        custom_size: Some(PARACHUTE_SIZE),
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(0.0, PARACHUTE_HEIGHT, 0.0),
+                    ..default()
+                });
+            });
+    }
+}
+
+/// Crates float down under their parachutes, then lie on the ground drifting left with
+/// the terrain until they're picked up or given up on.
+fn move_crates(
+    mut commands: Commands,
+    time: Res<Time>,
+    terrain: Res<Terrain>,
+    mut crate_query: Query<(Entity, &mut Transform, &mut SupplyCrate)>,
+) {
+    let despawn_x = -OFFSCREEN_MARGIN.x - CRATE_HALF_SIZE.x;
+    for (crate_entity, mut transform, mut supply_crate) in &mut crate_query {
+        let ground = terrain.height_at(transform.translation.x) + CRATE_HALF_SIZE.y;
+        let Some(lifetime) = &mut supply_crate.lifetime else {
+            transform.translation.y -= CRATE_FALL_SPEED * time.delta_seconds();
+            if transform.translation.y <= ground {
+                transform.translation.y = ground;
+                supply_crate.lifetime = Some(Timer::from_seconds(CRATE_LIFETIME, TimerMode::Once));
+                // The parachute is the crate's only child.
+                commands.entity(crate_entity).despawn_descendants();
+            }
+            continue;
+        };
+        lifetime.tick(time.delta());
+        transform.translation.x -= terrain.scroll_speed() * time.delta_seconds();
+        transform.translation.y = terrain.height_at(transform.translation.x) + CRATE_HALF_SIZE.y;
+        if lifetime.finished() || transform.translation.x < despawn_x {
+            commands.entity(crate_entity).despawn_recursive();
+        }
+    }
+}
+
+/// Rockets that hit the supply plane hurt it like any other civilian, and its crew call
+/// the drop off.
+fn hit_supply_planes(
+    mut commands: Commands,
+    rocket_query: Query<(Entity, &Transform), With<Rocket>>,
+    mut plane_query: Query<(Entity, &Transform, &Collider, &mut SupplyPlane)>,
+    locale: Res<Locale>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    for (rocket_entity, rocket_transform) in &rocket_query {
+        let rocket_box = Aabb2d::new(rocket_transform.translation.truncate(), ROCKET_HALF_SIZE);
+        let hit = plane_query
+            .iter_mut()
+            .find(|(_, plane_transform, collider, _)| {
+                rocket_box.intersects(&Aabb2d::new(
+                    plane_transform.translation.truncate(),
+                    collider.half_size,
+                ))
+            });
+        let Some((plane_entity, _, _, mut plane)) = hit else {
+            continue;
+        };
+        commands.entity(rocket_entity).despawn();
+        damage_events.send(DamageEvent {
+            target: plane_entity,
+            amount: ROCKET_DAMAGE,
+            source: DamageSource::Player,
+        });
+        if !plane.done {
+            plane.done = true;
+            toast_events.send(ToastEvent(locale.get("supply-cancelled").to_string()));
+        }
+    }
+}
+
+fn pick_up_crates(
+    mut commands: Commands,
+    crate_query: Query<(Entity, &Transform, &SupplyCrate)>,
+    mut player_query: Query<(&Transform, &Collider, &PlayerState, &mut Health), With<Player>>,
+    locale: Res<Locale>,
+    mut toast_events: EventWriter<ToastEvent>,
+    mut drone_events: EventWriter<DroneEvent>,
+) {
+    let Ok((player_transform, player_collider, PlayerState::Alive, mut health)) =
+        player_query.get_single_mut()
+    else {
+        return;
+    };
+    let player_box = Aabb2d::new(
+        player_transform.translation.truncate(),
+        player_collider.half_size,
+    );
+
+    for (crate_entity, crate_transform, supply_crate) in &crate_query {
+        let crate_box = Aabb2d::new(crate_transform.translation.truncate(), CRATE_HALF_SIZE);
+        if !player_box.intersects(&crate_box) {
+            continue;
+        }
+        commands.entity(crate_entity).despawn_recursive();
+        if supply_crate.drone {
+            drone_events.send(DroneEvent);
+        } else {
+            health.current = (health.current + CRATE_REPAIR).min(health.max);
+            toast_events.send(ToastEvent(locale.get("supply-picked-up").to_string()));
+        }
+    }
+}
+
+/// A friendly chevron over the supply plane, in the palette's health colour rather than
+/// the danger colour enemies are marked in.
+fn mark_friends(
+    mut gizmos: Gizmos,
+    settings: Res<Settings>,
+    plane_query: Query<&Transform, With<SupplyPlane>>,
+) {
+    let color = settings.palette.colors().health;
+    for plane_transform in &plane_query {
+        let tip = plane_transform.translation.truncate() + Vec2::Y * FRIEND_MARKER_OFFSET;
+        let arm = Vec2::new(FRIEND_MARKER_SIZE, FRIEND_MARKER_SIZE);
+        gizmos.line_2d(tip, tip + arm, color);
+        gizmos.line_2d(tip, tip + Vec2::new(-arm.x, arm.y), color);
+    }
+}
+
+fn clear_supplies(
+    mut commands: Commands,
+    supply_query: Query<Entity, Or<(With<SupplyPlane>, With<SupplyCrate>)>>,
+) {
+    for supply_entity in &supply_query {
+        commands.entity(supply_entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,240 @@
+use bevy::prelude::*;
+
+use crate::{
+    ai::EnemyAi,
+    camera::view_rect,
+    clouds::Concealed,
+    coast::Gunboat,
+    config::GameConfig,
+    controls::{Action, Controls},
+    mines::Mine,
+    night::InDarkness,
+    pause::Paused,
+    settings::Settings,
+    terrain::Terrain,
+    GameState, Plane, Player,
+};
+
+const LEAD_MARKER_ALPHA: f32 = 0.8;
+const LEAD_MARKER_RADIUS: f32 = 18.0;
+const GROUND_MARKER_HEIGHT: f32 = 24.0;
+const LOCK_MARKER_HALF_SIZE: f32 = 40.0;
+const LOCK_MARKER_CORNER: f32 = 12.0;
+
+/// The plane, mine or gunboat the player has locked on to with Tab, if any. Homing weapons
+/// and the HUD read this.
+#[derive(Resource, Default)]
+pub struct TargetLock {
+    pub target: Option<Entity>,
+}
+
+pub struct TargetingPlugin;
+
+impl Plugin for TargetingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TargetLock>().add_systems(
+            Update,
+            (
+                (
+                    release_lost_target,
+                    cycle_target_lock.run_if(not(resource_exists::<Paused>)),
+                    draw_target_lock,
+                )
+                    .chain(),
+                draw_lead_indicator.run_if(run_if_lead_indicator),
+            )
+                .chain()
+                .run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+fn run_if_lead_indicator(settings: Res<Settings>) -> bool {
+    settings.lead_indicator
+}
+
+/// Where a rocket fired straight up from `shooter_y` at `rocket_speed` meets a target
+/// currently at `target` moving with `target_velocity`, or `None` if the rocket can never
+/// catch it.
+pub fn intercept_point(
+    shooter_y: f32,
+    rocket_speed: f32,
+    target: Vec2,
+    target_velocity: Vec2,
+) -> Option<Vec2> {
+    let closing_speed = rocket_speed - target_velocity.y;
+    let height = target.y - shooter_y;
+    if height <= 0.0 || closing_speed <= 0.0 {
+        return None;
+    }
+
+    let time_to_impact = height / closing_speed;
+    Some(target + target_velocity * time_to_impact)
+}
+
+fn release_lost_target(
+    mut target_lock: ResMut<TargetLock>,
+    target_query: Query<(), Or<(With<Plane>, With<Mine>, With<Gunboat>)>>,
+) {
+    if let Some(target) = target_lock.target {
+        if target_query.get(target).is_err() {
+            target_lock.target = None;
+        }
+    }
+}
+
+/// The lock-on action (Tab by default) steps the lock through the visible planes from left
+/// to right, then the mines lying on the ground, then any gunboats offshore.
+fn cycle_target_lock(
+    controls: Res<Controls>,
+    settings: Res<Settings>,
+    mut target_lock: ResMut<TargetLock>,
+    plane_query: Query<
+        (Entity, &Transform),
+        (With<Plane>, Without<Concealed>, Without<InDarkness>),
+    >,
+    mine_query: Query<(Entity, &Transform, &Mine)>,
+    gunboat_query: Query<(Entity, &Transform), With<Gunboat>>,
+) {
+    if !controls.just_pressed(Action::CycleTarget) {
+        return;
+    }
+    let view = view_rect(&settings);
+
+    let in_view = |(_, x): &(Entity, f32)| (view.min.x..=view.max.x).contains(x);
+    let mut candidates: Vec<(Entity, f32)> = plane_query
+        .iter()
+        .map(|(plane_entity, plane_transform)| (plane_entity, plane_transform.translation.x))
+        .filter(in_view)
+        .collect();
+    candidates.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+    let mut mines: Vec<(Entity, f32)> = mine_query
+        .iter()
+        .filter(|(.., mine)| mine.is_armed())
+        .map(|(mine_entity, mine_transform, _)| (mine_entity, mine_transform.translation.x))
+        .filter(in_view)
+        .collect();
+    mines.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+    candidates.extend(mines);
+    let mut gunboats: Vec<(Entity, f32)> = gunboat_query
+        .iter()
+        .map(|(gunboat_entity, gunboat_transform)| {
+            (gunboat_entity, gunboat_transform.translation.x)
+        })
+        .filter(in_view)
+        .collect();
+    gunboats.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+    candidates.extend(gunboats);
+
+    let next = target_lock
+        .target
+        .and_then(|target| candidates.iter().position(|(entity, _)| *entity == target))
+        .map_or(0, |index| index + 1);
+    target_lock.target = candidates
+        .get(next)
+        .or_else(|| candidates.first())
+        .map(|(entity, _)| *entity);
+}
+
+fn draw_target_lock(
+    mut gizmos: Gizmos,
+    settings: Res<Settings>,
+    target_lock: Res<TargetLock>,
+    target_query: Query<
+        &Transform,
+        (
+            Or<(With<Plane>, With<Mine>, With<Gunboat>)>,
+            Without<Concealed>,
+        ),
+    >,
+) {
+    let Some(target_transform) = target_lock
+        .target
+        .and_then(|target| target_query.get(target).ok())
+    else {
+        return;
+    };
+    let center = target_transform.translation.truncate();
+    let color = settings.palette.colors().danger;
+
+    for corner in [
+        Vec2::new(-1.0, -1.0),
+        Vec2::new(1.0, -1.0),
+        Vec2::new(-1.0, 1.0),
+        Vec2::new(1.0, 1.0),
+    ] {
+        let point = center + corner * LOCK_MARKER_HALF_SIZE;
+        gizmos.line_2d(
+            point,
+            point - Vec2::new(corner.x, 0.0) * LOCK_MARKER_CORNER,
+            color,
+        );
+        gizmos.line_2d(
+            point,
+            point - Vec2::new(0.0, corner.y) * LOCK_MARKER_CORNER,
+            color,
+        );
+    }
+}
+
+/// Marks where the jeep needs to be for a rocket fired now to hit the locked plane, or
+/// failing that the plane it can line up with soonest.
+fn draw_lead_indicator(
+    mut gizmos: Gizmos,
+    settings: Res<Settings>,
+    terrain: Res<Terrain>,
+    config: Res<GameConfig>,
+    target_lock: Res<TargetLock>,
+    player_query: Query<&Transform, With<Player>>,
+    plane_query: Query<
+        (Entity, &Transform, &EnemyAi),
+        (With<Plane>, Without<Concealed>, Without<InDarkness>),
+    >,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let player_position = player_transform.translation.truncate();
+
+    let target = plane_query
+        .iter()
+        .filter(|(plane_entity, ..)| {
+            target_lock
+                .target
+                .is_none_or(|target| target == *plane_entity)
+        })
+        .filter_map(|(_, plane_transform, ai)| {
+            let plane_position = plane_transform.translation.truncate();
+            intercept_point(
+                player_position.y,
+                config.rocket_speed,
+                plane_position,
+                ai.velocity,
+            )
+            .map(|intercept| (plane_position, intercept))
+        })
+        .min_by(|(_, a), (_, b)| {
+            (a.x - player_position.x)
+                .abs()
+                .total_cmp(&(b.x - player_position.x).abs())
+        });
+    let Some((plane_position, intercept)) = target else {
+        return;
+    };
+    let color = settings
+        .palette
+        .colors()
+        .highlight
+        .with_a(LEAD_MARKER_ALPHA);
+
+    gizmos.line_2d(plane_position, intercept, color);
+    gizmos.circle_2d(intercept, LEAD_MARKER_RADIUS, color);
+    gizmos.line_2d(
+        intercept - Vec2::X * LEAD_MARKER_RADIUS,
+        intercept + Vec2::X * LEAD_MARKER_RADIUS,
+        color,
+    );
+
+    let ground = Vec2::new(intercept.x, terrain.height_at(intercept.x));
+    gizmos.line_2d(ground, ground + Vec2::Y * GROUND_MARKER_HEIGHT, color);
+}
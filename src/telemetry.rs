@@ -0,0 +1,184 @@
+use std::{collections::BTreeMap, fs};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    crash::CrashNotice, director::Director, jeep_motion::ShotFiredEvent, localization::Locale,
+    pause::Paused, photo_mode::PhotoMode, profile::mode_key, quit::QuitPrompt, settings::Settings,
+    stats::RunStats, toast::ToastEvent, DamageCause, GameState, Mission, PlayerKilledEvent,
+};
+
+/// Balance numbers summed over every run played with telemetry on.
+const TELEMETRY_PATH: &str = "telemetry.json";
+/// With the `online` feature, each run's numbers are also posted to this URL when it's set.
+#[cfg(feature = "online")]
+const ENDPOINT_VAR: &str = "BATTLE_JEEP_TELEMETRY_URL";
+#[cfg(feature = "online")]
+const REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct WeaponUsage {
+    rockets: u32,
+    missiles: u32,
+    flak: u32,
+    /// Bursts of the laser.
+    laser: u32,
+}
+
+/// How one run went. Nothing in here says who played it.
+#[derive(Resource, Clone, Default, Serialize)]
+struct RunReport {
+    mission: &'static str,
+    /// Seconds survived, not counting time spent paused.
+    survival_time: f32,
+    waves_cleared: u32,
+    score: u32,
+    shots: WeaponUsage,
+    deaths: BTreeMap<DamageCause, u32>,
+}
+
+/// Every reported run added together.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct Totals {
+    runs: u32,
+    average_survival_time: f32,
+    total_survival_time: f32,
+    total_score: u64,
+    shots: WeaponUsage,
+    deaths: BTreeMap<DamageCause, u32>,
+}
+
+impl Totals {
+    fn load() -> Self {
+        fs::read_to_string(TELEMETRY_PATH)
+            .ok()
+            .and_then(|totals| serde_json::from_str(&totals).ok())
+            .unwrap_or_default()
+    }
+
+    fn add(&mut self, run: &RunReport) {
+        self.runs += 1;
+        self.total_survival_time += run.survival_time;
+        self.average_survival_time = self.total_survival_time / self.runs as f32;
+        self.total_score += u64::from(run.score);
+        self.shots.rockets += run.shots.rockets;
+        self.shots.missiles += run.shots.missiles;
+        self.shots.flak += run.shots.flak;
+        self.shots.laser += run.shots.laser;
+        for (cause, deaths) in &run.deaths {
+            *self.deaths.entry(*cause).or_default() += deaths;
+        }
+    }
+
+    fn save(&self) {
+        match serde_json::to_string_pretty(self) {
+            Ok(totals) => {
+                if let Err(error) = fs::write(TELEMETRY_PATH, totals) {
+                    warn!("couldn't write {TELEMETRY_PATH}: {error}");
+                }
+            }
+            Err(error) => warn!("couldn't serialize telemetry: {error}"),
+        }
+    }
+}
+
+/// Keeps score of how each run goes and, when the player has opted in, adds it to the
+/// totals in [`TELEMETRY_PATH`] for tuning the game's balance.
+pub struct TelemetryPlugin;
+
+impl Plugin for TelemetryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunReport>()
+            .add_systems(
+                Update,
+                (
+                    count_shots_and_deaths.run_if(in_state(GameState::Playing)),
+                    toggle_telemetry.run_if(
+                        resource_exists::<Paused>
+                            .and_then(not(resource_exists::<PhotoMode>))
+                            .and_then(not(resource_exists::<QuitPrompt>))
+                            .and_then(not(resource_exists::<CrashNotice>)),
+                    ),
+                ),
+            )
+            .add_systems(OnEnter(GameState::GameOver), report_run)
+            .add_systems(OnExit(GameState::GameOver), reset_report);
+    }
+}
+
+/// `T` on the pause menu turns telemetry on and off.
+fn toggle_telemetry(
+    key_input: Res<ButtonInput<KeyCode>>,
+    locale: Res<Locale>,
+    mut settings: ResMut<Settings>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    if !key_input.just_pressed(KeyCode::KeyT) {
+        return;
+    }
+    settings.telemetry = !settings.telemetry;
+    let key = if settings.telemetry {
+        "telemetry-on"
+    } else {
+        "telemetry-off"
+    };
+    toast_events.send(ToastEvent(locale.get(key).to_string()));
+}
+
+fn count_shots_and_deaths(
+    mut report: ResMut<RunReport>,
+    mut shot_events: EventReader<ShotFiredEvent>,
+    mut killed_events: EventReader<PlayerKilledEvent>,
+) {
+    for shot in shot_events.read() {
+        match shot {
+            ShotFiredEvent::Rocket => report.shots.rockets += 1,
+            ShotFiredEvent::Missile => report.shots.missiles += 1,
+            ShotFiredEvent::Flak => report.shots.flak += 1,
+            ShotFiredEvent::Laser => report.shots.laser += 1,
+        }
+    }
+    for killed in killed_events.read() {
+        *report.deaths.entry(killed.cause).or_default() += 1;
+    }
+}
+
+fn report_run(
+    mut report: ResMut<RunReport>,
+    settings: Res<Settings>,
+    stats: Res<RunStats>,
+    director: Res<Director>,
+    mission: Res<Mission>,
+) {
+    if !settings.telemetry {
+        return;
+    }
+    report.mission = mode_key(&mission);
+    report.survival_time = stats.run_time;
+    report.waves_cleared = director.waves_cleared();
+    report.score = stats.score;
+
+    let mut totals = Totals::load();
+    totals.add(&report);
+    totals.save();
+
+    #[cfg(feature = "online")]
+    if let Ok(endpoint) = std::env::var(ENDPOINT_VAR) {
+        let report = report.clone();
+        bevy::tasks::IoTaskPool::get()
+            .spawn(async move {
+                let agent = ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build();
+                if let Err(error) = agent.post(&endpoint).send_json(&report) {
+                    debug!("couldn't send telemetry: {error}");
+                }
+            })
+            .detach();
+    }
+}
+
+fn reset_report(mut report: ResMut<RunReport>) {
+    *report = RunReport::default();
+}
@@ -0,0 +1,269 @@
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::Indices, render_asset::RenderAssetUsages, render_resource::PrimitiveTopology,
+        view::NoFrustumCulling,
+    },
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+};
+use bevy_rapier2d::prelude::*;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{camera::OFFSCREEN_MARGIN, GameState, GROUND_HEIGHT, PLAY_AREA};
+
+/// Average height of the ground above `GROUND_HEIGHT`.
+pub const TERRAIN_BASE_HEIGHT: f32 = 32.0;
+const TERRAIN_LAYERS: usize = 3;
+const TERRAIN_COLUMN_WIDTH: f32 = 16.0;
+/// How far below the surface the ground mesh extends.
+const TERRAIN_DEPTH: f32 = 200.0;
+const TERRAIN_SCROLL_SPEED: f32 = 60.0;
+pub const TERRAIN_COLOR: Color = Color::rgb(0.76, 0.62, 0.38);
+/// Tint at the bottom of a crater, scorched by the blast that dug it.
+const SCORCH_TINT: Color = Color::rgb(0.45, 0.4, 0.36);
+/// Craters at least this deep get the full scorch tint.
+const CRATER_SCORCH_DEPTH: f32 = 8.0;
+
+/// A sine wave making up part of the ground profile.
+#[derive(Clone, Serialize, Deserialize)]
+struct TerrainLayer {
+    amplitude: f32,
+    wavelength: f32,
+    phase: f32,
+}
+
+/// A hollow blown out of the ground, in the terrain's own coordinates so it scrolls
+/// along with the dunes.
+#[derive(Clone, Serialize, Deserialize)]
+struct Crater {
+    x: f32,
+    half_width: f32,
+    depth: f32,
+}
+
+impl Crater {
+    /// How far the ground is sunk at `sample_x`, as a smooth bowl that's deepest in the
+    /// middle.
+    fn depth_at(&self, sample_x: f32) -> f32 {
+        let offset = (sample_x - self.x).abs();
+        if offset >= self.half_width {
+            return 0.0;
+        }
+        self.depth * 0.5 * (1.0 + (offset / self.half_width * std::f32::consts::PI).cos())
+    }
+}
+
+/// The rolling ground profile. Heights are sampled in screen space and shift left as the
+/// terrain scrolls, so the jeep appears to be driving across the dunes.
+#[derive(Resource, Clone, Serialize, Deserialize)]
+pub struct Terrain {
+    layers: Vec<TerrainLayer>,
+    scroll: f32,
+    scroll_speed: f32,
+    #[serde(default)]
+    craters: Vec<Crater>,
+}
+
+impl Terrain {
+    pub fn generate(rng: &mut impl Rng) -> Self {
+        // Long gentle dunes with progressively smaller, shorter bumps layered on top.
+        let layers = (0..TERRAIN_LAYERS)
+            .map(|layer| {
+                let falloff = 2.0_f32.powi(layer as i32);
+                TerrainLayer {
+                    amplitude: rng.gen_range(10.0..18.0) / falloff,
+                    wavelength: rng.gen_range(700.0..1100.0) / falloff,
+                    phase: rng.gen_range(0.0..std::f32::consts::TAU),
+                }
+            })
+            .collect();
+
+        Self {
+            layers,
+            scroll: 0.0,
+            scroll_speed: TERRAIN_SCROLL_SPEED,
+            craters: Vec::new(),
+        }
+    }
+
+    /// World-space height of the ground surface at `x`.
+    pub fn height_at(&self, x: f32) -> f32 {
+        let sample_x = x + self.scroll;
+        let bumps: f32 = self
+            .layers
+            .iter()
+            .map(|layer| {
+                layer.amplitude
+                    * (sample_x / layer.wavelength * std::f32::consts::TAU + layer.phase).sin()
+            })
+            .sum();
+        GROUND_HEIGHT + TERRAIN_BASE_HEIGHT + bumps - self.crater_depth_at(x)
+    }
+
+    /// How far the ground at `x` has been blown out. Where craters overlap only the
+    /// deepest counts, so a string of bombs leaves a trench rather than a pit.
+    pub fn crater_depth_at(&self, x: f32) -> f32 {
+        let sample_x = x + self.scroll;
+        self.craters
+            .iter()
+            .map(|crater| crater.depth_at(sample_x))
+            .fold(0.0, f32::max)
+    }
+
+    /// Blows a crater into the ground centred on `x`. Only the newest `max_craters` are
+    /// kept.
+    pub fn dig_crater(&mut self, x: f32, half_width: f32, depth: f32, max_craters: usize) {
+        self.craters.push(Crater {
+            x: x + self.scroll,
+            half_width,
+            depth,
+        });
+        let excess = self.craters.len().saturating_sub(max_craters);
+        self.craters.drain(..excess);
+    }
+
+    /// Smooths the ground over again.
+    pub fn fill_craters(&mut self) {
+        self.craters.clear();
+    }
+
+    /// Rise over run of the ground surface at `x`.
+    pub fn slope_at(&self, x: f32) -> f32 {
+        const SAMPLE_OFFSET: f32 = 1.0;
+        (self.height_at(x + SAMPLE_OFFSET) - self.height_at(x - SAMPLE_OFFSET))
+            / (2.0 * SAMPLE_OFFSET)
+    }
+
+    /// How fast the ground moves left across the screen, in pixels per second.
+    pub fn scroll_speed(&self) -> f32 {
+        self.scroll_speed
+    }
+
+    /// Angle the ground surface makes with the horizontal at `x`.
+    pub fn angle_at(&self, x: f32) -> f32 {
+        self.slope_at(x).atan()
+    }
+}
+
+impl Default for Terrain {
+    fn default() -> Self {
+        Self::generate(&mut thread_rng())
+    }
+}
+
+#[derive(Component)]
+pub struct Ground;
+
+pub struct TerrainPlugin;
+
+impl Plugin for TerrainPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Terrain>()
+            .add_systems(Startup, spawn_ground)
+            .add_systems(
+                Update,
+                (
+                    scroll_terrain.run_if(in_state(GameState::Playing)),
+                    update_ground,
+                )
+                    .chain(),
+            );
+    }
+}
+
+fn spawn_ground(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
+    );
+    commands.spawn((
+        MaterialMesh2dBundle {
+            mesh: Mesh2dHandle(meshes.add(mesh)),
+            material: materials.add(TERRAIN_COLOR),
+            transform: Transform::from_xyz(0.0, 0.0, -0.5),
+            ..default()
+        },
+        // The mesh is rebuilt every frame, so its initial bounds mean nothing.
+        NoFrustumCulling,
+        RigidBody::Fixed,
+        Collider::segment(Vec2::ZERO, Vec2::X),
+        Ground,
+    ));
+}
+
+/// Craters that have scrolled out of sight behind the jeep are forgotten.
+fn scroll_terrain(mut terrain: ResMut<Terrain>, time: Res<Time>) {
+    terrain.scroll += terrain.scroll_speed * time.delta_seconds();
+    let forget_x = terrain.scroll - OFFSCREEN_MARGIN.x;
+    terrain
+        .craters
+        .retain(|crater| crater.x + crater.half_width > forget_x);
+}
+
+/// Rebuilds the ground mesh and its physics outline from the current terrain profile.
+/// The surface is tinted darker where it's been cratered.
+fn update_ground(
+    terrain: Res<Terrain>,
+    mut ground_query: Query<(&Mesh2dHandle, &mut Collider), With<Ground>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let Ok((mesh_handle, mut ground_collider)) = ground_query.get_single_mut() else {
+        return;
+    };
+    let Some(mesh) = meshes.get_mut(&mesh_handle.0) else {
+        return;
+    };
+
+    // Covers the widest zoomed-out view, plus a column either side.
+    let overhang = (OFFSCREEN_MARGIN.x / TERRAIN_COLUMN_WIDTH).ceil() + 1.0;
+    let columns = (PLAY_AREA.x / TERRAIN_COLUMN_WIDTH).ceil() as u32 + 2 * overhang as u32;
+    let surface: Vec<Vec2> = (0..=columns)
+        .map(|column| {
+            let x = (column as f32 - overhang) * TERRAIN_COLUMN_WIDTH;
+            Vec2::new(x, terrain.height_at(x))
+        })
+        .collect();
+
+    let mut positions = Vec::with_capacity(surface.len() * 2);
+    let mut colors = Vec::with_capacity(surface.len() * 2);
+    for point in &surface {
+        positions.push([point.x, point.y, 0.0]);
+        positions.push([point.x, GROUND_HEIGHT - TERRAIN_DEPTH, 0.0]);
+        let scorch = (terrain.crater_depth_at(point.x) / CRATER_SCORCH_DEPTH).min(1.0);
+        let [r, g, b, _] = SCORCH_TINT.as_rgba_f32();
+        colors.push([
+            1.0 + (r - 1.0) * scorch,
+            1.0 + (g - 1.0) * scorch,
+            1.0 + (b - 1.0) * scorch,
+            1.0,
+        ]);
+        colors.push([1.0; 4]);
+    }
+    let mut indices = Vec::with_capacity(columns as usize * 6);
+    for column in 0..columns {
+        let top_left = column * 2;
+        let (bottom_left, top_right, bottom_right) = (top_left + 1, top_left + 2, top_left + 3);
+        indices.extend([
+            top_left,
+            bottom_left,
+            top_right,
+            top_right,
+            bottom_left,
+            bottom_right,
+        ]);
+    }
+    let vertex_count = positions.len();
+
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0, 0.0, 1.0]; vertex_count]);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, vec![[0.0, 0.0]; vertex_count]);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh.insert_indices(Indices::U32(indices));
+
+    *ground_collider = Collider::polyline(surface, None);
+}
@@ -0,0 +1,191 @@
+use std::fs;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    mods::ModPacks,
+    settings::Settings,
+    terrain::{Ground, TERRAIN_COLOR},
+    SKY_COLOR,
+};
+
+/// Every `.ron` file in here is a theme manifest, named after its file stem.
+const THEME_DIR: &str = "assets/themes";
+pub const DEFAULT_THEME: &str = "desert";
+const DEFAULT_BOMB_TEXTURE: &str = "../assets/bomb.png";
+
+/// A visual theme as read from its manifest. Textures that are left out fall back to the
+/// standard ones.
+#[derive(Deserialize)]
+struct ThemeManifest {
+    name: String,
+    sky_color: (f32, f32, f32),
+    ground_color: (f32, f32, f32),
+    #[serde(default)]
+    plane_texture: Option<String>,
+    #[serde(default)]
+    bomb_texture: Option<String>,
+    #[serde(default)]
+    heat_haze: bool,
+}
+
+pub struct Theme {
+    /// File stem of the manifest, which is what settings refer to.
+    pub id: String,
+    pub name: String,
+    pub sky_color: Color,
+    pub ground_color: Color,
+    /// Overrides the plane livery's texture when set.
+    pub plane_texture: Option<String>,
+    pub bomb_texture: String,
+    /// Hot enough for heat haze to rise off the ground.
+    pub heat_haze: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            id: DEFAULT_THEME.to_string(),
+            name: "Desert".to_string(),
+            sky_color: SKY_COLOR,
+            ground_color: TERRAIN_COLOR,
+            plane_texture: None,
+            bomb_texture: DEFAULT_BOMB_TEXTURE.to_string(),
+            heat_haze: true,
+        }
+    }
+}
+
+impl Theme {
+    fn from_manifest(id: String, manifest: ThemeManifest) -> Self {
+        let (r, g, b) = manifest.sky_color;
+        let sky_color = Color::rgb(r, g, b);
+        let (r, g, b) = manifest.ground_color;
+        Self {
+            id,
+            name: manifest.name,
+            sky_color,
+            ground_color: Color::rgb(r, g, b),
+            plane_texture: manifest.plane_texture,
+            bomb_texture: manifest
+                .bomb_texture
+                .unwrap_or_else(|| DEFAULT_BOMB_TEXTURE.to_string()),
+            heat_haze: manifest.heat_haze,
+        }
+    }
+}
+
+/// Every theme found on disk, sorted by id, plus the built-in desert look for when a
+/// theme is missing.
+#[derive(Resource)]
+pub struct Themes {
+    packs: Vec<Theme>,
+    fallback: Theme,
+}
+
+impl FromWorld for Themes {
+    /// Themes in mod packs are read after the game's own, so one with the same id as an
+    /// earlier theme replaces it.
+    fn from_world(world: &mut World) -> Self {
+        let dirs = world.get_resource::<ModPacks>().map_or_else(
+            || vec![THEME_DIR.into()],
+            |packs| packs.data_dirs(THEME_DIR),
+        );
+        let mut themes: Vec<Theme> = Vec::new();
+        let found = dirs
+            .iter()
+            .flat_map(fs::read_dir)
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? != "ron" {
+                    return None;
+                }
+                let id = path.file_stem()?.to_str()?.to_string();
+                let manifest = fs::read_to_string(&path).ok()?;
+                match ron::from_str::<ThemeManifest>(&manifest) {
+                    Ok(manifest) => Some(Theme::from_manifest(id, manifest)),
+                    Err(error) => {
+                        warn!("skipping theme {}: {error}", path.display());
+                        None
+                    }
+                }
+            });
+        for theme in found {
+            themes.retain(|earlier| earlier.id != theme.id);
+            themes.push(theme);
+        }
+        themes.sort_by(|a, b| a.id.cmp(&b.id));
+        Self {
+            packs: themes,
+            fallback: Theme::default(),
+        }
+    }
+}
+
+impl Themes {
+    /// The theme with the given id, or the default one if it doesn't exist.
+    pub fn get(&self, id: &str) -> &Theme {
+        self.packs
+            .iter()
+            .find(|theme| theme.id == id)
+            .or_else(|| self.packs.iter().find(|theme| theme.id == DEFAULT_THEME))
+            .unwrap_or(&self.fallback)
+    }
+
+    fn next_after(&self, id: &str) -> &Theme {
+        let next = self
+            .packs
+            .iter()
+            .position(|theme| theme.id == id)
+            .map_or(0, |index| (index + 1) % self.packs.len());
+        self.packs.get(next).unwrap_or(&self.fallback)
+    }
+}
+
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Themes>()
+            .add_systems(Update, (cycle_theme, apply_theme).chain());
+    }
+}
+
+/// `F3` switches to the next theme.
+fn cycle_theme(
+    key_input: Res<ButtonInput<KeyCode>>,
+    themes: Res<Themes>,
+    mut settings: ResMut<Settings>,
+) {
+    if !key_input.just_pressed(KeyCode::F3) {
+        return;
+    }
+    settings.theme = themes.next_after(&settings.theme).id.clone();
+}
+
+/// Repaints the sky and ground. Enemy textures are picked up as they spawn.
+fn apply_theme(
+    settings: Res<Settings>,
+    themes: Res<Themes>,
+    mut clear_color: ResMut<ClearColor>,
+    ground_query: Query<&Handle<ColorMaterial>, With<Ground>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut applied: Local<Option<String>>,
+) {
+    if applied.as_deref() == Some(settings.theme.as_str()) {
+        return;
+    }
+    let Ok(ground_material) = ground_query.get_single() else {
+        return;
+    };
+    let Some(material) = materials.get_mut(ground_material) else {
+        return;
+    };
+
+    let theme = themes.get(&settings.theme);
+    material.color = theme.ground_color;
+    clear_color.0 = theme.sky_color;
+    *applied = Some(settings.theme.clone());
+}
@@ -0,0 +1,123 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{GameSet, GameState};
+
+/// Countdown to an enemy's next bomb, repeating for as long as it's in the air.
+#[derive(Component, Reflect, Clone, Serialize, Deserialize)]
+pub struct BombTimer(pub Timer);
+
+impl BombTimer {
+    pub fn new(seconds: f32) -> Self {
+        Self(Timer::from_seconds(seconds, TimerMode::Repeating))
+    }
+}
+
+/// Countdown to a gunboat's next shell.
+#[derive(Component, Reflect, Clone, Serialize, Deserialize)]
+pub struct ShellTimer(pub Timer);
+
+impl ShellTimer {
+    pub fn new(seconds: f32) -> Self {
+        Self(Timer::from_seconds(seconds, TimerMode::Repeating))
+    }
+}
+
+/// Components holding a timer that runs on game time.
+trait GameTimer: Component {
+    fn timer_mut(&mut self) -> &mut Timer;
+}
+
+impl GameTimer for BombTimer {
+    fn timer_mut(&mut self) -> &mut Timer {
+        &mut self.0
+    }
+}
+
+impl GameTimer for ShellTimer {
+    fn timer_mut(&mut self) -> &mut Timer {
+        &mut self.0
+    }
+}
+
+/// Every gameplay timer component is ticked here, once a frame, before anything that
+/// waits on one gets to check it.
+pub struct TimersPlugin;
+
+impl Plugin for TimersPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (tick_timers::<BombTimer>, tick_timers::<ShellTimer>)
+                .before(GameSet::Spawning)
+                .run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+fn tick_timers<T: GameTimer>(time: Res<Time>, mut timer_query: Query<&mut T>) {
+    for mut timer in &mut timer_query {
+        timer.timer_mut().tick(time.delta());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn app_with_bomb_timer(seconds: f32) -> (App, Entity) {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .init_state::<GameState>()
+            .add_plugins(TimersPlugin);
+        let entity = app.world.spawn(BombTimer::new(seconds)).id();
+        (app, entity)
+    }
+
+    /// Runs one frame `seconds` long.
+    fn step(app: &mut App, seconds: f32) {
+        app.world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs_f32(seconds));
+        app.update();
+    }
+
+    fn bomb_timer(app: &App, entity: Entity) -> &Timer {
+        &app.world
+            .get::<BombTimer>(entity)
+            .expect("bomb timer entity was spawned")
+            .0
+    }
+
+    #[test]
+    fn bomb_timer_ticks_once_per_frame() {
+        let (mut app, entity) = app_with_bomb_timer(2.0);
+        step(&mut app, 0.5);
+        assert_eq!(bomb_timer(&app, entity).elapsed_secs(), 0.5);
+    }
+
+    #[test]
+    fn bomb_timer_fires_once_per_interval() {
+        let (mut app, entity) = app_with_bomb_timer(2.0);
+        let mut bombs = 0;
+        for _ in 0..40 {
+            step(&mut app, 0.25);
+            if bomb_timer(&app, entity).just_finished() {
+                bombs += 1;
+            }
+        }
+        assert_eq!(bombs, 5);
+    }
+
+    #[test]
+    fn bomb_timer_stops_outside_play() {
+        let (mut app, entity) = app_with_bomb_timer(2.0);
+        app.world
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::GameOver);
+        step(&mut app, 0.5);
+        assert_eq!(bomb_timer(&app, entity).elapsed_secs(), 0.0);
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+/// At most this many toasts are on screen at once; the rest wait their turn.
+const MAX_VISIBLE_TOASTS: usize = 3;
+const TOAST_DURATION: f32 = 3.0;
+/// How long a toast takes to slide in from the edge of the screen, and back out.
+const TOAST_SLIDE_TIME: f32 = 0.25;
+/// How far off the edge a toast starts from.
+const TOAST_SLIDE_DISTANCE: f32 = 320.0;
+const TOAST_FONT_SIZE: f32 = 20.0;
+const TOAST_TEXT_COLOR: Color = Color::WHITE;
+const TOAST_BACKGROUND: Color = Color::rgba(0.1, 0.1, 0.15, 0.85);
+/// Clear of the target readout in the corner.
+const TOAST_TOP: Val = Val::Px(48.0);
+
+/// Pops up a short message in the corner of the screen, like an unlock or a save. The text
+/// is shown as it is, so it should already be in the player's language.
+#[derive(Event)]
+pub struct ToastEvent(pub String);
+
+/// Messages still waiting for a free spot on screen.
+#[derive(Resource, Default)]
+struct ToastQueue(VecDeque<String>);
+
+/// The column the toasts stack up in, newest at the bottom.
+#[derive(Component)]
+struct ToastStack;
+
+#[derive(Component)]
+struct Toast {
+    timer: Timer,
+}
+
+pub struct ToastPlugin;
+
+impl Plugin for ToastPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ToastEvent>()
+            .init_resource::<ToastQueue>()
+            .add_systems(Startup, spawn_toast_stack)
+            .add_systems(Update, (queue_toasts, show_toasts, slide_toasts).chain());
+    }
+}
+
+fn spawn_toast_stack(mut commands: Commands) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: TOAST_TOP,
+                right: Val::Px(0.0),
+                padding: UiRect::right(Val::Px(12.0)),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::FlexEnd,
+                row_gap: Val::Px(8.0),
+                ..default()
+            },
+            z_index: ZIndex::Global(5),
+            ..default()
+        },
+        ToastStack,
+    ));
+}
+
+fn queue_toasts(mut toast_events: EventReader<ToastEvent>, mut queue: ResMut<ToastQueue>) {
+    queue
+        .0
+        .extend(toast_events.read().map(|ToastEvent(text)| text.clone()));
+}
+
+/// Moves waiting messages onto the screen as room frees up.
+fn show_toasts(
+    mut commands: Commands,
+    mut queue: ResMut<ToastQueue>,
+    stack_query: Query<Entity, With<ToastStack>>,
+    toast_query: Query<(), With<Toast>>,
+) {
+    let Ok(stack_entity) = stack_query.get_single() else {
+        return;
+    };
+    let free = MAX_VISIBLE_TOASTS.saturating_sub(toast_query.iter().len());
+    let shown = free.min(queue.0.len());
+    for text in queue.0.drain(..shown) {
+        let toast = commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        padding: UiRect::axes(Val::Px(16.0), Val::Px(8.0)),
+                        left: Val::Px(TOAST_SLIDE_DISTANCE),
+                        ..default()
+                    },
+                    background_color: TOAST_BACKGROUND.into(),
+                    ..default()
+                },
+                Toast {
+                    timer: Timer::from_seconds(TOAST_DURATION, TimerMode::Once),
+                },
+            ))
+            .with_children(|toast| {
+                toast.spawn(TextBundle::from_section(
+                    text,
+                    TextStyle {
+                        font_size: TOAST_FONT_SIZE,
+                        color: TOAST_TEXT_COLOR,
+                        ..default()
+                    },
+                ));
+            })
+            .id();
+        commands.entity(stack_entity).add_child(toast);
+    }
+}
+
+/// Slides each toast in from the edge, holds it, then slides it back out and lets the ones
+/// below move up. Real time is used so toasts still come and go over the pause menu.
+fn slide_toasts(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut toast_query: Query<(Entity, &mut Toast, &mut Style)>,
+) {
+    for (toast_entity, mut toast, mut style) in &mut toast_query {
+        toast.timer.tick(time.delta());
+        if toast.timer.finished() {
+            commands.entity(toast_entity).despawn_recursive();
+            continue;
+        }
+        let elapsed = toast.timer.elapsed_secs();
+        let remaining = TOAST_DURATION - elapsed;
+        let shown = (elapsed.min(remaining) / TOAST_SLIDE_TIME).min(1.0);
+        let eased = 1.0 - (1.0 - shown).powi(2);
+        style.left = Val::Px(TOAST_SLIDE_DISTANCE * (1.0 - eased));
+    }
+}
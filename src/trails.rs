@@ -0,0 +1,151 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::{missile::HomingMissile, pause::Paused, Bomb, GameState, Rocket};
+
+/// A new point isn't laid down until the projectile has moved this far from the last one.
+const MIN_POINT_SPACING: f32 = 3.0;
+
+const ROCKET_TRAIL: Trail = Trail {
+    color: Color::rgb(1.0, 0.9, 0.6),
+    lifetime: 0.2,
+};
+const MISSILE_TRAIL: Trail = Trail {
+    color: Color::rgb(1.0, 0.7, 0.3),
+    lifetime: 0.45,
+};
+const BOMB_TRAIL: Trail = Trail {
+    color: Color::rgb(0.85, 0.85, 0.85),
+    lifetime: 0.3,
+};
+/// For the missiles fortresses and submarines send at the jeep.
+pub const ENEMY_MISSILE_TRAIL: Trail = Trail {
+    color: Color::rgb(1.0, 0.35, 0.25),
+    lifetime: 0.45,
+};
+
+/// Draws a fading streak behind whatever it's put on, so fast projectiles are easier to
+/// follow. Rockets, bombs and the jeep's missiles get one as they appear; anything else
+/// can be given one when it's spawned.
+#[derive(Component, Clone, Copy)]
+pub struct Trail {
+    pub color: Color,
+    /// How long each point of the streak lasts before it's faded out.
+    pub lifetime: f32,
+}
+
+/// The streak itself, kept apart from the projectile so it can fade out after the
+/// projectile is gone instead of vanishing with it.
+#[derive(Component)]
+struct Ribbon {
+    source: Entity,
+    trail: Trail,
+    /// Oldest first, with how long each has been there.
+    points: VecDeque<(Vec2, f32)>,
+}
+
+pub struct TrailsPlugin;
+
+impl Plugin for TrailsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                (trail_projectiles, spawn_ribbons, grow_ribbons)
+                    .chain()
+                    .run_if(not(resource_exists::<Paused>)),
+                draw_ribbons,
+            )
+                .chain()
+                .run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(OnExit(GameState::GameOver), clear_ribbons);
+    }
+}
+
+fn trail_projectiles(
+    mut commands: Commands,
+    rocket_query: Query<Entity, Added<Rocket>>,
+    missile_query: Query<Entity, Added<HomingMissile>>,
+    bomb_query: Query<Entity, Added<Bomb>>,
+) {
+    let projectiles = rocket_query
+        .iter()
+        .map(|entity| (entity, ROCKET_TRAIL))
+        .chain(missile_query.iter().map(|entity| (entity, MISSILE_TRAIL)))
+        .chain(bomb_query.iter().map(|entity| (entity, BOMB_TRAIL)));
+    for (entity, trail) in projectiles {
+        commands.entity(entity).try_insert(trail);
+    }
+}
+
+fn spawn_ribbons(mut commands: Commands, trail_query: Query<(Entity, &Trail), Added<Trail>>) {
+    for (source, &trail) in &trail_query {
+        commands.spawn(Ribbon {
+            source,
+            trail,
+            points: VecDeque::new(),
+        });
+    }
+}
+
+/// Ribbons follow their projectile until it's gone, then shrink away behind where it
+/// was last seen.
+fn grow_ribbons(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut ribbon_query: Query<(Entity, &mut Ribbon)>,
+    source_query: Query<&Transform, With<Trail>>,
+) {
+    for (ribbon_entity, mut ribbon) in &mut ribbon_query {
+        for (_, age) in &mut ribbon.points {
+            *age += time.delta_seconds();
+        }
+        let lifetime = ribbon.trail.lifetime;
+        while ribbon
+            .points
+            .front()
+            .is_some_and(|&(_, age)| age >= lifetime)
+        {
+            ribbon.points.pop_front();
+        }
+
+        match source_query.get(ribbon.source) {
+            Ok(source_transform) => {
+                let position = source_transform.translation.truncate();
+                let spaced = ribbon
+                    .points
+                    .back()
+                    .is_none_or(|&(last, _)| last.distance(position) >= MIN_POINT_SPACING);
+                if spaced {
+                    ribbon.points.push_back((position, 0.0));
+                }
+            }
+            Err(_) if ribbon.points.is_empty() => {
+                commands.entity(ribbon_entity).despawn();
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+/// A line from the oldest point to the newest, fading in from nothing at the tail.
+fn draw_ribbons(mut gizmos: Gizmos, ribbon_query: Query<&Ribbon>) {
+    for ribbon in &ribbon_query {
+        if ribbon.points.len() < 2 {
+            continue;
+        }
+        let trail = ribbon.trail;
+        gizmos.linestrip_gradient_2d(ribbon.points.iter().map(|&(position, age)| {
+            let freshness = 1.0 - age / trail.lifetime;
+            (position, trail.color.with_a(trail.color.a() * freshness))
+        }));
+    }
+}
+
+fn clear_ribbons(mut commands: Commands, ribbon_query: Query<Entity, With<Ribbon>>) {
+    for ribbon_entity in &ribbon_query {
+        commands.entity(ribbon_entity).despawn();
+    }
+}
@@ -0,0 +1,324 @@
+use bevy::{
+    ecs::system::RunSystemOnce,
+    math::bounding::{Aabb2d, BoundingCircle, IntersectsVolume},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ai::EnemyAi,
+    camera::OFFSCREEN_MARGIN,
+    controls::{Action, Controls},
+    debris::DebrisEvent,
+    explosion::ExplosionEvent,
+    flak::{flak_shell, FlakBurst, Fuse},
+    health::Health,
+    localization::Locale,
+    pause::Paused,
+    profile::ActiveProfile,
+    terrain::Terrain,
+    toast::ToastEvent,
+    GameSet, GameState, Plane, Player, PlayerState,
+};
+
+/// Credits a turret costs to put down.
+const TURRET_COST: u32 = 250;
+/// Most turrets that can be standing at once.
+const MAX_TURRETS: usize = 2;
+const TURRET_HEALTH: f32 = 3.0;
+/// Shells a turret is set down with. It's abandoned once they're gone.
+const TURRET_AMMO: u32 = 12;
+const TURRET_RELOAD: f32 = 1.2;
+/// Planes further away than this are left alone.
+const TURRET_RANGE: f32 = 480.0;
+const TURRET_BASE_SIZE: Vec2 = Vec2::new(26.0, 12.0);
+const TURRET_BARREL_SIZE: Vec2 = Vec2::new(4.0, 22.0);
+const TURRET_COLOR: Color = Color::rgb(0.36, 0.42, 0.3);
+const TURRET_BARREL_COLOR: Color = Color::rgb(0.25, 0.28, 0.22);
+/// Behind the jeep, so it drives past the turret rather than into it.
+const TURRET_DEPTH: f32 = -0.15;
+
+/// Flak is slow, so it has to be fired well ahead of where a plane is.
+const FLAK_SPEED: f32 = 260.0;
+const TURRET_BURST: FlakBurst = FlakBurst {
+    radius: 40.0,
+    damage: 1.0,
+};
+
+/// A stationary anti-aircraft gun the player paid for, which picks its own targets.
+#[derive(Component, Clone, Serialize, Deserialize)]
+pub struct AaTurret {
+    ammo: u32,
+    reload: Timer,
+    /// Set when the turret is spawned, since the barrel is spawned along with it.
+    #[serde(skip, default = "no_barrel")]
+    barrel: Entity,
+}
+
+fn no_barrel() -> Entity {
+    Entity::PLACEHOLDER
+}
+
+pub struct TurretsPlugin;
+
+impl Plugin for TurretsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                build_turrets.in_set(GameSet::Input),
+                (scroll_turrets, aim_turrets)
+                    .chain()
+                    .in_set(GameSet::Movement),
+                damage_turrets.in_set(GameSet::Collision),
+            )
+                .run_if(in_state(GameState::Playing).and_then(not(resource_exists::<Paused>))),
+        )
+        .add_systems(OnExit(GameState::GameOver), clear_turrets);
+    }
+}
+
+/// Build puts a turret down where the jeep is standing, paid for out of the profile's
+/// credits.
+fn build_turrets(
+    mut commands: Commands,
+    controls: Res<Controls>,
+    mut profile: ResMut<ActiveProfile>,
+    player_query: Query<(&Transform, &Player, &PlayerState)>,
+    turret_query: Query<(), With<AaTurret>>,
+    locale: Res<Locale>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    if !controls.just_pressed(Action::Build) {
+        return;
+    }
+    let Ok((player_transform, player, PlayerState::Alive)) = player_query.get_single() else {
+        return;
+    };
+    if !player.grounded {
+        return;
+    }
+    if turret_query.iter().count() >= MAX_TURRETS {
+        toast_events.send(ToastEvent(
+            locale.format("turret-limit", &[("max", &MAX_TURRETS)]),
+        ));
+        return;
+    }
+    if profile.0.credits < TURRET_COST {
+        toast_events.send(ToastEvent(
+            locale.format("turret-too-expensive", &[("cost", &TURRET_COST)]),
+        ));
+        return;
+    }
+    profile.0.credits -= TURRET_COST;
+    profile.0.save();
+    toast_events.send(ToastEvent(
+        locale.format("turret-built", &[("credits", &profile.0.credits)]),
+    ));
+
+    spawn_turret(
+        &mut commands,
+        Transform::from_xyz(player_transform.translation.x, 0.0, TURRET_DEPTH),
+        AaTurret {
+            ammo: TURRET_AMMO,
+            reload: Timer::from_seconds(TURRET_RELOAD, TimerMode::Once),
+            barrel: Entity::PLACEHOLDER,
+        },
+        Health::new(TURRET_HEALTH),
+    );
+}
+
+/// A turret and its barrel, which `turret` is pointed at.
+fn spawn_turret(
+    commands: &mut Commands,
+    transform: Transform,
+    mut turret: AaTurret,
+    health: Health,
+) {
+    let mut turret_commands = commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: TURRET_COLOR,
+                custom_size: Some(TURRET_BASE_SIZE),
+                anchor: bevy::sprite::Anchor::BottomCenter,
+                ..default()
+            },
+            transform,
+            ..default()
+        },
+        health,
+    ));
+    turret_commands.with_children(|parent| {
+        turret.barrel = parent
+            .spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: TURRET_BARREL_COLOR,
+                    custom_size: Some(TURRET_BARREL_SIZE),
+                    anchor: bevy::sprite::Anchor::BottomCenter,
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, TURRET_BASE_SIZE.y, -0.01),
+                ..default()
+            })
+            .id();
+    });
+    turret_commands.insert(turret);
+}
+
+/// Turrets are dug into the ground, so they're carried along with it.
+fn scroll_turrets(
+    mut commands: Commands,
+    time: Res<Time>,
+    terrain: Res<Terrain>,
+    mut turret_query: Query<(Entity, &mut Transform), With<AaTurret>>,
+) {
+    let despawn_x = -OFFSCREEN_MARGIN.x - TURRET_BASE_SIZE.x;
+    for (turret_entity, mut transform) in &mut turret_query {
+        transform.translation.x -= terrain.scroll_speed() * time.delta_seconds();
+        let x = transform.translation.x;
+        transform.translation.y = terrain.height_at(x);
+        if x < despawn_x {
+            commands.entity(turret_entity).despawn_recursive();
+        }
+    }
+}
+
+/// Where a shell fired from `muzzle` meets a plane at `target` flying at `velocity`, and
+/// how long it takes to get there. Refined a few times, since the flight time depends on
+/// the meeting point.
+fn intercept(muzzle: Vec2, target: Vec2, velocity: Vec2) -> (Vec2, f32) {
+    let mut point = target;
+    let mut flight_time = 0.0;
+    for _ in 0..3 {
+        flight_time = muzzle.distance(point) / FLAK_SPEED;
+        point = target + velocity * flight_time;
+    }
+    (point, flight_time)
+}
+
+/// Each turret swings round to lead the nearest plane in range and fires when it's
+/// reloaded, until it runs out of shells.
+fn aim_turrets(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut turret_query: Query<(Entity, &Transform, &mut AaTurret)>,
+    mut barrel_query: Query<&mut Transform, (Without<AaTurret>, Without<Plane>)>,
+    plane_query: Query<(&Transform, Option<&EnemyAi>), (With<Plane>, Without<AaTurret>)>,
+    locale: Res<Locale>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    for (turret_entity, transform, mut turret) in &mut turret_query {
+        turret.reload.tick(time.delta());
+        let muzzle = transform.translation.truncate() + Vec2::Y * TURRET_BASE_SIZE.y;
+        let target = plane_query
+            .iter()
+            .map(|(plane_transform, ai)| {
+                let position = plane_transform.translation.truncate();
+                (position, ai.map_or(Vec2::ZERO, |ai| ai.velocity))
+            })
+            .filter(|&(position, _)| {
+                position.y > muzzle.y && position.distance(muzzle) <= TURRET_RANGE
+            })
+            .min_by(|(a, _), (b, _)| a.distance(muzzle).total_cmp(&b.distance(muzzle)));
+        let Some((position, velocity)) = target else {
+            continue;
+        };
+
+        let (aim, flight_time) = intercept(muzzle, position, velocity);
+        let direction = (aim - muzzle).normalize_or_zero();
+        if let Ok(mut barrel_transform) = barrel_query.get_mut(turret.barrel) {
+            barrel_transform.rotation =
+                Quat::from_rotation_z(direction.y.atan2(direction.x) - std::f32::consts::FRAC_PI_2);
+        }
+        if !turret.reload.finished() {
+            continue;
+        }
+
+        turret.reload.reset();
+        turret.ammo -= 1;
+        // Timed to burst where the plane is expected to be.
+        commands.spawn(flak_shell(
+            muzzle + direction * TURRET_BARREL_SIZE.y,
+            direction * FLAK_SPEED,
+            Fuse::Timed(Timer::from_seconds(flight_time, TimerMode::Once)),
+            TURRET_BURST,
+        ));
+        if turret.ammo == 0 {
+            commands.entity(turret_entity).despawn_recursive();
+            toast_events.send(ToastEvent(locale.get("turret-out-of-ammo").to_string()));
+        }
+    }
+}
+
+/// Anything going off next to a turret knocks it about, and enough of it destroys it.
+fn damage_turrets(
+    mut commands: Commands,
+    mut explosion_events: EventReader<ExplosionEvent>,
+    mut turret_query: Query<(Entity, &Transform, &mut Health), With<AaTurret>>,
+    mut debris_events: EventWriter<DebrisEvent>,
+    locale: Res<Locale>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    for explosion in explosion_events.read() {
+        let blast = BoundingCircle::new(explosion.position, explosion.radius);
+        for (turret_entity, transform, mut health) in &mut turret_query {
+            let turret_box = Aabb2d::new(
+                transform.translation.truncate() + Vec2::Y * TURRET_BASE_SIZE.y / 2.0,
+                TURRET_BASE_SIZE / 2.0,
+            );
+            if health.is_dead() || !blast.intersects(&turret_box) {
+                continue;
+            }
+            if health.damage(1.0) {
+                commands.entity(turret_entity).despawn_recursive();
+                debris_events.send(DebrisEvent {
+                    position: transform.translation.truncate(),
+                });
+                toast_events.send(ToastEvent(locale.get("turret-destroyed").to_string()));
+            }
+        }
+    }
+}
+
+fn clear_turrets(mut commands: Commands, turret_query: Query<Entity, With<AaTurret>>) {
+    for entity in &turret_query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// What a saved run keeps of the turrets standing.
+#[derive(Serialize, Deserialize)]
+pub struct SavedTurrets(Vec<(Transform, AaTurret, Health)>);
+
+impl SavedTurrets {
+    pub fn capture(world: &mut World) -> Self {
+        world.run_system_once(save_turrets)
+    }
+
+    /// Swaps the turrets standing for these.
+    pub fn restore(self, world: &mut World) {
+        world.run_system_once_with(self, load_turrets);
+    }
+}
+
+fn save_turrets(turret_query: Query<(&Transform, &AaTurret, &Health)>) -> SavedTurrets {
+    SavedTurrets(
+        turret_query
+            .iter()
+            .map(|(transform, turret, health)| (*transform, turret.clone(), health.clone()))
+            .collect(),
+    )
+}
+
+fn load_turrets(
+    In(saved): In<SavedTurrets>,
+    mut commands: Commands,
+    turret_query: Query<Entity, With<AaTurret>>,
+) {
+    for entity in &turret_query {
+        commands.entity(entity).despawn_recursive();
+    }
+    for (transform, turret, health) in saved.0 {
+        spawn_turret(&mut commands, transform, turret, health);
+    }
+}
@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// How a tween gets from its start value to its end value.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub enum Ease {
+    Linear,
+    /// Quick to begin with, slowing into the end.
+    QuadOut,
+}
+
+impl Ease {
+    fn apply(self, t: f32) -> f32 {
+        match self {
+            Ease::Linear => t,
+            Ease::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+        }
+    }
+}
+
+/// A value animated between two others over a fixed time. Whatever owns it ticks it and
+/// reads the value back.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Tween {
+    from: f32,
+    to: f32,
+    ease: Ease,
+    timer: Timer,
+}
+
+impl Tween {
+    pub fn new(from: f32, to: f32, seconds: f32, ease: Ease) -> Self {
+        Self {
+            from,
+            to,
+            ease,
+            timer: Timer::from_seconds(seconds, TimerMode::Once),
+        }
+    }
+
+    pub fn tick(&mut self, delta: Duration) {
+        self.timer.tick(delta);
+    }
+
+    pub fn value(&self) -> f32 {
+        self.from
+            .lerp(self.to, self.ease.apply(self.timer.fraction()))
+    }
+
+    pub fn target(&self) -> f32 {
+        self.to
+    }
+
+    pub fn finished(&self) -> bool {
+        self.timer.finished()
+    }
+}
@@ -0,0 +1,118 @@
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use crate::{
+    camera::view_rect, jammer::Jammer, settings::Settings, terrain::Terrain, Bomb, GameState,
+    Plane, Player, BOMB_BLAST_RADIUS, PLAY_AREA,
+};
+
+const ARROW_ALPHA: f32 = 0.9;
+/// How far in from the edge of the view arrows are drawn, at the default zoom.
+const ARROW_INSET: f32 = 24.0;
+const ARROW_LENGTH: f32 = 20.0;
+const ARROW_HALF_WIDTH: f32 = 10.0;
+/// Planes further out than this aren't a threat yet.
+const ARROW_RANGE: f32 = 400.0;
+/// While a jammer is up, arrows point this far off either way and only show some of the
+/// time.
+const JAMMED_ARROW_SCATTER: f32 = 250.0;
+const JAMMED_ARROW_CHANCE: f64 = 0.3;
+
+const LANDING_MARKER_ALPHA: f32 = 0.8;
+/// Bombs landing within this distance of the jeep get a marker on the ground.
+const LANDING_WARNING_RANGE: f32 = BOMB_BLAST_RADIUS * 2.0;
+const LANDING_MARKER_MIN_RADIUS: f32 = 6.0;
+/// The marker grows to the full blast radius as the bomb comes down from this height.
+const LANDING_MARKER_FALL_HEIGHT: f32 = 400.0;
+
+pub struct WarningsPlugin;
+
+impl Plugin for WarningsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (draw_offscreen_arrows, draw_landing_markers).run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+/// Points at planes outside the view from the nearest edge, fading in as they close.
+/// A jammer in the air scrambles them.
+fn draw_offscreen_arrows(
+    mut gizmos: Gizmos,
+    settings: Res<Settings>,
+    plane_query: Query<&Transform, With<Plane>>,
+    jammer_query: Query<(), With<Jammer>>,
+) {
+    let jammed = !jammer_query.is_empty();
+    let mut rng = thread_rng();
+    let view = view_rect(&settings);
+    // Gizmos are drawn in world space, so scale the inset to stay the same on screen.
+    let inset = ARROW_INSET * view.width() / PLAY_AREA.x;
+    let (min, max) = (view.min + inset, view.max - inset);
+    let arrow_color = settings.palette.colors().danger.with_a(ARROW_ALPHA);
+
+    for plane_transform in &plane_query {
+        let mut plane_position = plane_transform.translation.truncate();
+        if jammed {
+            if !rng.gen_bool(JAMMED_ARROW_CHANCE) {
+                continue;
+            }
+            plane_position += Vec2::new(
+                rng.gen_range(-JAMMED_ARROW_SCATTER..JAMMED_ARROW_SCATTER),
+                rng.gen_range(-JAMMED_ARROW_SCATTER..JAMMED_ARROW_SCATTER),
+            );
+        }
+        let tip = plane_position.clamp(min, max);
+        let offset = plane_position - tip;
+        let distance = offset.length();
+        if distance <= inset || distance > ARROW_RANGE {
+            continue;
+        }
+
+        let direction = offset / distance;
+        let side = direction.perp() * ARROW_HALF_WIDTH;
+        let base = tip - direction * ARROW_LENGTH;
+        let color = arrow_color.with_a(ARROW_ALPHA * (1.0 - distance / ARROW_RANGE));
+        gizmos.line_2d(base + side, tip, color);
+        gizmos.line_2d(base - side, tip, color);
+        gizmos.line_2d(base + side, base - side, color);
+    }
+}
+
+/// Bombs fall straight down, so the spot right below each one is where it goes off.
+fn draw_landing_markers(
+    mut gizmos: Gizmos,
+    settings: Res<Settings>,
+    terrain: Res<Terrain>,
+    player_query: Query<&Transform, With<Player>>,
+    bomb_query: Query<&Transform, With<Bomb>>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let player_x = player_transform.translation.x;
+    let marker_color = settings
+        .palette
+        .colors()
+        .danger
+        .with_a(LANDING_MARKER_ALPHA);
+
+    for bomb_transform in &bomb_query {
+        let landing_x = bomb_transform.translation.x;
+        if (landing_x - player_x).abs() > LANDING_WARNING_RANGE {
+            continue;
+        }
+
+        let landing = Vec2::new(landing_x, terrain.height_at(landing_x));
+        let height = bomb_transform.translation.y - landing.y;
+        let closeness = (1.0 - height / LANDING_MARKER_FALL_HEIGHT).clamp(0.0, 1.0);
+        let radius = LANDING_MARKER_MIN_RADIUS.lerp(BOMB_BLAST_RADIUS, closeness);
+        gizmos.ellipse_2d(landing, 0.0, Vec2::new(radius, radius * 0.25), marker_color);
+        gizmos.line_2d(
+            landing - Vec2::X * LANDING_MARKER_MIN_RADIUS,
+            landing + Vec2::X * LANDING_MARKER_MIN_RADIUS,
+            marker_color,
+        );
+    }
+}
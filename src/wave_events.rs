@@ -0,0 +1,515 @@
+use bevy::{
+    ecs::system::RunSystemOnce,
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+};
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    accessibility::AccessibilitySettings,
+    bonus::{Hostile, Round},
+    camera::OFFSCREEN_MARGIN,
+    health::Health,
+    localization::Localized,
+    palette::Outlined,
+    rng::GameRng,
+    settings::Settings,
+    stats::RunStats,
+    terrain::Terrain,
+    theme::Themes,
+    timers::BombTimer,
+    Bomb, Collider, GameState, Player, PlayerState, BOMB_HALF_SIZE, PLAY_AREA,
+};
+
+/// Seconds between special events.
+const EVENT_INTERVAL: std::ops::Range<f32> = 25.0..45.0;
+/// Above the regular planes so the fly-overs don't get mixed up with enemies.
+const FLYOVER_ALTITUDE_OFFSET: f32 = 40.0;
+const FLYOVER_DEPTH: f32 = -0.2;
+
+const CARGO_PLANE_SPEED: f32 = 160.0;
+const CARGO_PLANE_COLOR: Color = Color::rgb(0.5, 0.7, 0.5);
+const SUPPLY_CRATE_HALF_SIZE: Vec2 = Vec2::splat(20.0);
+const SUPPLY_CRATE_COLOR: Color = Color::rgb(0.6, 0.45, 0.25);
+const PARACHUTE_COLOR: Color = Color::rgb(0.95, 0.95, 0.9);
+const SUPPLY_CRATE_FALL_SPEED: f32 = 70.0;
+const SUPPLY_CRATE_SCORE: u32 = 250;
+
+const AIR_RAID_WARNING: f32 = 3.0;
+const RAID_BOMBER_SPEED: f32 = 260.0;
+const RAID_BOMBER_COLOR: Color = Color::rgb(0.35, 0.35, 0.4);
+const RAID_BOMB_INTERVAL: f32 = 0.18;
+const RAID_BOMB_FALL_SPEED: f32 = 220.0;
+const SIREN_FLASH_RATE: f32 = 6.0;
+
+/// Margin past the widest view at which fly-overs and crates are cleaned up.
+const DESPAWN_MARGIN: f32 = 100.0;
+
+#[derive(Clone, Copy)]
+enum WaveEvent {
+    SupplyDrop,
+    AirRaid,
+}
+
+/// Counts down to the next special event, and through the siren of an air raid that
+/// has been announced but not started yet.
+#[derive(Resource, Clone, Serialize, Deserialize)]
+struct EventScheduler {
+    next_event: Timer,
+    siren: Option<Timer>,
+}
+
+impl EventScheduler {
+    fn new(rng: &mut impl Rng) -> Self {
+        Self {
+            next_event: random_interval(rng),
+            siren: None,
+        }
+    }
+}
+
+impl FromWorld for EventScheduler {
+    fn from_world(world: &mut World) -> Self {
+        Self::new(&mut world.resource_mut::<GameRng>().0)
+    }
+}
+
+fn random_interval(rng: &mut impl Rng) -> Timer {
+    Timer::from_seconds(rng.gen_range(EVENT_INTERVAL), TimerMode::Once)
+}
+
+/// A friendly transport crossing the sky that drops its crate at `drop_x`.
+#[derive(Component, Clone, Serialize, Deserialize)]
+struct CargoPlane {
+    drop_x: f32,
+    dropped: bool,
+}
+
+/// Heals the jeep and scores a bonus when driven into.
+#[derive(Component, Clone, Serialize, Deserialize)]
+struct SupplyCrate {
+    landed: bool,
+}
+
+#[derive(Component)]
+struct Parachute;
+
+/// Flies straight across the screen carpet-bombing the ground. It is too high to be
+/// shot down; the only defence is to get out from underneath.
+#[derive(Component)]
+struct RaidBomber;
+
+#[derive(Component)]
+struct SirenBanner;
+
+pub struct WaveEventsPlugin;
+
+impl Plugin for WaveEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EventScheduler>()
+            .add_systems(
+                Update,
+                (
+                    // Nothing new is sent in during a bonus round.
+                    schedule_events.run_if(in_state(Round::Waves)),
+                    sound_siren,
+                    fly_cargo_planes,
+                    drop_supply_crates,
+                    collect_supply_crates,
+                    fly_raid_bombers,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::GameOver), reset_events);
+    }
+}
+
+fn schedule_events(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    mut scheduler: ResMut<EventScheduler>,
+    mut rng: ResMut<GameRng>,
+) {
+    let rng = &mut rng.0;
+
+    if let Some(siren) = &mut scheduler.siren {
+        siren.tick(time.delta());
+        if siren.finished() {
+            scheduler.siren = None;
+            commands.spawn(raid_bomber(
+                flyover_sprite(&asset_server, RAID_BOMBER_COLOR),
+                BombTimer::new(RAID_BOMB_INTERVAL),
+            ));
+        }
+        return;
+    }
+
+    scheduler.next_event.tick(time.delta());
+    if !scheduler.next_event.finished() {
+        return;
+    }
+    scheduler.next_event = random_interval(rng);
+
+    let event = if rng.gen_bool(0.5) {
+        WaveEvent::SupplyDrop
+    } else {
+        WaveEvent::AirRaid
+    };
+    match event {
+        WaveEvent::SupplyDrop => {
+            commands.spawn((
+                flyover_sprite(&asset_server, CARGO_PLANE_COLOR),
+                CargoPlane {
+                    drop_x: rng.gen_range(PLAY_AREA.x * 0.2..PLAY_AREA.x * 0.8),
+                    dropped: false,
+                },
+            ));
+        }
+        WaveEvent::AirRaid => {
+            scheduler.siren = Some(Timer::from_seconds(AIR_RAID_WARNING, TimerMode::Once));
+            spawn_siren_banner(&mut commands);
+        }
+    }
+}
+
+fn raid_bomber(sprite: SpriteBundle, bomb_timer: BombTimer) -> impl Bundle {
+    (sprite, RaidBomber, Hostile, bomb_timer)
+}
+
+fn spawn_siren_banner(commands: &mut Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 48.0,
+                color: Color::RED,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(20.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        })
+        .with_text_justify(JustifyText::Center),
+        SirenBanner,
+        Localized("air-raid"),
+    ));
+}
+
+/// A plane sprite entering from the right edge, high above the fight.
+fn flyover_sprite(asset_server: &AssetServer, color: Color) -> SpriteBundle {
+    SpriteBundle {
+        texture: asset_server.load("../assets/plane.png"),
+        sprite: Sprite { color, ..default() },
+        transform: Transform::from_xyz(
+            PLAY_AREA.x + OFFSCREEN_MARGIN.x + DESPAWN_MARGIN / 2.0,
+            PLAY_AREA.y - FLYOVER_ALTITUDE_OFFSET,
+            FLYOVER_DEPTH,
+        )
+        .with_scale(Vec3::new(2.5, 2.5, 1.0)),
+        ..default()
+    }
+}
+
+/// Flashes the air raid banner until the bombers arrive, or holds it steady for players
+/// who have turned flashes off.
+fn sound_siren(
+    mut commands: Commands,
+    scheduler: Res<EventScheduler>,
+    accessibility: Res<AccessibilitySettings>,
+    mut banner_query: Query<(Entity, &mut Text), With<SirenBanner>>,
+) {
+    for (banner_entity, mut text) in &mut banner_query {
+        let Some(siren) = &scheduler.siren else {
+            commands.entity(banner_entity).despawn_recursive();
+            continue;
+        };
+        let on = !accessibility.flashes || (siren.elapsed_secs() * SIREN_FLASH_RATE).fract() < 0.5;
+        text.sections[0]
+            .style
+            .color
+            .set_a(if on { 1.0 } else { 0.2 });
+    }
+}
+
+fn fly_cargo_planes(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut plane_query: Query<(Entity, &mut Transform, &mut CargoPlane)>,
+) {
+    let despawn_x = -OFFSCREEN_MARGIN.x - DESPAWN_MARGIN;
+    for (plane_entity, mut plane_transform, mut cargo_plane) in &mut plane_query {
+        plane_transform.translation.x -= CARGO_PLANE_SPEED * time.delta_seconds();
+        let position = plane_transform.translation;
+
+        if !cargo_plane.dropped && position.x <= cargo_plane.drop_x {
+            cargo_plane.dropped = true;
+            spawn_supply_crate(
+                &mut commands,
+                Transform::from_xyz(position.x, position.y, FLYOVER_DEPTH),
+                SupplyCrate { landed: false },
+            );
+        }
+
+        if position.x < despawn_x {
+            commands.entity(plane_entity).despawn();
+        }
+    }
+}
+
+/// A crate, under its parachute until it has landed.
+fn spawn_supply_crate(commands: &mut Commands, transform: Transform, supply_crate: SupplyCrate) {
+    let landed = supply_crate.landed;
+    let mut crate_commands = commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: SUPPLY_CRATE_COLOR,
+                custom_size: Some(SUPPLY_CRATE_HALF_SIZE * 2.0),
+                ..default()
+            },
+            transform,
+            ..default()
+        },
+        supply_crate,
+        Collider {
+            half_size: SUPPLY_CRATE_HALF_SIZE,
+        },
+        Outlined {
+            half_size: SUPPLY_CRATE_HALF_SIZE,
+        },
+    ));
+    if landed {
+        return;
+    }
+    crate_commands.with_children(|supply_crate| {
+        supply_crate.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: PARACHUTE_COLOR,
+                    custom_size: Some(Vec2::new(56.0, 24.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, 44.0, 0.0),
+                ..default()
+            },
+            Parachute,
+        ));
+    });
+}
+
+/// Crates drift down under their parachutes, then sit on the ground and scroll away
+/// with it.
+fn drop_supply_crates(
+    mut commands: Commands,
+    time: Res<Time>,
+    terrain: Res<Terrain>,
+    mut crate_query: Query<(Entity, &mut Transform, &mut SupplyCrate, &Children)>,
+    parachute_query: Query<(), With<Parachute>>,
+) {
+    let despawn_x = -OFFSCREEN_MARGIN.x - DESPAWN_MARGIN;
+    for (crate_entity, mut crate_transform, mut supply_crate, children) in &mut crate_query {
+        let dt = time.delta_seconds();
+        let ground_y = terrain.height_at(crate_transform.translation.x) + SUPPLY_CRATE_HALF_SIZE.y;
+
+        if supply_crate.landed {
+            crate_transform.translation.x -= terrain.scroll_speed() * dt;
+            crate_transform.translation.y = ground_y;
+        } else {
+            crate_transform.translation.y -= SUPPLY_CRATE_FALL_SPEED * dt;
+            if crate_transform.translation.y <= ground_y {
+                crate_transform.translation.y = ground_y;
+                supply_crate.landed = true;
+                for &child in children {
+                    if parachute_query.contains(child) {
+                        commands.entity(child).despawn();
+                    }
+                }
+            }
+        }
+
+        if crate_transform.translation.x < despawn_x {
+            commands.entity(crate_entity).despawn_recursive();
+        }
+    }
+}
+
+fn collect_supply_crates(
+    mut commands: Commands,
+    crate_query: Query<(Entity, &Transform, &Collider), With<SupplyCrate>>,
+    mut player_query: Query<(&Transform, &Collider, &PlayerState, &mut Health), With<Player>>,
+    mut stats: ResMut<RunStats>,
+) {
+    let Ok((player_transform, player_collider, PlayerState::Alive, mut health)) =
+        player_query.get_single_mut()
+    else {
+        return;
+    };
+    let player_box = Aabb2d::new(
+        player_transform.translation.truncate(),
+        player_collider.half_size,
+    );
+
+    for (crate_entity, crate_transform, crate_collider) in &crate_query {
+        let crate_box = Aabb2d::new(
+            crate_transform.translation.truncate(),
+            crate_collider.half_size,
+        );
+        if player_box.intersects(&crate_box) {
+            commands.entity(crate_entity).despawn_recursive();
+            health.current = health.max;
+            stats.score += SUPPLY_CRATE_SCORE;
+        }
+    }
+}
+
+fn fly_raid_bombers(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    themes: Res<Themes>,
+    mut bomber_query: Query<(Entity, &mut Transform, &BombTimer), With<RaidBomber>>,
+) {
+    let despawn_x = -OFFSCREEN_MARGIN.x - DESPAWN_MARGIN;
+    let bomb_texture = &themes.get(&settings.theme).bomb_texture;
+    for (bomber_entity, mut bomber_transform, bomb_timer) in &mut bomber_query {
+        bomber_transform.translation.x -= RAID_BOMBER_SPEED * time.delta_seconds();
+        let position = bomber_transform.translation;
+
+        if bomb_timer.0.just_finished() && position.x > -BOMB_HALF_SIZE.x {
+            commands.spawn((
+                SpriteBundle {
+                    texture: asset_server.load(bomb_texture.clone()),
+                    transform: Transform::from_xyz(position.x, position.y, 0.0)
+                        .with_scale(Vec3::new(2.0, 2.0, 0.0)),
+                    ..default()
+                },
+                Bomb {
+                    falling_speed: RAID_BOMB_FALL_SPEED,
+                },
+                Outlined {
+                    half_size: BOMB_HALF_SIZE,
+                },
+            ));
+        }
+
+        if position.x < despawn_x {
+            commands.entity(bomber_entity).despawn();
+        }
+    }
+}
+
+fn reset_events(
+    mut commands: Commands,
+    mut scheduler: ResMut<EventScheduler>,
+    mut rng: ResMut<GameRng>,
+    entity_query: Query<
+        Entity,
+        Or<(
+            With<CargoPlane>,
+            With<SupplyCrate>,
+            With<RaidBomber>,
+            With<SirenBanner>,
+        )>,
+    >,
+) {
+    *scheduler = EventScheduler::new(&mut rng.0);
+    for entity in &entity_query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// What a saved run keeps of the special events: how long until the next one, and the
+/// fly-overs and crates of any under way.
+#[derive(Serialize, Deserialize)]
+pub struct SavedWaveEvents {
+    scheduler: EventScheduler,
+    cargo_planes: Vec<(Transform, CargoPlane)>,
+    supply_crates: Vec<(Transform, SupplyCrate)>,
+    raid_bombers: Vec<(Transform, BombTimer)>,
+}
+
+impl SavedWaveEvents {
+    pub fn capture(world: &mut World) -> Self {
+        world.run_system_once(save_wave_events)
+    }
+
+    /// Swaps the special events under way for these. An air raid that was still being
+    /// announced gets its banner back.
+    pub fn restore(self, world: &mut World) {
+        world.run_system_once_with(self, load_wave_events);
+    }
+}
+
+fn save_wave_events(
+    scheduler: Res<EventScheduler>,
+    cargo_plane_query: Query<(&Transform, &CargoPlane)>,
+    crate_query: Query<(&Transform, &SupplyCrate)>,
+    raid_bomber_query: Query<(&Transform, &BombTimer), With<RaidBomber>>,
+) -> SavedWaveEvents {
+    SavedWaveEvents {
+        scheduler: scheduler.clone(),
+        cargo_planes: cargo_plane_query
+            .iter()
+            .map(|(transform, cargo_plane)| (*transform, cargo_plane.clone()))
+            .collect(),
+        supply_crates: crate_query
+            .iter()
+            .map(|(transform, supply_crate)| (*transform, supply_crate.clone()))
+            .collect(),
+        raid_bombers: raid_bomber_query
+            .iter()
+            .map(|(transform, bomb_timer)| (*transform, bomb_timer.clone()))
+            .collect(),
+    }
+}
+
+fn load_wave_events(
+    In(saved): In<SavedWaveEvents>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    entity_query: Query<
+        Entity,
+        Or<(
+            With<CargoPlane>,
+            With<SupplyCrate>,
+            With<RaidBomber>,
+            With<SirenBanner>,
+        )>,
+    >,
+) {
+    for entity in &entity_query {
+        commands.entity(entity).despawn_recursive();
+    }
+    if saved.scheduler.siren.is_some() {
+        spawn_siren_banner(&mut commands);
+    }
+    commands.insert_resource(saved.scheduler);
+    for (transform, cargo_plane) in saved.cargo_planes {
+        commands.spawn((
+            SpriteBundle {
+                transform,
+                ..flyover_sprite(&asset_server, CARGO_PLANE_COLOR)
+            },
+            cargo_plane,
+        ));
+    }
+    for (transform, supply_crate) in saved.supply_crates {
+        spawn_supply_crate(&mut commands, transform, supply_crate);
+    }
+    for (transform, bomb_timer) in saved.raid_bombers {
+        commands.spawn(raid_bomber(
+            SpriteBundle {
+                transform,
+                ..flyover_sprite(&asset_server, RAID_BOMBER_COLOR)
+            },
+            bomb_timer,
+        ));
+    }
+}